@@ -1,3 +1,4 @@
+use super::ProjectIndex;
 use crate::template::DynamicProjectEnvironmentResource;
 use crate::traits::FileSystem;
 use anyhow::{Context, Result};
@@ -64,6 +65,7 @@ impl DependencyGraph {
 
         let mut visited = HashSet::new();
         let mut queue = VecDeque::new();
+        let mut project_index = None;
 
         // Start with the root node
         queue.push_back(root.clone());
@@ -101,8 +103,14 @@ impl DependencyGraph {
                     for dep_env in &dep.project.environments {
                         // Find the dependency project
                         // If create: true and project doesn't exist, skip it (will be created later)
+                        // The project index is only needed once a dependency must be resolved
+                        if project_index.is_none() {
+                            project_index = Some(Self::load_project_index(fs)?);
+                        }
+
                         match Self::find_dependency_project(
                             fs,
+                            project_index.as_ref().and_then(|index| index.as_ref()),
                             &dep.project.name,
                             dep_env,
                             dep.project.create,
@@ -128,21 +136,32 @@ impl DependencyGraph {
         Ok(graph)
     }
 
+    /// Load the project index of the infrastructure holding the projects directory
+    /// Returns the infrastructure root with its index, or None when there is no projects directory
+    fn load_project_index(fs: &dyn FileSystem) -> Result<Option<(PathBuf, ProjectIndex)>> {
+        // Try both relative and absolute paths (for tests)
+        let projects_dirs = [PathBuf::from("projects"), PathBuf::from("/projects")];
+
+        let Some(projects_dir) = projects_dirs.iter().find(|dir| fs.exists(dir)) else {
+            return Ok(None);
+        };
+
+        let infrastructure_root = projects_dir.parent().unwrap_or(Path::new("")).to_path_buf();
+        let index = ProjectIndex::load(fs, &infrastructure_root)?;
+
+        Ok(Some((infrastructure_root, index)))
+    }
+
     /// Find a dependency project by name and environment
     /// If create_if_missing is true and the project doesn't exist, returns None instead of an error
     fn find_dependency_project(
         fs: &dyn FileSystem,
+        project_index: Option<&(PathBuf, ProjectIndex)>,
         project_name: &str,
         environment_name: &str,
         create_if_missing: bool,
     ) -> Result<Option<DependencyNode>> {
-        // Search for the project in the projects directory
-        // Try both relative and absolute paths (for tests)
-        let projects_dirs = [PathBuf::from("projects"), PathBuf::from("/projects")];
-
-        let projects_dir = projects_dirs.iter().find(|dir| fs.exists(dir));
-
-        let Some(projects_dir) = projects_dir else {
+        let Some((infrastructure_root, index)) = project_index else {
             if create_if_missing {
                 // Project directory doesn't exist, but that's OK if create: true
                 return Ok(None);
@@ -154,14 +173,13 @@ impl DependencyGraph {
             );
         };
 
-        // Recursively search for the project
-        let project_path = match Self::search_project(fs, projects_dir, project_name) {
-            Ok(path) => path,
-            Err(_) if create_if_missing => {
+        let project_path = match index.find_project(project_name) {
+            Some(project) => infrastructure_root.join(&project.path),
+            None if create_if_missing => {
                 // Project doesn't exist, but that's OK if create: true
                 return Ok(None);
             }
-            Err(e) => return Err(e),
+            None => anyhow::bail!("Project '{}' not found", project_name),
         };
 
         let env_path = project_path.join("environments").join(environment_name);
@@ -186,41 +204,6 @@ impl DependencyGraph {
         )))
     }
 
-    /// Recursively search for a project by name
-    fn search_project(
-        fs: &dyn FileSystem,
-        search_dir: &Path,
-        project_name: &str,
-    ) -> Result<PathBuf> {
-        // Check if this directory contains a .pmp.project.yaml
-        let project_file = search_dir.join(".pmp.project.yaml");
-
-        if fs.exists(&project_file) {
-            // Load the project and check its name
-            let resource = crate::template::ProjectResource::from_file(fs, &project_file)
-                .with_context(|| format!("Failed to load project file: {:?}", project_file))?;
-
-            if resource.metadata.name == project_name {
-                return Ok(search_dir.to_path_buf());
-            }
-        }
-
-        // Search subdirectories
-        let entries = fs.read_dir(search_dir)?;
-
-        for entry in entries {
-            let path = entry;
-
-            if fs.is_dir(&path)
-                && let Ok(found_path) = Self::search_project(fs, &path, project_name)
-            {
-                return Ok(found_path);
-            }
-        }
-
-        anyhow::bail!("Project '{}' not found", project_name)
-    }
-
     /// Get the execution order (topologically sorted)
     /// Returns nodes in the order they should be executed (dependencies first)
     pub fn execution_order(&self) -> Result<Vec<DependencyNode>> {
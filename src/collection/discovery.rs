@@ -1,4 +1,5 @@
-use crate::template::metadata::{InfrastructureResource, ProjectReference, ProjectResource};
+use super::ProjectIndex;
+use crate::template::metadata::{InfrastructureResource, ProjectReference};
 use anyhow::Result;
 use std::path::{Path, PathBuf};
 
@@ -53,99 +54,29 @@ impl CollectionDiscovery {
     }
 
     /// Discover all projects in the "projects" folder of an infrastructure
-    /// Scans all levels of subdirectories to find .pmp.yaml files
+    /// Projects are read through the project index, which only re-parses changed files
     pub fn discover_projects(
         fs: &dyn crate::traits::FileSystem,
         output: &dyn crate::traits::Output,
         infrastructure_root: &Path,
     ) -> Result<Vec<ProjectReference>> {
-        let projects_dir = infrastructure_root.join("projects");
-
-        if !fs.exists(&projects_dir) {
-            return Ok(Vec::new());
-        }
-
-        let mut projects = Vec::new();
-
-        // Walk through the projects directory recursively looking for .pmp.project.yaml files
-        // Scan all levels, no depth limit (using a high value)
-        let entries = fs.walk_dir(&projects_dir, 100)?;
-
-        for path in entries {
-            // Look for .pmp.project.yaml files
-            if fs.is_file(&path)
-                && path.file_name() == Some(std::ffi::OsStr::new(".pmp.project.yaml"))
-                && let Some(project_dir) = path.parent()
-            {
-                // Try to load as a Project resource
-                match ProjectResource::from_file(fs, &path) {
-                    Ok(resource) => {
-                        // Get the resource kind from the first environment we find
-                        let kind = Self::get_project_kind(fs, output, project_dir)?;
-
-                        // Calculate relative path from infrastructure root
-                        let relative_path = project_dir
-                            .strip_prefix(infrastructure_root)
-                            .unwrap_or(project_dir)
-                            .to_string_lossy()
-                            .to_string();
-
-                        projects.push(ProjectReference {
-                            name: resource.metadata.name.clone(),
-                            kind,
-                            path: relative_path,
-                            labels: resource.metadata.labels.clone(),
-                        });
-                    }
-                    Err(e) => {
-                        output.warning(&format!("Failed to load project from {:?}: {}", path, e));
-                    }
-                }
-            }
-        }
-
-        Ok(projects)
+        let index = Self::load_index(fs, output, infrastructure_root)?;
+        index.project_references()
     }
 
-    /// Get the resource kind from a project by reading the first environment
-    fn get_project_kind(
+    /// Load the project index of an infrastructure, reporting files that could not be parsed
+    pub fn load_index(
         fs: &dyn crate::traits::FileSystem,
         output: &dyn crate::traits::Output,
-        project_dir: &Path,
-    ) -> Result<String> {
-        use crate::template::DynamicProjectEnvironmentResource;
-
-        let environments_dir = project_dir.join("environments");
-
-        if !fs.exists(&environments_dir) {
-            anyhow::bail!(
-                "No environments directory found in project: {:?}",
-                project_dir
-            );
-        }
+        infrastructure_root: &Path,
+    ) -> Result<ProjectIndex> {
+        let index = ProjectIndex::load(fs, infrastructure_root)?;
 
-        // Find the first .pmp.environment.yaml file
-        let entries = fs.walk_dir(&environments_dir, 2)?;
-
-        for path in entries {
-            if fs.is_file(&path)
-                && path.file_name() == Some(std::ffi::OsStr::new(".pmp.environment.yaml"))
-            {
-                match DynamicProjectEnvironmentResource::from_file(fs, &path) {
-                    Ok(resource) => {
-                        return Ok(resource.kind.clone());
-                    }
-                    Err(e) => {
-                        output.warning(&format!(
-                            "Failed to load environment from {:?}: {}",
-                            path, e
-                        ));
-                    }
-                }
-            }
+        for warning in &index.warnings {
+            output.warning(warning);
         }
 
-        anyhow::bail!("No valid environment found in project: {:?}", project_dir)
+        Ok(index)
     }
 
     /// Discover all environments in a project
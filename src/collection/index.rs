@@ -0,0 +1,603 @@
+use crate::template::metadata::{
+    DynamicProjectEnvironmentResource, ProjectReference, ProjectResource,
+};
+use crate::traits::FileSystem;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Default index location, relative to the infrastructure root
+pub const DEFAULT_INDEX_PATH: &str = ".pmp/cache/index.json";
+
+/// Bumped whenever the index layout changes; older indexes are rebuilt
+const INDEX_VERSION: u32 = 1;
+
+/// Maximum directory depth scanned below `projects/`
+const MAX_SCAN_DEPTH: usize = 100;
+
+/// Files modified this recently are stamped without their mtime, so an edit landing in the
+/// same mtime tick is still caught by the content hash on the next refresh
+const RACY_WINDOW: Duration = Duration::from_secs(2);
+
+/// Change marker of an indexed file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileStamp {
+    /// Modification time in nanoseconds since the Unix epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified: Option<u128>,
+
+    /// SHA-256 of the file content
+    pub hash: String,
+}
+
+/// Indexed `.pmp.environment.yaml`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedEnvironment {
+    /// Path of the environment directory relative to the infrastructure root
+    pub path: String,
+
+    pub file: FileStamp,
+
+    pub resource: DynamicProjectEnvironmentResource,
+}
+
+/// Indexed `.pmp.project.yaml` with its environments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedProject {
+    pub name: String,
+
+    /// Path of the project directory relative to the infrastructure root
+    pub path: String,
+
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+
+    pub file: FileStamp,
+
+    /// Environments by directory name
+    #[serde(default)]
+    pub environments: BTreeMap<String, IndexedEnvironment>,
+}
+
+impl IndexedProject {
+    /// Kind of the project, taken from its first environment
+    pub fn kind(&self) -> Option<&str> {
+        self.environments
+            .values()
+            .next()
+            .map(|env| env.resource.kind.as_str())
+    }
+}
+
+/// What a refresh of the index did
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RefreshStats {
+    /// Files parsed because they were new or changed
+    pub parsed: usize,
+
+    /// Files reused from the index
+    pub reused: usize,
+
+    /// Projects and environments dropped because their files disappeared
+    pub removed: usize,
+}
+
+impl RefreshStats {
+    pub fn changed(&self) -> bool {
+        self.parsed > 0 || self.removed > 0
+    }
+}
+
+/// On-disk index of the projects and environments of an infrastructure
+///
+/// The index caches parsed `.pmp.project.yaml` and `.pmp.environment.yaml` files in
+/// `.pmp/cache/index.json`. Every load rescans the directory tree but only re-parses files
+/// whose modification time and content hash changed, so large infrastructures are not
+/// re-parsed on each command.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectIndex {
+    version: u32,
+
+    /// Projects by relative path
+    #[serde(default)]
+    projects: BTreeMap<String, IndexedProject>,
+
+    /// Problems found while refreshing (files that could not be parsed)
+    #[serde(skip)]
+    pub warnings: Vec<String>,
+}
+
+impl ProjectIndex {
+    /// Index file of an infrastructure
+    pub fn index_path(infrastructure_root: &Path) -> PathBuf {
+        infrastructure_root.join(DEFAULT_INDEX_PATH)
+    }
+
+    /// Load the index of an infrastructure, refreshing entries whose files changed
+    ///
+    /// A missing, unreadable or outdated index is rebuilt. Failing to save the refreshed
+    /// index is not an error (e.g., read-only checkouts); it is reported in `warnings`.
+    pub fn load(fs: &dyn FileSystem, infrastructure_root: &Path) -> Result<Self> {
+        let mut index = Self::read_cached(fs, infrastructure_root);
+        let stats = index.refresh(fs, infrastructure_root)?;
+
+        if stats.changed()
+            && let Err(e) = index.save(fs, infrastructure_root)
+        {
+            index
+                .warnings
+                .push(format!("Project index not saved: {}", e));
+        }
+
+        Ok(index)
+    }
+
+    /// Rebuild the index from scratch and save it
+    pub fn rebuild(
+        fs: &dyn FileSystem,
+        infrastructure_root: &Path,
+    ) -> Result<(Self, RefreshStats)> {
+        let mut index = Self::empty();
+        let stats = index.refresh(fs, infrastructure_root)?;
+        index.save(fs, infrastructure_root)?;
+
+        Ok((index, stats))
+    }
+
+    /// Delete the index file; returns whether one existed
+    pub fn clear(fs: &dyn FileSystem, infrastructure_root: &Path) -> Result<bool> {
+        let path = Self::index_path(infrastructure_root);
+
+        if !fs.exists(&path) {
+            return Ok(false);
+        }
+
+        fs.remove_file(&path)?;
+        Ok(true)
+    }
+
+    fn empty() -> Self {
+        Self {
+            version: INDEX_VERSION,
+            ..Default::default()
+        }
+    }
+
+    fn read_cached(fs: &dyn FileSystem, infrastructure_root: &Path) -> Self {
+        let path = Self::index_path(infrastructure_root);
+
+        fs.read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<Self>(&content).ok())
+            .filter(|index| index.version == INDEX_VERSION)
+            .unwrap_or_else(Self::empty)
+    }
+
+    fn save(&self, fs: &dyn FileSystem, infrastructure_root: &Path) -> Result<()> {
+        let path = Self::index_path(infrastructure_root);
+
+        fs.write(&path, &serde_json::to_string(self)?)
+            .with_context(|| format!("Failed to write project index: {}", path.display()))
+    }
+
+    /// Bring the index in line with the files under `projects/`
+    pub fn refresh(
+        &mut self,
+        fs: &dyn FileSystem,
+        infrastructure_root: &Path,
+    ) -> Result<RefreshStats> {
+        let mut stats = RefreshStats::default();
+        let mut previous = std::mem::take(&mut self.projects);
+
+        let mut project_dirs = Vec::new();
+        let projects_dir = infrastructure_root.join("projects");
+        if fs.exists(&projects_dir) {
+            find_project_dirs(fs, &projects_dir, 0, &mut project_dirs)?;
+        }
+
+        for project_dir in project_dirs {
+            let relative = relative_path(infrastructure_root, &project_dir);
+            let project_file = project_dir.join(".pmp.project.yaml");
+            let cached = previous.remove(&relative);
+
+            let Some(mut project) =
+                self.refresh_project(fs, &project_file, &relative, cached, &mut stats)
+            else {
+                continue;
+            };
+
+            project.environments = self.refresh_environments(
+                fs,
+                infrastructure_root,
+                &project_dir,
+                project.environments,
+                &mut stats,
+            )?;
+            self.projects.insert(relative, project);
+        }
+
+        stats.removed += previous
+            .values()
+            .map(|project| 1 + project.environments.len())
+            .sum::<usize>();
+
+        Ok(stats)
+    }
+
+    fn refresh_project(
+        &mut self,
+        fs: &dyn FileSystem,
+        project_file: &Path,
+        relative: &str,
+        cached: Option<IndexedProject>,
+        stats: &mut RefreshStats,
+    ) -> Option<IndexedProject> {
+        let (stamp, unchanged) =
+            match check_file(fs, project_file, cached.as_ref().map(|p| &p.file)) {
+                Ok(result) => result,
+                Err(e) => {
+                    self.warnings
+                        .push(format!("Failed to read {:?}: {}", project_file, e));
+                    return None;
+                }
+            };
+
+        let environments = match cached {
+            Some(mut project) if unchanged => {
+                stats.reused += 1;
+                project.file = stamp;
+                return Some(project);
+            }
+            // Environments are refreshed on their own; keep them for the next step
+            Some(project) => project.environments,
+            None => BTreeMap::new(),
+        };
+
+        match ProjectResource::from_file(fs, project_file) {
+            Ok(resource) => {
+                stats.parsed += 1;
+                Some(IndexedProject {
+                    name: resource.metadata.name,
+                    path: relative.to_string(),
+                    labels: resource.metadata.labels,
+                    file: stamp,
+                    environments,
+                })
+            }
+            Err(e) => {
+                self.warnings.push(format!(
+                    "Failed to load project from {:?}: {}",
+                    project_file, e
+                ));
+                None
+            }
+        }
+    }
+
+    fn refresh_environments(
+        &mut self,
+        fs: &dyn FileSystem,
+        infrastructure_root: &Path,
+        project_dir: &Path,
+        mut previous: BTreeMap<String, IndexedEnvironment>,
+        stats: &mut RefreshStats,
+    ) -> Result<BTreeMap<String, IndexedEnvironment>> {
+        let mut environments = BTreeMap::new();
+        let environments_dir = project_dir.join("environments");
+
+        if fs.exists(&environments_dir) {
+            for env_dir in fs.read_dir(&environments_dir)? {
+                let env_file = env_dir.join(".pmp.environment.yaml");
+                let Some(env_name) = env_dir.file_name().map(|n| n.to_string_lossy().to_string())
+                else {
+                    continue;
+                };
+
+                if !fs.is_dir(&env_dir) || !fs.exists(&env_file) {
+                    continue;
+                }
+
+                let cached = previous.remove(&env_name);
+                let (stamp, unchanged) =
+                    match check_file(fs, &env_file, cached.as_ref().map(|e| &e.file)) {
+                        Ok(result) => result,
+                        Err(e) => {
+                            self.warnings
+                                .push(format!("Failed to read {:?}: {}", env_file, e));
+                            continue;
+                        }
+                    };
+
+                if let Some(mut environment) = cached.filter(|_| unchanged) {
+                    stats.reused += 1;
+                    environment.file = stamp;
+                    environments.insert(env_name, environment);
+                    continue;
+                }
+
+                match DynamicProjectEnvironmentResource::from_file(fs, &env_file) {
+                    Ok(resource) => {
+                        stats.parsed += 1;
+                        environments.insert(
+                            env_name,
+                            IndexedEnvironment {
+                                path: relative_path(infrastructure_root, &env_dir),
+                                file: stamp,
+                                resource,
+                            },
+                        );
+                    }
+                    Err(e) => self.warnings.push(format!(
+                        "Failed to load environment from {:?}: {}",
+                        env_file, e
+                    )),
+                }
+            }
+        }
+
+        stats.removed += previous.len();
+        Ok(environments)
+    }
+
+    /// Indexed projects, ordered by path
+    pub fn projects(&self) -> impl Iterator<Item = &IndexedProject> {
+        self.projects.values()
+    }
+
+    /// Project with the given name
+    pub fn find_project(&self, name: &str) -> Option<&IndexedProject> {
+        self.projects.values().find(|project| project.name == name)
+    }
+
+    /// All indexed environments with their project, ordered by project path and environment
+    pub fn environments(&self) -> impl Iterator<Item = (&IndexedProject, &IndexedEnvironment)> {
+        self.projects.values().flat_map(|project| {
+            project
+                .environments
+                .values()
+                .map(move |environment| (project, environment))
+        })
+    }
+
+    /// Project references, as returned by project discovery
+    ///
+    /// Fails when a project has no valid environment, since its kind cannot be determined.
+    pub fn project_references(&self) -> Result<Vec<ProjectReference>> {
+        self.projects
+            .values()
+            .map(|project| {
+                let kind = project.kind().with_context(|| {
+                    format!("No valid environment found in project: {:?}", project.path)
+                })?;
+
+                Ok(ProjectReference {
+                    name: project.name.clone(),
+                    kind: kind.to_string(),
+                    path: project.path.clone(),
+                    labels: project.labels.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Collect directories holding a `.pmp.project.yaml`
+///
+/// Environment folders of projects and hidden directories (`.terraform`, `.git`, ...) are
+/// not scanned.
+fn find_project_dirs(
+    fs: &dyn FileSystem,
+    dir: &Path,
+    depth: usize,
+    found: &mut Vec<PathBuf>,
+) -> Result<()> {
+    let is_project = fs.is_file(&dir.join(".pmp.project.yaml"));
+    if is_project {
+        found.push(dir.to_path_buf());
+    }
+
+    if depth >= MAX_SCAN_DEPTH {
+        return Ok(());
+    }
+
+    let mut entries = fs.read_dir(dir)?;
+    entries.sort();
+
+    for entry in entries {
+        let Some(name) = entry.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+
+        if name.starts_with('.') || (is_project && name == "environments") || !fs.is_dir(&entry) {
+            continue;
+        }
+
+        find_project_dirs(fs, &entry, depth + 1, found)?;
+    }
+
+    Ok(())
+}
+
+/// Stamp a file and tell whether it matches its previous stamp
+///
+/// The content is only hashed when the modification time is unknown or differs.
+fn check_file(
+    fs: &dyn FileSystem,
+    path: &Path,
+    previous: Option<&FileStamp>,
+) -> Result<(FileStamp, bool)> {
+    let modified = fs.modified(path);
+    let settled = modified.filter(|time| {
+        SystemTime::now()
+            .duration_since(*time)
+            .is_ok_and(|age| age > RACY_WINDOW)
+    });
+    let modified_nanos = settled
+        .and_then(|time| time.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .map(|since_epoch| since_epoch.as_nanos());
+
+    if let Some(previous) = previous
+        && modified_nanos.is_some()
+        && previous.modified == modified_nanos
+    {
+        return Ok((previous.clone(), true));
+    }
+
+    let content = fs.read_to_string(path)?;
+    let stamp = FileStamp {
+        modified: modified_nanos,
+        hash: format!("{:x}", Sha256::digest(content.as_bytes())),
+    };
+    let unchanged = previous.is_some_and(|previous| previous.hash == stamp.hash);
+
+    Ok((stamp, unchanged))
+}
+
+fn relative_path(infrastructure_root: &Path, path: &Path) -> String {
+    path.strip_prefix(infrastructure_root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::MockFileSystem;
+
+    fn write_project(fs: &MockFileSystem, dir: &str, name: &str, envs: &[(&str, &str)]) {
+        let project_dir = PathBuf::from("/infra/projects").join(dir);
+        fs.write(
+            &project_dir.join(".pmp.project.yaml"),
+            &format!(
+                "apiVersion: pmp.io/v1\nkind: Project\nmetadata:\n  name: {}\n  labels:\n    team: core\n",
+                name
+            ),
+        )
+        .unwrap();
+
+        for (env, kind) in envs {
+            fs.write(
+                &project_dir.join("environments").join(env).join(".pmp.environment.yaml"),
+                &format!(
+                    "apiVersion: pmp.io/v1\nkind: {}\nmetadata:\n  name: {}\n  environment_name: {}\nspec:\n  resource:\n    apiVersion: pmp.io/v1\n    kind: {}\n  executor:\n    name: opentofu\n  inputs: {{}}\n",
+                    kind, name, env, kind
+                ),
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_load_indexes_projects_and_environments() {
+        let fs = MockFileSystem::new();
+        write_project(
+            &fs,
+            "vpc",
+            "vpc",
+            &[("dev", "Network"), ("prod", "Network")],
+        );
+        write_project(&fs, "apps/api", "api", &[("dev", "Workload")]);
+
+        let index = ProjectIndex::load(&fs, Path::new("/infra")).unwrap();
+
+        assert!(index.warnings.is_empty(), "{:?}", index.warnings);
+        assert!(fs.has_file(Path::new("/infra/.pmp/cache/index.json")));
+
+        let refs = index.project_references().unwrap();
+        let names: Vec<_> = refs
+            .iter()
+            .map(|r| (r.name.as_str(), r.kind.as_str(), r.path.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("api", "Workload", "projects/apps/api"),
+                ("vpc", "Network", "projects/vpc")
+            ]
+        );
+        assert_eq!(refs[1].labels["team"], "core");
+
+        let envs: Vec<_> = index
+            .environments()
+            .map(|(p, e)| format!("{}:{}", p.name, e.path))
+            .collect();
+        assert_eq!(
+            envs,
+            vec![
+                "api:projects/apps/api/environments/dev",
+                "vpc:projects/vpc/environments/dev",
+                "vpc:projects/vpc/environments/prod",
+            ]
+        );
+        assert_eq!(index.find_project("vpc").unwrap().path, "projects/vpc");
+    }
+
+    #[test]
+    fn test_refresh_is_incremental() {
+        let fs = MockFileSystem::new();
+        let root = Path::new("/infra");
+        write_project(&fs, "vpc", "vpc", &[("dev", "Network")]);
+        write_project(&fs, "db", "db", &[("dev", "Database")]);
+
+        let (mut index, stats) = ProjectIndex::rebuild(&fs, root).unwrap();
+        assert_eq!(stats.parsed, 4);
+
+        let stats = index.refresh(&fs, root).unwrap();
+        assert_eq!(
+            stats,
+            RefreshStats {
+                parsed: 0,
+                reused: 4,
+                removed: 0
+            }
+        );
+
+        write_project(&fs, "vpc", "vpc", &[("dev", "Network2")]);
+        fs.remove_dir_all(Path::new("/infra/projects/db")).unwrap();
+
+        let stats = index.refresh(&fs, root).unwrap();
+        assert_eq!(
+            stats,
+            RefreshStats {
+                parsed: 1,
+                reused: 1,
+                removed: 2
+            }
+        );
+        assert_eq!(index.find_project("vpc").unwrap().kind(), Some("Network2"));
+        assert!(index.find_project("db").is_none());
+    }
+
+    #[test]
+    fn test_invalid_files_are_reported_and_skipped() {
+        let fs = MockFileSystem::new();
+        write_project(&fs, "vpc", "vpc", &[("dev", "Network")]);
+        fs.write(
+            Path::new("/infra/projects/broken/.pmp.project.yaml"),
+            "kind: [",
+        )
+        .unwrap();
+
+        let index = ProjectIndex::load(&fs, Path::new("/infra")).unwrap();
+
+        assert_eq!(index.projects().count(), 1);
+        assert_eq!(index.warnings.len(), 1);
+        assert!(index.warnings[0].contains("broken"));
+    }
+
+    #[test]
+    fn test_clear() {
+        let fs = MockFileSystem::new();
+        let root = Path::new("/infra");
+        write_project(&fs, "vpc", "vpc", &[("dev", "Network")]);
+
+        assert!(!ProjectIndex::clear(&fs, root).unwrap());
+        ProjectIndex::load(&fs, root).unwrap();
+        assert!(ProjectIndex::clear(&fs, root).unwrap());
+        assert!(!fs.has_file(&ProjectIndex::index_path(root)));
+    }
+}
@@ -70,9 +70,11 @@ impl CollectionManager {
                 environments: std::collections::HashMap::new(),
                 hooks: None,
                 executor: None,
+                executors: vec![],
                 cost: None,
                 policy: None,
                 secrets: None,
+                history: None,
             },
         };
 
@@ -1,7 +1,9 @@
 mod dependency_graph;
 mod discovery;
+mod index;
 mod manager;
 
 pub use dependency_graph::{DependencyGraph, DependencyNode};
 pub use discovery::CollectionDiscovery;
+pub use index::ProjectIndex;
 pub use manager::CollectionManager;
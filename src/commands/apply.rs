@@ -1,7 +1,7 @@
 use crate::collection::{CollectionDiscovery, CollectionManager, DependencyNode};
 use crate::commands::project_group::ProjectGroupHandler;
-use crate::commands::{CostCommand, ExecutionHelper, PolicyCommand};
-use crate::executor::{Executor, ExecutorConfig, OpenTofuExecutor};
+use crate::commands::{CostCommand, ExecutionHelper, PlanBundle, PolicyCommand};
+use crate::executor::ExecutorConfig;
 use crate::hooks::{HookOutcome, HooksRunner};
 use crate::template::metadata::{FailureBehavior, ParallelConfig};
 use crate::template::{DynamicProjectEnvironmentResource, ProjectResource};
@@ -93,42 +93,55 @@ impl ApplyCommand {
                 .to_str()
                 .context("Failed to convert environment path to string")?;
 
-            // Run pre-apply hooks
-            if !hooks.pre_apply.is_empty()
-                && HooksRunner::run_hooks(&hooks.pre_apply, env_dir_str, "pre-apply")?
-                    == HookOutcome::Cancel
-            {
-                ctx.output.blank();
-                ctx.output.warning("Apply cancelled by pre-apply hook");
-                return Ok(());
-            }
-
-            // Process ProjectGroup - create/update and then execute on all defined projects
-            ctx.output.blank();
-            ctx.output.subsection("Processing Project Group");
-            ProjectGroupHandler::process_projects(
-                ctx, &resource, &env_name, None, // template_packs_paths
-            )?;
+            // Pre/post hook and apply failures are handed to the on-failure and finally hooks
+            let result = (|| -> Result<HookOutcome> {
+                // Run pre-apply hooks
+                if !hooks.pre_apply.is_empty()
+                    && HooksRunner::run_hooks(ctx, &hooks.pre_apply, env_dir_str, "pre-apply")?
+                        == HookOutcome::Cancel
+                {
+                    ctx.output.blank();
+                    ctx.output.warning("Apply cancelled by pre-apply hook");
+                    return Ok(HookOutcome::Cancel);
+                }
 
-            // Execute apply on all configured projects
-            ProjectGroupHandler::execute_command_on_projects(
-                ctx, &resource, &env_name, "apply", extra_args,
-            )?;
+                // Process ProjectGroup - create/update and then execute on all defined projects
+                ctx.output.blank();
+                ctx.output.subsection("Processing Project Group");
+                ProjectGroupHandler::process_projects(
+                    ctx, &resource, &env_name, None, // template_packs_paths
+                )?;
+
+                // Execute apply on all configured projects
+                ProjectGroupHandler::execute_command_on_projects(
+                    ctx, &resource, &env_name, "apply", extra_args,
+                )?;
+
+                // Run post-apply hooks
+                if !hooks.post_apply.is_empty()
+                    && HooksRunner::run_hooks(ctx, &hooks.post_apply, env_dir_str, "post-apply")?
+                        == HookOutcome::Cancel
+                {
+                    ctx.output.blank();
+                    ctx.output
+                        .warning("Post-apply hooks cancelled further execution");
+                    return Ok(HookOutcome::Cancel);
+                }
 
-            // Run post-apply hooks
-            if !hooks.post_apply.is_empty()
-                && HooksRunner::run_hooks(&hooks.post_apply, env_dir_str, "post-apply")?
-                    == HookOutcome::Cancel
-            {
                 ctx.output.blank();
-                ctx.output
-                    .warning("Post-apply hooks cancelled further execution");
-                return Ok(());
-            }
+                ctx.output.success("Apply completed successfully");
 
-            ctx.output.blank();
-            ctx.output.success("Apply completed successfully");
-            return Ok(());
+                Ok(HookOutcome::Continue)
+            })();
+
+            return ExecutionHelper::finish_with_hooks(
+                ctx,
+                &hooks.on_failure_apply,
+                &hooks.finally_apply,
+                env_dir_str,
+                "apply",
+                result,
+            );
         }
 
         // Check for dependencies (non-ProjectGroup projects)
@@ -174,7 +187,7 @@ impl ApplyCommand {
         // No dependencies - proceed with single project execution
 
         // Load collection to get infrastructure-level hooks
-        let (collection, _collection_root) = CollectionDiscovery::find_collection(&*ctx.fs)?
+        let (collection, collection_root) = CollectionDiscovery::find_collection(&*ctx.fs)?
             .context("Infrastructure is required to run commands")?;
 
         let infrastructure_hooks = collection.get_hooks();
@@ -186,7 +199,7 @@ impl ApplyCommand {
         );
 
         // Get executor
-        let executor = Self::get_executor(&executor_config.name)?;
+        let executor = ctx.executor_registry.get(&executor_config.name)?;
 
         // Check if executor is installed
         ctx.output.subsection("Prerequisites");
@@ -209,51 +222,231 @@ impl ApplyCommand {
             .to_str()
             .context("Failed to convert environment path to string")?;
 
-        // Run pre-apply hooks
-        if !hooks.pre_apply.is_empty()
-            && HooksRunner::run_hooks(&hooks.pre_apply, env_dir_str, "pre-apply")?
-                == HookOutcome::Cancel
-        {
+        // Pre/post hook and apply failures are handed to the on-failure and finally hooks
+        let result = (|| -> Result<HookOutcome> {
+            // Run pre-apply hooks
+            if !hooks.pre_apply.is_empty()
+                && HooksRunner::run_hooks(ctx, &hooks.pre_apply, env_dir_str, "pre-apply")?
+                    == HookOutcome::Cancel
+            {
+                ctx.output.blank();
+                ctx.output.warning("Apply cancelled by pre-apply hook");
+                return Ok(HookOutcome::Cancel);
+            }
+
+            // Run helm repo update if configured
+            crate::commands::ExecutionHelper::run_helm_repo_update_if_needed(
+                ctx,
+                &collection,
+                executor.get_name(),
+            )?;
+
+            // Initialize executor
+            ctx.output.subsection("Initialization");
+            ctx.output
+                .dimmed(&format!("Initializing {}...", executor.get_name()));
+            let init_output = executor.init(env_dir_str)?;
+
+            if !init_output.status.success() {
+                // Display captured stdout and stderr before failing
+                if !init_output.stdout.is_empty()
+                    && let Ok(stdout_str) = String::from_utf8(init_output.stdout.clone())
+                {
+                    ctx.output.error(&stdout_str);
+                }
+                if !init_output.stderr.is_empty()
+                    && let Ok(stderr_str) = String::from_utf8(init_output.stderr.clone())
+                {
+                    ctx.output.error(&stderr_str);
+                }
+                anyhow::bail!(
+                    "Initialization failed with exit code: {:?}",
+                    init_output.status.code()
+                );
+            }
+
+            ctx.output.success("Initialization completed");
+
+            // Build executor config
+            let mut command_options = std::collections::HashMap::new();
+            if let Some(config) = &executor_config.config {
+                for (cmd_name, cmd_config) in &config.commands {
+                    command_options.insert(cmd_name.clone(), cmd_config.options.clone());
+                }
+            }
+
+            let execution_config = ExecutorConfig {
+                plan_command: None,
+                apply_command: None,
+                destroy_command: None,
+                refresh_command: None,
+                test_command: None,
+                command_options,
+                plan_file: None,
+            };
+
+            // Check cost estimation if requested (before apply)
+            if show_cost {
+                if Self::check_cost_before_apply(ctx, &env_path, &collection)? {
+                    // Cost threshold exceeded and blocking is enabled
+                    return Ok(HookOutcome::Cancel);
+                }
+            }
+
+            // Run OPA policy validation (before apply)
+            if !skip_policy {
+                if !PolicyCommand::run_pre_operation_validation(ctx, &env_path, &collection)? {
+                    // Policy validation failed and blocking is enabled
+                    ctx.output
+                        .dimmed("Use --skip-policy to bypass policy validation");
+                    return Ok(HookOutcome::Cancel);
+                }
+            }
+
+            // Run apply
+            ctx.output.subsection("Running Apply");
+            ctx.output
+                .dimmed(&format!("Executing {} apply...", executor.get_name()));
+            ExecutionHelper::with_state_guard(
+                ctx,
+                &collection,
+                &collection_root,
+                executor.as_ref(),
+                env_dir_str,
+                &project_name,
+                &env_name,
+                "apply",
+                || executor.apply(&execution_config, env_dir_str, extra_args),
+            )?;
+
+            // Run post-apply hooks
+            if !hooks.post_apply.is_empty()
+                && HooksRunner::run_hooks(ctx, &hooks.post_apply, env_dir_str, "post-apply")?
+                    == HookOutcome::Cancel
+            {
+                ctx.output.blank();
+                ctx.output
+                    .warning("Post-apply hooks cancelled further execution");
+                return Ok(HookOutcome::Cancel);
+            }
+
             ctx.output.blank();
-            ctx.output.warning("Apply cancelled by pre-apply hook");
-            return Ok(());
-        }
+            ctx.output.success("Apply completed successfully");
+
+            Ok(HookOutcome::Continue)
+        })();
 
-        // Run helm repo update if configured
-        crate::commands::ExecutionHelper::run_helm_repo_update_if_needed(
+        ExecutionHelper::finish_with_hooks(
             ctx,
-            &collection,
-            executor.get_name(),
-        )?;
+            &hooks.on_failure_apply,
+            &hooks.finally_apply,
+            env_dir_str,
+            "apply",
+            result,
+        )
+    }
 
-        // Initialize executor
-        ctx.output.subsection("Initialization");
-        ctx.output
-            .dimmed(&format!("Initializing {}...", executor.get_name()));
-        let init_output = executor.init(env_dir_str)?;
+    /// Apply exactly the plans saved by `preview --out`
+    ///
+    /// Plans are verified against the current dependency graph and environment files
+    /// before anything runs, then applied level by level in dependency order.
+    pub fn execute_saved_plans(
+        ctx: &crate::context::Context,
+        plan_dir: &str,
+        skip_policy: bool,
+        extra_args: &[String],
+    ) -> Result<()> {
+        let bundle_dir = PathBuf::from(plan_dir);
+        let manifest = PlanBundle::load_manifest(ctx, &bundle_dir)?;
 
-        if !init_output.status.success() {
-            // Display captured stdout and stderr before failing
-            if !init_output.stdout.is_empty()
-                && let Ok(stdout_str) = String::from_utf8(init_output.stdout.clone())
-            {
-                ctx.output.error(&stdout_str);
+        let (collection, collection_root) = CollectionDiscovery::find_collection(&*ctx.fs)?
+            .context("Infrastructure is required to run commands")?;
+
+        ctx.output.section("Apply Saved Plans");
+        ctx.output.key_value_highlight("Project", &manifest.project);
+        ctx.output.environment_badge(&manifest.environment);
+        ctx.output.key_value("Planned at", &manifest.created_at);
+        if let Some(git_sha) = &manifest.git_sha {
+            ctx.output.key_value("Git commit", git_sha);
+        }
+
+        // Verify the bundle against the current dependency graph and inputs
+        let env_path = collection_root.join(&manifest.environment_path);
+        let levels = PlanBundle::execution_levels(
+            ctx,
+            &env_path,
+            &manifest.project,
+            &manifest.environment,
+        )?;
+        let planned_levels = PlanBundle::verify(ctx, &bundle_dir, &manifest, &levels)?;
+
+        ctx.output.subsection("Saved Plans");
+        for (level_index, level) in planned_levels.iter().enumerate() {
+            for plan in level {
+                ctx.output.dimmed(&format!(
+                    "  Level {}: {} ({}) <- {}",
+                    level_index + 1,
+                    plan.node.project_name,
+                    plan.node.environment_name,
+                    plan.plan_file.display()
+                ));
             }
-            if !init_output.stderr.is_empty()
-                && let Ok(stderr_str) = String::from_utf8(init_output.stderr.clone())
-            {
-                ctx.output.error(&stderr_str);
+        }
+
+        // Run OPA policy validation (before apply) against the saved JSON plans
+        if !skip_policy {
+            for plan in planned_levels.iter().flatten() {
+                if !PolicyCommand::run_pre_operation_validation_with_plan(
+                    ctx,
+                    plan.plan_json_file.as_deref(),
+                    &collection,
+                )? {
+                    ctx.output
+                        .dimmed("Use --skip-policy to bypass policy validation");
+                    return Ok(());
+                }
             }
-            anyhow::bail!(
-                "Initialization failed with exit code: {:?}",
-                init_output.status.code()
-            );
         }
 
-        ctx.output.success("Initialization completed");
+        ctx.output.blank();
+        if !ctx.input.confirm("Apply these saved plans?", Some(true))? {
+            ctx.output.warning("Apply cancelled");
+            return Ok(());
+        }
+
+        // Apply level by level; a failure stops the remaining levels
+        for plan in planned_levels.iter().flatten() {
+            Self::execute_saved_plan_on_node(ctx, &plan.node, &plan.plan_file, extra_args)
+                .with_context(|| {
+                    format!(
+                        "Apply failed for {} ({})",
+                        plan.node.project_name, plan.node.environment_name
+                    )
+                })?;
+        }
+
+        ctx.output.blank();
+        ctx.output.success("Saved plans applied successfully");
+
+        Ok(())
+    }
+
+    /// Apply a single saved plan on a node
+    fn execute_saved_plan_on_node(
+        ctx: &crate::context::Context,
+        node: &DependencyNode,
+        plan_file: &Path,
+        extra_args: &[String],
+    ) -> Result<()> {
+        let env_file = node.environment_path.join(".pmp.environment.yaml");
+        let resource = DynamicProjectEnvironmentResource::from_file(&*ctx.fs, &env_file)
+            .context("Failed to load environment resource")?;
+
+        let executor_config = resource.get_executor_config();
+        let executor = ctx.executor_registry.get(&executor_config.name)?;
 
-        // Build executor config
         let mut command_options = std::collections::HashMap::new();
+
         if let Some(config) = &executor_config.config {
             for (cmd_name, cmd_config) in &config.commands {
                 command_options.insert(cmd_name.clone(), cmd_config.options.clone());
@@ -267,47 +460,16 @@ impl ApplyCommand {
             refresh_command: None,
             test_command: None,
             command_options,
+            plan_file: Some(plan_file.to_string_lossy().to_string()),
         };
 
-        // Check cost estimation if requested (before apply)
-        if show_cost {
-            if Self::check_cost_before_apply(ctx, &env_path, &collection)? {
-                // Cost threshold exceeded and blocking is enabled
-                return Ok(());
-            }
-        }
-
-        // Run OPA policy validation (before apply)
-        if !skip_policy {
-            if !PolicyCommand::run_pre_operation_validation(ctx, &env_path, &collection)? {
-                // Policy validation failed and blocking is enabled
-                ctx.output
-                    .dimmed("Use --skip-policy to bypass policy validation");
-                return Ok(());
-            }
-        }
-
-        // Run apply
-        ctx.output.subsection("Running Apply");
-        ctx.output
-            .dimmed(&format!("Executing {} apply...", executor.get_name()));
-        executor.apply(&execution_config, env_dir_str, extra_args)?;
-
-        // Run post-apply hooks
-        if !hooks.post_apply.is_empty()
-            && HooksRunner::run_hooks(&hooks.post_apply, env_dir_str, "post-apply")?
-                == HookOutcome::Cancel
-        {
-            ctx.output.blank();
-            ctx.output
-                .warning("Post-apply hooks cancelled further execution");
-            return Ok(());
-        }
-
-        ctx.output.blank();
-        ctx.output.success("Apply completed successfully");
-
-        Ok(())
+        ExecutionHelper::execute_apply_on_node(
+            ctx,
+            node,
+            executor.as_ref(),
+            &execution_config,
+            extra_args,
+        )
     }
 
     /// Check cost estimation before apply and block if threshold exceeded
@@ -516,14 +678,6 @@ impl ApplyCommand {
         Ok((env_path, selected_project.name.clone(), env_name))
     }
 
-    /// Get the appropriate executor based on name
-    fn get_executor(name: &str) -> Result<Box<dyn Executor>> {
-        match name {
-            "opentofu" => Ok(Box::new(OpenTofuExecutor::new())),
-            _ => anyhow::bail!("Unknown executor: {}", name),
-        }
-    }
-
     /// Build parallel config from CLI flag or infrastructure config
     fn build_parallel_config(
         cli_parallel: Option<usize>,
@@ -565,7 +719,7 @@ impl ApplyCommand {
         let executor_config = resource.get_executor_config();
 
         // Get executor
-        let executor = ExecutionHelper::get_executor(&executor_config.name)?;
+        let executor = ctx.executor_registry.get(&executor_config.name)?;
 
         // Build executor config
         let mut command_options = std::collections::HashMap::new();
@@ -583,6 +737,7 @@ impl ApplyCommand {
             refresh_command: None,
             test_command: None,
             command_options,
+            plan_file: None,
         };
 
         // Execute apply on this node
@@ -595,3 +750,95 @@ impl ApplyCommand {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::DefaultExecutorRegistry;
+    use crate::executor::external::register_external_executors;
+    use crate::template::metadata::ExternalExecutorConfig;
+    use crate::test_helpers::{create_opentofu_infrastructure, create_opentofu_template_pack};
+    use crate::traits::{
+        FileSystem, MockCommandExecutor, MockFileSystem, MockOutput, MockUserInput,
+    };
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn test_failed_apply_without_dependencies_runs_failure_hooks() {
+        let root = tempfile::TempDir::new().unwrap();
+        let fs = Arc::new(MockFileSystem::new());
+        fs.set_current_dir(root.path().to_path_buf());
+        create_opentofu_template_pack(&fs);
+        create_opentofu_infrastructure(&fs, &["dev"]);
+
+        // The executor runs in the environment directory, so it must exist on disk
+        let env_dir = root.path().join("projects/vpc/environments/dev");
+        std::fs::create_dir_all(&env_dir).unwrap();
+        fs.write(
+            &env_dir.join(".pmp.environment.yaml"),
+            r#"apiVersion: pmp.io/v1
+kind: Network
+metadata:
+  name: vpc
+  environment_name: dev
+spec:
+  resource:
+    apiVersion: pmp.io/v1
+    kind: Network
+  executor:
+    name: failing
+  inputs: {}
+  hooks:
+    on_failure_apply:
+      - type: command
+        config:
+          command: "./page.sh"
+    finally_apply:
+      - type: command
+        config:
+          command: "./cleanup.sh"
+"#,
+        )
+        .unwrap();
+
+        // Succeeds for every operation but apply
+        let registry = DefaultExecutorRegistry::new();
+        let errors = register_external_executors(
+            &registry,
+            &[ExternalExecutorConfig {
+                name: "failing".to_string(),
+                command: "sh".to_string(),
+                args: vec![
+                    "-c".to_string(),
+                    "cat > /dev/null; [ \"$1\" != apply ]".to_string(),
+                    "executor".to_string(),
+                ],
+                env: Default::default(),
+            }],
+        );
+        assert!(errors.is_empty());
+
+        let output = Arc::new(MockOutput::new());
+        let ctx = crate::context::Context::test_with(
+            fs,
+            Arc::new(MockUserInput::new()),
+            output.clone(),
+            Arc::new(MockCommandExecutor::new()),
+            Arc::new(registry),
+        );
+
+        let result = ApplyCommand::execute(
+            &ctx,
+            Some(env_dir.to_str().unwrap()),
+            false,
+            true,
+            None,
+            &[],
+        );
+
+        assert!(result.unwrap_err().to_string().contains("apply failed"));
+        let text = output.to_text();
+        assert!(text.contains("Running on-failure-apply hooks..."));
+        assert!(text.contains("Running finally-apply hooks..."));
+    }
+}
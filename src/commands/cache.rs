@@ -0,0 +1,64 @@
+use crate::collection::{CollectionDiscovery, ProjectIndex};
+use crate::context::Context;
+use crate::output;
+use crate::state::SnapshotStore;
+use anyhow::{Context as AnyhowContext, Result};
+
+/// Manage the on-disk caches of an infrastructure (`.pmp/cache`)
+pub struct CacheCommand;
+
+impl CacheCommand {
+    /// Execute the cache rebuild command
+    pub fn execute_rebuild(ctx: &Context) -> Result<()> {
+        ctx.output.section("Rebuild Project Index");
+
+        let (_, infrastructure_root) = CollectionDiscovery::find_collection(&*ctx.fs)?
+            .context("Infrastructure is required. Run 'pmp init' first.")?;
+
+        let (index, stats) = ProjectIndex::rebuild(&*ctx.fs, &infrastructure_root)?;
+
+        for warning in &index.warnings {
+            ctx.output.warning(warning);
+        }
+
+        ctx.output
+            .key_value("Projects", &index.projects().count().to_string());
+        ctx.output
+            .key_value("Environments", &index.environments().count().to_string());
+        ctx.output
+            .key_value("Files parsed", &stats.parsed.to_string());
+        output::blank();
+        ctx.output.success(&format!(
+            "Index written to {}",
+            ProjectIndex::index_path(&infrastructure_root).display()
+        ));
+
+        Ok(())
+    }
+
+    /// Execute the cache clear command
+    ///
+    /// Removes the project index; with `all`, state snapshots used by search are removed too.
+    pub fn execute_clear(ctx: &Context, all: bool) -> Result<()> {
+        ctx.output.section("Clear Cache");
+
+        let (_, infrastructure_root) = CollectionDiscovery::find_collection(&*ctx.fs)?
+            .context("Infrastructure is required. Run 'pmp init' first.")?;
+
+        if ProjectIndex::clear(&*ctx.fs, &infrastructure_root)? {
+            ctx.output.success("Project index removed");
+        } else {
+            ctx.output.dimmed("No project index found.");
+        }
+
+        if all {
+            if SnapshotStore::for_infrastructure(&*ctx.fs, &infrastructure_root).clear()? {
+                ctx.output.success("State snapshots removed");
+            } else {
+                ctx.output.dimmed("No state snapshots found.");
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,11 +1,10 @@
-use crate::collection::{CollectionDiscovery, DependencyGraph};
+use crate::collection::{CollectionDiscovery, ProjectIndex};
 use crate::context::Context;
 use crate::output;
-use crate::template::DynamicProjectEnvironmentResource;
 use anyhow::{Context as _, Result};
 use serde::Serialize;
-use std::collections::{HashMap, HashSet};
-use std::path::{Path, PathBuf};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
 use std::process::Command;
 
 #[derive(Debug, Serialize, Clone)]
@@ -61,36 +60,21 @@ impl CiDetectChangesCommand {
                 .context("Infrastructure is required. Run 'pmp infrastructure init' first.")?;
 
         // Discover all projects
-        let project_refs =
-            CollectionDiscovery::discover_projects(&*ctx.fs, &*ctx.output, &infrastructure_root)?;
+        let index = CollectionDiscovery::load_index(&*ctx.fs, &*ctx.output, &infrastructure_root)?;
 
         // Step 5: Build map of all project environments
         let mut project_envs: HashMap<(String, String), PathBuf> = HashMap::new();
 
-        for project_ref in &project_refs {
-            let project_path = infrastructure_root.join(&project_ref.path);
-            let environments_dir = project_path.join("environments");
-
-            if let Ok(env_entries) = ctx.fs.read_dir(&environments_dir) {
-                for env_path in env_entries {
-                    let env_file = env_path.join(".pmp.environment.yaml");
-                    if ctx.fs.exists(&env_file)
-                        && let Ok(resource) =
-                            DynamicProjectEnvironmentResource::from_file(&*ctx.fs, &env_file)
-                    {
-                        let key = (
-                            resource.metadata.name.clone(),
-                            resource.metadata.environment_name.clone(),
-                        );
-                        project_envs.insert(key, env_path);
-                    }
-                }
-            }
+        for (_project, environment) in index.environments() {
+            let key = (
+                environment.resource.metadata.name.clone(),
+                environment.resource.metadata.environment_name.clone(),
+            );
+            project_envs.insert(key, infrastructure_root.join(&environment.path));
         }
 
         // Step 6: Include all dependent projects
-        let affected_projects =
-            Self::include_dependents(&changed_projects, &project_envs, ctx, &infrastructure_root)?;
+        let affected_projects = Self::include_dependents(&changed_projects, &project_envs, &index);
 
         // Step 6: Format and output results
         Self::output_results(&affected_projects, output_format)?;
@@ -175,44 +159,37 @@ impl CiDetectChangesCommand {
     }
 
     /// Include all projects that depend on the changed projects
+    /// Dependencies are read from the project index and followed transitively
     fn include_dependents(
         changed_projects: &HashSet<(String, String)>,
         project_envs: &HashMap<(String, String), PathBuf>,
-        ctx: &Context,
-        infrastructure_root: &Path,
-    ) -> Result<Vec<ChangedProject>> {
-        let mut affected = HashSet::new();
-
-        // Add initially changed projects
-        for (name, env) in changed_projects {
-            affected.insert((name.clone(), env.clone()));
+        index: &ProjectIndex,
+    ) -> Vec<ChangedProject> {
+        // Reverse dependency edges: dependency -> projects depending on it
+        let mut dependents: HashMap<(String, String), Vec<(String, String)>> = HashMap::new();
+
+        for (_project, environment) in index.environments() {
+            let metadata = &environment.resource.metadata;
+            let key = (metadata.name.clone(), metadata.environment_name.clone());
+
+            for dep in &environment.resource.spec.dependencies {
+                for dep_env in &dep.project.environments {
+                    dependents
+                        .entry((dep.project.name.clone(), dep_env.clone()))
+                        .or_default()
+                        .push(key.clone());
+                }
+            }
         }
 
-        // For each changed project, find all projects that depend on it
-        // We need to check ALL projects and build their dependency graphs
-        for project_key in project_envs.keys() {
-            let (proj_name, proj_env) = project_key;
-
-            // Try to build dependency graph for this project
-            if let Ok(dep_graph) =
-                DependencyGraph::build(&*ctx.fs, infrastructure_root, proj_name, proj_env)
-            {
-                // Check if this project depends on any of the changed projects
-                for (changed_name, changed_env) in changed_projects {
-                    // Check if this project's dependency graph includes the changed project
-                    if let Ok(execution_order) = dep_graph.execution_order() {
-                        for node in &execution_order {
-                            if node.project_name == *changed_name
-                                && node.environment_name == *changed_env
-                            {
-                                // This project depends on a changed project, so include it
-                                affected.insert((proj_name.clone(), proj_env.clone()));
-                                break;
-                            }
-                        }
-                    }
-                }
+        let mut affected = HashSet::new();
+        let mut queue: VecDeque<(String, String)> = changed_projects.iter().cloned().collect();
+
+        while let Some(key) = queue.pop_front() {
+            if let Some(deps) = dependents.get(&key) {
+                queue.extend(deps.iter().filter(|d| !affected.contains(*d)).cloned());
             }
+            affected.insert(key);
         }
 
         // Build result list with full paths
@@ -235,7 +212,7 @@ impl CiDetectChangesCommand {
                 .then_with(|| a.environment.cmp(&b.environment))
         });
 
-        Ok(result)
+        result
     }
 
     /// Output results in the specified format
@@ -266,6 +243,8 @@ impl CiDetectChangesCommand {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::traits::{FileSystem, MockFileSystem};
+    use std::path::Path;
 
     #[test]
     fn test_extract_projects_from_valid_paths() {
@@ -317,4 +296,62 @@ mod tests {
         let result = CiDetectChangesCommand::extract_projects_from_paths(&paths, None).unwrap();
         assert!(result.is_empty());
     }
+
+    fn write_project(fs: &MockFileSystem, name: &str, depends_on: &[&str]) {
+        let project_dir = PathBuf::from("/infra/projects").join(name);
+        fs.write(
+            &project_dir.join(".pmp.project.yaml"),
+            &format!(
+                "apiVersion: pmp.io/v1\nkind: Project\nmetadata:\n  name: {}\n",
+                name
+            ),
+        )
+        .unwrap();
+
+        let dependencies: String = depends_on
+            .iter()
+            .map(|dep| {
+                format!(
+                    "  - project:\n      name: {}\n      environments: [dev]\n",
+                    dep
+                )
+            })
+            .collect();
+        fs.write(
+            &project_dir.join("environments/dev/.pmp.environment.yaml"),
+            &format!(
+                "apiVersion: pmp.io/v1\nkind: Workload\nmetadata:\n  name: {}\n  environment_name: dev\nspec:\n  resource:\n    apiVersion: pmp.io/v1\n    kind: Workload\n  executor:\n    name: opentofu\n  inputs: {{}}\n  dependencies:\n{}",
+                name, dependencies
+            ),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_include_dependents_follows_dependencies_transitively() {
+        let fs = MockFileSystem::new();
+        write_project(&fs, "vpc", &[]);
+        write_project(&fs, "db", &["vpc"]);
+        write_project(&fs, "api", &["db"]);
+        write_project(&fs, "docs", &[]);
+
+        let root = Path::new("/infra");
+        let index = ProjectIndex::load(&fs, root).unwrap();
+        let project_envs: HashMap<(String, String), PathBuf> = index
+            .environments()
+            .map(|(project, env)| {
+                (
+                    (project.name.clone(), "dev".to_string()),
+                    root.join(&env.path),
+                )
+            })
+            .collect();
+
+        let changed = HashSet::from([("vpc".to_string(), "dev".to_string())]);
+        let affected = CiDetectChangesCommand::include_dependents(&changed, &project_envs, &index);
+
+        let names: Vec<_> = affected.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["api", "db", "vpc"]);
+        assert_eq!(affected[2].path, "/infra/projects/vpc/environments/dev");
+    }
 }
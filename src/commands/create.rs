@@ -7,7 +7,8 @@ use crate::template::metadata::{
 };
 use crate::template::utils::interpolate_all;
 use crate::template::{
-    TemplateDiscovery, TemplateInfo, TemplatePackInfo, TemplateRenderer, TemplateResolver,
+    RenderBaseline, TemplateDiscovery, TemplateInfo, TemplatePackInfo, TemplateRenderer,
+    TemplateResolver,
 };
 use anyhow::{Context, Result};
 use serde_json::Value;
@@ -449,7 +450,7 @@ impl CreateCommand {
                 plugin_info.plugin_name.as_str(),
             ));
 
-            let _generated_files = renderer
+            let generated_files = renderer
                 .render_template(
                     ctx,
                     &plugin_info.plugin_path,
@@ -458,6 +459,7 @@ impl CreateCommand {
                     plugin_context,
                 )
                 .context("Failed to render plugin files")?;
+            RenderBaseline::record(&*ctx.fs, environment_path, &module_path, &generated_files)?;
 
             // Build AddedPlugin struct
             let plugin_project_ref = PluginProjectReference {
@@ -548,6 +550,16 @@ impl CreateCommand {
                 continue;
             }
 
+            // Computed inputs are derived from the inputs collected so far
+            if crate::template::computed::apply_computed_input(
+                input_def,
+                &mut inputs,
+                project_name,
+                environment_name,
+            )? {
+                continue;
+            }
+
             if let Some(default) = &input_def.default {
                 // Build variables map for interpolation
                 let vars =
@@ -614,6 +626,16 @@ impl CreateCommand {
                 continue;
             }
 
+            // Computed inputs are derived from the inputs collected so far
+            if crate::template::computed::apply_computed_input(
+                input_def,
+                &mut inputs,
+                project_name,
+                Some(environment_name),
+            )? {
+                continue;
+            }
+
             if let Some(config) = preconfig_inputs.get(&input_def.name) {
                 if let Some(value) = &config.value {
                     inputs.insert(input_def.name.clone(), value.clone());
@@ -694,6 +716,16 @@ impl CreateCommand {
                 continue;
             }
 
+            // Computed inputs are derived from the inputs collected so far
+            if crate::template::computed::apply_computed_input(
+                input_def,
+                &mut inputs,
+                project_name,
+                environment_name,
+            )? {
+                continue;
+            }
+
             // Check if input should be shown based on conditions
             if !input_def.should_show(&inputs) {
                 // Conditions not met, use default value if available
@@ -1839,6 +1871,14 @@ impl CreateCommand {
                         predefined_inputs.as_ref(),
                     )
                     .context("Failed to collect inputs")?;
+
+                    // Check rules relating several inputs
+                    crate::template::computed::check_validations(
+                        &selected_template.resource.spec.validations,
+                        &inputs,
+                        &project_name,
+                        Some(&selected_environment),
+                    )?;
                 }
                 InputCollectionItem::Plugin { config, .. } => {
                     // Collect plugin inputs
@@ -1964,7 +2004,7 @@ impl CreateCommand {
 
         for base_path in &resolved_base_paths {
             if ctx.fs.exists(base_path) {
-                let _generated_files = renderer
+                let generated_files = renderer
                     .render_template(
                         ctx,
                         base_path,
@@ -1973,21 +2013,25 @@ impl CreateCommand {
                         None,
                     )
                     .context("Failed to render template")?;
+
+                // Record the rendered content so updates can merge it with local edits
+                RenderBaseline::record(
+                    &*ctx.fs,
+                    &environment_path,
+                    &environment_path,
+                    &generated_files,
+                )?;
             }
         }
 
         // Step 15.5: Generate common file (e.g., _common.tf) if executor config is present
-        // The executor itself decides whether to generate anything (only opentofu does)
+        // The executor itself decides whether to generate anything (only HCL executors do)
         let template_executor_name = selected_template.resource.spec.executor.name();
         if let Some(executor_config) = &infrastructure.spec.executor
             && !executor_config.config.is_empty()
         {
-            // Create executor instance based on template's executor
-            let executor: Box<dyn crate::executor::Executor> = match template_executor_name {
-                "opentofu" => Box::new(crate::executor::OpenTofuExecutor::new()),
-                "none" => Box::new(crate::executor::NoneExecutor::new()),
-                _ => anyhow::bail!("Unknown executor: {}", template_executor_name),
-            };
+            // Resolve executor instance based on template's executor
+            let executor = ctx.executor_registry.get(template_executor_name)?;
 
             let metadata = crate::executor::ProjectMetadata {
                 api_version: &selected_template.resource.spec.api_version,
@@ -2125,6 +2169,16 @@ impl CreateCommand {
 
         // Collect each input defined in the template
         for input_def in inputs_spec {
+            // Computed inputs are derived from the inputs collected so far
+            if crate::template::computed::apply_computed_input(
+                input_def,
+                &mut inputs,
+                project_name,
+                environment_name,
+            )? {
+                continue;
+            }
+
             // Check if there's a predefined value for this input
             if let Some(predefined) = predefined_inputs.and_then(|p| p.get(&input_def.name)) {
                 // Use the predefined value directly (with variable interpolation)
@@ -2263,6 +2317,29 @@ impl CreateCommand {
 
         // Check for explicit input type
         if let Some(ref input_type) = input_spec.input_type {
+            // Resolve dynamic select options, falling back to free text when there are none
+            if input_type.options_from().is_some() {
+                return match crate::template::options::prompt_options(
+                    ctx,
+                    input_type,
+                    &vars,
+                    environment_name,
+                ) {
+                    Some(options) => Self::prompt_for_typed_input(
+                        ctx,
+                        &description,
+                        &input_type.with_options(options),
+                        effective_default.as_ref(),
+                    ),
+                    None => Self::prompt_for_free_text_select(
+                        ctx,
+                        &description,
+                        input_type,
+                        effective_default.as_ref(),
+                    ),
+                };
+            }
+
             return Self::prompt_for_typed_input(
                 ctx,
                 &description,
@@ -2360,6 +2437,50 @@ impl CreateCommand {
         }
     }
 
+    /// Prompt for a select or multiselect input without options as free text
+    ///
+    /// Multiselect values are entered comma-separated.
+    fn prompt_for_free_text_select(
+        ctx: &crate::context::Context,
+        description: &str,
+        input_type: &InputType,
+        default: Option<&Value>,
+    ) -> Result<Value> {
+        if let InputType::MultiSelect { .. } = input_type {
+            let default_str = default.and_then(|v| v.as_array()).map(|values| {
+                values
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            });
+            let answer = ctx
+                .input
+                .text(
+                    &format!("{} (comma-separated)", description),
+                    default_str.as_deref(),
+                )
+                .context("Failed to get input")?;
+
+            return Ok(Value::Array(
+                answer
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(|s| Value::String(s.to_string()))
+                    .collect(),
+            ));
+        }
+
+        // Don't pass empty string as default to avoid "()" display
+        let default_str = default.and_then(|v| v.as_str()).filter(|s| !s.is_empty());
+        let answer = ctx
+            .input
+            .text(description, default_str)
+            .context("Failed to get input")?;
+        Ok(Value::String(answer))
+    }
+
     /// Prompt for a typed input based on InputType
     fn prompt_for_typed_input(
         ctx: &crate::context::Context,
@@ -2462,7 +2583,7 @@ impl CreateCommand {
                     }
                 }
             }
-            InputType::Select { options } => {
+            InputType::Select { options, .. } => {
                 // Build list of display labels
                 let labels: Vec<String> = options.iter().map(|opt| opt.label.clone()).collect();
 
@@ -2488,7 +2609,9 @@ impl CreateCommand {
 
                 Ok(Value::String(selected_option.value.clone()))
             }
-            InputType::MultiSelect { options, min, max } => {
+            InputType::MultiSelect {
+                options, min, max, ..
+            } => {
                 // Build list of display labels
                 let labels: Vec<String> = options.iter().map(|opt| opt.label.clone()).collect();
 
@@ -2695,6 +2818,9 @@ impl CreateCommand {
                 *allow_prerelease,
                 *allow_build,
             ),
+            InputType::Computed { .. } => {
+                anyhow::bail!("Computed inputs are derived from other inputs, not prompted")
+            }
         }
     }
 
@@ -4370,6 +4496,16 @@ impl CreateCommand {
 
         // Fill in defaults for any missing inputs
         for input_def in &template.resource.spec.inputs {
+            // Computed inputs are derived from the inputs collected so far
+            if crate::template::computed::apply_computed_input(
+                input_def,
+                &mut final_inputs,
+                project_name,
+                Some(environment_name),
+            )? {
+                continue;
+            }
+
             if !final_inputs.contains_key(&input_def.name) && use_all_defaults {
                 // Use default value if available
                 if let Some(default) = &input_def.default {
@@ -4378,6 +4514,14 @@ impl CreateCommand {
             }
         }
 
+        // Check rules relating several inputs
+        crate::template::computed::check_validations(
+            &template.resource.spec.validations,
+            &final_inputs,
+            project_name,
+            Some(environment_name),
+        )?;
+
         // Step 5.5: Process installed plugins from template spec
         let mut collected_plugins = Vec::new();
 
@@ -4454,7 +4598,7 @@ impl CreateCommand {
 
         for base_path in &resolved_base_paths {
             if ctx.fs.exists(base_path) {
-                renderer
+                let generated_files = renderer
                     .render_template(
                         ctx,
                         base_path,
@@ -4463,18 +4607,21 @@ impl CreateCommand {
                         None,
                     )
                     .context("Failed to render template")?;
+                RenderBaseline::record(
+                    &*ctx.fs,
+                    &environment_path,
+                    &environment_path,
+                    &generated_files,
+                )?;
             }
         }
 
-        // Step 11: Generate _common.tf if needed (only opentofu executor does this)
+        // Step 11: Generate _common.tf if needed (only HCL executors do this)
         let template_executor_name = template.resource.spec.executor.name();
         if let Some(executor_config) = &infrastructure.spec.executor
             && !executor_config.config.is_empty()
         {
-            let executor: Box<dyn crate::executor::Executor> = match template_executor_name {
-                "opentofu" => Box::new(crate::executor::OpenTofuExecutor::new()),
-                _ => Box::new(crate::executor::NoneExecutor::new()),
-            };
+            let executor = ctx.executor_registry.get(template_executor_name)?;
 
             let metadata = crate::executor::ProjectMetadata {
                 api_version: &template.resource.spec.api_version,
@@ -6061,6 +6208,79 @@ spec:
         );
     }
 
+    #[test]
+    fn test_input_type_select_with_options_from() {
+        let fs = Arc::new(MockFileSystem::new());
+
+        setup_template_pack(
+            &fs,
+            "test-pack",
+            "test-template",
+            "TestResource",
+            r#"    region:
+      type: select
+      options_from:
+        source: file
+        path: regions.yaml
+      description: "Region"
+    zones:
+      type: multiselect
+      options_from:
+        source: file
+        path: "zones/${var:region}.yaml"
+      description: "Zones""#,
+        );
+
+        setup_infrastructure(
+            &fs,
+            r#"    - apiVersion: pmp.io/v1
+      kind: TestResource"#,
+        );
+
+        let current_dir = std::env::current_dir().unwrap();
+        fs.write(
+            &current_dir.join("regions.yaml"),
+            "Ireland: eu-west-1\nVirginia: us-east-1\n",
+        )
+        .unwrap();
+
+        let input = MockUserInput::new();
+        input.add_response(MockResponse::Select(
+            "📁 TestResource (pmp.io/v1) - Test resource type".to_string(),
+        )); // category selection
+        input.add_response(MockResponse::Select(
+            "📄 test-template - Test template".to_string(),
+        )); // template selection
+        input.add_response(MockResponse::Text("test-project".to_string())); // project name
+        input.add_response(MockResponse::Select("Virginia".to_string())); // region (by label)
+        input.add_response(MockResponse::Text("a, b".to_string())); // zones (no zones file)
+        input.add_response(MockResponse::Confirm(false)); // apply after create
+
+        let ctx = create_test_context(Arc::clone(&fs), input);
+
+        let result = CreateCommand::execute(
+            &ctx, None, // output_path
+            None, // template_packs_paths
+            None, // inputs_str
+            None, // template_spec
+            false, None, None,
+        );
+
+        assert!(
+            result.is_ok(),
+            "Create command should succeed: {:?}",
+            result
+        );
+
+        let env_content = fs
+            .get_file_contents(
+                &current_dir.join("projects/test-project/environments/dev/.pmp.environment.yaml"),
+            )
+            .unwrap();
+        assert!(env_content.contains("region: us-east-1"));
+        assert!(env_content.contains("- a\n") && env_content.contains("- b\n"));
+    }
+
     #[test]
     fn test_input_type_number_with_constraints() {
         // Set up mock filesystem
@@ -6405,6 +6625,79 @@ spec:
         );
     }
 
+    #[test]
+    fn test_computed_inputs_and_validations_with_predefined_inputs() {
+        let fs = Arc::new(MockFileSystem::new());
+
+        setup_template_pack(
+            &fs,
+            "test-pack",
+            "test-template",
+            "TestResource",
+            r#"    - name: min_replicas
+      default: 1
+    - name: max_replicas
+      default: 3
+    - name: release
+      type: computed
+      value: "{{_project_name}}-{{_environment}}"
+  validations:
+    - rule: "{{lte min_replicas max_replicas}}"
+      message: "min_replicas must not exceed max_replicas""#,
+        );
+
+        setup_infrastructure(
+            &fs,
+            r#"    - apiVersion: pmp.io/v1
+      kind: TestResource"#,
+        );
+
+        let create = |inputs: &str, project_name: &str| {
+            let input = MockUserInput::new();
+            input.add_response(MockResponse::Confirm(false)); // apply after create
+            let ctx = create_test_context(Arc::clone(&fs), input);
+
+            CreateCommand::execute(
+                &ctx,
+                None,
+                None,
+                Some(inputs),
+                Some("test-pack/test-template"),
+                false,
+                Some(project_name),
+                Some("dev"),
+            )
+        };
+
+        let err = create(
+            r#"{"min_replicas": 5, "max_replicas": 4}"#,
+            "invalid-project",
+        )
+        .unwrap_err();
+        assert!(
+            format!("{:#}", err).contains("min_replicas must not exceed max_replicas"),
+            "{:#}",
+            err
+        );
+
+        let result = create(r#"{"min_replicas": 2, "max_replicas": 4}"#, "valid-project");
+        assert!(
+            result.is_ok(),
+            "Create command should succeed: {:?}",
+            result
+        );
+
+        let env_yaml_path = std::env::current_dir()
+            .unwrap()
+            .join("projects/valid-project/environments/dev/.pmp.environment.yaml");
+        let env_content = fs.get_file_contents(&env_yaml_path).unwrap();
+        assert!(
+            env_content.contains("release: valid-project-dev"),
+            "{}",
+            env_content
+        );
+    }
+
     #[test]
     fn test_helper_module_creates_files() {
         use crate::test_helpers::{create_comprehensive_template_pack, create_test_infrastructure};
@@ -6865,6 +7158,7 @@ spec:
             Some(env_path_str.to_str().unwrap()), // absolute path to environment
             None,                                 // template_packs_paths
             None,                                 // inputs_str
+            crate::template::MergeStrategy::default(),
         );
 
         assert!(
@@ -6946,6 +7240,74 @@ spec:
         );
     }
 
+    #[test]
+    fn test_update_project_preserves_local_edits() {
+        use crate::commands::update::UpdateCommand;
+        use crate::test_helpers::{create_opentofu_infrastructure, create_opentofu_template_pack};
+
+        let fs = Arc::new(MockFileSystem::new());
+        create_opentofu_template_pack(&fs);
+        create_opentofu_infrastructure(&fs, &["dev", "staging", "prod"]);
+
+        let input_create = MockUserInput::new();
+        input_create.add_response(MockResponse::Select(
+            "📁 Web Applications - Web application templates".to_string(),
+        ));
+        input_create.add_response(MockResponse::Select(
+            "📄 webapp - Web application with monitoring".to_string(),
+        ));
+        input_create.add_response(MockResponse::Select("Dev - Dev environment".to_string()));
+        input_create.add_response(MockResponse::Text("my-webapp".to_string()));
+        input_create.add_response(MockResponse::Confirm(false)); // use plugin defaults
+        input_create.add_response(MockResponse::Text("awesome-app".to_string())); // app_name
+        input_create.add_response(MockResponse::Text("3000".to_string())); // port
+        input_create.add_response(MockResponse::Select("Yes".to_string())); // enable_tls
+        input_create.add_response(MockResponse::Select("Production".to_string())); // environment_type
+        input_create.add_response(MockResponse::Confirm(false)); // apply
+
+        let ctx_create = create_test_context(Arc::clone(&fs), input_create);
+        CreateCommand::execute(&ctx_create, None, None, None, None, false, None, None).unwrap();
+
+        let env_path = fs
+            .current_dir()
+            .unwrap()
+            .join("projects/my-webapp/environments/dev");
+        assert!(fs.has_file(&env_path.join(".pmp/rendered.json")));
+
+        // Hand edit a generated file
+        let main_tf_path = env_path.join("main.tf");
+        let edited = fs
+            .get_file_contents(&main_tf_path)
+            .unwrap()
+            .replace("replicas = 2", "replicas = 4 # tuned by hand");
+        fs.write(&main_tf_path, &edited).unwrap();
+
+        let input_update = MockUserInput::new();
+        input_update.add_response(MockResponse::Select("Update the project".to_string()));
+        input_update.add_response(MockResponse::Text("awesome-app".to_string())); // app_name
+        input_update.add_response(MockResponse::Text("8080".to_string())); // port
+        input_update.add_response(MockResponse::Confirm(true)); // enable_tls
+        input_update.add_response(MockResponse::Select("Production".to_string())); // environment_type
+        input_update.add_response(MockResponse::Confirm(true)); // regenerate
+        input_update.add_response(MockResponse::Confirm(false)); // apply
+
+        let ctx_update = create_test_context(Arc::clone(&fs), input_update);
+        UpdateCommand::execute(
+            &ctx_update,
+            Some(env_path.to_str().unwrap()),
+            None,
+            None,
+            crate::template::MergeStrategy::Merge,
+        )
+        .unwrap();
+
+        // Both the template change and the hand edit are kept
+        let main_tf_content = fs.get_file_contents(&main_tf_path).unwrap();
+        assert!(main_tf_content.contains("# Port: 8080"));
+        assert!(main_tf_content.contains("replicas = 4 # tuned by hand"));
+        assert!(!main_tf_content.contains("<<<<<<<"));
+    }
+
     #[test]
     fn test_update_project_add_new_plugin() {
         use crate::commands::update::UpdateCommand;
@@ -7008,6 +7370,7 @@ spec:
             Some(env_path_str.to_str().unwrap()), // absolute path to environment
             None,                                 // template_packs_paths
             None,                                 // inputs_str
+            crate::template::MergeStrategy::default(),
         );
 
         assert!(
@@ -7112,6 +7475,7 @@ spec:
             Some(env_path_str.to_str().unwrap()), // absolute path to environment
             None,                                 // template_packs_paths
             None,                                 // inputs_str
+            crate::template::MergeStrategy::default(),
         );
 
         assert!(
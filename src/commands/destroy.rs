@@ -1,7 +1,7 @@
 use crate::collection::{CollectionDiscovery, CollectionManager, DependencyNode};
 use crate::commands::project_group::ProjectGroupHandler;
 use crate::commands::ExecutionHelper;
-use crate::executor::{Executor, ExecutorConfig, OpenTofuExecutor};
+use crate::executor::ExecutorConfig;
 use crate::hooks::{HookOutcome, HooksRunner};
 use crate::template::metadata::{FailureBehavior, ParallelConfig};
 use crate::template::{DynamicProjectEnvironmentResource, ProjectResource};
@@ -119,35 +119,52 @@ impl DestroyCommand {
                 .to_str()
                 .context("Failed to convert environment path to string")?;
 
-            // Run pre-destroy hooks
-            if !hooks.pre_destroy.is_empty()
-                && HooksRunner::run_hooks(&hooks.pre_destroy, env_dir_str, "pre-destroy")?
-                    == HookOutcome::Cancel
-            {
-                ctx.output.blank();
-                ctx.output.warning("Destroy cancelled by pre-destroy hook");
-                return Ok(());
-            }
+            // Pre/post hook and destroy failures are handed to the on-failure and finally hooks
+            let result = (|| -> Result<HookOutcome> {
+                // Run pre-destroy hooks
+                if !hooks.pre_destroy.is_empty()
+                    && HooksRunner::run_hooks(ctx, &hooks.pre_destroy, env_dir_str, "pre-destroy")?
+                        == HookOutcome::Cancel
+                {
+                    ctx.output.blank();
+                    ctx.output.warning("Destroy cancelled by pre-destroy hook");
+                    return Ok(HookOutcome::Cancel);
+                }
 
-            // Execute destroy on all configured projects (in reverse order)
-            ProjectGroupHandler::execute_command_on_projects(
-                ctx, &resource, &env_name, "destroy", extra_args,
-            )?;
+                // Execute destroy on all configured projects (in reverse order)
+                ProjectGroupHandler::execute_command_on_projects(
+                    ctx, &resource, &env_name, "destroy", extra_args,
+                )?;
+
+                // Run post-destroy hooks
+                if !hooks.post_destroy.is_empty()
+                    && HooksRunner::run_hooks(
+                        ctx,
+                        &hooks.post_destroy,
+                        env_dir_str,
+                        "post-destroy",
+                    )? == HookOutcome::Cancel
+                {
+                    ctx.output.blank();
+                    ctx.output
+                        .warning("Post-destroy hooks cancelled further execution");
+                    return Ok(HookOutcome::Cancel);
+                }
 
-            // Run post-destroy hooks
-            if !hooks.post_destroy.is_empty()
-                && HooksRunner::run_hooks(&hooks.post_destroy, env_dir_str, "post-destroy")?
-                    == HookOutcome::Cancel
-            {
                 ctx.output.blank();
-                ctx.output
-                    .warning("Post-destroy hooks cancelled further execution");
-                return Ok(());
-            }
+                ctx.output.success("Infrastructure destroyed successfully");
 
-            ctx.output.blank();
-            ctx.output.success("Infrastructure destroyed successfully");
-            return Ok(());
+                Ok(HookOutcome::Continue)
+            })();
+
+            return ExecutionHelper::finish_with_hooks(
+                ctx,
+                &hooks.on_failure_destroy,
+                &hooks.finally_destroy,
+                env_dir_str,
+                "destroy",
+                result,
+            );
         }
 
         // Check for dependencies (non-ProjectGroup projects)
@@ -222,7 +239,7 @@ impl DestroyCommand {
         let executor_config = resource.get_executor_config();
 
         // Load collection to get infrastructure-level hooks
-        let (collection, _collection_root) = CollectionDiscovery::find_collection(&*ctx.fs)?
+        let (collection, collection_root) = CollectionDiscovery::find_collection(&*ctx.fs)?
             .context("Infrastructure is required to run commands")?;
 
         let infrastructure_hooks = collection.get_hooks();
@@ -234,7 +251,7 @@ impl DestroyCommand {
         );
 
         // Get executor
-        let executor = Self::get_executor(&executor_config.name)?;
+        let executor = ctx.executor_registry.get(&executor_config.name)?;
 
         // Check if executor is installed
         ctx.output.subsection("Prerequisites");
@@ -257,87 +274,110 @@ impl DestroyCommand {
             .to_str()
             .context("Failed to convert environment path to string")?;
 
-        // Run pre-destroy hooks
-        if !hooks.pre_destroy.is_empty()
-            && HooksRunner::run_hooks(&hooks.pre_destroy, env_dir_str, "pre-destroy")?
-                == HookOutcome::Cancel
-        {
-            ctx.output.blank();
-            ctx.output.warning("Destroy cancelled by pre-destroy hook");
-            return Ok(());
-        }
-
-        // Run helm repo update if configured
-        crate::commands::ExecutionHelper::run_helm_repo_update_if_needed(
-            ctx,
-            &collection,
-            executor.get_name(),
-        )?;
-
-        // Initialize executor
-        ctx.output.subsection("Initialization");
-        ctx.output
-            .dimmed(&format!("Initializing {}...", executor.get_name()));
-        let init_output = executor.init(env_dir_str)?;
-
-        if !init_output.status.success() {
-            // Display captured stdout and stderr before failing
-            if !init_output.stdout.is_empty()
-                && let Ok(stdout_str) = String::from_utf8(init_output.stdout.clone())
+        // Pre/post hook and destroy failures are handed to the on-failure and finally hooks
+        let result = (|| -> Result<HookOutcome> {
+            // Run pre-destroy hooks
+            if !hooks.pre_destroy.is_empty()
+                && HooksRunner::run_hooks(ctx, &hooks.pre_destroy, env_dir_str, "pre-destroy")?
+                    == HookOutcome::Cancel
             {
-                ctx.output.error(&stdout_str);
+                ctx.output.blank();
+                ctx.output.warning("Destroy cancelled by pre-destroy hook");
+                return Ok(HookOutcome::Cancel);
             }
-            if !init_output.stderr.is_empty()
-                && let Ok(stderr_str) = String::from_utf8(init_output.stderr.clone())
-            {
-                ctx.output.error(&stderr_str);
+
+            // Run helm repo update if configured
+            crate::commands::ExecutionHelper::run_helm_repo_update_if_needed(
+                ctx,
+                &collection,
+                executor.get_name(),
+            )?;
+
+            // Initialize executor
+            ctx.output.subsection("Initialization");
+            ctx.output
+                .dimmed(&format!("Initializing {}...", executor.get_name()));
+            let init_output = executor.init(env_dir_str)?;
+
+            if !init_output.status.success() {
+                // Display captured stdout and stderr before failing
+                if !init_output.stdout.is_empty()
+                    && let Ok(stdout_str) = String::from_utf8(init_output.stdout.clone())
+                {
+                    ctx.output.error(&stdout_str);
+                }
+                if !init_output.stderr.is_empty()
+                    && let Ok(stderr_str) = String::from_utf8(init_output.stderr.clone())
+                {
+                    ctx.output.error(&stderr_str);
+                }
+                anyhow::bail!(
+                    "Initialization failed with exit code: {:?}",
+                    init_output.status.code()
+                );
             }
-            anyhow::bail!(
-                "Initialization failed with exit code: {:?}",
-                init_output.status.code()
-            );
-        }
 
-        ctx.output.success("Initialization completed");
+            ctx.output.success("Initialization completed");
 
-        // Build executor config
-        let mut command_options = std::collections::HashMap::new();
-        if let Some(config) = &executor_config.config {
-            for (cmd_name, cmd_config) in &config.commands {
-                command_options.insert(cmd_name.clone(), cmd_config.options.clone());
+            // Build executor config
+            let mut command_options = std::collections::HashMap::new();
+            if let Some(config) = &executor_config.config {
+                for (cmd_name, cmd_config) in &config.commands {
+                    command_options.insert(cmd_name.clone(), cmd_config.options.clone());
+                }
             }
-        }
 
-        let execution_config = ExecutorConfig {
-            plan_command: None,
-            apply_command: None,
-            destroy_command: None,
-            refresh_command: None,
-            test_command: None,
-            command_options,
-        };
+            let execution_config = ExecutorConfig {
+                plan_command: None,
+                apply_command: None,
+                destroy_command: None,
+                refresh_command: None,
+                test_command: None,
+                command_options,
+                plan_file: None,
+            };
 
-        // Run destroy
-        ctx.output.subsection("Running Destroy");
-        ctx.output
-            .dimmed(&format!("Executing {} destroy...", executor.get_name()));
-        executor.destroy(&execution_config, env_dir_str, extra_args)?;
+            // Run destroy
+            ctx.output.subsection("Running Destroy");
+            ctx.output
+                .dimmed(&format!("Executing {} destroy...", executor.get_name()));
+            ExecutionHelper::with_state_guard(
+                ctx,
+                &collection,
+                &collection_root,
+                executor.as_ref(),
+                env_dir_str,
+                &project_name,
+                &env_name,
+                "destroy",
+                || executor.destroy(&execution_config, env_dir_str, extra_args),
+            )?;
+
+            // Run post-destroy hooks
+            if !hooks.post_destroy.is_empty()
+                && HooksRunner::run_hooks(ctx, &hooks.post_destroy, env_dir_str, "post-destroy")?
+                    == HookOutcome::Cancel
+            {
+                ctx.output.blank();
+                ctx.output
+                    .warning("Post-destroy hooks cancelled further execution");
+                return Ok(HookOutcome::Cancel);
+            }
 
-        // Run post-destroy hooks
-        if !hooks.post_destroy.is_empty()
-            && HooksRunner::run_hooks(&hooks.post_destroy, env_dir_str, "post-destroy")?
-                == HookOutcome::Cancel
-        {
             ctx.output.blank();
-            ctx.output
-                .warning("Post-destroy hooks cancelled further execution");
-            return Ok(());
-        }
+            ctx.output.success("Infrastructure destroyed successfully");
 
-        ctx.output.blank();
-        ctx.output.success("Infrastructure destroyed successfully");
+            Ok(HookOutcome::Continue)
+        })();
 
-        Ok(())
+        ExecutionHelper::finish_with_hooks(
+            ctx,
+            &hooks.on_failure_destroy,
+            &hooks.finally_destroy,
+            env_dir_str,
+            "destroy",
+            result,
+        )
     }
 
     /// Detect context and select project/environment
@@ -459,14 +499,6 @@ impl DestroyCommand {
         Ok((env_path, selected_project.name.clone(), env_name))
     }
 
-    /// Get the appropriate executor based on name
-    fn get_executor(name: &str) -> Result<Box<dyn Executor>> {
-        match name {
-            "opentofu" => Ok(Box::new(OpenTofuExecutor::new())),
-            _ => anyhow::bail!("Unknown executor: {}", name),
-        }
-    }
-
     /// Build parallel config from CLI flag or infrastructure config
     fn build_parallel_config(
         cli_parallel: Option<usize>,
@@ -508,7 +540,7 @@ impl DestroyCommand {
         let executor_config = resource.get_executor_config();
 
         // Get executor
-        let executor = ExecutionHelper::get_executor(&executor_config.name)?;
+        let executor = ctx.executor_registry.get(&executor_config.name)?;
 
         // Build executor config
         let mut command_options = std::collections::HashMap::new();
@@ -526,6 +558,7 @@ impl DestroyCommand {
             refresh_command: None,
             test_command: None,
             command_options,
+            plan_file: None,
         };
 
         // Execute destroy on this node
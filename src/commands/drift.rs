@@ -1,6 +1,8 @@
 use crate::collection::CollectionDiscovery;
+use crate::commands::ExecutionHelper;
 use crate::context::Context;
-use crate::executor::{Executor, ExecutorConfig, OpenTofuExecutor};
+use crate::diff::{AttributeChangeType, DiffChangeType, JsonPlanParser, ParsedPlan, PlanParser};
+use crate::executor::{DEFAULT_PLAN_FILE, ExecutorConfig};
 use crate::output;
 use crate::template::DynamicProjectEnvironmentResource;
 use anyhow::{Context as AnyhowContext, Result};
@@ -286,13 +288,7 @@ impl DriftCommand {
         resource: &DynamicProjectEnvironmentResource,
     ) -> Result<DriftReport> {
         // Execute terraform/opentofu refresh and plan to detect drift
-        let executor_name = &resource.spec.executor.name;
-
-        if executor_name != "opentofu" && executor_name != "terraform" {
-            anyhow::bail!("Drift detection only supports opentofu/terraform executors");
-        }
-
-        let executor = OpenTofuExecutor::new();
+        let executor = ctx.executor_registry.get(&resource.spec.executor.name)?;
         let env_path_str = env_path.to_str().context("Invalid path")?;
 
         // Run refresh to update state
@@ -310,10 +306,33 @@ impl DriftCommand {
         // Run plan to detect changes with output capture
         ctx.output.dimmed("Detecting changes...");
 
-        let plan_output = executor.plan_with_output(env_path_str, &[])?;
+        let saved_plan = executor.save_plan(env_path_str, DEFAULT_PLAN_FILE, &[]);
+
+        // The binary plan may hold sensitive values and is only needed to render the JSON
+        let plan_path = env_path.join(DEFAULT_PLAN_FILE);
+        if ctx.fs.exists(&plan_path) {
+            ctx.fs.remove_file(&plan_path)?;
+        }
+
+        let saved_plan = saved_plan?;
+        let exit_code = saved_plan.output.status.code();
 
-        // Parse plan output to extract changes
-        let changes = Self::parse_plan_output(&plan_output)?;
+        if !matches!(exit_code, Some(0) | Some(2)) {
+            let stderr = String::from_utf8_lossy(&saved_plan.output.stderr);
+            anyhow::bail!(
+                "Plan failed with exit code {:?}: {}",
+                exit_code,
+                stderr.trim()
+            );
+        }
+
+        // Prefer the JSON plan, falling back to the human-readable output
+        let plan = match &saved_plan.json {
+            Some(plan_json) => JsonPlanParser::new().parse(plan_json)?,
+            None => PlanParser::new().parse(&String::from_utf8_lossy(&saved_plan.output.stdout))?,
+        };
+
+        let changes = Self::changes_from_plan(&plan, exit_code);
 
         Ok(DriftReport {
             project: resource.metadata.name.clone(),
@@ -324,85 +343,60 @@ impl DriftCommand {
         })
     }
 
-    /// Parse Terraform/OpenTofu plan output to extract drift changes
-    fn parse_plan_output(output: &std::process::Output) -> Result<Vec<DriftChange>> {
-        let stdout = String::from_utf8_lossy(&output.stdout);
+    /// Convert a parsed plan into drift changes
+    ///
+    /// Each changed attribute becomes a drift entry; resources without attribute-level
+    /// details (e.g., data source reads) are reported as a whole.
+    fn changes_from_plan(plan: &ParsedPlan, exit_code: Option<i32>) -> Vec<DriftChange> {
         let mut changes = Vec::new();
 
-        // Parse the plan output
-        // Terraform/OpenTofu plan shows changes in this format:
-        // # resource_address will be created/updated/destroyed
-        // + attribute = "value"  (added)
-        // ~ attribute = "old" -> "new"  (modified)
-        // - attribute = "value"  (removed)
-
-        let lines: Vec<&str> = stdout.lines().collect();
-        let mut current_resource = String::new();
-
-        // Regex patterns for parsing
-        let resource_pattern = regex::Regex::new(
-            r"^#\s+(.+?)\s+(will be|must be)\s+(created|updated|destroyed|replaced)",
-        )
-        .unwrap();
-        let change_pattern = regex::Regex::new(r"^\s+([+~-])\s+(.+?)\s+=\s+(.+)$").unwrap();
-        let arrow_pattern = regex::Regex::new(r#""(.+?)"\s+->\s+"(.+?)""#).unwrap();
-
-        for line in lines {
-            // Check for resource declaration
-            if let Some(caps) = resource_pattern.captures(line) {
-                current_resource = caps.get(1).map(|m| m.as_str()).unwrap_or("").to_string();
-            }
-            // Check for attribute changes
-            else if let Some(caps) = change_pattern.captures(line) {
-                let symbol = caps.get(1).map(|m| m.as_str()).unwrap_or("");
-                let attribute = caps.get(2).map(|m| m.as_str()).unwrap_or("").trim();
-                let value_part = caps.get(3).map(|m| m.as_str()).unwrap_or("").trim();
-
-                let change_type = match symbol {
-                    "+" => ChangeType::Added,
-                    "-" => ChangeType::Removed,
-                    "~" => ChangeType::Modified,
-                    _ => continue,
+        for resource in &plan.resources {
+            let before_count = changes.len();
+
+            for attr in &resource.attributes {
+                let change_type = match attr.change_type {
+                    AttributeChangeType::Added => ChangeType::Added,
+                    AttributeChangeType::Removed => ChangeType::Removed,
+                    AttributeChangeType::Modified => ChangeType::Modified,
+                    AttributeChangeType::Unchanged => continue,
                 };
 
-                // For modifications, try to extract old -> new values
-                let (expected, actual) = if change_type == ChangeType::Modified {
-                    if let Some(arrow_caps) = arrow_pattern.captures(value_part) {
-                        let old = arrow_caps
-                            .get(1)
-                            .map(|m| m.as_str())
-                            .unwrap_or("")
-                            .to_string();
-                        let new = arrow_caps
-                            .get(2)
-                            .map(|m| m.as_str())
-                            .unwrap_or("")
-                            .to_string();
-                        (old, new)
+                let display = |value: &Option<String>, missing: &str| {
+                    if attr.sensitive {
+                        "(sensitive)".to_string()
                     } else {
-                        // Fallback if pattern doesn't match
-                        ("(unknown)".to_string(), value_part.to_string())
+                        value.clone().unwrap_or_else(|| missing.to_string())
                     }
-                } else if change_type == ChangeType::Added {
-                    ("(not set)".to_string(), value_part.to_string())
-                } else {
-                    (value_part.to_string(), "(removed)".to_string())
                 };
 
-                if !current_resource.is_empty() {
-                    changes.push(DriftChange {
-                        resource_address: current_resource.clone(),
-                        change_type,
-                        attribute: attribute.to_string(),
-                        expected,
-                        actual,
-                    });
-                }
+                changes.push(DriftChange {
+                    resource_address: resource.address.clone(),
+                    change_type,
+                    attribute: attr.name.clone(),
+                    expected: display(&attr.old_value, "(not set)"),
+                    actual: display(&attr.new_value, "(removed)"),
+                });
+            }
+
+            if changes.len() == before_count && resource.change_type != DiffChangeType::NoOp {
+                let change_type = match resource.change_type {
+                    DiffChangeType::Create => ChangeType::Added,
+                    DiffChangeType::Destroy => ChangeType::Removed,
+                    _ => ChangeType::Modified,
+                };
+
+                changes.push(DriftChange {
+                    resource_address: resource.address.clone(),
+                    change_type,
+                    attribute: "(resource)".to_string(),
+                    expected: "declared state".to_string(),
+                    actual: resource.change_type.label().to_string(),
+                });
             }
         }
 
         // If no detailed changes were found but exit code was 2, add a summary
-        if changes.is_empty() && output.status.code() == Some(2) {
+        if changes.is_empty() && exit_code == Some(2) {
             changes.push(DriftChange {
                 resource_address: "(multiple resources)".to_string(),
                 change_type: ChangeType::Modified,
@@ -412,7 +406,7 @@ impl DriftCommand {
             });
         }
 
-        Ok(changes)
+        changes
     }
 
     /// Display drift changes
@@ -483,9 +477,9 @@ impl DriftCommand {
     fn reconcile_drift(
         ctx: &Context,
         env_path: &Path,
-        _resource: &DynamicProjectEnvironmentResource,
+        resource: &DynamicProjectEnvironmentResource,
     ) -> Result<()> {
-        let executor = OpenTofuExecutor::new();
+        let executor = ctx.executor_registry.get(&resource.spec.executor.name)?;
         let env_path_str = env_path.to_str().context("Invalid path")?;
 
         ctx.output.info("Applying changes to reconcile drift...");
@@ -499,7 +493,20 @@ impl DriftCommand {
             ..Default::default()
         };
 
-        executor.apply(&config, env_path_str, &[])?;
+        let (infrastructure, infrastructure_root) = CollectionDiscovery::find_collection(&*ctx.fs)?
+            .context("Infrastructure is required to run commands")?;
+
+        ExecutionHelper::with_state_guard(
+            ctx,
+            &infrastructure,
+            &infrastructure_root,
+            executor.as_ref(),
+            env_path_str,
+            &resource.metadata.name,
+            &resource.metadata.environment_name,
+            "apply",
+            || executor.apply(&config, env_path_str, &[]),
+        )?;
 
         ctx.output
             .success("Drift reconciled successfully. Infrastructure now matches configuration.");
@@ -1,6 +1,6 @@
 use crate::collection::CollectionDiscovery;
 use crate::context::Context;
-use crate::executor::{Executor, ExecutorConfig, OpenTofuExecutor};
+use crate::executor::ExecutorConfig;
 use crate::hooks::{HookOutcome, HooksRunner};
 use crate::output;
 use crate::template::time_limit::{format_expiration_status, is_expired};
@@ -726,7 +726,7 @@ impl EnvCommand {
         }
 
         // Get executor
-        let executor = Self::get_executor(&executor_config.name)?;
+        let executor = ctx.executor_registry.get(&executor_config.name)?;
 
         let env_dir_str = env
             .environment_path
@@ -734,7 +734,7 @@ impl EnvCommand {
             .context("Failed to convert environment path to string")?;
 
         // Load collection to get infrastructure-level hooks
-        let (collection, _) = CollectionDiscovery::find_collection(&*ctx.fs)?
+        let (collection, collection_root) = CollectionDiscovery::find_collection(&*ctx.fs)?
             .context("Infrastructure is required to run commands")?;
 
         let infrastructure_hooks = collection.get_hooks();
@@ -747,7 +747,7 @@ impl EnvCommand {
 
         // Run pre-destroy hooks
         if !hooks.pre_destroy.is_empty()
-            && HooksRunner::run_hooks(&hooks.pre_destroy, env_dir_str, "pre-destroy")?
+            && HooksRunner::run_hooks(ctx, &hooks.pre_destroy, env_dir_str, "pre-destroy")?
                 == HookOutcome::Cancel
         {
             ctx.output.warning(&format!(
@@ -784,28 +784,31 @@ impl EnvCommand {
             refresh_command: None,
             test_command: None,
             command_options,
+            plan_file: None,
         };
 
         // Run destroy
         ctx.output.dimmed("  Running destroy...");
-        executor.destroy(&execution_config, env_dir_str, &[])?;
+        crate::commands::ExecutionHelper::with_state_guard(
+            ctx,
+            &collection,
+            &collection_root,
+            executor.as_ref(),
+            env_dir_str,
+            &env.project_name,
+            &env.environment_name,
+            "destroy",
+            || executor.destroy(&execution_config, env_dir_str, &[]),
+        )?;
 
         // Run post-destroy hooks
         if !hooks.post_destroy.is_empty() {
-            let _ = HooksRunner::run_hooks(&hooks.post_destroy, env_dir_str, "post-destroy");
+            let _ = HooksRunner::run_hooks(ctx, &hooks.post_destroy, env_dir_str, "post-destroy");
         }
 
         Ok(())
     }
 
-    /// Get executor by name
-    fn get_executor(name: &str) -> Result<Box<dyn Executor>> {
-        match name {
-            "opentofu" | "terraform" => Ok(Box::new(OpenTofuExecutor)),
-            _ => anyhow::bail!("Unknown executor: {}", name),
-        }
-    }
-
     /// Compare inputs between two environments
     fn compare_inputs(
         source: &HashMap<String, serde_json::Value>,
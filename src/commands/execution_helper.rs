@@ -2,8 +2,10 @@ use crate::collection::{DependencyGraph, DependencyNode};
 use crate::commands::parallel::{
     ContinueDecision, display_level_results, execute_level_parallel, should_continue_after_failures,
 };
-use crate::executor::{Executor, ExecutorConfig, NoneExecutor, OpenTofuExecutor};
+use crate::executor::{Executor, ExecutorConfig};
 use crate::hooks::{HookOutcome, HooksRunner};
+use crate::locking::{LockConfig, LockInfo, create_lock_store, current_owner, lock_key};
+use crate::state::{BackupConfig, BackupStore, SavedBackup, SnapshotStore, StateDocument};
 use crate::template::DynamicProjectEnvironmentResource;
 use crate::template::metadata::{InfrastructureResource, ParallelConfig};
 use anyhow::{Context, Result};
@@ -15,6 +17,196 @@ use std::sync::Arc;
 pub struct ExecutionHelper;
 
 impl ExecutionHelper {
+    /// Lock store configuration of an infrastructure (`spec.executor.config.lock`)
+    pub fn lock_config(infrastructure: &InfrastructureResource) -> Result<LockConfig> {
+        match &infrastructure.spec.executor {
+            Some(executor) => LockConfig::from_executor_config(&executor.config),
+            None => Ok(LockConfig::default()),
+        }
+    }
+
+    /// State backup configuration of an infrastructure (`spec.executor.config.backups`)
+    pub fn backup_config(infrastructure: &InfrastructureResource) -> Result<BackupConfig> {
+        match &infrastructure.spec.executor {
+            Some(executor) => BackupConfig::from_executor_config(&executor.config),
+            None => Ok(BackupConfig::default()),
+        }
+    }
+
+    /// Pull the current state through the executor and store it as a backup
+    ///
+    /// Returns None when there is no state yet. Retention rules are applied afterwards.
+    #[allow(clippy::too_many_arguments)]
+    pub fn backup_state(
+        ctx: &crate::context::Context,
+        infrastructure: &InfrastructureResource,
+        infrastructure_root: &Path,
+        executor: &dyn Executor,
+        working_dir: &str,
+        project_name: &str,
+        environment_name: &str,
+        reason: &str,
+    ) -> Result<Option<SavedBackup>> {
+        let config = Self::backup_config(infrastructure)?;
+        let content = executor.state_pull(working_dir)?;
+
+        if content.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let store = BackupStore::new(&*ctx.fs, config.backup_dir(infrastructure_root));
+        let saved = store.save(project_name, environment_name, &content, reason)?;
+        store.prune(project_name, environment_name, &config)?;
+
+        Ok(Some(saved))
+    }
+
+    /// Run a state-modifying operation holding the state lock, after an automatic state backup
+    ///
+    /// A failed backup is reported but does not block the operation; automatic backups
+    /// can be disabled with `auto: false` in the backup configuration.
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_state_guard<T>(
+        ctx: &crate::context::Context,
+        infrastructure: &InfrastructureResource,
+        infrastructure_root: &Path,
+        executor: &dyn Executor,
+        working_dir: &str,
+        project_name: &str,
+        environment_name: &str,
+        operation: &str,
+        f: impl FnOnce() -> Result<T>,
+    ) -> Result<T> {
+        Self::with_state_lock(
+            ctx,
+            infrastructure,
+            infrastructure_root,
+            project_name,
+            environment_name,
+            operation,
+            || {
+                if Self::backup_config(infrastructure)?.auto {
+                    let reason = format!("pre-{}", operation);
+                    match Self::backup_state(
+                        ctx,
+                        infrastructure,
+                        infrastructure_root,
+                        executor,
+                        working_dir,
+                        project_name,
+                        environment_name,
+                        &reason,
+                    ) {
+                        Ok(Some(saved)) if saved.created => ctx
+                            .output
+                            .dimmed(&format!("State backed up ({})", saved.entry.id)),
+                        Ok(Some(saved)) => ctx.output.dimmed(&format!(
+                            "State unchanged since backup {}",
+                            saved.entry.id
+                        )),
+                        Ok(None) => {}
+                        Err(e) => ctx
+                            .output
+                            .warning(&format!("Skipping {} state backup: {}", reason, e)),
+                    }
+                }
+
+                let result = f()?;
+
+                Self::refresh_state_snapshot(
+                    ctx,
+                    infrastructure_root,
+                    executor,
+                    working_dir,
+                    project_name,
+                    environment_name,
+                );
+
+                Ok(result)
+            },
+        )
+    }
+
+    /// Update the search snapshot of an environment from its current state
+    ///
+    /// Snapshots are a cache, so failures (e.g., executors without `state pull`) are only
+    /// reported.
+    pub fn refresh_state_snapshot(
+        ctx: &crate::context::Context,
+        infrastructure_root: &Path,
+        executor: &dyn Executor,
+        working_dir: &str,
+        project_name: &str,
+        environment_name: &str,
+    ) {
+        let store = SnapshotStore::for_infrastructure(&*ctx.fs, infrastructure_root);
+        let result = executor.state_pull(working_dir).and_then(|content| {
+            if content.trim().is_empty() {
+                store.remove(project_name, environment_name)
+            } else {
+                store
+                    .save(
+                        project_name,
+                        environment_name,
+                        StateDocument::parse(&content)?,
+                    )
+                    .map(|_| ())
+            }
+        });
+
+        if let Err(e) = result {
+            ctx.output
+                .dimmed(&format!("State snapshot not updated: {}", e));
+        }
+    }
+
+    /// Run a state-modifying operation while holding the pmp state lock of an environment
+    ///
+    /// The lock is released afterwards even if the operation fails. Acquisition can be
+    /// disabled with `auto: false` in the lock configuration.
+    pub fn with_state_lock<T>(
+        ctx: &crate::context::Context,
+        infrastructure: &InfrastructureResource,
+        infrastructure_root: &Path,
+        project_name: &str,
+        environment_name: &str,
+        operation: &str,
+        f: impl FnOnce() -> Result<T>,
+    ) -> Result<T> {
+        let config = Self::lock_config(infrastructure)?;
+
+        if !config.auto {
+            return f();
+        }
+
+        let store = create_lock_store(&config, infrastructure_root)?;
+        let lock = LockInfo::new(
+            &lock_key(project_name, environment_name),
+            &current_owner(),
+            operation,
+            None,
+            Some(config.ttl),
+        );
+
+        store
+            .acquire(&lock)
+            .with_context(|| format!("Failed to acquire state lock for {}", operation))?;
+        ctx.output
+            .dimmed(&format!("Acquired state lock {} ({})", lock.key, lock.id));
+
+        let result = f();
+
+        match store.release(&lock.key, &lock.id) {
+            Ok(()) => ctx.output.dimmed(&format!("Released state lock {}", lock.key)),
+            Err(e) => ctx.output.warning(&format!(
+                "Failed to release state lock {} (lock ID: {}): {}",
+                lock.key, lock.id, e
+            )),
+        }
+
+        result
+    }
+
     /// Merge infrastructure hooks with environment hooks
     /// Environment hooks take precedence (append to infrastructure hooks)
     pub fn merge_hooks(
@@ -32,6 +224,16 @@ impl ExecutionHelper {
             merged.post_apply.extend(env_hooks.post_apply.clone());
             merged.pre_destroy.extend(env_hooks.pre_destroy.clone());
             merged.post_destroy.extend(env_hooks.post_destroy.clone());
+            merged
+                .on_failure_apply
+                .extend(env_hooks.on_failure_apply.clone());
+            merged
+                .on_failure_destroy
+                .extend(env_hooks.on_failure_destroy.clone());
+            merged.finally_apply.extend(env_hooks.finally_apply.clone());
+            merged
+                .finally_destroy
+                .extend(env_hooks.finally_destroy.clone());
             merged.pre_refresh.extend(env_hooks.pre_refresh.clone());
             merged.post_refresh.extend(env_hooks.post_refresh.clone());
         }
@@ -39,6 +241,53 @@ impl ExecutionHelper {
         merged
     }
 
+    /// Run the on-failure and finally hooks of an operation, then return its result
+    ///
+    /// On-failure hooks only run when the operation failed, finally hooks always run. When the
+    /// operation failed, failing hooks are reported as warnings so its own error is returned.
+    pub fn finish_with_hooks(
+        ctx: &crate::context::Context,
+        on_failure: &[crate::template::metadata::Hook],
+        finally: &[crate::template::metadata::Hook],
+        env_dir_str: &str,
+        operation: &str,
+        result: Result<HookOutcome>,
+    ) -> Result<()> {
+        let on_failure_result = if result.is_err() {
+            HooksRunner::run_hooks_after(
+                ctx,
+                on_failure,
+                env_dir_str,
+                &format!("on-failure-{}", operation),
+                &result,
+            )
+        } else {
+            Ok(HookOutcome::Continue)
+        };
+        let finally_result = HooksRunner::run_hooks_after(
+            ctx,
+            finally,
+            env_dir_str,
+            &format!("finally-{}", operation),
+            &result,
+        );
+
+        for (phase, hooks_result) in [
+            ("on-failure", on_failure_result),
+            ("finally", finally_result),
+        ] {
+            if let Err(e) = hooks_result {
+                if result.is_ok() {
+                    return Err(e);
+                }
+                ctx.output
+                    .warning(&format!("{}-{} hooks failed: {:#}", phase, operation, e));
+            }
+        }
+
+        result.map(|_| ())
+    }
+
     /// Run helm repo update if configured in infrastructure
     ///
     /// This is a workaround for the Terraform Helm provider issue where it doesn't
@@ -51,8 +300,8 @@ impl ExecutionHelper {
         infrastructure: &InfrastructureResource,
         executor_name: &str,
     ) -> Result<()> {
-        // Only run for opentofu executor
-        if executor_name != "opentofu" {
+        // Only run for executors that drive the Helm provider
+        if executor_name != "opentofu" && executor_name != "terraform" {
             return Ok(());
         }
 
@@ -196,7 +445,7 @@ impl ExecutionHelper {
             let executor_config = resource.get_executor_config();
 
             // Get executor
-            let executor = Self::get_executor(&executor_config.name)?;
+            let executor = ctx.executor_registry.get(&executor_config.name)?;
 
             // Build executor config
             let mut command_options = std::collections::HashMap::new();
@@ -213,6 +462,7 @@ impl ExecutionHelper {
                 refresh_command: None,
                 test_command: None,
                 command_options,
+                plan_file: None,
             };
 
             // Execute the command on this node
@@ -414,7 +664,7 @@ impl ExecutionHelper {
 
         // Run pre-preview hooks
         if !hooks.pre_preview.is_empty()
-            && HooksRunner::run_hooks(&hooks.pre_preview, env_dir_str, "pre-preview")?
+            && HooksRunner::run_hooks(ctx, &hooks.pre_preview, env_dir_str, "pre-preview")?
                 == HookOutcome::Cancel
         {
             ctx.output.warning(&format!(
@@ -458,7 +708,7 @@ impl ExecutionHelper {
 
         // Run post-preview hooks
         if !hooks.post_preview.is_empty()
-            && HooksRunner::run_hooks(&hooks.post_preview, env_dir_str, "post-preview")?
+            && HooksRunner::run_hooks(ctx, &hooks.post_preview, env_dir_str, "post-preview")?
                 == HookOutcome::Cancel
         {
             ctx.output.warning(&format!(
@@ -514,7 +764,7 @@ impl ExecutionHelper {
 
         // Run pre-test hooks
         if !hooks.pre_test.is_empty()
-            && HooksRunner::run_hooks(&hooks.pre_test, env_dir_str, "pre-test")?
+            && HooksRunner::run_hooks(ctx, &hooks.pre_test, env_dir_str, "pre-test")?
                 == HookOutcome::Cancel
         {
             ctx.output.warning(&format!(
@@ -560,7 +810,7 @@ impl ExecutionHelper {
 
         // Run post-test hooks
         if !hooks.post_test.is_empty()
-            && HooksRunner::run_hooks(&hooks.post_test, env_dir_str, "post-test")?
+            && HooksRunner::run_hooks(ctx, &hooks.post_test, env_dir_str, "post-test")?
                 == HookOutcome::Cancel
         {
             ctx.output.warning(&format!(
@@ -601,8 +851,9 @@ impl ExecutionHelper {
             .context("Failed to convert environment path to string")?;
 
         // Load collection to get infrastructure-level hooks
-        let (collection, _) = crate::collection::CollectionDiscovery::find_collection(&*ctx.fs)?
-            .context("Infrastructure is required to run commands")?;
+        let (collection, collection_root) =
+            crate::collection::CollectionDiscovery::find_collection(&*ctx.fs)?
+                .context("Infrastructure is required to run commands")?;
 
         let infrastructure_hooks = collection.get_hooks();
 
@@ -614,68 +865,90 @@ impl ExecutionHelper {
         // Merge hooks: environment hooks take precedence over infrastructure hooks
         let hooks = Self::merge_hooks(&infrastructure_hooks, env_resource.spec.hooks.as_ref());
 
-        // Run pre-apply hooks
-        if !hooks.pre_apply.is_empty()
-            && HooksRunner::run_hooks(&hooks.pre_apply, env_dir_str, "pre-apply")?
-                == HookOutcome::Cancel
-        {
-            ctx.output.warning(&format!(
-                "Apply cancelled by pre-apply hook for {} ({})",
-                node.project_name, node.environment_name
-            ));
-            return Ok(());
-        }
-
-        // Run helm repo update if configured
-        Self::run_helm_repo_update_if_needed(ctx, &collection, executor.get_name())?;
-
-        // Initialize executor
-        ctx.output
-            .dimmed(&format!("Initializing {}...", executor.get_name()));
-        let init_output = executor.init(env_dir_str)?;
-
-        if !init_output.status.success() {
-            if !init_output.stdout.is_empty()
-                && let Ok(stdout_str) = String::from_utf8(init_output.stdout.clone())
+        // Pre/post hook and apply failures are handed to the on-failure and finally hooks
+        let result = (|| -> Result<HookOutcome> {
+            // Run pre-apply hooks
+            if !hooks.pre_apply.is_empty()
+                && HooksRunner::run_hooks(ctx, &hooks.pre_apply, env_dir_str, "pre-apply")?
+                    == HookOutcome::Cancel
             {
-                ctx.output.error(&stdout_str);
+                ctx.output.warning(&format!(
+                    "Apply cancelled by pre-apply hook for {} ({})",
+                    node.project_name, node.environment_name
+                ));
+                return Ok(HookOutcome::Cancel);
             }
-            if !init_output.stderr.is_empty()
-                && let Ok(stderr_str) = String::from_utf8(init_output.stderr.clone())
-            {
-                ctx.output.error(&stderr_str);
+
+            // Run helm repo update if configured
+            Self::run_helm_repo_update_if_needed(ctx, &collection, executor.get_name())?;
+
+            // Initialize executor
+            ctx.output
+                .dimmed(&format!("Initializing {}...", executor.get_name()));
+            let init_output = executor.init(env_dir_str)?;
+
+            if !init_output.status.success() {
+                if !init_output.stdout.is_empty()
+                    && let Ok(stdout_str) = String::from_utf8(init_output.stdout.clone())
+                {
+                    ctx.output.error(&stdout_str);
+                }
+                if !init_output.stderr.is_empty()
+                    && let Ok(stderr_str) = String::from_utf8(init_output.stderr.clone())
+                {
+                    ctx.output.error(&stderr_str);
+                }
+                anyhow::bail!(
+                    "Initialization failed with exit code: {:?}",
+                    init_output.status.code()
+                );
             }
-            anyhow::bail!(
-                "Initialization failed with exit code: {:?}",
-                init_output.status.code()
-            );
-        }
 
-        ctx.output.success("Initialization completed");
+            ctx.output.success("Initialization completed");
 
-        // Run apply
-        ctx.output
-            .dimmed(&format!("Executing {} apply...", executor.get_name()));
-        executor.apply(execution_config, env_dir_str, extra_args)?;
+            // Run apply
+            ctx.output
+                .dimmed(&format!("Executing {} apply...", executor.get_name()));
+            Self::with_state_guard(
+                ctx,
+                &collection,
+                &collection_root,
+                executor,
+                env_dir_str,
+                &node.project_name,
+                &node.environment_name,
+                "apply",
+                || executor.apply(execution_config, env_dir_str, extra_args),
+            )?;
+
+            // Run post-apply hooks
+            if !hooks.post_apply.is_empty()
+                && HooksRunner::run_hooks(ctx, &hooks.post_apply, env_dir_str, "post-apply")?
+                    == HookOutcome::Cancel
+            {
+                ctx.output.warning(&format!(
+                    "Post-apply hooks cancelled for {} ({})",
+                    node.project_name, node.environment_name
+                ));
+                return Ok(HookOutcome::Cancel);
+            }
 
-        // Run post-apply hooks
-        if !hooks.post_apply.is_empty()
-            && HooksRunner::run_hooks(&hooks.post_apply, env_dir_str, "post-apply")?
-                == HookOutcome::Cancel
-        {
-            ctx.output.warning(&format!(
-                "Post-apply hooks cancelled for {} ({})",
+            ctx.output.success(&format!(
+                "Apply completed for {} ({})",
                 node.project_name, node.environment_name
             ));
-            return Ok(());
-        }
-
-        ctx.output.success(&format!(
-            "Apply completed for {} ({})",
-            node.project_name, node.environment_name
-        ));
 
-        Ok(())
+            Ok(HookOutcome::Continue)
+        })();
+
+        Self::finish_with_hooks(
+            ctx,
+            &hooks.on_failure_apply,
+            &hooks.finally_apply,
+            env_dir_str,
+            "apply",
+            result,
+        )
     }
 
     /// Execute destroy on a single node
@@ -701,8 +974,9 @@ impl ExecutionHelper {
             .context("Failed to convert environment path to string")?;
 
         // Load collection to get infrastructure-level hooks
-        let (collection, _) = crate::collection::CollectionDiscovery::find_collection(&*ctx.fs)?
-            .context("Infrastructure is required to run commands")?;
+        let (collection, collection_root) =
+            crate::collection::CollectionDiscovery::find_collection(&*ctx.fs)?
+                .context("Infrastructure is required to run commands")?;
 
         let infrastructure_hooks = collection.get_hooks();
 
@@ -714,76 +988,89 @@ impl ExecutionHelper {
         // Merge hooks: environment hooks take precedence over infrastructure hooks
         let hooks = Self::merge_hooks(&infrastructure_hooks, env_resource.spec.hooks.as_ref());
 
-        // Run pre-destroy hooks
-        if !hooks.pre_destroy.is_empty()
-            && HooksRunner::run_hooks(&hooks.pre_destroy, env_dir_str, "pre-destroy")?
-                == HookOutcome::Cancel
-        {
-            ctx.output.warning(&format!(
-                "Destroy cancelled by pre-destroy hook for {} ({})",
-                node.project_name, node.environment_name
-            ));
-            return Ok(());
-        }
-
-        // Run helm repo update if configured
-        Self::run_helm_repo_update_if_needed(ctx, &collection, executor.get_name())?;
-
-        // Initialize executor
-        ctx.output
-            .dimmed(&format!("Initializing {}...", executor.get_name()));
-        let init_output = executor.init(env_dir_str)?;
-
-        if !init_output.status.success() {
-            if !init_output.stdout.is_empty()
-                && let Ok(stdout_str) = String::from_utf8(init_output.stdout.clone())
+        // Pre/post hook and destroy failures are handed to the on-failure and finally hooks
+        let result = (|| -> Result<HookOutcome> {
+            // Run pre-destroy hooks
+            if !hooks.pre_destroy.is_empty()
+                && HooksRunner::run_hooks(ctx, &hooks.pre_destroy, env_dir_str, "pre-destroy")?
+                    == HookOutcome::Cancel
             {
-                ctx.output.error(&stdout_str);
+                ctx.output.warning(&format!(
+                    "Destroy cancelled by pre-destroy hook for {} ({})",
+                    node.project_name, node.environment_name
+                ));
+                return Ok(HookOutcome::Cancel);
             }
-            if !init_output.stderr.is_empty()
-                && let Ok(stderr_str) = String::from_utf8(init_output.stderr.clone())
-            {
-                ctx.output.error(&stderr_str);
+
+            // Run helm repo update if configured
+            Self::run_helm_repo_update_if_needed(ctx, &collection, executor.get_name())?;
+
+            // Initialize executor
+            ctx.output
+                .dimmed(&format!("Initializing {}...", executor.get_name()));
+            let init_output = executor.init(env_dir_str)?;
+
+            if !init_output.status.success() {
+                if !init_output.stdout.is_empty()
+                    && let Ok(stdout_str) = String::from_utf8(init_output.stdout.clone())
+                {
+                    ctx.output.error(&stdout_str);
+                }
+                if !init_output.stderr.is_empty()
+                    && let Ok(stderr_str) = String::from_utf8(init_output.stderr.clone())
+                {
+                    ctx.output.error(&stderr_str);
+                }
+                anyhow::bail!(
+                    "Initialization failed with exit code: {:?}",
+                    init_output.status.code()
+                );
             }
-            anyhow::bail!(
-                "Initialization failed with exit code: {:?}",
-                init_output.status.code()
-            );
-        }
 
-        ctx.output.success("Initialization completed");
+            ctx.output.success("Initialization completed");
 
-        // Run destroy
-        ctx.output
-            .dimmed(&format!("Executing {} destroy...", executor.get_name()));
-        executor.destroy(execution_config, env_dir_str, extra_args)?;
+            // Run destroy
+            ctx.output
+                .dimmed(&format!("Executing {} destroy...", executor.get_name()));
+            Self::with_state_guard(
+                ctx,
+                &collection,
+                &collection_root,
+                executor,
+                env_dir_str,
+                &node.project_name,
+                &node.environment_name,
+                "destroy",
+                || executor.destroy(execution_config, env_dir_str, extra_args),
+            )?;
+
+            // Run post-destroy hooks
+            if !hooks.post_destroy.is_empty()
+                && HooksRunner::run_hooks(ctx, &hooks.post_destroy, env_dir_str, "post-destroy")?
+                    == HookOutcome::Cancel
+            {
+                ctx.output.warning(&format!(
+                    "Post-destroy hooks cancelled for {} ({})",
+                    node.project_name, node.environment_name
+                ));
+                return Ok(HookOutcome::Cancel);
+            }
 
-        // Run post-destroy hooks
-        if !hooks.post_destroy.is_empty()
-            && HooksRunner::run_hooks(&hooks.post_destroy, env_dir_str, "post-destroy")?
-                == HookOutcome::Cancel
-        {
-            ctx.output.warning(&format!(
-                "Post-destroy hooks cancelled for {} ({})",
+            ctx.output.success(&format!(
+                "Destroy completed for {} ({})",
                 node.project_name, node.environment_name
             ));
-            return Ok(());
-        }
 
-        ctx.output.success(&format!(
-            "Destroy completed for {} ({})",
-            node.project_name, node.environment_name
-        ));
-
-        Ok(())
-    }
-
-    /// Get the appropriate executor based on name
-    pub fn get_executor(name: &str) -> Result<Box<dyn Executor>> {
-        match name {
-            "opentofu" => Ok(Box::new(OpenTofuExecutor::new())),
-            "none" => Ok(Box::new(NoneExecutor::new())),
-            _ => anyhow::bail!("Unknown executor: {}", name),
-        }
+            Ok(HookOutcome::Continue)
+        })();
+
+        Self::finish_with_hooks(
+            ctx,
+            &hooks.on_failure_destroy,
+            &hooks.finally_destroy,
+            env_dir_str,
+            "destroy",
+            result,
+        )
     }
 }
@@ -0,0 +1,174 @@
+use crate::collection::CollectionDiscovery;
+use crate::context::Context;
+use crate::history::{OperationHistory, OperationRecord};
+use crate::output;
+use crate::template::metadata::HistoryConfig;
+use anyhow::{Context as AnyhowContext, Result};
+
+/// Browse the operations recorded by `pmp ui` (`.pmp/history`)
+pub struct HistoryCommand;
+
+impl HistoryCommand {
+    /// Operation history of the current infrastructure and its configuration
+    fn open(ctx: &Context) -> Result<(OperationHistory<'_>, HistoryConfig)> {
+        let (infrastructure, infrastructure_root) = CollectionDiscovery::find_collection(&*ctx.fs)?
+            .context("Infrastructure is required. Run 'pmp init' first.")?;
+        let config = infrastructure.spec.history.unwrap_or_default();
+
+        Ok((
+            OperationHistory::for_infrastructure(&*ctx.fs, &infrastructure_root, &config),
+            config,
+        ))
+    }
+
+    /// Execute the history list command
+    pub fn execute_list(
+        ctx: &Context,
+        project: Option<&str>,
+        environment: Option<&str>,
+        operation: Option<&str>,
+        failed: bool,
+        limit: usize,
+    ) -> Result<()> {
+        ctx.output.section("Operation History");
+
+        let (history, _) = Self::open(ctx)?;
+        let (records, warnings) = history.list_with_warnings()?;
+        for warning in &warnings {
+            ctx.output.warning(warning);
+        }
+
+        let records: Vec<OperationRecord> = records
+            .into_iter()
+            .filter(|r| project.is_none() || r.project.as_deref() == project)
+            .filter(|r| environment.is_none() || r.environment.as_deref() == environment)
+            .filter(|r| operation.is_none_or(|op| r.operation == op))
+            .filter(|r| !failed || r.success == Some(false))
+            .collect();
+
+        if records.is_empty() {
+            ctx.output.dimmed("No operations found.");
+            return Ok(());
+        }
+
+        for record in records.iter().take(limit) {
+            let status_icon = match record.success {
+                None => "…",
+                Some(true) => "✓",
+                Some(false) => "✗",
+            };
+
+            ctx.output.info(&format!(
+                "{} {}  {}  {} {}",
+                status_icon,
+                &record.id[..record.id.len().min(8)],
+                record.started_at.format("%Y-%m-%d %H:%M:%S"),
+                record.operation,
+                record.target()
+            ));
+            ctx.output.dimmed(&format!(
+                "   by {}, {}",
+                record.user,
+                Self::duration(record).unwrap_or_else(|| record.status().to_string())
+            ));
+        }
+
+        output::blank();
+        ctx.output.info(&format!(
+            "Showing {} of {} operation(s)",
+            records.len().min(limit),
+            records.len()
+        ));
+
+        Ok(())
+    }
+
+    /// Execute the history show command
+    pub fn execute_show(ctx: &Context, id: &str, show_log: bool) -> Result<()> {
+        ctx.output.section("Operation");
+
+        let (history, _) = Self::open(ctx)?;
+        let record = history.get(id)?;
+
+        ctx.output.key_value("ID", &record.id);
+        ctx.output.key_value("Operation", &record.operation);
+        if let Some(project) = &record.project {
+            ctx.output.key_value("Project", project);
+        }
+        if let Some(environment) = &record.environment {
+            ctx.output.key_value("Environment", environment);
+        }
+        ctx.output.key_value("Path", &record.path);
+        ctx.output.key_value("User", &record.user);
+        ctx.output
+            .key_value("Started", &record.started_at.to_rfc3339());
+        if let Some(finished_at) = record.finished_at {
+            ctx.output.key_value("Finished", &finished_at.to_rfc3339());
+        }
+        if let Some(duration) = Self::duration(&record) {
+            ctx.output.key_value("Duration", &duration);
+        }
+        ctx.output.key_value("Status", record.status());
+        if let Some(error) = &record.error {
+            ctx.output.key_value("Error", error);
+        }
+
+        if !show_log {
+            return Ok(());
+        }
+
+        ctx.output.subsection("Log");
+
+        match history.log(&record.id)? {
+            Some(log) => {
+                for line in log.lines() {
+                    ctx.output.info(line);
+                }
+            }
+            None => ctx.output.dimmed("No log saved."),
+        }
+
+        Ok(())
+    }
+
+    /// Execute the history prune command
+    pub fn execute_prune(ctx: &Context) -> Result<()> {
+        ctx.output.section("Prune Operation History");
+
+        let (history, config) = Self::open(ctx)?;
+        if config.keep_last.is_none() && config.max_age_days.is_none() {
+            ctx.output
+                .dimmed("No retention rules configured (spec.history.keep_last / max_age_days).");
+            return Ok(());
+        }
+
+        let removed = history.prune(&config)?;
+
+        for record in &removed {
+            ctx.output.dimmed(&format!(
+                "Deleted {} ({} {})",
+                record.id,
+                record.operation,
+                record.target()
+            ));
+        }
+
+        ctx.output
+            .success(&format!("Deleted {} operation(s)", removed.len()));
+
+        Ok(())
+    }
+
+    /// How long a finished operation took (e.g., "42s", "3m 5s")
+    fn duration(record: &OperationRecord) -> Option<String> {
+        let seconds = (record.finished_at? - record.started_at)
+            .num_seconds()
+            .max(0);
+
+        Some(if seconds < 60 {
+            format!("{}s", seconds)
+        } else {
+            format!("{}m {}s", seconds / 60, seconds % 60)
+        })
+    }
+}
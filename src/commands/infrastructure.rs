@@ -214,9 +214,11 @@ impl InfrastructureCommand {
                 environments,
                 hooks: None,
                 executor: executor_config,
+                executors: vec![],
                 cost: None,
                 policy: None,
                 secrets: None,
+                history: None,
             },
         };
 
@@ -1,4 +1,5 @@
 pub mod apply;
+pub mod cache;
 pub mod ci;
 pub mod ci_detect_changes;
 pub mod clone;
@@ -10,9 +11,11 @@ pub mod drift;
 pub mod env;
 mod execution_helper;
 pub mod parallel;
+mod plan_bundle;
 pub mod find;
 pub mod generate;
 pub mod graph;
+pub mod history;
 pub mod import;
 pub mod infrastructure;
 pub mod marketplace;
@@ -26,8 +29,10 @@ pub mod template;
 pub mod test;
 pub mod ui;
 pub mod update;
+pub mod upgrade;
 
 pub use apply::ApplyCommand;
+pub use cache::CacheCommand;
 pub use ci::CiCommand;
 pub use ci_detect_changes::CiDetectChangesCommand;
 pub use clone::CloneCommand;
@@ -41,9 +46,11 @@ pub use execution_helper::ExecutionHelper;
 pub use find::FindCommand;
 pub use generate::GenerateCommand;
 pub use graph::GraphCommand;
+pub use history::HistoryCommand;
 pub use import::ImportCommand;
 pub use infrastructure::InfrastructureCommand;
 pub use marketplace::MarketplaceCommand;
+pub use plan_bundle::PlanBundle;
 pub use policy::PolicyCommand;
 pub use preview::PreviewCommand;
 pub use refresh::RefreshCommand;
@@ -53,3 +60,4 @@ pub use template::TemplateCommand;
 pub use test::TestCommand;
 pub use ui::UiCommand;
 pub use update::UpdateCommand;
+pub use upgrade::UpgradeCommand;
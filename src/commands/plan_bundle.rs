@@ -0,0 +1,746 @@
+use crate::collection::{DependencyGraph, DependencyNode};
+use crate::context::Context;
+use crate::executor::{DEFAULT_PLAN_FILE, DEFAULT_PLAN_JSON_FILE};
+use crate::template::DynamicProjectEnvironmentResource;
+use crate::traits::FileSystem;
+use anyhow::{Context as AnyhowContext, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+/// Manifest file name inside a plan bundle directory
+pub const PLAN_MANIFEST_FILE: &str = "manifest.json";
+
+/// Current version of the plan bundle manifest format
+const PLAN_MANIFEST_VERSION: u32 = 1;
+
+/// Environment files and directories that are not inputs of a plan
+const IGNORED_INPUTS: &[&str] = &[
+    ".terraform",
+    ".terraform.tfstate.lock.info",
+    DEFAULT_PLAN_FILE,
+    DEFAULT_PLAN_JSON_FILE,
+];
+
+/// Manifest describing the saved plans of a `pmp project preview --out` run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanManifest {
+    pub version: u32,
+    pub created_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_sha: Option<String>,
+    /// Project the preview was run for
+    pub project: String,
+    /// Environment the preview was run for
+    pub environment: String,
+    /// Environment path relative to the infrastructure root
+    pub environment_path: String,
+    /// One saved plan per dependency node
+    pub plans: Vec<PlanManifestEntry>,
+}
+
+/// Saved plan of a single project environment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanManifestEntry {
+    pub project: String,
+    pub environment: String,
+    /// Environment path relative to the infrastructure root
+    pub environment_path: String,
+    pub executor: String,
+    /// Binary plan file relative to the bundle directory
+    pub plan_file: String,
+    /// SHA-256 of the binary plan file
+    pub plan_hash: String,
+    /// JSON rendering of the plan relative to the bundle directory, validated by policies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plan_json_file: Option<String>,
+    /// SHA-256 of the JSON plan file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plan_json_hash: Option<String>,
+    /// SHA-256 of the environment files the plan was computed from
+    pub inputs_hash: String,
+    /// SHA-256 of .pmp.environment.yaml
+    pub environment_file_hash: String,
+    pub created_at: String,
+}
+
+impl PlanManifestEntry {
+    /// Key matching `DependencyNode::key`
+    pub fn key(&self) -> String {
+        format!("{}:{}", self.project, self.environment)
+    }
+}
+
+/// Saved plan of a node that matches its current inputs
+#[derive(Debug, Clone)]
+pub struct VerifiedPlan {
+    pub node: DependencyNode,
+    /// Absolute path of the binary plan
+    pub plan_file: PathBuf,
+    /// Absolute path of the JSON plan, when the executor renders one
+    pub plan_json_file: Option<PathBuf>,
+}
+
+/// Saved plan bundles: binary plans per dependency node plus a manifest,
+/// written by `preview --out` and applied by `apply --plan`
+pub struct PlanBundle;
+
+impl PlanBundle {
+    /// Create the bundle directory, drop any previous manifest and return its absolute path
+    pub fn prepare(ctx: &Context, bundle_dir: &Path) -> Result<PathBuf> {
+        ctx.fs
+            .create_dir_all(bundle_dir)
+            .with_context(|| format!("Failed to create plan directory: {:?}", bundle_dir))?;
+
+        let manifest_path = bundle_dir.join(PLAN_MANIFEST_FILE);
+        if ctx.fs.exists(&manifest_path) {
+            ctx.fs.remove_file(&manifest_path)?;
+        }
+
+        let bundle_dir = if bundle_dir.is_absolute() {
+            bundle_dir.to_path_buf()
+        } else {
+            ctx.fs.current_dir()?.join(bundle_dir)
+        };
+
+        Ok(bundle_dir.components().collect())
+    }
+
+    /// Path of the binary plan of a node inside the bundle
+    pub fn plan_file_path(bundle_dir: &Path, node: &DependencyNode) -> PathBuf {
+        bundle_dir
+            .join(&node.project_name)
+            .join(&node.environment_name)
+            .join(DEFAULT_PLAN_FILE)
+    }
+
+    /// Path of the JSON plan of a node inside the bundle
+    pub fn plan_json_file_path(bundle_dir: &Path, node: &DependencyNode) -> PathBuf {
+        Self::plan_file_path(bundle_dir, node).with_file_name(DEFAULT_PLAN_JSON_FILE)
+    }
+
+    /// Prepare the plan file location of a node, removing plans left by a previous run
+    pub fn prepare_plan_file(
+        ctx: &Context,
+        bundle_dir: &Path,
+        node: &DependencyNode,
+    ) -> Result<PathBuf> {
+        let plan_file = Self::plan_file_path(bundle_dir, node);
+
+        if let Some(parent) = plan_file.parent() {
+            ctx.fs.create_dir_all(parent)?;
+        }
+
+        for file in [&plan_file, &Self::plan_json_file_path(bundle_dir, node)] {
+            if ctx.fs.exists(file) {
+                ctx.fs.remove_file(file)?;
+            }
+        }
+
+        Ok(plan_file)
+    }
+
+    /// Render the saved plan of a node as JSON with its executor
+    pub fn render_plan_json(
+        ctx: &Context,
+        node: &DependencyNode,
+        plan_file: &Path,
+    ) -> Result<Option<String>> {
+        let executor = ctx
+            .executor_registry
+            .get(&Self::executor_name(ctx, node)?)?;
+        let working_dir = node
+            .environment_path
+            .to_str()
+            .context("Failed to convert environment path to string")?;
+
+        executor.show_plan_json(working_dir, &plan_file.to_string_lossy())
+    }
+
+    /// Dependency levels of a project environment, in `DependencyGraph::group_by_level` order
+    pub fn execution_levels(
+        ctx: &Context,
+        env_path: &Path,
+        project_name: &str,
+        env_name: &str,
+    ) -> Result<Vec<Vec<DependencyNode>>> {
+        DependencyGraph::build(&*ctx.fs, env_path, project_name, env_name)
+            .context("Failed to build dependency graph")?
+            .group_by_level()
+    }
+
+    /// Whether a node produces a plan (dependency-only projects use the none executor)
+    pub fn requires_plan(ctx: &Context, node: &DependencyNode) -> Result<bool> {
+        Ok(Self::executor_name(ctx, node)? != "none")
+    }
+
+    /// Record the saved plan of a node after its plan has run
+    ///
+    /// The JSON rendering of the plan, if any, is stored next to it for policy validation.
+    pub fn record(
+        ctx: &Context,
+        bundle_dir: &Path,
+        infrastructure_root: &Path,
+        node: &DependencyNode,
+        plan_json: Option<&str>,
+    ) -> Result<PlanManifestEntry> {
+        let plan_file = Self::plan_file_path(bundle_dir, node);
+        let executor = Self::executor_name(ctx, node)?;
+
+        if !ctx.fs.exists(&plan_file) {
+            anyhow::bail!(
+                "No saved plan was written for {} (executor '{}' may not support saved plans)",
+                node.key(),
+                executor
+            );
+        }
+
+        let plan_json_file = match plan_json {
+            Some(json) => {
+                let path = Self::plan_json_file_path(bundle_dir, node);
+                ctx.fs
+                    .write(&path, json)
+                    .with_context(|| format!("Failed to write JSON plan: {:?}", path))?;
+                Some(path)
+            }
+            None => None,
+        };
+
+        Ok(PlanManifestEntry {
+            project: node.project_name.clone(),
+            environment: node.environment_name.clone(),
+            environment_path: relative_path(infrastructure_root, &node.environment_path),
+            executor,
+            plan_file: relative_path(bundle_dir, &plan_file),
+            plan_hash: hash_file(&*ctx.fs, &plan_file)?,
+            plan_json_file: plan_json_file
+                .as_ref()
+                .map(|path| relative_path(bundle_dir, path)),
+            plan_json_hash: plan_json_file
+                .as_ref()
+                .map(|path| hash_file(&*ctx.fs, path))
+                .transpose()?,
+            inputs_hash: hash_environment_inputs(&*ctx.fs, &node.environment_path)?,
+            environment_file_hash: hash_file(
+                &*ctx.fs,
+                &node.environment_path.join(".pmp.environment.yaml"),
+            )?,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        })
+    }
+
+    /// Write the manifest once every node that needs a plan has one
+    pub fn write_manifest(
+        ctx: &Context,
+        bundle_dir: &Path,
+        infrastructure_root: &Path,
+        root: &DependencyNode,
+        levels: &[Vec<DependencyNode>],
+        mut plans: Vec<PlanManifestEntry>,
+    ) -> Result<PathBuf> {
+        for node in levels.iter().flatten() {
+            if Self::requires_plan(ctx, node)? && !plans.iter().any(|p| p.key() == node.key()) {
+                anyhow::bail!(
+                    "Plan bundle is incomplete: no plan was saved for {}",
+                    node.key()
+                );
+            }
+        }
+
+        plans.sort_by_key(|p| p.key());
+
+        let manifest = PlanManifest {
+            version: PLAN_MANIFEST_VERSION,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            git_sha: git_sha(ctx, infrastructure_root),
+            project: root.project_name.clone(),
+            environment: root.environment_name.clone(),
+            environment_path: relative_path(infrastructure_root, &root.environment_path),
+            plans,
+        };
+
+        let manifest_path = bundle_dir.join(PLAN_MANIFEST_FILE);
+        let content =
+            serde_json::to_string_pretty(&manifest).context("Failed to serialize plan manifest")?;
+        ctx.fs
+            .write(&manifest_path, &content)
+            .with_context(|| format!("Failed to write plan manifest: {:?}", manifest_path))?;
+
+        Ok(manifest_path)
+    }
+
+    /// Load the manifest of a bundle directory
+    pub fn load_manifest(ctx: &Context, bundle_dir: &Path) -> Result<PlanManifest> {
+        let manifest_path = bundle_dir.join(PLAN_MANIFEST_FILE);
+
+        if !ctx.fs.exists(&manifest_path) {
+            anyhow::bail!(
+                "Plan manifest not found: {:?}. Run 'pmp project preview --out <dir>' first.",
+                manifest_path
+            );
+        }
+
+        let content = ctx.fs.read_to_string(&manifest_path)?;
+        let manifest: PlanManifest = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse plan manifest: {:?}", manifest_path))?;
+
+        if manifest.version != PLAN_MANIFEST_VERSION {
+            anyhow::bail!(
+                "Unsupported plan manifest version {} (expected {})",
+                manifest.version,
+                PLAN_MANIFEST_VERSION
+            );
+        }
+
+        Ok(manifest)
+    }
+
+    /// Verify that the saved plans still match the dependency graph and their inputs
+    ///
+    /// Returns the plans to apply per level.
+    /// Fails listing every stale or missing plan, so nothing is applied from a stale bundle.
+    pub fn verify(
+        ctx: &Context,
+        bundle_dir: &Path,
+        manifest: &PlanManifest,
+        levels: &[Vec<DependencyNode>],
+    ) -> Result<Vec<Vec<VerifiedPlan>>> {
+        let mut problems = Vec::new();
+        let mut planned_levels = Vec::new();
+
+        for level in levels {
+            let mut planned = Vec::new();
+
+            for node in level {
+                let entry = manifest.plans.iter().find(|p| p.key() == node.key());
+
+                let Some(entry) = entry else {
+                    if Self::requires_plan(ctx, node)? {
+                        problems.push(format!("{}: no saved plan in the bundle", node.key()));
+                    }
+                    continue;
+                };
+
+                let plan = VerifiedPlan {
+                    node: node.clone(),
+                    plan_file: bundle_dir.join(&entry.plan_file),
+                    plan_json_file: entry.plan_json_file.as_ref().map(|f| bundle_dir.join(f)),
+                };
+
+                if let Some(problem) = Self::check_entry(ctx, entry, &plan)? {
+                    problems.push(format!("{}: {}", node.key(), problem));
+                }
+
+                planned.push(plan);
+            }
+
+            if !planned.is_empty() {
+                planned_levels.push(planned);
+            }
+        }
+
+        for entry in &manifest.plans {
+            if !levels.iter().flatten().any(|n| n.key() == entry.key()) {
+                problems.push(format!(
+                    "{}: no longer part of the dependency graph",
+                    entry.key()
+                ));
+            }
+        }
+
+        if !problems.is_empty() {
+            anyhow::bail!(
+                "Refusing to apply stale plans. Re-run 'pmp project preview --out'.\n  - {}",
+                problems.join("\n  - ")
+            );
+        }
+
+        Ok(planned_levels)
+    }
+
+    /// Check a single saved plan, returning the reason it cannot be applied
+    fn check_entry(
+        ctx: &Context,
+        entry: &PlanManifestEntry,
+        plan: &VerifiedPlan,
+    ) -> Result<Option<String>> {
+        let node = &plan.node;
+
+        if !ctx.fs.exists(&plan.plan_file) {
+            return Ok(Some(format!("plan file {:?} is missing", plan.plan_file)));
+        }
+
+        if hash_file(&*ctx.fs, &plan.plan_file)? != entry.plan_hash {
+            return Ok(Some("plan file does not match its recorded hash".to_string()));
+        }
+
+        if let Some(plan_json_file) = &plan.plan_json_file {
+            if !ctx.fs.exists(plan_json_file) {
+                return Ok(Some(format!("JSON plan {:?} is missing", plan_json_file)));
+            }
+
+            if Some(hash_file(&*ctx.fs, plan_json_file)?) != entry.plan_json_hash {
+                return Ok(Some(
+                    "JSON plan does not match its recorded hash".to_string(),
+                ));
+            }
+        }
+
+        let env_file = node.environment_path.join(".pmp.environment.yaml");
+        if hash_file(&*ctx.fs, &env_file)? != entry.environment_file_hash {
+            return Ok(Some(
+                ".pmp.environment.yaml changed since the plan was created".to_string(),
+            ));
+        }
+
+        if hash_environment_inputs(&*ctx.fs, &node.environment_path)? != entry.inputs_hash {
+            return Ok(Some(
+                "environment files changed since the plan was created".to_string(),
+            ));
+        }
+
+        Ok(None)
+    }
+
+    /// Executor name of a node
+    fn executor_name(ctx: &Context, node: &DependencyNode) -> Result<String> {
+        let env_file = node.environment_path.join(".pmp.environment.yaml");
+        let resource = DynamicProjectEnvironmentResource::from_file(&*ctx.fs, &env_file)
+            .context("Failed to load environment resource")?;
+
+        Ok(resource.get_executor_config().name.clone())
+    }
+}
+
+/// SHA-256 of a file as lowercase hex
+fn hash_file(fs: &dyn FileSystem, path: &Path) -> Result<String> {
+    let content = fs.read(path)?;
+    Ok(format!("{:x}", Sha256::digest(&content)))
+}
+
+/// SHA-256 over the relative paths and contents of the files a plan is computed from
+///
+/// Provider caches, state files and plan artifacts are excluded.
+fn hash_environment_inputs(fs: &dyn FileSystem, env_path: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+
+    // Sorting component-wise visits files in the same order as a sorted depth-first walk
+    let mut files: Vec<PathBuf> = fs
+        .walk_dir(env_path, usize::MAX)
+        .with_context(|| format!("Failed to read {:?}", env_path))?
+        .into_iter()
+        .filter(|path| fs.is_file(path))
+        .filter_map(|path| Some(path.strip_prefix(env_path).ok()?.to_path_buf()))
+        .filter(|relative| !relative.iter().any(is_ignored_input))
+        .collect();
+    files.sort();
+
+    for relative in files {
+        hasher.update(relative.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        hasher.update(fs.read(&env_path.join(&relative))?);
+        hasher.update([0]);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Whether an environment file or directory is excluded from the inputs hash
+fn is_ignored_input(name: &std::ffi::OsStr) -> bool {
+    let name = name.to_string_lossy();
+
+    IGNORED_INPUTS.contains(&name.as_ref())
+        || name.ends_with(".tfstate")
+        || name.ends_with(".tfstate.backup")
+}
+
+/// Path relative to a base directory, falling back to the path itself
+fn relative_path(base: &Path, path: &Path) -> String {
+    path.strip_prefix(base)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Current git commit of a directory, if it is inside a git repository
+fn git_sha(ctx: &Context, dir: &Path) -> Option<String> {
+    let output = ctx
+        .command
+        .execute("git", &["rev-parse", "HEAD"], dir)
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::RealFileSystem;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn environment_yaml(name: &str, executor: &str) -> String {
+        format!(
+            r#"
+apiVersion: pmp.io/v1
+kind: TestResource
+metadata:
+  name: {}
+  environment_name: dev
+spec:
+  resource:
+    apiVersion: pmp.io/v1
+    kind: TestResource
+  executor:
+    name: {}
+  inputs: {{}}
+"#,
+            name, executor
+        )
+    }
+
+    /// Create an environment with a main.tf and return its dependency node
+    fn create_environment(root: &Path, name: &str, executor: &str) -> DependencyNode {
+        let env_path = root.join("projects").join(name).join("environments/dev");
+        std::fs::create_dir_all(&env_path).unwrap();
+        std::fs::write(
+            env_path.join(".pmp.environment.yaml"),
+            environment_yaml(name, executor),
+        )
+        .unwrap();
+        std::fs::write(env_path.join("main.tf"), "resource \"null_resource\" \"a\" {}").unwrap();
+
+        DependencyNode::new(name.to_string(), "dev".to_string(), env_path)
+    }
+
+    fn real_context() -> Context {
+        Context {
+            fs: Arc::new(RealFileSystem),
+            ..Context::test()
+        }
+    }
+
+    /// Save a fake binary plan for a node and build a manifest containing it
+    fn saved_bundle(ctx: &Context, root: &Path, node: &DependencyNode) -> (PathBuf, PlanManifest) {
+        let bundle_dir = PlanBundle::prepare(ctx, &root.join("plans")).unwrap();
+        let plan_file = PlanBundle::prepare_plan_file(ctx, &bundle_dir, node).unwrap();
+        std::fs::write(&plan_file, b"binary-plan").unwrap();
+
+        let entry = PlanBundle::record(ctx, &bundle_dir, root, node, None).unwrap();
+        let levels = vec![vec![node.clone()]];
+        PlanBundle::write_manifest(ctx, &bundle_dir, root, node, &levels, vec![entry]).unwrap();
+
+        let manifest = PlanBundle::load_manifest(ctx, &bundle_dir).unwrap();
+        (bundle_dir, manifest)
+    }
+
+    #[test]
+    fn test_manifest_round_trip_and_verify() {
+        let temp = TempDir::new().unwrap();
+        let ctx = real_context();
+        let node = create_environment(temp.path(), "vpc", "opentofu");
+
+        let (bundle_dir, manifest) = saved_bundle(&ctx, temp.path(), &node);
+
+        assert_eq!(manifest.project, "vpc");
+        assert_eq!(manifest.environment_path, "projects/vpc/environments/dev");
+        assert_eq!(manifest.plans.len(), 1);
+        assert_eq!(manifest.plans[0].plan_file, "vpc/dev/plan.tfplan");
+
+        let levels = vec![vec![node.clone()]];
+        let planned = PlanBundle::verify(&ctx, &bundle_dir, &manifest, &levels).unwrap();
+
+        assert_eq!(planned.len(), 1);
+        assert_eq!(
+            planned[0][0].plan_file,
+            bundle_dir.join("vpc/dev/plan.tfplan")
+        );
+        assert_eq!(planned[0][0].plan_json_file, None);
+    }
+
+    #[test]
+    fn test_json_plan_is_recorded_and_verified() {
+        let temp = TempDir::new().unwrap();
+        let ctx = real_context();
+        let node = create_environment(temp.path(), "vpc", "opentofu");
+        let bundle_dir = PlanBundle::prepare(&ctx, &temp.path().join("plans")).unwrap();
+        let plan_file = PlanBundle::prepare_plan_file(&ctx, &bundle_dir, &node).unwrap();
+        std::fs::write(&plan_file, b"binary-plan").unwrap();
+
+        let entry = PlanBundle::record(
+            &ctx,
+            &bundle_dir,
+            temp.path(),
+            &node,
+            Some(r#"{"resource_changes":[]}"#),
+        )
+        .unwrap();
+        assert_eq!(entry.plan_json_file.as_deref(), Some("vpc/dev/plan.json"));
+        assert!(entry.plan_json_hash.is_some());
+
+        let levels = vec![vec![node.clone()]];
+        PlanBundle::write_manifest(&ctx, &bundle_dir, temp.path(), &node, &levels, vec![entry])
+            .unwrap();
+        let manifest = PlanBundle::load_manifest(&ctx, &bundle_dir).unwrap();
+        let planned = PlanBundle::verify(&ctx, &bundle_dir, &manifest, &levels).unwrap();
+        assert_eq!(
+            planned[0][0].plan_json_file,
+            Some(bundle_dir.join("vpc/dev/plan.json"))
+        );
+
+        std::fs::write(bundle_dir.join("vpc/dev/plan.json"), "{}").unwrap();
+        let err = PlanBundle::verify(&ctx, &bundle_dir, &manifest, &levels)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("JSON plan does not match its recorded hash"));
+
+        // A new run drops the JSON plan of the previous one
+        PlanBundle::prepare_plan_file(&ctx, &bundle_dir, &node).unwrap();
+        assert!(!bundle_dir.join("vpc/dev/plan.json").exists());
+    }
+
+    #[test]
+    fn test_verify_refuses_changed_environment_file() {
+        let temp = TempDir::new().unwrap();
+        let ctx = real_context();
+        let node = create_environment(temp.path(), "vpc", "opentofu");
+        let (bundle_dir, manifest) = saved_bundle(&ctx, temp.path(), &node);
+
+        std::fs::write(
+            node.environment_path.join(".pmp.environment.yaml"),
+            environment_yaml("vpc", "terraform"),
+        )
+        .unwrap();
+
+        let err = PlanBundle::verify(&ctx, &bundle_dir, &manifest, &[vec![node]])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Refusing to apply stale plans"));
+        assert!(err.contains(".pmp.environment.yaml changed"));
+    }
+
+    #[test]
+    fn test_verify_refuses_changed_inputs_and_tampered_plan() {
+        let temp = TempDir::new().unwrap();
+        let ctx = real_context();
+        let node = create_environment(temp.path(), "vpc", "opentofu");
+        let (bundle_dir, manifest) = saved_bundle(&ctx, temp.path(), &node);
+
+        std::fs::write(node.environment_path.join("main.tf"), "# edited").unwrap();
+        let err = PlanBundle::verify(&ctx, &bundle_dir, &manifest, &[vec![node.clone()]])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("environment files changed"));
+
+        std::fs::write(bundle_dir.join("vpc/dev/plan.tfplan"), b"other-plan").unwrap();
+        let err = PlanBundle::verify(&ctx, &bundle_dir, &manifest, &[vec![node]])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("does not match its recorded hash"));
+    }
+
+    #[test]
+    fn test_verify_refuses_graph_changes() {
+        let temp = TempDir::new().unwrap();
+        let ctx = real_context();
+        let node = create_environment(temp.path(), "vpc", "opentofu");
+        let database = create_environment(temp.path(), "database", "opentofu");
+        let shared = create_environment(temp.path(), "shared", "none");
+        let (bundle_dir, manifest) = saved_bundle(&ctx, temp.path(), &node);
+
+        // A new dependency without a plan is refused; dependency-only projects are skipped
+        let levels = vec![vec![database, shared.clone()], vec![node]];
+        let err = PlanBundle::verify(&ctx, &bundle_dir, &manifest, &levels)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("database:dev: no saved plan"));
+        assert!(!err.contains("shared:dev"));
+
+        // A planned project that left the graph is refused
+        let err = PlanBundle::verify(&ctx, &bundle_dir, &manifest, &[vec![shared]])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("vpc:dev: no longer part of the dependency graph"));
+    }
+
+    #[test]
+    fn test_write_manifest_requires_every_plan() {
+        let temp = TempDir::new().unwrap();
+        let ctx = real_context();
+        let node = create_environment(temp.path(), "vpc", "opentofu");
+        let bundle_dir = PlanBundle::prepare(&ctx, &temp.path().join("plans")).unwrap();
+
+        let result = PlanBundle::write_manifest(
+            &ctx,
+            &bundle_dir,
+            temp.path(),
+            &node,
+            &[vec![node.clone()]],
+            vec![],
+        );
+
+        assert!(result.is_err());
+        assert!(!bundle_dir.join(PLAN_MANIFEST_FILE).exists());
+    }
+
+    #[test]
+    fn test_bundle_with_mock_filesystem() {
+        let ctx = Context::test();
+        let root = ctx.fs.current_dir().unwrap();
+        let env_path = root.join("projects/vpc/environments/dev");
+        ctx.fs
+            .write(
+                &env_path.join(".pmp.environment.yaml"),
+                &environment_yaml("vpc", "opentofu"),
+            )
+            .unwrap();
+        ctx.fs.write(&env_path.join("main.tf"), "# vpc").unwrap();
+        let node = DependencyNode::new("vpc".to_string(), "dev".to_string(), env_path.clone());
+
+        let bundle_dir = PlanBundle::prepare(&ctx, Path::new("./plans")).unwrap();
+        assert_eq!(bundle_dir, root.join("plans"));
+
+        let plan_file = PlanBundle::prepare_plan_file(&ctx, &bundle_dir, &node).unwrap();
+        ctx.fs.write(&plan_file, "binary-plan").unwrap();
+        let entry = PlanBundle::record(&ctx, &bundle_dir, &root, &node, None).unwrap();
+        assert_eq!(entry.environment_path, "projects/vpc/environments/dev");
+
+        let levels = vec![vec![node.clone()]];
+        PlanBundle::write_manifest(&ctx, &bundle_dir, &root, &node, &levels, vec![entry]).unwrap();
+        let manifest = PlanBundle::load_manifest(&ctx, &bundle_dir).unwrap();
+        assert!(PlanBundle::verify(&ctx, &bundle_dir, &manifest, &levels).is_ok());
+
+        ctx.fs.write(&env_path.join("main.tf"), "# edited").unwrap();
+        let err = PlanBundle::verify(&ctx, &bundle_dir, &manifest, &levels)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("environment files changed"));
+    }
+
+    #[test]
+    fn test_inputs_hash_ignores_state_and_provider_cache() {
+        let temp = TempDir::new().unwrap();
+        let node = create_environment(temp.path(), "vpc", "opentofu");
+        let env_path = &node.environment_path;
+        let before = hash_environment_inputs(&RealFileSystem, env_path).unwrap();
+
+        std::fs::create_dir_all(env_path.join(".terraform/providers")).unwrap();
+        std::fs::write(env_path.join(".terraform/providers/cache"), "x").unwrap();
+        std::fs::write(env_path.join("terraform.tfstate"), "{}").unwrap();
+        std::fs::write(env_path.join(DEFAULT_PLAN_JSON_FILE), "{}").unwrap();
+        assert_eq!(
+            hash_environment_inputs(&RealFileSystem, env_path).unwrap(),
+            before
+        );
+
+        std::fs::write(env_path.join("variables.tf"), "variable \"a\" {}").unwrap();
+        assert_ne!(
+            hash_environment_inputs(&RealFileSystem, env_path).unwrap(),
+            before
+        );
+    }
+}
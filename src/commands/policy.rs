@@ -775,6 +775,20 @@ impl PolicyCommand {
         ctx: &Context,
         env_path: &Path,
         infrastructure: &crate::template::metadata::InfrastructureResource,
+    ) -> Result<bool> {
+        Self::run_pre_operation_validation_with_plan(
+            ctx,
+            Some(&env_path.join("plan.json")),
+            infrastructure,
+        )
+    }
+
+    /// Run OPA validation against a specific JSON plan (e.g. one saved in a plan bundle)
+    /// Returns true if validation passed or was skipped
+    pub fn run_pre_operation_validation_with_plan(
+        ctx: &Context,
+        plan_json: Option<&Path>,
+        infrastructure: &crate::template::metadata::InfrastructureResource,
     ) -> Result<bool> {
         let policy_config = infrastructure.spec.policy.as_ref();
 
@@ -812,10 +826,9 @@ impl PolicyCommand {
 
         ctx.output.dimmed(&format!("Loaded {} policies", loaded));
 
-        // Try to load plan.json from environment path
-        let plan_json = env_path.join("plan.json");
-        let input = if ctx.fs.exists(&plan_json) {
-            let content = ctx.fs.read_to_string(&plan_json)?;
+        // Try to load the JSON plan
+        let input = if let Some(plan_json) = plan_json.filter(|p| ctx.fs.exists(p)) {
+            let content = ctx.fs.read_to_string(plan_json)?;
             serde_json::from_str(&content)
                 .context("Failed to parse plan.json")?
         } else {
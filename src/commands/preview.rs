@@ -1,14 +1,17 @@
 use crate::collection::{CollectionDiscovery, CollectionManager, DependencyNode};
 use crate::commands::project_group::ProjectGroupHandler;
-use crate::commands::{CostCommand, ExecutionHelper, PolicyCommand};
-use crate::diff::{AsciiRenderer, DiffRenderer, DiffRenderOptions, HtmlRenderer, PlanParser};
-use crate::executor::{Executor, ExecutorConfig, OpenTofuExecutor};
+use crate::commands::{CostCommand, ExecutionHelper, PlanBundle, PolicyCommand};
+use crate::diff::{
+    AsciiRenderer, DiffRenderOptions, DiffRenderer, HtmlRenderer, JsonPlanParser, PlanParser,
+    PlanSummary,
+};
+use crate::executor::{DEFAULT_PLAN_FILE, DEFAULT_PLAN_JSON_FILE, Executor, ExecutorConfig};
 use crate::hooks::{HookOutcome, HooksRunner};
 use crate::template::metadata::{FailureBehavior, ParallelConfig};
 use crate::template::{DynamicProjectEnvironmentResource, ProjectResource};
 use anyhow::{Context, Result};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 /// Handles the 'preview' command - runs executor plan with hooks
 pub struct PreviewCommand;
@@ -28,6 +31,7 @@ impl PreviewCommand {
         diff_output: Option<&str>,
         show_unchanged: bool,
         show_sensitive: bool,
+        out: Option<&str>,
         extra_args: &[String],
     ) -> Result<()> {
         // Check for template packs before proceeding
@@ -86,6 +90,12 @@ impl PreviewCommand {
         // Check if this is a ProjectGroup with spec.projects defined
         // ProjectGroups have special handling - they execute preview on their defined projects
         if executor_config.name == "none" && !resource.spec.projects.is_empty() {
+            if out.is_some() {
+                anyhow::bail!(
+                    "Saving plans with --out is not supported for project groups. Run preview on the member projects instead."
+                );
+            }
+
             // Load collection to get infrastructure-level hooks
             let (collection, _collection_root) = CollectionDiscovery::find_collection(&*ctx.fs)?
                 .context("Infrastructure is required to run commands")?;
@@ -103,7 +113,7 @@ impl PreviewCommand {
 
             // Run pre-preview hooks
             if !hooks.pre_preview.is_empty()
-                && HooksRunner::run_hooks(&hooks.pre_preview, env_dir_str, "pre-preview")?
+                && HooksRunner::run_hooks(ctx, &hooks.pre_preview, env_dir_str, "pre-preview")?
                     == HookOutcome::Cancel
             {
                 ctx.output.blank();
@@ -132,7 +142,7 @@ impl PreviewCommand {
 
             // Run post-preview hooks
             if !hooks.post_preview.is_empty()
-                && HooksRunner::run_hooks(&hooks.post_preview, env_dir_str, "post-preview")?
+                && HooksRunner::run_hooks(ctx, &hooks.post_preview, env_dir_str, "post-preview")?
                     == HookOutcome::Cancel
             {
                 ctx.output.blank();
@@ -157,18 +167,46 @@ impl PreviewCommand {
 
         if let Some(graph) = maybe_graph {
             // Load collection to get parallel config
-            let (collection, _) = CollectionDiscovery::find_collection(&*ctx.fs)?
+            let (collection, collection_root) = CollectionDiscovery::find_collection(&*ctx.fs)?
                 .context("Infrastructure is required to run commands")?;
 
             // Build parallel config from CLI flag or infrastructure config
             let parallel_config = Self::build_parallel_config(parallel, &collection);
 
+            // Prepare the plan bundle when saving plans
+            let bundle_dir = match out {
+                Some(dir) => Some(PlanBundle::prepare(ctx, Path::new(dir))?),
+                None => None,
+            };
+            let saved_plans = Arc::new(Mutex::new(Vec::new()));
+
             // Execute preview on entire dependency graph
             let ctx_clone = ctx.clone();
+            let node_bundle_dir = bundle_dir.clone();
+            let node_root = collection_root.clone();
+            let node_saved_plans = Arc::clone(&saved_plans);
             let executor_fn: Arc<
                 dyn Fn(&crate::context::Context, &DependencyNode) -> Result<()> + Send + Sync,
             > = Arc::new(move |ctx, node| {
-                Self::execute_preview_on_node_wrapper(ctx, node)
+                let Some(bundle_dir) = &node_bundle_dir else {
+                    return Self::execute_preview_on_node_wrapper(ctx, node, None);
+                };
+
+                if !PlanBundle::requires_plan(ctx, node)? {
+                    return Self::execute_preview_on_node_wrapper(ctx, node, None);
+                }
+
+                let plan_file = PlanBundle::prepare_plan_file(ctx, bundle_dir, node)?;
+                Self::execute_preview_on_node_wrapper(ctx, node, Some(&plan_file))?;
+
+                let plan_json = PlanBundle::render_plan_json(ctx, node, &plan_file)?;
+                let entry =
+                    PlanBundle::record(ctx, bundle_dir, &node_root, node, plan_json.as_deref())?;
+                node_saved_plans
+                    .lock()
+                    .map_err(|_| anyhow::anyhow!("Saved plans lock poisoned"))?
+                    .push(entry);
+                Ok(())
             });
 
             ExecutionHelper::execute_on_graph_parallel(
@@ -180,6 +218,22 @@ impl PreviewCommand {
                 executor_fn,
             )?;
 
+            if let Some(bundle_dir) = &bundle_dir {
+                let plans = saved_plans
+                    .lock()
+                    .map_err(|_| anyhow::anyhow!("Saved plans lock poisoned"))?
+                    .clone();
+                let manifest_path = PlanBundle::write_manifest(
+                    ctx,
+                    bundle_dir,
+                    &collection_root,
+                    &graph.root,
+                    &graph.group_by_level()?,
+                    plans,
+                )?;
+                Self::display_saved_plans(ctx, &manifest_path);
+            }
+
             ctx.output.blank();
             ctx.output
                 .success("Preview completed successfully for all projects");
@@ -189,7 +243,7 @@ impl PreviewCommand {
         // No dependencies - proceed with single project execution
 
         // Load collection to get infrastructure-level hooks
-        let (collection, _collection_root) = CollectionDiscovery::find_collection(&*ctx.fs)?
+        let (collection, collection_root) = CollectionDiscovery::find_collection(&*ctx.fs)?
             .context("Infrastructure is required to run commands")?;
 
         let infrastructure_hooks = collection.get_hooks();
@@ -200,8 +254,23 @@ impl PreviewCommand {
             resource.spec.hooks.as_ref(),
         );
 
+        // Saved plans are stored per project environment inside the plan bundle
+        let node = DependencyNode::new(project_name.clone(), env_name.clone(), env_path.clone());
+        let bundle_dir = match out {
+            Some(dir) => Some(PlanBundle::prepare(ctx, Path::new(dir))?),
+            None => None,
+        };
+        let plan_file = match &bundle_dir {
+            Some(bundle_dir) if PlanBundle::requires_plan(ctx, &node)? => Some(
+                PlanBundle::prepare_plan_file(ctx, bundle_dir, &node)?
+                    .to_string_lossy()
+                    .to_string(),
+            ),
+            _ => None,
+        };
+
         // Get executor
-        let executor = Self::get_executor(&executor_config.name)?;
+        let executor = ctx.executor_registry.get(&executor_config.name)?;
 
         // Check if executor is installed
         ctx.output.subsection("Prerequisites");
@@ -226,7 +295,7 @@ impl PreviewCommand {
 
         // Run pre-preview hooks
         if !hooks.pre_preview.is_empty()
-            && HooksRunner::run_hooks(&hooks.pre_preview, env_dir_str, "pre-preview")?
+            && HooksRunner::run_hooks(ctx, &hooks.pre_preview, env_dir_str, "pre-preview")?
                 == HookOutcome::Cancel
         {
             ctx.output.blank();
@@ -282,6 +351,7 @@ impl PreviewCommand {
             refresh_command: None,
             test_command: None,
             command_options,
+            plan_file,
         };
 
         // Run plan
@@ -289,9 +359,9 @@ impl PreviewCommand {
         ctx.output
             .dimmed(&format!("Executing {} plan...", executor.get_name()));
 
-        if show_diff {
+        let plan_summary = if show_diff {
             // Use plan_with_output to capture output for diff visualization
-            Self::execute_plan_with_diff(
+            Some(Self::execute_plan_with_diff(
                 ctx,
                 executor.as_ref(),
                 &execution_config,
@@ -302,10 +372,37 @@ impl PreviewCommand {
                 diff_output,
                 show_unchanged,
                 show_sensitive,
-            )?;
+            )?)
         } else {
             // Standard plan execution with direct output
             executor.plan(&execution_config, env_dir_str, extra_args)?;
+            None
+        };
+
+        // Record the saved plan in the bundle manifest
+        if let Some(bundle_dir) = &bundle_dir {
+            let plans = match &execution_config.plan_file {
+                Some(plan_file) => {
+                    let plan_json = executor.show_plan_json(env_dir_str, plan_file)?;
+                    vec![PlanBundle::record(
+                        ctx,
+                        bundle_dir,
+                        &collection_root,
+                        &node,
+                        plan_json.as_deref(),
+                    )?]
+                }
+                None => vec![],
+            };
+            let manifest_path = PlanBundle::write_manifest(
+                ctx,
+                bundle_dir,
+                &collection_root,
+                &node,
+                &[vec![node.clone()]],
+                plans,
+            )?;
+            Self::display_saved_plans(ctx, &manifest_path);
         }
 
         // Show cost estimation if requested
@@ -325,8 +422,13 @@ impl PreviewCommand {
 
         // Run post-preview hooks
         if !hooks.post_preview.is_empty()
-            && HooksRunner::run_hooks(&hooks.post_preview, env_dir_str, "post-preview")?
-                == HookOutcome::Cancel
+            && HooksRunner::run_hooks_with_plan(
+                ctx,
+                &hooks.post_preview,
+                env_dir_str,
+                "post-preview",
+                plan_summary.as_ref(),
+            )? == HookOutcome::Cancel
         {
             ctx.output.blank();
             ctx.output
@@ -519,14 +621,6 @@ impl PreviewCommand {
         Ok((env_path, selected_project.name.clone(), env_name))
     }
 
-    /// Get the appropriate executor based on name
-    fn get_executor(name: &str) -> Result<Box<dyn Executor>> {
-        match name {
-            "opentofu" => Ok(Box::new(OpenTofuExecutor::new())),
-            _ => anyhow::bail!("Unknown executor: {}", name),
-        }
-    }
-
     /// Build parallel config from CLI flag or infrastructure config
     fn build_parallel_config(
         cli_parallel: Option<usize>,
@@ -554,10 +648,25 @@ impl PreviewCommand {
         }
     }
 
+    /// Show where the saved plans were written
+    fn display_saved_plans(ctx: &crate::context::Context, manifest_path: &Path) {
+        ctx.output.blank();
+        ctx.output.subsection("Saved Plans");
+        ctx.output
+            .key_value("Manifest", &manifest_path.display().to_string());
+        if let Some(bundle_dir) = manifest_path.parent() {
+            ctx.output.dimmed(&format!(
+                "Apply exactly these plans with: pmp project apply --plan {}",
+                bundle_dir.display()
+            ));
+        }
+    }
+
     /// Wrapper for execute_preview_on_node that works with parallel execution
     fn execute_preview_on_node_wrapper(
         ctx: &crate::context::Context,
         node: &DependencyNode,
+        plan_file: Option<&Path>,
     ) -> Result<()> {
         // Load environment resource
         let env_file = node.environment_path.join(".pmp.environment.yaml");
@@ -568,7 +677,7 @@ impl PreviewCommand {
         let executor_config = resource.get_executor_config();
 
         // Get executor
-        let executor = ExecutionHelper::get_executor(&executor_config.name)?;
+        let executor = ctx.executor_registry.get(&executor_config.name)?;
 
         // Build executor config
         let mut command_options = std::collections::HashMap::new();
@@ -586,6 +695,7 @@ impl PreviewCommand {
             refresh_command: None,
             test_command: None,
             command_options,
+            plan_file: plan_file.map(|p| p.to_string_lossy().to_string()),
         };
 
         // Execute preview on this node
@@ -598,7 +708,7 @@ impl PreviewCommand {
         )
     }
 
-    /// Execute plan with diff visualization
+    /// Execute plan with diff visualization, returning the plan's summary
     #[allow(clippy::too_many_arguments)]
     fn execute_plan_with_diff(
         ctx: &crate::context::Context,
@@ -611,9 +721,19 @@ impl PreviewCommand {
         diff_output: Option<&str>,
         show_unchanged: bool,
         show_sensitive: bool,
-    ) -> Result<()> {
-        // Run plan and capture output
-        let output = executor.plan_with_output(working_dir, extra_args)?;
+    ) -> Result<PlanSummary> {
+        // Run plan saving the binary plan and capture output
+        let plan_file = config.plan_file.as_deref().unwrap_or(DEFAULT_PLAN_FILE);
+        let saved_plan = executor.save_plan(working_dir, plan_file, extra_args);
+
+        // The binary plan is only kept when it goes into a plan bundle
+        let plan_path = PathBuf::from(working_dir).join(plan_file);
+        if config.plan_file.is_none() && ctx.fs.exists(&plan_path) {
+            ctx.fs.remove_file(&plan_path)?;
+        }
+
+        let saved_plan = saved_plan?;
+        let output = &saved_plan.output;
 
         // Check if plan succeeded
         if !output.status.success() && output.status.code() != Some(2) {
@@ -630,10 +750,19 @@ impl PreviewCommand {
             );
         }
 
-        // Parse plan output
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let parser = PlanParser::new();
-        let parsed_plan = parser.parse(&stdout)?;
+        // Parse the JSON plan when available, falling back to the human-readable output
+        let parsed_plan = if let Some(plan_json) = &saved_plan.json {
+            // Persist the JSON plan so policy validation evaluates the same plan
+            ctx.fs.write(
+                &PathBuf::from(working_dir).join(DEFAULT_PLAN_JSON_FILE),
+                plan_json,
+            )?;
+
+            JsonPlanParser::new().parse(plan_json)?
+        } else {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            PlanParser::new().parse(&stdout)?
+        };
 
         // Build render options
         let terminal_width = Self::get_terminal_width();
@@ -685,7 +814,7 @@ impl PreviewCommand {
             ctx.output.success("No changes. Your infrastructure matches the configuration.");
         }
 
-        Ok(())
+        Ok(parsed_plan.summary)
     }
 
     /// Print colored diff to terminal using output colors
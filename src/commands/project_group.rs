@@ -1,7 +1,7 @@
 //! Project Group operations - handles creating/updating projects defined in a project group
 
 use crate::collection::CollectionDiscovery;
-use crate::executor::{Executor, ExecutorConfig, OpenTofuExecutor};
+use crate::executor::{Executor, ExecutorConfig};
 use crate::hooks::{HookOutcome, HooksRunner};
 use crate::template::metadata::{
     PluginDependency, ProjectGroupInputConfig, ProjectGroupPluginReferenceProject,
@@ -212,7 +212,7 @@ impl ProjectGroupHandler {
         }
 
         // Get executor
-        let executor = Self::get_executor(&executor_config.name)?;
+        let executor = ctx.executor_registry.get(&executor_config.name)?;
 
         // Execute the command
         Self::execute_with_executor(ctx, &env_path, executor.as_ref(), command, extra_args)
@@ -231,7 +231,7 @@ impl ProjectGroupHandler {
             .context("Failed to convert environment path to string")?;
 
         // Load collection to get infrastructure-level hooks
-        let (collection, _collection_root) = CollectionDiscovery::find_collection(&*ctx.fs)?
+        let (collection, collection_root) = CollectionDiscovery::find_collection(&*ctx.fs)?
             .context("Infrastructure is required to run commands")?;
 
         let infrastructure_hooks = collection.get_hooks();
@@ -257,7 +257,7 @@ impl ProjectGroupHandler {
         };
 
         if !pre_hooks.is_empty()
-            && HooksRunner::run_hooks(pre_hooks, env_dir_str, &format!("pre-{}", command))?
+            && HooksRunner::run_hooks(ctx, pre_hooks, env_dir_str, &format!("pre-{}", command))?
                 == HookOutcome::Cancel
         {
             ctx.output
@@ -303,6 +303,7 @@ impl ProjectGroupHandler {
             refresh_command: None,
             test_command: None,
             command_options,
+            plan_file: None,
         };
 
         // Execute the command
@@ -315,12 +316,32 @@ impl ProjectGroupHandler {
             "apply" => {
                 ctx.output
                     .dimmed(&format!("Executing {} apply...", executor.get_name()));
-                executor.apply(&execution_config, env_dir_str, extra_args)?;
+                crate::commands::ExecutionHelper::with_state_guard(
+                    ctx,
+                    &collection,
+                    &collection_root,
+                    executor,
+                    env_dir_str,
+                    &env_resource.metadata.name,
+                    &env_resource.metadata.environment_name,
+                    "apply",
+                    || executor.apply(&execution_config, env_dir_str, extra_args),
+                )?;
             }
             "destroy" => {
                 ctx.output
                     .dimmed(&format!("Executing {} destroy...", executor.get_name()));
-                executor.destroy(&execution_config, env_dir_str, extra_args)?;
+                crate::commands::ExecutionHelper::with_state_guard(
+                    ctx,
+                    &collection,
+                    &collection_root,
+                    executor,
+                    env_dir_str,
+                    &env_resource.metadata.name,
+                    &env_resource.metadata.environment_name,
+                    "destroy",
+                    || executor.destroy(&execution_config, env_dir_str, extra_args),
+                )?;
             }
             _ => return Err(anyhow::anyhow!("Unknown command: {}", command)),
         }
@@ -334,7 +355,7 @@ impl ProjectGroupHandler {
         };
 
         if !post_hooks.is_empty()
-            && HooksRunner::run_hooks(post_hooks, env_dir_str, &format!("post-{}", command))?
+            && HooksRunner::run_hooks(ctx, post_hooks, env_dir_str, &format!("post-{}", command))?
                 == HookOutcome::Cancel
         {
             ctx.output.warning(&format!(
@@ -348,14 +369,6 @@ impl ProjectGroupHandler {
         Ok(())
     }
 
-    /// Get the appropriate executor based on name
-    fn get_executor(name: &str) -> Result<Box<dyn Executor>> {
-        match name {
-            "opentofu" => Ok(Box::new(OpenTofuExecutor::new())),
-            _ => anyhow::bail!("Unknown executor: {}", name),
-        }
-    }
-
     /// Process a single project from the project group configuration
     fn process_single_project(
         ctx: &crate::context::Context,
@@ -459,6 +472,7 @@ impl ProjectGroupHandler {
                     Some(env_path.to_str().unwrap()),
                     template_packs_paths,
                     inputs_json.as_deref(),
+                    crate::template::MergeStrategy::default(),
                 )?;
             } else {
                 ctx.output.warning(&format!(
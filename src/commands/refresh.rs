@@ -1,5 +1,5 @@
 use crate::collection::{CollectionDiscovery, CollectionManager};
-use crate::executor::{Executor, ExecutorConfig, OpenTofuExecutor};
+use crate::executor::ExecutorConfig;
 use crate::hooks::{HookOutcome, HooksRunner};
 use crate::template::{DynamicProjectEnvironmentResource, ProjectResource};
 use anyhow::{Context, Result};
@@ -69,7 +69,7 @@ impl RefreshCommand {
         let executor_config = resource.get_executor_config();
 
         // Load collection to get infrastructure-level hooks
-        let (collection, _collection_root) = CollectionDiscovery::find_collection(&*ctx.fs)?
+        let (collection, collection_root) = CollectionDiscovery::find_collection(&*ctx.fs)?
             .context("Infrastructure is required to run commands")?;
 
         let infrastructure_hooks = collection.get_hooks();
@@ -81,7 +81,7 @@ impl RefreshCommand {
         );
 
         // Get executor
-        let executor = Self::get_executor(&executor_config.name)?;
+        let executor = ctx.executor_registry.get(&executor_config.name)?;
 
         // Check if executor is installed
         ctx.output.subsection("Prerequisites");
@@ -106,7 +106,7 @@ impl RefreshCommand {
 
         // Run pre-refresh hooks
         if !hooks.pre_refresh.is_empty()
-            && HooksRunner::run_hooks(&hooks.pre_refresh, env_dir_str, "pre-refresh")?
+            && HooksRunner::run_hooks(ctx, &hooks.pre_refresh, env_dir_str, "pre-refresh")?
                 == HookOutcome::Cancel
         {
             ctx.output.blank();
@@ -155,6 +155,7 @@ impl RefreshCommand {
             refresh_command: None,
             test_command: None,
             command_options,
+            plan_file: None,
         };
 
         // Run refresh
@@ -162,10 +163,18 @@ impl RefreshCommand {
         ctx.output
             .dimmed(&format!("Executing {} refresh...", executor.get_name()));
         executor.refresh(&execution_config, env_dir_str, extra_args)?;
+        crate::commands::ExecutionHelper::refresh_state_snapshot(
+            ctx,
+            &collection_root,
+            executor.as_ref(),
+            env_dir_str,
+            &resource.metadata.name,
+            &resource.metadata.environment_name,
+        );
 
         // Run post-refresh hooks
         if !hooks.post_refresh.is_empty()
-            && HooksRunner::run_hooks(&hooks.post_refresh, env_dir_str, "post-refresh")?
+            && HooksRunner::run_hooks(ctx, &hooks.post_refresh, env_dir_str, "post-refresh")?
                 == HookOutcome::Cancel
         {
             ctx.output.blank();
@@ -298,12 +307,4 @@ impl RefreshCommand {
 
         Ok((env_path, selected_project.name.clone(), env_name))
     }
-
-    /// Get the appropriate executor based on name
-    fn get_executor(name: &str) -> Result<Box<dyn Executor>> {
-        match name {
-            "opentofu" => Ok(Box::new(OpenTofuExecutor::new())),
-            _ => anyhow::bail!("Unknown executor: {}", name),
-        }
-    }
 }
@@ -1,6 +1,7 @@
 use crate::collection::CollectionDiscovery;
 use crate::context::Context;
 use crate::output;
+use crate::state::{SnapshotStore, StateDocument, StateSnapshot};
 use crate::template::metadata::DynamicProjectEnvironmentResource;
 use anyhow::{Context as AnyhowContext, Result};
 use serde::{Deserialize, Serialize};
@@ -39,9 +40,12 @@ pub struct Match {
 }
 
 impl SearchCommand {
-    pub fn execute_by_tags(ctx: &Context, tag_filters: Vec<String>) -> Result<()> {
-        ctx.output.section("Search by Tags");
-        output::blank();
+    pub fn execute_by_tags(ctx: &Context, tag_filters: Vec<String>, format: &str) -> Result<()> {
+        let structured = Self::is_structured(format)?;
+        if !structured {
+            ctx.output.section("Search by Tags");
+            output::blank();
+        }
 
         let (_infrastructure, infrastructure_root) =
             CollectionDiscovery::find_collection(&*ctx.fs)?
@@ -58,306 +62,270 @@ impl SearchCommand {
             }
         }
 
-        ctx.output.subsection("Search Criteria");
-        for (key, value) in &filter_map {
-            if let Some(v) = value {
-                ctx.output.dimmed(&format!("{} = {}", key, v));
-            } else {
-                ctx.output.dimmed(&format!("{} (any value)", key));
+        if !structured {
+            ctx.output.subsection("Search Criteria");
+            for (key, value) in &filter_map {
+                if let Some(v) = value {
+                    ctx.output.dimmed(&format!("{} = {}", key, v));
+                } else {
+                    ctx.output.dimmed(&format!("{} (any value)", key));
+                }
             }
+            output::blank();
         }
-        output::blank();
 
         // Search projects
-        let projects = crate::collection::CollectionDiscovery::discover_projects(
-            &*ctx.fs,
-            &*ctx.output,
-            &infrastructure_root,
-        )?;
+        let index = CollectionDiscovery::load_index(&*ctx.fs, &*ctx.output, &infrastructure_root)?;
 
         let mut results = Vec::new();
 
-        for project in &projects {
-            let project_path = infrastructure_root.join(&project.path);
-            let environments_dir = project_path.join("environments");
-
-            if !ctx.fs.exists(&environments_dir) {
-                continue;
-            }
-
-            for env_entry in ctx.fs.read_dir(&environments_dir)? {
-                if !ctx.fs.is_dir(&env_entry) {
-                    continue;
-                }
+        for (_project, environment) in index.environments() {
+            let resource = &environment.resource;
 
-                let env_file = env_entry.join(".pmp.environment.yaml");
-                if !ctx.fs.exists(&env_file) {
-                    continue;
-                }
+            // Load tags
+            if let Ok(tag_config) = Self::load_tags(ctx, &infrastructure_root, resource) {
+                let mut matches = Vec::new();
+                let mut all_match = true;
 
-                let resource = DynamicProjectEnvironmentResource::from_file(&*ctx.fs, &env_file)?;
-
-                // Load tags
-                if let Ok(tag_config) = Self::load_tags(ctx, &infrastructure_root, &resource) {
-                    let mut matches = Vec::new();
-                    let mut all_match = true;
-
-                    for (filter_key, filter_value) in &filter_map {
-                        if let Some(tag_value) = tag_config.tags.get(filter_key) {
-                            if let Some(expected_value) = filter_value {
-                                if tag_value == expected_value {
-                                    matches.push(Match {
-                                        field: filter_key.clone(),
-                                        value: tag_value.clone(),
-                                        context: None,
-                                    });
-                                } else {
-                                    all_match = false;
-                                    break;
-                                }
-                            } else {
-                                // Just checking for key existence
+                for (filter_key, filter_value) in &filter_map {
+                    if let Some(tag_value) = tag_config.tags.get(filter_key) {
+                        if let Some(expected_value) = filter_value {
+                            if tag_value == expected_value {
                                 matches.push(Match {
                                     field: filter_key.clone(),
                                     value: tag_value.clone(),
                                     context: None,
                                 });
+                            } else {
+                                all_match = false;
+                                break;
                             }
                         } else {
-                            all_match = false;
-                            break;
+                            // Just checking for key existence
+                            matches.push(Match {
+                                field: filter_key.clone(),
+                                value: tag_value.clone(),
+                                context: None,
+                            });
                         }
+                    } else {
+                        all_match = false;
+                        break;
                     }
+                }
 
-                    if all_match && !matches.is_empty() {
-                        results.push(SearchResult {
-                            project: resource.metadata.name.clone(),
-                            environment: resource.metadata.environment_name.clone(),
-                            match_type: MatchType::Tag,
-                            matches,
-                        });
-                    }
+                if all_match && !matches.is_empty() {
+                    results.push(SearchResult {
+                        project: resource.metadata.name.clone(),
+                        environment: resource.metadata.environment_name.clone(),
+                        match_type: MatchType::Tag,
+                        matches,
+                    });
                 }
             }
         }
 
         // Display results
-        Self::display_search_results(ctx, &results)?;
+        Self::display_search_results(ctx, &results, format)?;
 
         Ok(())
     }
 
+    /// Search resources in the state snapshots of all environments
+    ///
+    /// `query` matches a resource type exactly or any part of a resource address;
+    /// `attribute_filters` (KEY=VALUE or KEY, dotted keys for nested attributes) must all match.
     pub fn execute_by_resources(
         ctx: &Context,
-        resource_type: Option<&str>,
-        resource_name: Option<&str>,
+        query: Option<&str>,
+        attribute_filters: &[String],
+        refresh: bool,
+        format: &str,
     ) -> Result<()> {
-        ctx.output.section("Search by Resources");
-        output::blank();
-
-        let (_infrastructure, infrastructure_root) =
-            CollectionDiscovery::find_collection(&*ctx.fs)?
-                .context("Infrastructure is required. Run 'pmp init' first.")?;
-
-        ctx.output.subsection("Search Criteria");
-        if let Some(rtype) = resource_type {
-            ctx.output.dimmed(&format!("Resource type: {}", rtype));
-        }
-        if let Some(rname) = resource_name {
-            ctx.output.dimmed(&format!("Resource name: {}", rname));
-        }
-        output::blank();
-
-        // Search projects
-        let projects = crate::collection::CollectionDiscovery::discover_projects(
-            &*ctx.fs,
-            &*ctx.output,
-            &infrastructure_root,
-        )?;
+        let structured = Self::is_structured(format)?;
+        let filters = Self::parse_filters(attribute_filters);
 
-        let mut results = Vec::new();
-
-        for project in &projects {
-            let project_path = infrastructure_root.join(&project.path);
-            let environments_dir = project_path.join("environments");
+        if !structured {
+            ctx.output.section("Search by Resources");
+            output::blank();
 
-            if !ctx.fs.exists(&environments_dir) {
-                continue;
+            ctx.output.subsection("Search Criteria");
+            if let Some(query) = query {
+                ctx.output.dimmed(&format!("Resource: {}", query));
             }
-
-            for env_entry in ctx.fs.read_dir(&environments_dir)? {
-                if !ctx.fs.is_dir(&env_entry) {
-                    continue;
-                }
-
-                let env_file = env_entry.join(".pmp.environment.yaml");
-                if !ctx.fs.exists(&env_file) {
-                    continue;
+            for (key, value) in &filters {
+                match value {
+                    Some(v) => ctx.output.dimmed(&format!("{} = {}", key, v)),
+                    None => ctx.output.dimmed(&format!("{} (any value)", key)),
                 }
+            }
+            output::blank();
+        }
 
-                let resource = DynamicProjectEnvironmentResource::from_file(&*ctx.fs, &env_file)?;
-
-                // Search in Terraform files
-                let matches = Self::search_terraform_resources(
-                    ctx,
-                    &env_entry,
-                    resource_type,
-                    resource_name,
-                )?;
+        let (snapshots, missing) = Self::load_snapshots(ctx, refresh, structured)?;
+        let mut results = Vec::new();
 
-                if !matches.is_empty() {
-                    results.push(SearchResult {
-                        project: resource.metadata.name.clone(),
-                        environment: resource.metadata.environment_name.clone(),
-                        match_type: MatchType::Resource,
-                        matches,
-                    });
-                }
+        for snapshot in &snapshots {
+            let matches: Vec<Match> = snapshot
+                .state
+                .resources
+                .iter()
+                .filter(|r| query.is_none_or(|q| r.resource_type == q || r.address.contains(q)))
+                .filter(|r| Self::attributes_match(&r.attributes, &filters))
+                .map(|r| Match {
+                    field: "resource".to_string(),
+                    value: r.address.clone(),
+                    context: Self::resource_context(&r.attributes, &filters),
+                })
+                .collect();
+
+            if !matches.is_empty() {
+                results.push(SearchResult {
+                    project: snapshot.project.clone(),
+                    environment: snapshot.environment.clone(),
+                    match_type: MatchType::Resource,
+                    matches,
+                });
             }
         }
 
-        // Display results
-        Self::display_search_results(ctx, &results)?;
+        Self::display_search_results(ctx, &results, format)?;
+        if !structured {
+            Self::display_missing_snapshots(ctx, missing);
+        }
 
         Ok(())
     }
 
-    pub fn execute_by_name(ctx: &Context, pattern: &str) -> Result<()> {
-        ctx.output.section("Search by Name");
-        output::blank();
+    pub fn execute_by_name(ctx: &Context, pattern: &str, format: &str) -> Result<()> {
+        let structured = Self::is_structured(format)?;
+        if !structured {
+            ctx.output.section("Search by Name");
+            output::blank();
+        }
 
         let (_infrastructure, infrastructure_root) =
             CollectionDiscovery::find_collection(&*ctx.fs)?
                 .context("Infrastructure is required. Run 'pmp init' first.")?;
 
-        ctx.output.subsection("Search Criteria");
-        ctx.output.dimmed(&format!("Pattern: {}", pattern));
-        output::blank();
+        if !structured {
+            ctx.output.subsection("Search Criteria");
+            ctx.output.dimmed(&format!("Pattern: {}", pattern));
+            output::blank();
+        }
 
         // Search projects
-        let projects = crate::collection::CollectionDiscovery::discover_projects(
-            &*ctx.fs,
-            &*ctx.output,
-            &infrastructure_root,
-        )?;
+        let index = CollectionDiscovery::load_index(&*ctx.fs, &*ctx.output, &infrastructure_root)?;
 
         let mut results = Vec::new();
 
-        for project in &projects {
-            let project_path = infrastructure_root.join(&project.path);
-            let environments_dir = project_path.join("environments");
-
-            if !ctx.fs.exists(&environments_dir) {
-                continue;
+        for (_project, environment) in index.environments() {
+            let resource = &environment.resource;
+
+            // Check project name
+            if resource.metadata.name.contains(pattern) {
+                results.push(SearchResult {
+                    project: resource.metadata.name.clone(),
+                    environment: resource.metadata.environment_name.clone(),
+                    match_type: MatchType::Name,
+                    matches: vec![Match {
+                        field: "project".to_string(),
+                        value: resource.metadata.name.clone(),
+                        context: None,
+                    }],
+                });
             }
 
-            for env_entry in ctx.fs.read_dir(&environments_dir)? {
-                if !ctx.fs.is_dir(&env_entry) {
-                    continue;
-                }
-
-                let env_file = env_entry.join(".pmp.environment.yaml");
-                if !ctx.fs.exists(&env_file) {
-                    continue;
-                }
-
-                let resource = DynamicProjectEnvironmentResource::from_file(&*ctx.fs, &env_file)?;
-
-                // Check project name
-                if resource.metadata.name.contains(pattern) {
-                    results.push(SearchResult {
-                        project: resource.metadata.name.clone(),
-                        environment: resource.metadata.environment_name.clone(),
-                        match_type: MatchType::Name,
-                        matches: vec![Match {
-                            field: "project".to_string(),
-                            value: resource.metadata.name.clone(),
-                            context: None,
-                        }],
-                    });
-                }
-
-                // Check environment name
-                if resource.metadata.environment_name.contains(pattern)
-                    && !resource.metadata.name.contains(pattern)
-                {
-                    results.push(SearchResult {
-                        project: resource.metadata.name.clone(),
-                        environment: resource.metadata.environment_name.clone(),
-                        match_type: MatchType::Name,
-                        matches: vec![Match {
-                            field: "environment".to_string(),
-                            value: resource.metadata.environment_name.clone(),
-                            context: None,
-                        }],
-                    });
-                }
+            // Check environment name
+            if resource.metadata.environment_name.contains(pattern)
+                && !resource.metadata.name.contains(pattern)
+            {
+                results.push(SearchResult {
+                    project: resource.metadata.name.clone(),
+                    environment: resource.metadata.environment_name.clone(),
+                    match_type: MatchType::Name,
+                    matches: vec![Match {
+                        field: "environment".to_string(),
+                        value: resource.metadata.environment_name.clone(),
+                        context: None,
+                    }],
+                });
             }
         }
 
         // Display results
-        Self::display_search_results(ctx, &results)?;
+        Self::display_search_results(ctx, &results, format)?;
 
         Ok(())
     }
 
-    pub fn execute_by_output(ctx: &Context, output_name: &str) -> Result<()> {
-        ctx.output.section("Search by Output");
-        output::blank();
-
-        let (_infrastructure, infrastructure_root) =
-            CollectionDiscovery::find_collection(&*ctx.fs)?
-                .context("Infrastructure is required. Run 'pmp init' first.")?;
+    /// Search output values in the state snapshots of all environments
+    ///
+    /// Every filter (NAME=VALUE or NAME) must match an output of the environment.
+    pub fn execute_by_output(
+        ctx: &Context,
+        output_filters: &[String],
+        refresh: bool,
+        format: &str,
+    ) -> Result<()> {
+        let structured = Self::is_structured(format)?;
+        let filters = Self::parse_filters(output_filters);
 
-        ctx.output.subsection("Search Criteria");
-        ctx.output.dimmed(&format!("Output name: {}", output_name));
-        output::blank();
+        if !structured {
+            ctx.output.section("Search by Output");
+            output::blank();
 
-        // Search projects
-        let projects = crate::collection::CollectionDiscovery::discover_projects(
-            &*ctx.fs,
-            &*ctx.output,
-            &infrastructure_root,
-        )?;
+            ctx.output.subsection("Search Criteria");
+            for (name, value) in &filters {
+                match value {
+                    Some(v) => ctx.output.dimmed(&format!("{} = {}", name, v)),
+                    None => ctx.output.dimmed(&format!("{} (any value)", name)),
+                }
+            }
+            output::blank();
+        }
 
+        let (snapshots, missing) = Self::load_snapshots(ctx, refresh, structured)?;
         let mut results = Vec::new();
 
-        for project in &projects {
-            let project_path = infrastructure_root.join(&project.path);
-            let environments_dir = project_path.join("environments");
-
-            if !ctx.fs.exists(&environments_dir) {
-                continue;
-            }
+        for snapshot in &snapshots {
+            let mut matches = Vec::new();
 
-            for env_entry in ctx.fs.read_dir(&environments_dir)? {
-                if !ctx.fs.is_dir(&env_entry) {
-                    continue;
-                }
+            for (name, expected) in &filters {
+                let Some(state_output) = snapshot.state.outputs.get(name) else {
+                    break;
+                };
+                let value = Self::format_value(&state_output.value);
 
-                let env_file = env_entry.join(".pmp.environment.yaml");
-                if !ctx.fs.exists(&env_file) {
-                    continue;
+                if expected.as_ref().is_some_and(|e| *e != value) {
+                    break;
                 }
 
-                let resource = DynamicProjectEnvironmentResource::from_file(&*ctx.fs, &env_file)?;
-
-                // Search for outputs in Terraform files
-                let matches = Self::search_terraform_outputs(ctx, &env_entry, output_name)?;
+                matches.push(Match {
+                    field: "output".to_string(),
+                    value: name.clone(),
+                    context: Some(if state_output.sensitive {
+                        "(sensitive)".to_string()
+                    } else {
+                        value
+                    }),
+                });
+            }
 
-                if !matches.is_empty() {
-                    results.push(SearchResult {
-                        project: resource.metadata.name.clone(),
-                        environment: resource.metadata.environment_name.clone(),
-                        match_type: MatchType::Output,
-                        matches,
-                    });
-                }
+            if !matches.is_empty() && matches.len() == filters.len() {
+                results.push(SearchResult {
+                    project: snapshot.project.clone(),
+                    environment: snapshot.environment.clone(),
+                    match_type: MatchType::Output,
+                    matches,
+                });
             }
         }
 
-        // Display results
-        Self::display_search_results(ctx, &results)?;
+        Self::display_search_results(ctx, &results, format)?;
+        if !structured {
+            Self::display_missing_snapshots(ctx, missing);
+        }
 
         Ok(())
     }
@@ -389,106 +357,205 @@ impl SearchCommand {
         Ok(TagConfig { tags })
     }
 
-    fn search_terraform_resources(
+    /// State snapshots of all environments, pulled live first when `refresh` is set
+    ///
+    /// Returns the snapshots and the number of environments without one.
+    fn load_snapshots(
         ctx: &Context,
-        env_path: &Path,
-        resource_type: Option<&str>,
-        resource_name: Option<&str>,
-    ) -> Result<Vec<Match>> {
-        let mut matches = Vec::new();
-
-        // Parse Terraform files (.tf) to find resource definitions
-        // Format: resource "type" "name" { ... }
-        let resource_regex = regex::Regex::new(r#"resource\s+"([^"]+)"\s+"([^"]+)"\s*\{"#).unwrap();
-
-        for path in ctx.fs.read_dir(env_path)? {
-            if path.extension().and_then(|s| s.to_str()) == Some("tf")
-                && let Ok(content) = ctx.fs.read_to_string(&path)
-            {
-                for (line_num, line) in content.lines().enumerate() {
-                    if let Some(captures) = resource_regex.captures(line) {
-                        let res_type = captures.get(1).map(|m| m.as_str()).unwrap_or("");
-                        let res_name = captures.get(2).map(|m| m.as_str()).unwrap_or("");
+        refresh: bool,
+        quiet: bool,
+    ) -> Result<(Vec<StateSnapshot>, usize)> {
+        let (_infrastructure, infrastructure_root) =
+            CollectionDiscovery::find_collection(&*ctx.fs)?
+                .context("Infrastructure is required. Run 'pmp init' first.")?;
+        let store = SnapshotStore::for_infrastructure(&*ctx.fs, &infrastructure_root);
 
-                        // Check if type matches (if specified)
-                        let type_matches =
-                            resource_type.is_none() || resource_type == Some(res_type);
+        let index = CollectionDiscovery::load_index(&*ctx.fs, &*ctx.output, &infrastructure_root)?;
 
-                        // Check if name matches (if specified)
-                        let name_matches = resource_name.is_none()
-                            || resource_name.map(|n| res_name.contains(n)).unwrap_or(false);
+        let mut snapshots = Vec::new();
+        let mut missing = 0;
 
-                        if type_matches && name_matches {
-                            let file_name = path
-                                .file_name()
-                                .and_then(|n| n.to_str())
-                                .unwrap_or("unknown");
+        for (_project, environment) in index.environments() {
+            let resource = &environment.resource;
+            let env_entry = infrastructure_root.join(&environment.path);
+            let project_name = &resource.metadata.name;
+            let environment = &resource.metadata.environment_name;
 
-                            matches.push(Match {
-                                field: "resource".to_string(),
-                                value: format!("{}.{}", res_type, res_name),
-                                context: Some(format!(
-                                    "{}:{} - {}",
-                                    file_name,
-                                    line_num + 1,
-                                    line.trim()
-                                )),
-                            });
-                        }
-                    }
+            if refresh {
+                if !quiet {
+                    ctx.output.dimmed(&format!(
+                        "Pulling state of {} ({})...",
+                        project_name, environment
+                    ));
+                }
+                if let Err(e) = Self::pull_snapshot(ctx, &infrastructure_root, &env_entry, resource)
+                    && !quiet
+                {
+                    ctx.output.warning(&format!(
+                        "Could not pull state of {} ({}): {}",
+                        project_name, environment, e
+                    ));
                 }
             }
+
+            match store.load(project_name, environment)? {
+                Some(snapshot) => snapshots.push(snapshot),
+                None => missing += 1,
+            }
         }
 
-        Ok(matches)
+        if refresh && !quiet {
+            output::blank();
+        }
+
+        Ok((snapshots, missing))
     }
 
-    fn search_terraform_outputs(
+    /// Initialize an environment and refresh its snapshot from the backend
+    fn pull_snapshot(
         ctx: &Context,
+        infrastructure_root: &Path,
         env_path: &Path,
-        output_name: &str,
-    ) -> Result<Vec<Match>> {
-        let mut matches = Vec::new();
-
-        // Parse Terraform files (.tf) to find output definitions
-        // Format: output "name" { ... }
-        let output_regex = regex::Regex::new(r#"output\s+"([^"]+)"\s*\{"#).unwrap();
+        resource: &DynamicProjectEnvironmentResource,
+    ) -> Result<()> {
+        let executor = ctx.executor_registry.get(&resource.spec.executor.name)?;
+        let env_dir_str = env_path
+            .to_str()
+            .context("Failed to convert path to string")?;
+
+        let init_output = executor.init(env_dir_str)?;
+        if !init_output.status.success() {
+            anyhow::bail!(
+                "Initialization failed: {}",
+                String::from_utf8_lossy(&init_output.stderr).trim()
+            );
+        }
 
-        for path in ctx.fs.read_dir(env_path)? {
-            if path.extension().and_then(|s| s.to_str()) == Some("tf")
-                && let Ok(content) = ctx.fs.read_to_string(&path)
-            {
-                for (line_num, line) in content.lines().enumerate() {
-                    if let Some(captures) = output_regex.captures(line) {
-                        let out_name = captures.get(1).map(|m| m.as_str()).unwrap_or("");
+        let content = executor.state_pull(env_dir_str)?;
+        let store = SnapshotStore::for_infrastructure(&*ctx.fs, infrastructure_root);
+
+        if content.trim().is_empty() {
+            store.remove(&resource.metadata.name, &resource.metadata.environment_name)
+        } else {
+            store
+                .save(
+                    &resource.metadata.name,
+                    &resource.metadata.environment_name,
+                    StateDocument::parse(&content)?,
+                )
+                .map(|_| ())
+        }
+    }
 
-                        // Check if output name matches (exact or contains)
-                        if out_name == output_name || out_name.contains(output_name) {
-                            let file_name = path
-                                .file_name()
-                                .and_then(|n| n.to_str())
-                                .unwrap_or("unknown");
+    /// Parse KEY=VALUE / KEY filters, keeping their order
+    fn parse_filters(filters: &[String]) -> Vec<(String, Option<String>)> {
+        filters
+            .iter()
+            .map(|filter| match filter.split_once('=') {
+                Some((key, value)) => (key.to_string(), Some(value.to_string())),
+                None => (filter.to_string(), None),
+            })
+            .collect()
+    }
 
-                            matches.push(Match {
-                                field: "output".to_string(),
-                                value: out_name.to_string(),
-                                context: Some(format!(
-                                    "{}:{} - {}",
-                                    file_name,
-                                    line_num + 1,
-                                    line.trim()
-                                )),
-                            });
-                        }
-                    }
+    /// Attribute at a dotted path (e.g., `tags.Name`, `ingress.0.from_port`)
+    fn attribute_at<'v>(
+        attributes: &'v serde_json::Value,
+        path: &str,
+    ) -> Option<&'v serde_json::Value> {
+        path.split('.')
+            .try_fold(attributes, |value, key| match value {
+                serde_json::Value::Object(map) => map.get(key),
+                serde_json::Value::Array(items) => {
+                    key.parse::<usize>().ok().and_then(|i| items.get(i))
                 }
+                _ => None,
+            })
+    }
+
+    /// Whether resource attributes satisfy every filter
+    fn attributes_match(
+        attributes: &serde_json::Value,
+        filters: &[(String, Option<String>)],
+    ) -> bool {
+        filters.iter().all(|(key, expected)| {
+            match (Self::attribute_at(attributes, key), expected) {
+                (None, _) | (Some(serde_json::Value::Null), _) => false,
+                (Some(_), None) => true,
+                (Some(value), Some(expected)) => Self::format_value(value) == *expected,
             }
+        })
+    }
+
+    /// Context shown for a matched resource: the filtered attributes, or its ID
+    fn resource_context(
+        attributes: &serde_json::Value,
+        filters: &[(String, Option<String>)],
+    ) -> Option<String> {
+        if filters.is_empty() {
+            return Self::attribute_at(attributes, "id")
+                .map(|id| format!("id = {}", Self::format_value(id)));
+        }
+
+        let values: Vec<String> = filters
+            .iter()
+            .filter_map(|(key, _)| {
+                Self::attribute_at(attributes, key)
+                    .map(|value| format!("{} = {}", key, Self::format_value(value)))
+            })
+            .collect();
+
+        Some(values.join(", "))
+    }
+
+    /// Render a state value for comparison and display
+    fn format_value(value: &serde_json::Value) -> String {
+        match value {
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
         }
+    }
 
-        Ok(matches)
+    /// Whether a --format value asks for JSON/YAML output
+    fn is_structured(format: &str) -> Result<bool> {
+        match format {
+            "text" => Ok(false),
+            "json" | "yaml" => Ok(true),
+            _ => anyhow::bail!(
+                "Unsupported output format: {}. Use 'text', 'json' or 'yaml'",
+                format
+            ),
+        }
     }
 
-    fn display_search_results(ctx: &Context, results: &[SearchResult]) -> Result<()> {
+    fn display_missing_snapshots(ctx: &Context, missing: usize) {
+        if missing > 0 {
+            ctx.output.dimmed(&format!(
+                "{} environment(s) have no state snapshot yet. Use --refresh to pull their state.",
+                missing
+            ));
+        }
+    }
+
+    fn display_search_results(ctx: &Context, results: &[SearchResult], format: &str) -> Result<()> {
+        match format {
+            "json" => {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(results).context("Failed to serialize to JSON")?
+                );
+                return Ok(());
+            }
+            "yaml" => {
+                print!(
+                    "{}",
+                    serde_yaml::to_string(results).context("Failed to serialize to YAML")?
+                );
+                return Ok(());
+            }
+            _ => {}
+        }
+
         if results.is_empty() {
             ctx.output.info("No matches found");
             return Ok(());
@@ -676,6 +743,81 @@ mod tests {
         assert!(SearchCommand::tags_match_filters(&tags, &filters));
     }
 
+    #[test]
+    fn test_parse_filters_keeps_order() {
+        let filters = vec!["vpc_id=vpc-1".to_string(), "endpoint".to_string()];
+
+        assert_eq!(
+            SearchCommand::parse_filters(&filters),
+            vec![
+                ("vpc_id".to_string(), Some("vpc-1".to_string())),
+                ("endpoint".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_attribute_at_nested_paths() {
+        let attributes = serde_json::json!({
+            "id": "i-123",
+            "tags": {"Name": "web"},
+            "ebs_block_device": [{"volume_size": 20}]
+        });
+
+        assert_eq!(
+            SearchCommand::attribute_at(&attributes, "tags.Name"),
+            Some(&serde_json::json!("web"))
+        );
+        assert_eq!(
+            SearchCommand::attribute_at(&attributes, "ebs_block_device.0.volume_size"),
+            Some(&serde_json::json!(20))
+        );
+        assert_eq!(SearchCommand::attribute_at(&attributes, "tags.Team"), None);
+    }
+
+    #[test]
+    fn test_attributes_match() {
+        let attributes = serde_json::json!({
+            "instance_type": "t3.micro",
+            "monitoring": true,
+            "tags": {"Name": "web", "Team": null}
+        });
+        let filters = |raw: &[&str]| {
+            SearchCommand::parse_filters(&raw.iter().map(|f| f.to_string()).collect::<Vec<_>>())
+        };
+
+        assert!(SearchCommand::attributes_match(&attributes, &[]));
+        assert!(SearchCommand::attributes_match(
+            &attributes,
+            &filters(&["instance_type=t3.micro", "monitoring=true", "tags.Name"])
+        ));
+        assert!(!SearchCommand::attributes_match(
+            &attributes,
+            &filters(&["instance_type=t3.large"])
+        ));
+        assert!(!SearchCommand::attributes_match(
+            &attributes,
+            &filters(&["tags.Team"])
+        ));
+    }
+
+    #[test]
+    fn test_resource_context() {
+        let attributes = serde_json::json!({"id": "i-123", "tags": {"Name": "web"}});
+
+        assert_eq!(
+            SearchCommand::resource_context(&attributes, &[]),
+            Some("id = i-123".to_string())
+        );
+        assert_eq!(
+            SearchCommand::resource_context(
+                &attributes,
+                &[("tags.Name".to_string(), Some("web".to_string()))]
+            ),
+            Some("tags.Name = web".to_string())
+        );
+    }
+
     #[test]
     fn test_search_result_serialization() {
         let result = SearchResult {
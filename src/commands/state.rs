@@ -1,11 +1,20 @@
 use crate::collection::CollectionDiscovery;
+use crate::commands::ExecutionHelper;
 use crate::context::Context;
-use crate::executor::{Executor, ExecutorConfig, OpenTofuExecutor};
+use crate::executor::opentofu::generate_backend_config;
+use crate::executor::{Executor, ExecutorConfig, ProjectMetadata};
+use crate::locking::{LockInfo, LockStore, create_lock_store, current_owner, lock_key};
 use crate::output;
+use crate::state::{
+    self, AuditEntry, AuditLog, BackupEntry, BackupStore, ResourceMode, StateDocument,
+    StateResource, StateSummary, parse_backend_target, verify_migration, with_backend,
+};
 use crate::template::DynamicProjectEnvironmentResource;
+use crate::template::metadata::InfrastructureResource;
 use anyhow::{Context as AnyhowContext, Result};
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::sync::Arc;
 
 pub struct StateCommand;
 
@@ -28,15 +37,53 @@ struct DriftInfo {
     changes: Vec<String>,
 }
 
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
-struct StateBackup {
-    project: String,
-    environment: String,
-    timestamp: String,
-    backup_id: String,
-    state_content: String,
+/// Project environment whose state is inspected or modified by name
+struct StateTarget {
+    env_path: PathBuf,
+    resource: DynamicProjectEnvironmentResource,
+    executor: Arc<dyn Executor>,
+    state: StateDocument,
+}
+
+impl StateTarget {
+    fn describe(&self) -> String {
+        format!(
+            "{} ({})",
+            self.resource.metadata.name, self.resource.metadata.environment_name
+        )
+    }
+}
+
+/// Result of migrating one environment
+enum MigrationOutcome {
+    /// The environment already uses the target backend
+    UpToDate,
+
+    /// Dry run: the environment would be migrated
+    Pending(Option<StateSummary>),
+
+    /// The state was migrated and verified
+    Migrated(Option<StateSummary>),
+}
+
+impl MigrationOutcome {
+    fn describe(&self) -> String {
+        let resources = |summary: &Option<StateSummary>| match summary {
+            Some(summary) => format!("{} resource(s)", summary.resource_count),
+            None => "no state".to_string(),
+        };
+
+        match self {
+            Self::UpToDate => "already on target backend".to_string(),
+            Self::Pending(before) => format!("would migrate ({})", resources(before)),
+            Self::Migrated(after) => format!("migrated ({}, verified)", resources(after)),
+        }
+    }
 }
 
+/// Temporary file used to push a restored state to the backend
+const RESTORE_STATE_FILE: &str = ".pmp-restore.tfstate";
+
 impl StateCommand {
     /// Execute the state list command
     pub fn execute_list(ctx: &Context, show_details: bool) -> Result<()> {
@@ -59,6 +106,9 @@ impl StateCommand {
             return Ok(());
         }
 
+        let lock_config = ExecutionHelper::lock_config(&infrastructure)?;
+        let lock_store = create_lock_store(&lock_config, &infrastructure_root)?;
+
         // Collect state info for all projects
         let mut state_infos = Vec::new();
         for project in &projects {
@@ -74,6 +124,7 @@ impl StateCommand {
                     {
                         let state_info = Self::get_state_info(
                             ctx,
+                            lock_store.as_ref(),
                             &resource.metadata.name,
                             &resource.metadata.environment_name,
                             &env_path,
@@ -147,6 +198,7 @@ impl StateCommand {
                             ctx,
                             &resource.metadata.name,
                             &resource.metadata.environment_name,
+                            &resource.spec.executor.name,
                             &env_path,
                         )?;
                         drift_infos.push(drift_info);
@@ -166,12 +218,31 @@ impl StateCommand {
         ctx: &Context,
         project_name: &str,
         environment: Option<&str>,
+        reason: Option<&str>,
+        ttl: Option<u64>,
     ) -> Result<()> {
         ctx.output
             .section(&format!("Locking State: {}", project_name));
 
         let env_path = Self::resolve_project_environment(ctx, project_name, environment)?;
-        Self::lock_state(ctx, &env_path)?;
+        let key = Self::state_lock_key(ctx, &env_path)?;
+        let store = Self::open_lock_store(ctx)?;
+
+        ctx.output.dimmed("Acquiring state lock...");
+        let lock = store.acquire(&LockInfo::new(
+            &key,
+            &current_owner(),
+            "manual",
+            reason,
+            ttl,
+        ))?;
+
+        ctx.output.key_value("Lock store", store.get_type());
+        ctx.output.key_value("Lock ID", &lock.id);
+        ctx.output.key_value("Owner", &lock.owner);
+        if let Some(expires_at) = &lock.expires_at {
+            ctx.output.key_value("Expires", &expires_at.to_rfc3339());
+        }
 
         ctx.output.success("State locked successfully");
         Ok(())
@@ -182,13 +253,19 @@ impl StateCommand {
         ctx: &Context,
         project_name: &str,
         environment: Option<&str>,
+        lock_id: Option<&str>,
         force: bool,
     ) -> Result<()> {
         ctx.output
             .section(&format!("Unlocking State: {}", project_name));
 
         let env_path = Self::resolve_project_environment(ctx, project_name, environment)?;
-        Self::unlock_state(ctx, &env_path, force)?;
+        let key = Self::state_lock_key(ctx, &env_path)?;
+        let store = Self::open_lock_store(ctx)?;
+
+        if !Self::unlock_state(ctx, store.as_ref(), &key, lock_id, force)? {
+            return Ok(());
+        }
 
         ctx.output.success("State unlocked successfully");
         Ok(())
@@ -232,7 +309,7 @@ impl StateCommand {
                             resource.metadata.name, resource.metadata.environment_name
                         ));
 
-                        if Self::sync_state(ctx, &env_path)? {
+                        if Self::sync_state(ctx, &resource.spec.executor.name, &env_path)? {
                             synced_count += 1;
                         }
                     }
@@ -249,6 +326,7 @@ impl StateCommand {
     /// Get state information for a project environment
     fn get_state_info(
         ctx: &Context,
+        lock_store: &dyn LockStore,
         project_name: &str,
         environment: &str,
         env_path: &Path,
@@ -260,12 +338,11 @@ impl StateCommand {
 
         // Check if state file exists
         let state_file = env_path.join("terraform.tfstate");
-        let locked = Self::is_state_locked(ctx, env_path)?;
-        let lock_info = if locked {
-            Self::get_lock_info(ctx, env_path)?
-        } else {
-            None
-        };
+        let lock = lock_store
+            .get(&lock_key(project_name, environment))?
+            .filter(|lock| !lock.is_expired());
+        let locked = lock.is_some();
+        let lock_info = lock.map(|lock| lock.describe());
 
         let mut resource_count = None;
         let mut last_modified = None;
@@ -295,35 +372,12 @@ impl StateCommand {
         })
     }
 
-    /// Check if state is locked
-    fn is_state_locked(ctx: &Context, env_path: &Path) -> Result<bool> {
-        // Check for .terraform.tfstate.lock.info file
-        let lock_file = env_path
-            .join(".terraform")
-            .join("terraform.tfstate.lock.info");
-        Ok(ctx.fs.exists(&lock_file))
-    }
-
-    /// Get lock information
-    fn get_lock_info(ctx: &Context, env_path: &Path) -> Result<Option<String>> {
-        let lock_file = env_path
-            .join(".terraform")
-            .join("terraform.tfstate.lock.info");
-        if ctx.fs.exists(&lock_file)
-            && let Ok(content) = ctx.fs.read_to_string(&lock_file)
-            && let Ok(lock_data) = serde_json::from_str::<serde_json::Value>(&content)
-            && let Some(who) = lock_data.get("Who").and_then(|v| v.as_str())
-        {
-            return Ok(Some(who.to_string()));
-        }
-        Ok(None)
-    }
-
     /// Check for drift
     fn check_drift(
         ctx: &Context,
         project_name: &str,
         environment: &str,
+        executor_name: &str,
         env_path: &Path,
     ) -> Result<DriftInfo> {
         let env_dir_str = env_path
@@ -333,18 +387,15 @@ impl StateCommand {
         ctx.output
             .dimmed(&format!("Checking {}:{}...", project_name, environment));
 
-        // Run tofu plan -detailed-exitcode to detect drift
-        let executor = OpenTofuExecutor::new();
+        // Run plan -detailed-exitcode through the environment's executor to detect drift
+        let executor = ctx.executor_registry.get(executor_name)?;
 
         // Initialize if needed
         let _ = executor.init(env_dir_str);
 
         // Run plan with -detailed-exitcode
         // Exit code 0 = no changes, 1 = error, 2 = changes detected
-        let output = Command::new("tofu")
-            .args(["plan", "-detailed-exitcode", "-no-color", "-input=false"])
-            .current_dir(env_dir_str)
-            .output();
+        let output = executor.plan_with_output(env_dir_str, &["-input=false".to_string()]);
 
         let (has_drift, changes) = if let Ok(output) = output {
             let exit_code = output.status.code().unwrap_or(1);
@@ -377,82 +428,72 @@ impl StateCommand {
         })
     }
 
-    /// Lock state
-    fn lock_state(ctx: &Context, env_path: &Path) -> Result<()> {
-        let env_dir_str = env_path
-            .to_str()
-            .context("Failed to convert path to string")?;
-
-        // Use tofu force-unlock with a dummy lock ID (this is a simplified version)
-        // In production, this would integrate with the backend's locking mechanism
-        ctx.output.dimmed("Acquiring state lock...");
-
-        // For now, we'll create a lock marker file
-        let lock_file = env_path
-            .join(".terraform")
-            .join("terraform.tfstate.lock.info");
-        let hostname = whoami::fallible::hostname().unwrap_or_else(|_| "unknown".to_string());
-        let lock_data = serde_json::json!({
-            "ID": uuid::Uuid::new_v4().to_string(),
-            "Operation": "manual-lock",
-            "Who": format!("{}@{}", whoami::username(), hostname),
-            "Version": "1.0.0",
-            "Created": chrono::Utc::now().to_rfc3339(),
-            "Path": env_dir_str
-        });
-
-        let terraform_dir = env_path.join(".terraform");
-        if !ctx.fs.exists(&terraform_dir) {
-            ctx.fs.create_dir_all(&terraform_dir)?;
-        }
-
-        ctx.fs
-            .write(&lock_file, &serde_json::to_string_pretty(&lock_data)?)?;
+    /// Lock store configured for the current infrastructure
+    fn open_lock_store(ctx: &Context) -> Result<Box<dyn LockStore>> {
+        let (infrastructure, infrastructure_root) = CollectionDiscovery::find_collection(&*ctx.fs)?
+            .context("Infrastructure is required.")?;
 
-        Ok(())
+        let lock_config = ExecutionHelper::lock_config(&infrastructure)?;
+        create_lock_store(&lock_config, &infrastructure_root)
     }
 
-    /// Unlock state
-    fn unlock_state(ctx: &Context, env_path: &Path, force: bool) -> Result<()> {
-        let lock_file = env_path
-            .join(".terraform")
-            .join("terraform.tfstate.lock.info");
+    /// Lock key of an environment directory
+    fn state_lock_key(ctx: &Context, env_path: &Path) -> Result<String> {
+        let resource = DynamicProjectEnvironmentResource::from_file(
+            &*ctx.fs,
+            &env_path.join(".pmp.environment.yaml"),
+        )?;
+
+        Ok(lock_key(
+            &resource.metadata.name,
+            &resource.metadata.environment_name,
+        ))
+    }
 
-        if !ctx.fs.exists(&lock_file) {
+    /// Unlock state, returning false if it was not locked
+    ///
+    /// Without --force the lock must be identified by its ID or held by the current user.
+    fn unlock_state(
+        ctx: &Context,
+        store: &dyn LockStore,
+        key: &str,
+        lock_id: Option<&str>,
+        force: bool,
+    ) -> Result<bool> {
+        let Some(current) = store.get(key)? else {
             ctx.output.dimmed("State is not locked");
-            return Ok(());
-        }
+            return Ok(false);
+        };
+
+        ctx.output
+            .dimmed(&format!("Current lock: {}", current.describe()));
 
         if force {
             ctx.output.dimmed("Force unlocking state...");
-            ctx.fs.remove_file(&lock_file)?;
+            store.force_release(key)?;
+        } else if let Some(lock_id) = lock_id {
+            store.release(key, lock_id)?;
+        } else if current.owner == current_owner() || current.is_expired() {
+            store.release(key, &current.id)?;
         } else {
-            // Check lock ownership
-            if let Ok(content) = ctx.fs.read_to_string(&lock_file)
-                && let Ok(lock_data) = serde_json::from_str::<serde_json::Value>(&content)
-                && let Some(who) = lock_data.get("Who").and_then(|v| v.as_str())
-            {
-                let hostname =
-                    whoami::fallible::hostname().unwrap_or_else(|_| "unknown".to_string());
-                let current_user = format!("{}@{}", whoami::username(), hostname);
-                if who != current_user {
-                    anyhow::bail!("Lock is held by {}. Use --force to override.", who);
-                }
-            }
-            ctx.fs.remove_file(&lock_file)?;
+            anyhow::bail!(
+                "Lock is held by {}. Use --id {} or --force to override.",
+                current.owner,
+                current.id
+            );
         }
 
-        Ok(())
+        Ok(true)
     }
 
     /// Sync state with remote
-    fn sync_state(_ctx: &Context, env_path: &Path) -> Result<bool> {
+    fn sync_state(ctx: &Context, executor_name: &str, env_path: &Path) -> Result<bool> {
         let env_dir_str = env_path
             .to_str()
             .context("Failed to convert path to string")?;
 
-        // Run tofu refresh to sync state
-        let executor = OpenTofuExecutor::new();
+        // Run refresh through the environment's executor to sync state
+        let executor = ctx.executor_registry.get(executor_name)?;
         let config = ExecutorConfig {
             plan_command: None,
             apply_command: None,
@@ -532,7 +573,7 @@ impl StateCommand {
             if state.locked {
                 locked_count += 1;
                 if let Some(lock_info) = &state.lock_info {
-                    ctx.output.dimmed(&format!("   Locked by: {}", lock_info));
+                    ctx.output.dimmed(&format!("   Locked: {}", lock_info));
                 }
             }
 
@@ -607,42 +648,137 @@ impl StateCommand {
     pub fn execute_backup(ctx: &Context, path: Option<&str>) -> Result<()> {
         ctx.output.section("State Backup");
 
-        let current_path = if let Some(p) = path {
-            PathBuf::from(p)
-        } else {
-            std::env::current_dir()?
-        };
+        let (env_path, resource) = Self::resolve_environment_dir(ctx, path)?;
+        let (infrastructure, infrastructure_root) = CollectionDiscovery::find_collection(&*ctx.fs)?
+            .context("Infrastructure is required. Run 'pmp init' first.")?;
 
-        let env_yaml = current_path.join(".pmp.environment.yaml");
+        ctx.output.key_value("Project", &resource.metadata.name);
+        ctx.output
+            .key_value("Environment", &resource.metadata.environment_name);
+        output::blank();
 
-        if !ctx.fs.exists(&env_yaml) {
-            anyhow::bail!(
-                "Not in an environment directory. Navigate to a project environment or use --path"
-            );
+        let executor = ctx.executor_registry.get(&resource.spec.executor.name)?;
+        let env_dir_str = Self::initialize_executor(ctx, executor.as_ref(), &env_path)?;
+
+        let Some(saved) = ExecutionHelper::backup_state(
+            ctx,
+            &infrastructure,
+            &infrastructure_root,
+            executor.as_ref(),
+            env_dir_str,
+            &resource.metadata.name,
+            &resource.metadata.environment_name,
+            "manual",
+        )?
+        else {
+            ctx.output.warning("No state found. Nothing to back up.");
+            return Ok(());
+        };
+
+        if saved.created {
+            ctx.output
+                .success(&format!("Backup created: {}", saved.entry.id));
+        } else {
+            ctx.output.info(&format!(
+                "State unchanged since backup {}. Reusing it.",
+                saved.entry.id
+            ));
         }
 
-        let resource = DynamicProjectEnvironmentResource::from_file(&*ctx.fs, &env_yaml)?;
+        Self::display_backup(ctx, &saved.entry);
+
+        Ok(())
+    }
+
+    /// Execute the state backups list command
+    pub fn execute_backups_list(ctx: &Context, path: Option<&str>) -> Result<()> {
+        ctx.output.section("State Backups");
+
+        let (_, resource) = Self::resolve_environment_dir(ctx, path)?;
+        let (infrastructure, infrastructure_root) = CollectionDiscovery::find_collection(&*ctx.fs)?
+            .context("Infrastructure is required. Run 'pmp init' first.")?;
 
         ctx.output.key_value("Project", &resource.metadata.name);
         ctx.output
             .key_value("Environment", &resource.metadata.environment_name);
+
+        let config = ExecutionHelper::backup_config(&infrastructure)?;
+        let store = BackupStore::new(&*ctx.fs, config.backup_dir(&infrastructure_root));
+        let entries = store.list(&resource.metadata.name, &resource.metadata.environment_name)?;
+
         output::blank();
 
-        // Create backup
-        let backup_id = chrono::Utc::now().format("%Y%m%d_%H%M%S").to_string();
-        let backup = Self::create_backup(ctx, &current_path, &resource, &backup_id)?;
+        if entries.is_empty() {
+            ctx.output.dimmed("No backups found.");
+            return Ok(());
+        }
+
+        // Newest first, each compared with the backup before it
+        for (index, entry) in entries.iter().enumerate().rev() {
+            let serial = entry
+                .summary
+                .serial
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| "-".to_string());
+            let lineage = entry
+                .summary
+                .lineage
+                .as_deref()
+                .map(|l| l.chars().take(8).collect::<String>())
+                .unwrap_or_else(|| "-".to_string());
 
-        // Save backup
-        let backup_dir = current_path.join(".pmp").join("backups");
-        ctx.fs.create_dir_all(&backup_dir)?;
+            ctx.output.info(&format!(
+                "{}  {}  serial {}  lineage {}  {} resource(s)",
+                entry.id, entry.reason, serial, lineage, entry.summary.resource_count
+            ));
 
-        let backup_file = backup_dir.join(format!("{}.json", backup_id));
-        let backup_json = serde_json::to_string_pretty(&backup)?;
-        ctx.fs.write(&backup_file, &backup_json)?;
+            match index.checked_sub(1).map(|previous| &entries[previous]) {
+                Some(previous) => ctx.output.dimmed(&format!(
+                    "   vs {}: {}",
+                    previous.id,
+                    entry.summary.diff_from(&previous.summary)
+                )),
+                None => ctx.output.dimmed("   oldest backup"),
+            }
+        }
 
+        output::blank();
         ctx.output
-            .success(&format!("Backup created: {}", backup_file.display()));
-        ctx.output.key_value("Backup ID", &backup_id);
+            .info(&format!("Total backups: {}", entries.len()));
+
+        Ok(())
+    }
+
+    /// Execute the state backups prune command
+    pub fn execute_backups_prune(ctx: &Context, path: Option<&str>) -> Result<()> {
+        ctx.output.section("Prune State Backups");
+
+        let (_, resource) = Self::resolve_environment_dir(ctx, path)?;
+        let (infrastructure, infrastructure_root) = CollectionDiscovery::find_collection(&*ctx.fs)?
+            .context("Infrastructure is required. Run 'pmp init' first.")?;
+
+        let config = ExecutionHelper::backup_config(&infrastructure)?;
+        if config.keep_last.is_none() && config.max_age_days.is_none() {
+            ctx.output.dimmed(
+                "No retention rules configured (spec.executor.config.backups.keep_last / max_age_days).",
+            );
+            return Ok(());
+        }
+
+        let store = BackupStore::new(&*ctx.fs, config.backup_dir(&infrastructure_root));
+        let removed = store.prune(
+            &resource.metadata.name,
+            &resource.metadata.environment_name,
+            &config,
+        )?;
+
+        for entry in &removed {
+            ctx.output
+                .dimmed(&format!("Deleted {} ({})", entry.id, entry.reason));
+        }
+
+        ctx.output
+            .success(&format!("Deleted {} backup(s)", removed.len()));
 
         Ok(())
     }
@@ -656,51 +792,24 @@ impl StateCommand {
     ) -> Result<()> {
         ctx.output.section("State Restore");
 
-        let current_path = if let Some(p) = path {
-            PathBuf::from(p)
-        } else {
-            std::env::current_dir()?
-        };
-
-        let env_yaml = current_path.join(".pmp.environment.yaml");
-
-        if !ctx.fs.exists(&env_yaml) {
-            anyhow::bail!(
-                "Not in an environment directory. Navigate to a project environment or use --path"
-            );
-        }
-
-        let resource = DynamicProjectEnvironmentResource::from_file(&*ctx.fs, &env_yaml)?;
+        let (env_path, resource) = Self::resolve_environment_dir(ctx, path)?;
+        let (infrastructure, infrastructure_root) = CollectionDiscovery::find_collection(&*ctx.fs)?
+            .context("Infrastructure is required. Run 'pmp init' first.")?;
+        let project = &resource.metadata.name;
+        let environment = &resource.metadata.environment_name;
 
-        ctx.output.key_value("Project", &resource.metadata.name);
-        ctx.output
-            .key_value("Environment", &resource.metadata.environment_name);
+        ctx.output.key_value("Project", project);
+        ctx.output.key_value("Environment", environment);
         ctx.output.key_value("Backup ID", backup_id);
         output::blank();
 
         // Load backup
-        let backup_dir = current_path.join(".pmp").join("backups");
-        let backup_file = backup_dir.join(format!("{}.json", backup_id));
+        let config = ExecutionHelper::backup_config(&infrastructure)?;
+        let store = BackupStore::new(&*ctx.fs, config.backup_dir(&infrastructure_root));
+        let (entry, state_content) = store.load(project, environment, backup_id)?;
 
-        if !ctx.fs.exists(&backup_file) {
-            anyhow::bail!("Backup not found: {}", backup_id);
-        }
-
-        let backup_json = ctx.fs.read_to_string(&backup_file)?;
-        let backup: StateBackup = serde_json::from_str(&backup_json)?;
-
-        // Verify backup matches current project
-        if backup.project != resource.metadata.name
-            || backup.environment != resource.metadata.environment_name
-        {
-            ctx.output
-                .error("Backup does not match current project/environment");
-            ctx.output.dimmed(&format!(
-                "Backup is for: {}:{}",
-                backup.project, backup.environment
-            ));
-            anyhow::bail!("Backup mismatch");
-        }
+        Self::display_backup(ctx, &entry);
+        output::blank();
 
         // Confirm restore
         if !force {
@@ -715,8 +824,21 @@ impl StateCommand {
             }
         }
 
-        // Restore state
-        Self::restore_backup(ctx, &current_path, &backup)?;
+        let executor = ctx.executor_registry.get(&resource.spec.executor.name)?;
+        let env_dir_str = Self::initialize_executor(ctx, executor.as_ref(), &env_path)?;
+
+        // Back up the current state, then push the backup to the backend
+        ExecutionHelper::with_state_guard(
+            ctx,
+            &infrastructure,
+            &infrastructure_root,
+            executor.as_ref(),
+            env_dir_str,
+            project,
+            environment,
+            "restore",
+            || Self::push_state(ctx, executor.as_ref(), &env_path, &state_content),
+        )?;
 
         ctx.output.success("State restored successfully");
 
@@ -724,139 +846,934 @@ impl StateCommand {
     }
 
     /// Execute the state migrate command
-    pub fn execute_migrate(ctx: &Context, backend_type: &str, path: Option<&str>) -> Result<()> {
+    ///
+    /// Moves the state of one environment (or, with `all`, every environment of the
+    /// infrastructure) to the target backend: `_common.tf` is regenerated with the target
+    /// backend, `init -migrate-state` copies the state, and the migrated state is checked
+    /// against the original one. A failed environment gets its previous `_common.tf` back.
+    #[allow(clippy::too_many_arguments)]
+    pub fn execute_migrate(
+        ctx: &Context,
+        backend_type: Option<&str>,
+        backend_file: Option<&str>,
+        params: &[String],
+        path: Option<&str>,
+        all: bool,
+        dry_run: bool,
+        yes: bool,
+    ) -> Result<()> {
         ctx.output.section("State Migration");
 
-        let current_path = if let Some(p) = path {
-            PathBuf::from(p)
+        let (mut infrastructure, infrastructure_root) =
+            CollectionDiscovery::find_collection(&*ctx.fs)?
+                .context("Infrastructure is required. Run 'pmp init' first.")?;
+        let target =
+            parse_backend_target(&*ctx.fs, backend_type, backend_file.map(Path::new), params)?;
+
+        let executor_config = infrastructure
+            .spec
+            .executor
+            .as_ref()
+            .map(|e| e.config.clone())
+            .unwrap_or_default();
+        let target_config = with_backend(&executor_config, &target);
+
+        let environments = if all {
+            Self::discover_environment_dirs(ctx, &infrastructure_root)?
         } else {
-            std::env::current_dir()?
+            vec![Self::resolve_environment_dir(ctx, path)?]
         };
 
-        let env_yaml = current_path.join(".pmp.environment.yaml");
+        ctx.output
+            .key_value("Current Backend", state::backend_type(&executor_config));
+        ctx.output
+            .key_value_highlight("Target Backend", state::backend_type(&target_config));
+        ctx.output
+            .key_value("Environments", &environments.len().to_string());
+        if dry_run {
+            ctx.output.key_value("Mode", "dry run");
+        }
+        output::blank();
 
-        if !ctx.fs.exists(&env_yaml) {
-            anyhow::bail!(
-                "Not in an environment directory. Navigate to a project environment or use --path"
+        if environments.is_empty() {
+            ctx.output.dimmed("No environments found.");
+            return Ok(());
+        }
+
+        if !dry_run && !yes {
+            let confirmed = ctx.input.confirm(
+                &format!(
+                    "Migrate the state of {} environment(s) to the {} backend?",
+                    environments.len(),
+                    state::backend_type(&target_config)
+                ),
+                Some(false),
+            )?;
+
+            if !confirmed {
+                ctx.output.dimmed("Migration cancelled.");
+                return Ok(());
+            }
+        }
+
+        let mut results = Vec::new();
+        for (env_path, resource) in &environments {
+            ctx.output.subsection(&format!(
+                "{} ({})",
+                resource.metadata.name, resource.metadata.environment_name
+            ));
+
+            let result = Self::migrate_environment(
+                ctx,
+                &infrastructure,
+                &infrastructure_root,
+                &target_config,
+                env_path,
+                resource,
+                dry_run,
             );
+
+            match &result {
+                Ok(outcome) => ctx.output.dimmed(&outcome.describe()),
+                Err(e) => ctx.output.error(&format!("{:#}", e)),
+            }
+
+            results.push((resource, result));
         }
 
-        let resource = DynamicProjectEnvironmentResource::from_file(&*ctx.fs, &env_yaml)?;
+        Self::display_migration_report(ctx, &results);
+
+        let failed = results.iter().filter(|(_, r)| r.is_err()).count();
+        if failed > 0 {
+            anyhow::bail!("State migration failed for {} environment(s)", failed);
+        }
+
+        if dry_run {
+            ctx.output.dimmed("Dry run: nothing was changed.");
+            return Ok(());
+        }
+
+        if all {
+            // Every environment now uses the target backend; new and updated projects must too
+            if let Some(executor) = infrastructure.spec.executor.as_mut() {
+                executor.config = target_config;
+                infrastructure
+                    .save(
+                        &*ctx.fs,
+                        &infrastructure_root.join(".pmp.infrastructure.yaml"),
+                    )
+                    .context("Failed to save .pmp.infrastructure.yaml")?;
+                ctx.output
+                    .dimmed("Updated spec.executor.config.backend in .pmp.infrastructure.yaml");
+            }
+        } else {
+            ctx.output.warning(
+                "The infrastructure still configures the previous backend. Migrate the remaining environments with --all before running 'pmp project update'.",
+            );
+        }
+
+        ctx.output.success("State migration completed successfully");
+
+        Ok(())
+    }
+
+    /// Migrate the state of a single environment to the target backend
+    fn migrate_environment(
+        ctx: &Context,
+        infrastructure: &InfrastructureResource,
+        infrastructure_root: &Path,
+        target_config: &HashMap<String, serde_json::Value>,
+        env_path: &Path,
+        resource: &DynamicProjectEnvironmentResource,
+        dry_run: bool,
+    ) -> Result<MigrationOutcome> {
+        let project = &resource.metadata.name;
+        let environment = &resource.metadata.environment_name;
+        let executor = ctx.executor_registry.get(&resource.spec.executor.name)?;
+
+        // Environments whose _common.tf already starts with the target backend are done
+        let common_file = env_path.join("_common.tf");
+        let previous_common = if ctx.fs.exists(&common_file) {
+            Some(ctx.fs.read_to_string(&common_file)?)
+        } else {
+            None
+        };
+        let target_backend = generate_backend_config(
+            target_config,
+            Some(&resource.api_version),
+            Some(&resource.kind),
+            Some(environment),
+            Some(project),
+        )?;
+
+        if previous_common
+            .as_deref()
+            .is_some_and(|content| content.starts_with(&target_backend))
+        {
+            return Ok(MigrationOutcome::UpToDate);
+        }
+
+        let env_dir_str = Self::initialize_executor(ctx, executor.as_ref(), env_path)?;
+        let before = Self::pull_summary(executor.as_ref(), env_dir_str)?;
+
+        if dry_run {
+            return Ok(MigrationOutcome::Pending(before));
+        }
+
+        ExecutionHelper::with_state_lock(
+            ctx,
+            infrastructure,
+            infrastructure_root,
+            project,
+            environment,
+            "migrate",
+            || {
+                // Unlike automatic backups, the pre-migration backup is required
+                if let Some(saved) = ExecutionHelper::backup_state(
+                    ctx,
+                    infrastructure,
+                    infrastructure_root,
+                    executor.as_ref(),
+                    env_dir_str,
+                    project,
+                    environment,
+                    "pre-migration",
+                )? {
+                    ctx.output
+                        .dimmed(&format!("State backed up ({})", saved.entry.id));
+                }
+
+                let migrated = Self::regenerate_common_file(
+                    ctx,
+                    executor.as_ref(),
+                    infrastructure,
+                    target_config,
+                    env_path,
+                    resource,
+                )
+                .and_then(|_| {
+                    ctx.output.dimmed("Running init -migrate-state...");
+                    executor.migrate_state(env_dir_str)
+                })
+                .and_then(|_| Self::pull_summary(executor.as_ref(), env_dir_str))
+                .and_then(|after| {
+                    verify_migration(before.as_ref(), after.as_ref())?;
+                    Ok(after)
+                });
+
+                match migrated {
+                    Ok(after) => Ok(MigrationOutcome::Migrated(after)),
+                    Err(e) => {
+                        match &previous_common {
+                            Some(content) => ctx.fs.write(&common_file, content)?,
+                            None if ctx.fs.exists(&common_file) => {
+                                ctx.fs.remove_file(&common_file)?
+                            }
+                            None => {}
+                        }
+                        ctx.output.warning(&format!(
+                            "Restored the previous _common.tf. Run '{} init -reconfigure' in {} before the next operation.",
+                            executor.get_name(),
+                            env_path.display()
+                        ));
+                        Err(e)
+                    }
+                }
+            },
+        )
+    }
+
+    /// Regenerate `_common.tf` of an environment for the given executor configuration
+    fn regenerate_common_file(
+        ctx: &Context,
+        executor: &dyn crate::executor::Executor,
+        infrastructure: &InfrastructureResource,
+        executor_config: &HashMap<String, serde_json::Value>,
+        env_path: &Path,
+        resource: &DynamicProjectEnvironmentResource,
+    ) -> Result<()> {
+        let metadata = ProjectMetadata {
+            api_version: &resource.api_version,
+            kind: &resource.kind,
+            environment: &resource.metadata.environment_name,
+            project_name: &resource.metadata.name,
+        };
+        let plugins = resource
+            .spec
+            .plugins
+            .as_ref()
+            .map(|p| p.added.as_slice())
+            .filter(|added| !added.is_empty());
+
+        executor
+            .generate_common_file(
+                ctx,
+                env_path,
+                executor_config,
+                &metadata,
+                plugins,
+                &resource.spec.template_reference_projects,
+                &resource.spec.secrets,
+                infrastructure.spec.secrets.as_ref(),
+            )
+            .context("Failed to generate common file")
+    }
+
+    /// Summary of the current state (None when no state exists)
+    fn pull_summary(
+        executor: &dyn crate::executor::Executor,
+        working_dir: &str,
+    ) -> Result<Option<StateSummary>> {
+        let content = executor.state_pull(working_dir)?;
+
+        if content.trim().is_empty() {
+            return Ok(None);
+        }
+
+        StateSummary::parse(&content).map(Some)
+    }
+
+    /// Display the per-environment result of a migration
+    fn display_migration_report(
+        ctx: &Context,
+        results: &[(&DynamicProjectEnvironmentResource, Result<MigrationOutcome>)],
+    ) {
+        ctx.output.subsection("Migration Report");
+
+        for (resource, result) in results {
+            let status = match result {
+                Ok(outcome) => outcome.describe(),
+                Err(e) => format!("failed: {}", e),
+            };
+
+            ctx.output.info(&format!(
+                "{:<30} {:<12} {}",
+                resource.metadata.name, resource.metadata.environment_name, status
+            ));
+        }
 
-        ctx.output.key_value("Project", &resource.metadata.name);
-        ctx.output
-            .key_value("Environment", &resource.metadata.environment_name);
-        ctx.output.key_value("Target Backend", backend_type);
         output::blank();
+    }
+
+    /// All environments of the infrastructure
+    fn discover_environment_dirs(
+        ctx: &Context,
+        infrastructure_root: &Path,
+    ) -> Result<Vec<(PathBuf, DynamicProjectEnvironmentResource)>> {
+        let projects =
+            CollectionDiscovery::discover_projects(&*ctx.fs, &*ctx.output, infrastructure_root)?;
 
-        // Create backup before migration
-        ctx.output.info("Creating backup before migration...");
-        let backup_id = format!(
-            "pre_migration_{}",
-            chrono::Utc::now().format("%Y%m%d_%H%M%S")
+        let mut environments = Vec::new();
+        for project in &projects {
+            let environments_dir = infrastructure_root.join(&project.path).join("environments");
+
+            if let Ok(env_entries) = ctx.fs.read_dir(&environments_dir) {
+                for env_path in env_entries {
+                    let env_file = env_path.join(".pmp.environment.yaml");
+                    if ctx.fs.exists(&env_file) {
+                        let resource =
+                            DynamicProjectEnvironmentResource::from_file(&*ctx.fs, &env_file)?;
+                        environments.push((env_path, resource));
+                    }
+                }
+            }
+        }
+
+        Ok(environments)
+    }
+
+    /// Execute the state show command
+    pub fn execute_show(
+        ctx: &Context,
+        project: &str,
+        environment: Option<&str>,
+        address: Option<&str>,
+        format: &str,
+    ) -> Result<()> {
+        let structured = Self::is_structured(format)?;
+        let target = Self::load_state_target(ctx, project, environment, !structured)?;
+
+        if let Some(address) = address {
+            let resource = target.state.resource(address).with_context(|| {
+                format!(
+                    "Resource '{}' not found in state of {}",
+                    address,
+                    target.describe()
+                )
+            })?;
+
+            if Self::print_structured(resource, format)? {
+                return Ok(());
+            }
+
+            ctx.output
+                .section(&format!("Resource: {}", resource.address));
+            ctx.output.key_value("Type", &resource.resource_type);
+            ctx.output.key_value(
+                "Mode",
+                match resource.mode {
+                    ResourceMode::Managed => "managed",
+                    ResourceMode::Data => "data",
+                },
+            );
+            if let Some(module) = &resource.module {
+                ctx.output.key_value("Module", module);
+            }
+            if let Some(provider) = &resource.provider {
+                ctx.output.key_value("Provider", provider);
+            }
+
+            ctx.output.subsection("Attributes");
+            if let Some(attributes) = resource.attributes.as_object() {
+                let mut names: Vec<_> = attributes.keys().collect();
+                names.sort();
+                for name in names {
+                    ctx.output
+                        .key_value(name, &Self::format_value(&attributes[name]));
+                }
+            }
+
+            return Ok(());
+        }
+
+        let state = Self::mask_sensitive_outputs(target.state.clone(), false);
+        if Self::print_structured(&state, format)? {
+            return Ok(());
+        }
+
+        ctx.output.section("State");
+        ctx.output
+            .key_value("Project", &target.resource.metadata.name);
+        ctx.output
+            .key_value("Environment", &target.resource.metadata.environment_name);
+        if let Some(version) = &state.terraform_version {
+            ctx.output.key_value("Version", version);
+        }
+        if let Some(serial) = state.serial {
+            ctx.output.key_value("Serial", &serial.to_string());
+        }
+        if let Some(lineage) = &state.lineage {
+            ctx.output.key_value("Lineage", lineage);
+        }
+        ctx.output
+            .key_value("Resources", &state.managed_resources().count().to_string());
+        ctx.output.key_value(
+            "Data Sources",
+            &(state.resources.len() - state.managed_resources().count()).to_string(),
         );
-        let backup = Self::create_backup(ctx, &current_path, &resource, &backup_id)?;
+        ctx.output
+            .key_value("Outputs", &state.outputs.len().to_string());
 
-        let backup_dir = current_path.join(".pmp").join("backups");
-        ctx.fs.create_dir_all(&backup_dir)?;
+        if !state.resources.is_empty() {
+            let mut by_type: BTreeMap<&str, usize> = BTreeMap::new();
+            for resource in state.managed_resources() {
+                *by_type.entry(&resource.resource_type).or_default() += 1;
+            }
 
-        let backup_file = backup_dir.join(format!("{}.json", backup_id));
-        let backup_json = serde_json::to_string_pretty(&backup)?;
-        ctx.fs.write(&backup_file, &backup_json)?;
+            ctx.output.subsection("Resources by Type");
+            for (resource_type, count) in by_type {
+                ctx.output.key_value(resource_type, &count.to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Execute the state resources command
+    pub fn execute_resources(
+        ctx: &Context,
+        project: &str,
+        environment: Option<&str>,
+        resource_type: Option<&str>,
+        format: &str,
+    ) -> Result<()> {
+        let structured = Self::is_structured(format)?;
+        let target = Self::load_state_target(ctx, project, environment, !structured)?;
+
+        let resources: Vec<&StateResource> = target
+            .state
+            .resources
+            .iter()
+            .filter(|r| resource_type.is_none_or(|t| r.resource_type == t))
+            .collect();
+
+        if Self::print_structured(&resources, format)? {
+            return Ok(());
+        }
 
         ctx.output
-            .success(&format!("Backup created: {}", backup_id));
+            .section(&format!("State Resources: {}", target.describe()));
+
+        if resources.is_empty() {
+            ctx.output.dimmed("No resources found.");
+            return Ok(());
+        }
+
+        for resource in &resources {
+            match resource.attributes.get("id").and_then(|id| id.as_str()) {
+                Some(id) => ctx.output.info(&format!("{}  ({})", resource.address, id)),
+                None => ctx.output.info(&resource.address),
+            }
+        }
+
         output::blank();
+        ctx.output
+            .info(&format!("Total resources: {}", resources.len()));
 
-        // Migrate backend
-        Self::migrate_backend(ctx, &current_path, backend_type)?;
+        Ok(())
+    }
+
+    /// Execute the state outputs command
+    pub fn execute_outputs(
+        ctx: &Context,
+        project: &str,
+        environment: Option<&str>,
+        show_sensitive: bool,
+        format: &str,
+    ) -> Result<()> {
+        let structured = Self::is_structured(format)?;
+        let target = Self::load_state_target(ctx, project, environment, !structured)?;
+        let state = Self::mask_sensitive_outputs(target.state.clone(), show_sensitive);
+
+        if Self::print_structured(&state.outputs, format)? {
+            return Ok(());
+        }
 
         ctx.output
-            .success("Backend migration completed successfully");
+            .section(&format!("State Outputs: {}", target.describe()));
+
+        if state.outputs.is_empty() {
+            ctx.output.dimmed("No outputs found.");
+            return Ok(());
+        }
+
+        for (name, output) in &state.outputs {
+            ctx.output
+                .key_value(name, &Self::format_value(&output.value));
+        }
+
+        Ok(())
+    }
+
+    /// Execute the state mv command
+    pub fn execute_mv(
+        ctx: &Context,
+        project: &str,
+        environment: Option<&str>,
+        source: &str,
+        destination: &str,
+        yes: bool,
+    ) -> Result<()> {
+        ctx.output.section("State Move");
+
+        let target = Self::load_state_target(ctx, project, environment, true)?;
+        let moved = target.state.matching(source);
+
+        if moved.is_empty() {
+            anyhow::bail!(
+                "Resource '{}' not found in state of {}",
+                source,
+                target.describe()
+            );
+        }
+        if !target.state.matching(destination).is_empty() {
+            anyhow::bail!(
+                "Destination '{}' already exists in state of {}",
+                destination,
+                target.describe()
+            );
+        }
+
+        ctx.output
+            .key_value("Project", &target.resource.metadata.name);
         ctx.output
-            .dimmed(&format!("Backup available for rollback: {}", backup_id));
+            .key_value("Environment", &target.resource.metadata.environment_name);
+        ctx.output.key_value("From", source);
+        ctx.output.key_value_highlight("To", destination);
+        ctx.output.key_value("Instances", &moved.len().to_string());
+        output::blank();
+
+        if !yes
+            && !ctx
+                .input
+                .confirm("Move these resources in the state?", Some(false))?
+        {
+            ctx.output.dimmed("Move cancelled.");
+            return Ok(());
+        }
+
+        let addresses = vec![source.to_string(), destination.to_string()];
+        Self::modify_state(ctx, &target, "state-mv", addresses, |working_dir| {
+            target.executor.state_mv(working_dir, source, destination)
+        })?;
+
+        ctx.output
+            .success(&format!("Moved {} to {}", source, destination));
 
         Ok(())
     }
 
-    /// Create a state backup
-    fn create_backup(
+    /// Execute the state rm command
+    pub fn execute_rm(
         ctx: &Context,
-        env_path: &Path,
-        resource: &DynamicProjectEnvironmentResource,
-        backup_id: &str,
-    ) -> Result<StateBackup> {
-        // Read current state file
-        let state_file = env_path.join("terraform.tfstate");
-        let state_content = if ctx.fs.exists(&state_file) {
-            ctx.fs.read_to_string(&state_file)?
+        project: &str,
+        environment: Option<&str>,
+        addresses: &[String],
+        yes: bool,
+    ) -> Result<()> {
+        ctx.output.section("State Remove");
+
+        let target = Self::load_state_target(ctx, project, environment, true)?;
+
+        let mut removed = Vec::new();
+        for address in addresses {
+            let matched = target.state.matching(address);
+            if matched.is_empty() {
+                anyhow::bail!(
+                    "Resource '{}' not found in state of {}",
+                    address,
+                    target.describe()
+                );
+            }
+            removed.extend(matched);
+        }
+
+        ctx.output
+            .key_value("Project", &target.resource.metadata.name);
+        ctx.output
+            .key_value("Environment", &target.resource.metadata.environment_name);
+        ctx.output.subsection("Resources to Remove");
+        for resource in &removed {
+            ctx.output.info(&resource.address);
+        }
+        output::blank();
+        ctx.output
+            .dimmed("The resources will no longer be managed but are not destroyed.");
+
+        if !yes
+            && !ctx.input.confirm(
+                &format!(
+                    "Remove {} resource instance(s) from the state?",
+                    removed.len()
+                ),
+                Some(false),
+            )?
+        {
+            ctx.output.dimmed("Remove cancelled.");
+            return Ok(());
+        }
+
+        Self::modify_state(
+            ctx,
+            &target,
+            "state-rm",
+            addresses.to_vec(),
+            |working_dir| target.executor.state_rm(working_dir, addresses),
+        )?;
+
+        ctx.output.success(&format!(
+            "Removed {} resource instance(s) from the state",
+            removed.len()
+        ));
+
+        Ok(())
+    }
+
+    /// Execute the state audit command
+    pub fn execute_audit(ctx: &Context, project: Option<&str>, limit: usize) -> Result<()> {
+        ctx.output.section("State Audit Log");
+
+        let (_, infrastructure_root) = CollectionDiscovery::find_collection(&*ctx.fs)?
+            .context("Infrastructure is required. Run 'pmp init' first.")?;
+
+        let entries = AuditLog::for_infrastructure(&*ctx.fs, &infrastructure_root).entries()?;
+        let entries: Vec<_> = entries
+            .iter()
+            .rev()
+            .filter(|e| project.is_none_or(|p| e.project == p))
+            .take(limit)
+            .collect();
+
+        if entries.is_empty() {
+            ctx.output.dimmed("No audit entries found.");
+            return Ok(());
+        }
+
+        for entry in entries {
+            let status = match &entry.error {
+                Some(error) => format!("failed: {}", error),
+                None => "ok".to_string(),
+            };
+
+            ctx.output.info(&format!(
+                "{}  {}  {}  {}/{}  {}",
+                entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                entry.user,
+                entry.operation,
+                entry.project,
+                entry.environment,
+                status
+            ));
+            ctx.output
+                .dimmed(&format!("   {}", entry.addresses.join(" -> ")));
+            if let Some(backup_id) = &entry.backup_id {
+                ctx.output.dimmed(&format!("   backup {}", backup_id));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resolve a project environment by name, initialize its executor and pull its state
+    fn load_state_target(
+        ctx: &Context,
+        project: &str,
+        environment: Option<&str>,
+        verbose: bool,
+    ) -> Result<StateTarget> {
+        let env_path = Self::resolve_project_environment(ctx, project, environment)?;
+        let resource = DynamicProjectEnvironmentResource::from_file(
+            &*ctx.fs,
+            &env_path.join(".pmp.environment.yaml"),
+        )?;
+        let executor = ctx.executor_registry.get(&resource.spec.executor.name)?;
+
+        let env_dir_str = if verbose {
+            Self::initialize_executor(ctx, executor.as_ref(), &env_path)?
+        } else {
+            let env_dir_str = env_path
+                .to_str()
+                .context("Failed to convert path to string")?;
+            let init_output = executor.init(env_dir_str)?;
+
+            if !init_output.status.success() {
+                anyhow::bail!(
+                    "Initialization failed: {}",
+                    String::from_utf8_lossy(&init_output.stderr).trim()
+                );
+            }
+
+            env_dir_str
+        };
+
+        let content = executor.state_pull(env_dir_str)?;
+        let state = if content.trim().is_empty() {
+            StateDocument::default()
         } else {
-            String::from("{}")
+            StateDocument::parse(&content)?
         };
 
-        Ok(StateBackup {
-            project: resource.metadata.name.clone(),
-            environment: resource.metadata.environment_name.clone(),
-            timestamp: chrono::Utc::now().to_rfc3339(),
-            backup_id: backup_id.to_string(),
-            state_content,
+        Ok(StateTarget {
+            env_path,
+            resource,
+            executor,
+            state,
         })
     }
 
-    /// Restore a backup
-    fn restore_backup(ctx: &Context, env_path: &Path, backup: &StateBackup) -> Result<()> {
-        let state_file = env_path.join("terraform.tfstate");
+    /// Run a state-modifying command holding the state lock, after a required backup,
+    /// and record it in the audit log
+    fn modify_state(
+        ctx: &Context,
+        target: &StateTarget,
+        operation: &str,
+        addresses: Vec<String>,
+        f: impl FnOnce(&str) -> Result<()>,
+    ) -> Result<()> {
+        let (infrastructure, infrastructure_root) = CollectionDiscovery::find_collection(&*ctx.fs)?
+            .context("Infrastructure is required. Run 'pmp init' first.")?;
+        let project = &target.resource.metadata.name;
+        let environment = &target.resource.metadata.environment_name;
+        let working_dir = target
+            .env_path
+            .to_str()
+            .context("Failed to convert path to string")?;
 
-        // Create backup of current state before restore
-        if ctx.fs.exists(&state_file) {
-            let current_backup = env_path.join("terraform.tfstate.before-restore");
-            let current_content = ctx.fs.read_to_string(&state_file)?;
-            ctx.fs.write(&current_backup, &current_content)?;
-            ctx.output.dimmed(&format!(
-                "Current state backed up to: {}",
-                current_backup.display()
-            ));
+        let mut entry = AuditEntry::new(operation, project, environment, addresses);
+
+        let result = ExecutionHelper::with_state_lock(
+            ctx,
+            &infrastructure,
+            &infrastructure_root,
+            project,
+            environment,
+            operation,
+            || {
+                if let Some(saved) = ExecutionHelper::backup_state(
+                    ctx,
+                    &infrastructure,
+                    &infrastructure_root,
+                    target.executor.as_ref(),
+                    working_dir,
+                    project,
+                    environment,
+                    &format!("pre-{}", operation),
+                )? {
+                    ctx.output
+                        .dimmed(&format!("State backed up ({})", saved.entry.id));
+                    entry.backup_id = Some(saved.entry.id);
+                }
+
+                f(working_dir)?;
+
+                ExecutionHelper::refresh_state_snapshot(
+                    ctx,
+                    &infrastructure_root,
+                    target.executor.as_ref(),
+                    working_dir,
+                    project,
+                    environment,
+                );
+
+                Ok(())
+            },
+        );
+
+        if let Err(e) = &result {
+            entry.success = false;
+            entry.error = Some(format!("{:#}", e));
         }
 
-        // Write restored state
-        ctx.fs.write(&state_file, &backup.state_content)?;
+        if let Err(e) = AuditLog::for_infrastructure(&*ctx.fs, &infrastructure_root).record(&entry)
+        {
+            ctx.output
+                .warning(&format!("Failed to record audit entry: {}", e));
+        }
 
-        Ok(())
+        result
     }
 
-    /// Migrate backend
-    fn migrate_backend(ctx: &Context, env_path: &Path, backend_type: &str) -> Result<()> {
-        ctx.output
-            .info(&format!("Migrating to {} backend...", backend_type));
+    /// Whether a --format value asks for JSON/YAML output
+    fn is_structured(format: &str) -> Result<bool> {
+        match format {
+            "text" => Ok(false),
+            "json" | "yaml" => Ok(true),
+            _ => anyhow::bail!(
+                "Unsupported output format: {}. Use 'text', 'json' or 'yaml'",
+                format
+            ),
+        }
+    }
 
-        // This would generate new backend configuration
-        // For now, we'll create a placeholder implementation
+    /// Print a value as JSON or YAML; returns false for text output
+    fn print_structured<T: serde::Serialize + ?Sized>(value: &T, format: &str) -> Result<bool> {
+        match format {
+            "json" => println!(
+                "{}",
+                serde_json::to_string_pretty(value).context("Failed to serialize to JSON")?
+            ),
+            "yaml" => print!(
+                "{}",
+                serde_yaml::to_string(value).context("Failed to serialize to YAML")?
+            ),
+            _ => return Ok(false),
+        }
 
-        let backend_config = match backend_type {
-            "s3" => {
-                "terraform {\n  backend \"s3\" {\n    # Configure S3 backend parameters\n  }\n}\n"
-            }
-            "azurerm" => {
-                "terraform {\n  backend \"azurerm\" {\n    # Configure Azure backend parameters\n  }\n}\n"
-            }
-            "gcs" => {
-                "terraform {\n  backend \"gcs\" {\n    # Configure GCS backend parameters\n  }\n}\n"
+        Ok(true)
+    }
+
+    /// Replace sensitive output values unless they were asked for
+    fn mask_sensitive_outputs(mut state: StateDocument, show_sensitive: bool) -> StateDocument {
+        if !show_sensitive {
+            for output in state.outputs.values_mut().filter(|o| o.sensitive) {
+                output.value = serde_json::Value::String("(sensitive)".to_string());
             }
-            "local" => "terraform {\n  backend \"local\" {}\n}\n",
-            _ => anyhow::bail!("Unsupported backend type: {}", backend_type),
+        }
+        state
+    }
+
+    /// Render an attribute or output value on one line
+    fn format_value(value: &serde_json::Value) -> String {
+        match value {
+            serde_json::Value::String(s) => s.clone(),
+            serde_json::Value::Null => "null".to_string(),
+            other => other.to_string(),
+        }
+    }
+
+    /// Environment directory from --path or the current directory
+    fn resolve_environment_dir(
+        ctx: &Context,
+        path: Option<&str>,
+    ) -> Result<(PathBuf, DynamicProjectEnvironmentResource)> {
+        let current_path = if let Some(p) = path {
+            PathBuf::from(p)
+        } else {
+            std::env::current_dir()?
         };
 
-        // Write new backend configuration
-        let backend_file = env_path.join("_backend.tf");
-        ctx.fs.write(&backend_file, backend_config)?;
+        let env_yaml = current_path.join(".pmp.environment.yaml");
+
+        if !ctx.fs.exists(&env_yaml) {
+            anyhow::bail!(
+                "Not in an environment directory. Navigate to a project environment or use --path"
+            );
+        }
+
+        let resource = DynamicProjectEnvironmentResource::from_file(&*ctx.fs, &env_yaml)?;
+
+        Ok((current_path, resource))
+    }
+
+    /// Initialize the executor so state commands can reach the backend
+    fn initialize_executor<'a>(
+        ctx: &Context,
+        executor: &dyn crate::executor::Executor,
+        env_path: &'a Path,
+    ) -> Result<&'a str> {
+        let env_dir_str = env_path
+            .to_str()
+            .context("Failed to convert path to string")?;
 
-        ctx.output.success(&format!(
-            "Backend configuration written to: {}",
-            backend_file.display()
-        ));
         ctx.output
-            .dimmed("Run 'tofu init -migrate-state' to complete migration");
+            .dimmed(&format!("Initializing {}...", executor.get_name()));
+        let init_output = executor.init(env_dir_str)?;
 
-        Ok(())
+        if !init_output.status.success() {
+            ctx.output
+                .error(&String::from_utf8_lossy(&init_output.stderr));
+            anyhow::bail!(
+                "Initialization failed with exit code: {:?}",
+                init_output.status.code()
+            );
+        }
+
+        Ok(env_dir_str)
+    }
+
+    /// Push a state document to the backend through a temporary file
+    fn push_state(
+        ctx: &Context,
+        executor: &dyn crate::executor::Executor,
+        env_path: &Path,
+        state_content: &str,
+    ) -> Result<()> {
+        let env_dir_str = env_path
+            .to_str()
+            .context("Failed to convert path to string")?;
+        let state_file = env_path.join(RESTORE_STATE_FILE);
+        ctx.fs.write(&state_file, state_content)?;
+
+        // Force is required to push a state with an older serial
+        let result = executor.state_push(env_dir_str, RESTORE_STATE_FILE, true);
+        ctx.fs.remove_file(&state_file)?;
+
+        result
+    }
+
+    /// Display the details of a backup
+    fn display_backup(ctx: &Context, entry: &BackupEntry) {
+        ctx.output.key_value("Backup ID", &entry.id);
+        ctx.output
+            .key_value("Created", &entry.created_at.to_rfc3339());
+        ctx.output.key_value("Reason", &entry.reason);
+        if let Some(serial) = entry.summary.serial {
+            ctx.output.key_value("Serial", &serial.to_string());
+        }
+        if let Some(lineage) = &entry.summary.lineage {
+            ctx.output.key_value("Lineage", lineage);
+        }
+        ctx.output
+            .key_value("Resources", &entry.summary.resource_count.to_string());
     }
 }
@@ -1,16 +1,79 @@
+use crate::commands::update::template_version;
 use crate::context::Context;
 use crate::output;
+use crate::template::diff::{self, DiffSide, TemplateDiff};
+use crate::template::inheritance::ResolvedTemplate;
+use crate::template::lint::LintResult;
+use crate::template::lint_fix;
+use crate::template::testing::{self, TemplateTestRunner, TestOutcome};
 use crate::template::{
     discovery::parse_colon_separated_paths, LintFormatter, LintOptions, TemplateLinter,
-    TemplateDiscovery,
+    TemplateDiscovery, TemplatePackInfo, TemplateRenderer, TemplateResolver,
 };
 use anyhow::{Context as AnyhowContext, Result};
+use std::collections::HashSet;
+use std::fmt;
 use std::path::PathBuf;
 
 pub struct TemplateCommand;
 
+/// Reference to a template on the command line: `[pack/]template[@version]`
+#[derive(Debug, Clone, PartialEq)]
+struct TemplateRef {
+    pack: Option<String>,
+    name: String,
+    version: Option<semver::Version>,
+}
+
+impl TemplateRef {
+    /// Parse a reference; a bare `@version` refers to another version of `base`
+    fn parse(value: &str, base: Option<&TemplateRef>) -> Result<Self> {
+        let (path, version) = match value.split_once('@') {
+            Some((path, version)) => {
+                let version = semver::Version::parse(version)
+                    .with_context(|| format!("Invalid template version: {}", version))?;
+                (path, Some(version))
+            }
+            None => (value, None),
+        };
+
+        if path.is_empty() {
+            let base = base.with_context(|| format!("Missing template name: {}", value))?;
+            return Ok(Self {
+                version,
+                ..base.clone()
+            });
+        }
+
+        let (pack, name) = match path.split_once('/') {
+            Some((pack, name)) => (Some(pack.to_string()), name.to_string()),
+            None => (None, path.to_string()),
+        };
+
+        Ok(Self {
+            pack,
+            name,
+            version,
+        })
+    }
+}
+
+impl fmt::Display for TemplateRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(pack) = &self.pack {
+            write!(f, "{}/", pack)?;
+        }
+        write!(f, "{}", self.name)?;
+        if let Some(version) = &self.version {
+            write!(f, "@{}", version)?;
+        }
+        Ok(())
+    }
+}
+
 impl TemplateCommand {
     /// Execute the template lint command
+    #[allow(clippy::too_many_arguments)]
     pub fn execute_lint(
         ctx: &Context,
         pack_filter: Option<&str>,
@@ -18,6 +81,7 @@ impl TemplateCommand {
         include_info: bool,
         skip_unused_inputs: bool,
         skip_handlebars: bool,
+        fix: bool,
         template_packs_paths: Option<&str>,
     ) -> Result<()> {
         // Parse custom paths for template pack discovery
@@ -63,20 +127,45 @@ impl TemplateCommand {
             include_info,
         };
 
-        let mut total_errors = 0;
-        let mut total_warnings = 0;
         let mut all_results = Vec::new();
 
         for pack in &packs_to_lint {
             let result =
                 TemplateLinter::lint_pack(ctx.fs.as_ref(), ctx.output.as_ref(), pack, &all_packs, &options)?;
 
-            total_errors += result.count_by_severity(crate::template::lint::LintSeverity::Error);
-            total_warnings += result.count_by_severity(crate::template::lint::LintSeverity::Warning);
-
             all_results.push(result);
         }
 
+        if fix && Self::apply_lint_fixes(ctx, &all_results, format != "json")? {
+            // Lint again so the report reflects the fixed files
+            let all_packs = TemplateDiscovery::discover_template_packs_with_custom_paths(
+                ctx.fs.as_ref(),
+                ctx.output.as_ref(),
+                &custom_paths_refs,
+            )?;
+            let linted: HashSet<&PathBuf> = packs_to_lint.iter().map(|p| &p.path).collect();
+
+            all_results.clear();
+            for pack in all_packs.iter().filter(|p| linted.contains(&p.path)) {
+                all_results.push(TemplateLinter::lint_pack(
+                    ctx.fs.as_ref(),
+                    ctx.output.as_ref(),
+                    pack,
+                    &all_packs,
+                    &options,
+                )?);
+            }
+        }
+
+        let total_errors: usize = all_results
+            .iter()
+            .map(|r| r.count_by_severity(crate::template::lint::LintSeverity::Error))
+            .sum();
+        let total_warnings: usize = all_results
+            .iter()
+            .map(|r| r.count_by_severity(crate::template::lint::LintSeverity::Warning))
+            .sum();
+
         // Output results
         match format {
             "json" => {
@@ -115,6 +204,405 @@ impl TemplateCommand {
         Ok(())
     }
 
+    /// Apply the fixes of lint issues, printing a diff of each file before writing it
+    ///
+    /// Returns whether any file was changed.
+    fn apply_lint_fixes(ctx: &Context, results: &[LintResult], show_diff: bool) -> Result<bool> {
+        let files = lint_fix::plan_fixes(ctx.fs.as_ref(), results)?;
+
+        if files.is_empty() {
+            if show_diff {
+                ctx.output.info("No fixable issues found");
+            }
+            return Ok(false);
+        }
+
+        if show_diff {
+            let diffs: Vec<diff::FileDiff> = files
+                .iter()
+                .map(|file| {
+                    let path = file.path.display().to_string();
+                    diff::FileDiff {
+                        diff: diff::unified_diff(&path, &file.original, &file.fixed),
+                        path,
+                        change: diff::ItemChange::Changed,
+                    }
+                })
+                .collect();
+            Self::print_file_diffs(ctx, "Fixes", &diffs);
+            output::blank();
+        }
+
+        for file in &files {
+            ctx.fs
+                .write(&file.path, &file.fixed)
+                .with_context(|| format!("Failed to write {}", file.path.display()))?;
+        }
+
+        if show_diff {
+            let fixes: usize = files.iter().map(|f| f.fixes).sum();
+            ctx.output.success(&format!(
+                "Applied {} fix(es) to {} file(s)",
+                fixes,
+                files.len()
+            ));
+            output::blank();
+        }
+
+        Ok(true)
+    }
+
+    /// Execute the template diff command
+    ///
+    /// Templates are referenced as `[pack/]template[@version]`; `to` may be just `@version` to
+    /// compare two versions of the `from` template. Without a version, the latest is used.
+    pub fn execute_diff(
+        ctx: &Context,
+        from: &str,
+        to: &str,
+        inputs_file: Option<&str>,
+        format: &str,
+        template_packs_paths: Option<&str>,
+    ) -> Result<()> {
+        let custom_paths_owned: Vec<String> = template_packs_paths
+            .map(parse_colon_separated_paths)
+            .unwrap_or_default();
+        let custom_paths_refs: Vec<&str> = custom_paths_owned.iter().map(|s| s.as_str()).collect();
+
+        let all_packs = TemplateDiscovery::discover_template_packs_with_custom_paths(
+            ctx.fs.as_ref(),
+            ctx.output.as_ref(),
+            &custom_paths_refs,
+        )?;
+
+        let from_ref = TemplateRef::parse(from, None)?;
+        let to_ref = TemplateRef::parse(to, Some(&from_ref))?;
+
+        let (from_label, from_pack, from_template) =
+            Self::resolve_template_ref(ctx, &from_ref, &all_packs)?;
+        let (to_label, to_pack, to_template) =
+            Self::resolve_template_ref(ctx, &to_ref, &all_packs)?;
+
+        let inputs = inputs_file
+            .map(|path| diff::load_inputs_file(ctx.fs.as_ref(), std::path::Path::new(path)))
+            .transpose()?;
+
+        let result = TemplateDiff::compare(
+            ctx,
+            &DiffSide {
+                label: from_label,
+                template: &from_template,
+                pack_path: &from_pack,
+            },
+            &DiffSide {
+                label: to_label,
+                template: &to_template,
+                pack_path: &to_pack,
+            },
+            inputs.as_ref(),
+        )?;
+
+        match format {
+            "json" => {
+                let json = serde_json::to_string_pretty(&result)?;
+                println!("{}", json);
+            }
+            _ => Self::print_diff(ctx, &result),
+        }
+
+        Ok(())
+    }
+
+    /// Execute the template test command
+    ///
+    /// Renders the golden-file test cases of every template (see `template::testing`) and
+    /// fails if any rendered output differs from its expected files.
+    pub fn execute_test(
+        ctx: &Context,
+        pack_filter: Option<&str>,
+        template_filter: Option<&str>,
+        update: bool,
+        template_packs_paths: Option<&str>,
+    ) -> Result<()> {
+        let custom_paths_owned: Vec<String> = template_packs_paths
+            .map(parse_colon_separated_paths)
+            .unwrap_or_default();
+        let custom_paths_refs: Vec<&str> = custom_paths_owned.iter().map(|s| s.as_str()).collect();
+
+        let all_packs = TemplateDiscovery::discover_template_packs_with_custom_paths(
+            ctx.fs.as_ref(),
+            ctx.output.as_ref(),
+            &custom_paths_refs,
+        )?;
+
+        let packs: Vec<&TemplatePackInfo> = all_packs
+            .iter()
+            .filter(|p| pack_filter.is_none_or(|name| p.resource.metadata.name == name))
+            .collect();
+
+        if packs.is_empty() {
+            if let Some(filter) = pack_filter {
+                anyhow::bail!("Template pack '{}' not found", filter);
+            }
+            ctx.output.warning("No template packs found");
+            return Ok(());
+        }
+
+        ctx.output.section("Template Tests");
+
+        let (mut passed, mut failed, mut updated) = (0, 0, 0);
+
+        for pack in packs {
+            let renderer = TemplateRenderer::new_with_partials(ctx.fs.as_ref(), Some(&pack.path))
+                .context("Failed to initialize template renderer with partials")?;
+            let templates = TemplateDiscovery::discover_templates_in_pack(
+                ctx.fs.as_ref(),
+                ctx.output.as_ref(),
+                &pack.path,
+            )?;
+
+            for template in templates {
+                let name = &template.resource.metadata.name;
+                if template_filter.is_some_and(|filter| filter != name) {
+                    continue;
+                }
+
+                let cases = TemplateTestRunner::discover_cases(ctx.fs.as_ref(), &template.path)?;
+                if cases.is_empty() {
+                    continue;
+                }
+
+                let label = format!(
+                    "{}/{}@{}",
+                    pack.resource.metadata.name,
+                    name,
+                    template_version(&template)
+                );
+                ctx.output.subsection(&label);
+
+                let resolved = TemplateResolver::resolve(
+                    ctx.fs.as_ref(),
+                    ctx.output.as_ref(),
+                    &template,
+                    pack,
+                    &all_packs,
+                )?;
+
+                for case in &cases {
+                    match TemplateTestRunner::run_case(ctx, &renderer, &resolved, case, update) {
+                        TestOutcome::Passed => {
+                            ctx.output.success(&case.name);
+                            passed += 1;
+                        }
+                        TestOutcome::Updated { files } => {
+                            ctx.output.info(&format!(
+                                "{}: updated {} file(s)",
+                                case.name,
+                                files.len()
+                            ));
+                            updated += 1;
+                        }
+                        TestOutcome::Failed { files } => {
+                            ctx.output.error(&format!(
+                                "{}: {} file(s) differ from {}",
+                                case.name,
+                                files.len(),
+                                case.path.join(testing::EXPECTED_DIR).display()
+                            ));
+                            Self::print_file_diffs(ctx, "Differences", &files);
+                            failed += 1;
+                        }
+                        TestOutcome::Error { message } => {
+                            ctx.output.error(&format!("{}: {}", case.name, message));
+                            failed += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        output::blank();
+
+        if passed + failed + updated == 0 {
+            ctx.output.warning(&format!(
+                "No test cases found (add {}/<case>/{} to a template)",
+                testing::TESTS_DIR,
+                testing::INPUTS_FILE
+            ));
+            return Ok(());
+        }
+
+        let summary = if update {
+            format!("{} passed, {} updated, {} failed", passed, updated, failed)
+        } else {
+            format!("{} passed, {} failed", passed, failed)
+        };
+
+        if failed > 0 {
+            anyhow::bail!("Template tests failed: {}", summary);
+        }
+
+        ctx.output.success(&format!("Template tests: {}", summary));
+        Ok(())
+    }
+
+    /// Find the template a reference points to and resolve its inheritance
+    fn resolve_template_ref(
+        ctx: &Context,
+        template_ref: &TemplateRef,
+        all_packs: &[TemplatePackInfo],
+    ) -> Result<(String, PathBuf, ResolvedTemplate)> {
+        let mut candidates = Vec::new();
+
+        for pack in all_packs {
+            if template_ref
+                .pack
+                .as_ref()
+                .is_some_and(|name| *name != pack.resource.metadata.name)
+            {
+                continue;
+            }
+
+            let templates = TemplateDiscovery::discover_templates_in_pack(
+                ctx.fs.as_ref(),
+                ctx.output.as_ref(),
+                &pack.path,
+            )?;
+
+            for template in templates {
+                if template.resource.metadata.name != template_ref.name {
+                    continue;
+                }
+
+                let version = template_version(&template);
+                if template_ref.version.as_ref().is_some_and(|v| *v != version) {
+                    continue;
+                }
+
+                candidates.push((pack, template, version));
+            }
+        }
+
+        let packs: HashSet<&str> = candidates
+            .iter()
+            .map(|(pack, _, _)| pack.resource.metadata.name.as_str())
+            .collect();
+        if packs.len() > 1 {
+            let mut packs: Vec<&str> = packs.into_iter().collect();
+            packs.sort();
+            anyhow::bail!(
+                "Template '{}' exists in several packs ({}); use <pack>/{}",
+                template_ref.name,
+                packs.join(", "),
+                template_ref.name
+            );
+        }
+
+        let (pack, template, version) = candidates
+            .into_iter()
+            .max_by(|a, b| a.2.cmp(&b.2))
+            .with_context(|| format!("Template '{}' not found", template_ref))?;
+
+        let resolved = TemplateResolver::resolve(
+            ctx.fs.as_ref(),
+            ctx.output.as_ref(),
+            &template,
+            pack,
+            all_packs,
+        )?;
+
+        Ok((
+            format!(
+                "{}/{}@{}",
+                pack.resource.metadata.name, template_ref.name, version
+            ),
+            pack.path.clone(),
+            resolved,
+        ))
+    }
+
+    fn print_diff(ctx: &Context, result: &TemplateDiff) {
+        ctx.output.section("Template Diff");
+        ctx.output.key_value("From", &result.from);
+        ctx.output.key_value("To", &result.to);
+
+        let breaking_label = |breaking: bool| if breaking { " [breaking]" } else { "" };
+
+        if !result.inputs.is_empty() {
+            ctx.output.subsection("Inputs");
+            for input in &result.inputs {
+                ctx.output.info(&format!(
+                    "  {}: {}{}",
+                    input.name,
+                    input.change,
+                    breaking_label(input.breaking)
+                ));
+            }
+        }
+
+        for (title, items) in [
+            ("Dependencies", &result.dependencies),
+            ("Plugins", &result.plugins),
+            ("Hooks", &result.hooks),
+        ] {
+            if items.is_empty() {
+                continue;
+            }
+
+            ctx.output.subsection(title);
+            for item in items {
+                ctx.output.info(&format!(
+                    "  {} {}{}",
+                    item.change,
+                    item.name,
+                    breaking_label(item.breaking)
+                ));
+            }
+        }
+
+        Self::print_file_diffs(ctx, "Source Files", &result.files);
+        if let Some(rendered) = &result.rendered {
+            Self::print_file_diffs(ctx, "Rendered Files", rendered);
+        }
+
+        output::blank();
+        let breaking = result.breaking_changes();
+        if result.is_empty() {
+            ctx.output.success("No differences found");
+        } else if breaking > 0 {
+            ctx.output
+                .warning(&format!("{} breaking change(s)", breaking));
+        } else {
+            ctx.output.success("No breaking changes");
+        }
+    }
+
+    fn print_file_diffs(ctx: &Context, title: &str, files: &[diff::FileDiff]) {
+        if files.is_empty() {
+            return;
+        }
+
+        ctx.output.subsection(title);
+        for file in files {
+            ctx.output.info(&format!("  {} {}", file.change, file.path));
+        }
+
+        for file in files {
+            output::blank();
+            for line in file.diff.lines() {
+                if line.starts_with("---") || line.starts_with("+++") || line.starts_with("@@") {
+                    ctx.output.lavender(line);
+                } else if line.starts_with('+') {
+                    ctx.output.cyan(line);
+                } else if line.starts_with('-') {
+                    ctx.output.dark_yellow(line);
+                } else {
+                    ctx.output.dimmed(line);
+                }
+            }
+        }
+    }
+
     /// Execute the template scaffold command
     pub fn execute_scaffold(ctx: &Context, output_dir: Option<&str>) -> Result<()> {
         ctx.output.section("Template Scaffolding");
@@ -347,3 +835,141 @@ impl TemplateCommand {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{FileSystem, MockFileSystem, MockOutput};
+    use std::sync::Arc;
+
+    fn write_template(fs: &MockFileSystem, version: &str, inputs: &str, main_tf: &str) {
+        let dir = PathBuf::from("/packs/core/templates/api/versions").join(version);
+        fs.write(
+            &dir.join(".pmp.template.yaml"),
+            &format!(
+                "apiVersion: pmp.io/v1\nkind: Template\nmetadata:\n  name: api\nspec:\n  apiVersion: pmp.io/v1\n  kind: Api\n  executor: opentofu\n  inputs:\n{}",
+                inputs
+            ),
+        )
+        .unwrap();
+        fs.write(&dir.join("src/main.tf.hbs"), main_tf).unwrap();
+    }
+
+    #[test]
+    fn test_template_ref_parse() {
+        let base = TemplateRef::parse("core/api@1.0.0", None).unwrap();
+        assert_eq!(base.pack.as_deref(), Some("core"));
+        assert_eq!(base.name, "api");
+
+        let other = TemplateRef::parse("@2.0.0", Some(&base)).unwrap();
+        assert_eq!(other.to_string(), "core/api@2.0.0");
+
+        assert_eq!(TemplateRef::parse("api", None).unwrap().to_string(), "api");
+        assert!(TemplateRef::parse("@2.0.0", None).is_err());
+        assert!(TemplateRef::parse("api@latest", None).is_err());
+    }
+
+    #[test]
+    fn test_execute_diff() {
+        let fs = Arc::new(MockFileSystem::new());
+        fs.write(
+            &PathBuf::from("/packs/core/.pmp.template-pack.yaml"),
+            "apiVersion: pmp.io/v1\nkind: TemplatePack\nmetadata:\n  name: core\nspec: {}\n",
+        )
+        .unwrap();
+        write_template(
+            &fs,
+            "1.0.0",
+            "    - name: size\n      default: small\n",
+            "size = \"{{size}}\"\n",
+        );
+        write_template(
+            &fs,
+            "2.0.0",
+            "    - name: size\n      default: small\n    - name: region\n",
+            "size = \"{{size}}\"\nregion = \"{{region}}\"\n",
+        );
+        fs.write(&PathBuf::from("/inputs.yaml"), "region: eu-west-1\n")
+            .unwrap();
+
+        let output = Arc::new(MockOutput::new());
+        let ctx = Context::test_with(
+            fs,
+            Arc::new(crate::traits::MockUserInput::new()),
+            output.clone(),
+            Arc::new(crate::traits::MockCommandExecutor::new()),
+            Arc::new(crate::executor::DefaultExecutorRegistry::new()),
+        );
+
+        TemplateCommand::execute_diff(
+            &ctx,
+            "api@1.0.0",
+            "@2.0.0",
+            Some("/inputs.yaml"),
+            "text",
+            Some("/packs"),
+        )
+        .unwrap();
+
+        let messages = format!("{:?}", output.get_messages());
+        assert!(messages.contains("core/api@1.0.0"));
+        assert!(messages.contains("region: added (required) [breaking]"));
+        assert!(messages.contains("changed main.tf.hbs"));
+        assert!(messages.contains("+region = \\\"{{region}}\\\""));
+        assert!(messages.contains("+region = \\\"eu-west-1\\\""));
+        assert!(messages.contains("1 breaking change(s)"));
+    }
+
+    #[test]
+    fn test_execute_lint_fix() {
+        let fs = Arc::new(MockFileSystem::new());
+        fs.write(
+            &PathBuf::from("/packs/core/.pmp.template-pack.yaml"),
+            "apiVersion: pmp.io/v1\nkind: TemplatePack\nmetadata:\n  name: core\nspec: {}\n",
+        )
+        .unwrap();
+        let template_file = PathBuf::from("/packs/core/templates/api/.pmp.template.yaml");
+        fs.write(
+            &template_file,
+            "apiVersion: pmp.io/v1\nkind: Template\nmetadata:\n  name: api\n  description: API\nspec:\n  apiVersion: pmp.io/v1\n  kind: Api\n  executor: opentofu\n  # Inputs\n  inputs:\n    size:\n      description: Size\n      enum_values: [small, large]\n      default: small\n    legacy:\n      description: Legacy\n",
+        )
+        .unwrap();
+        fs.write(
+            &PathBuf::from("/packs/core/templates/api/src/main.tf.hbs"),
+            "size = \"{{size}}\"\n",
+        )
+        .unwrap();
+
+        let output = Arc::new(MockOutput::new());
+        let ctx = Context::test_with(
+            fs.clone(),
+            Arc::new(crate::traits::MockUserInput::new()),
+            output.clone(),
+            Arc::new(crate::traits::MockCommandExecutor::new()),
+            Arc::new(crate::executor::DefaultExecutorRegistry::new()),
+        );
+
+        TemplateCommand::execute_lint(
+            &ctx,
+            None,
+            "text",
+            false,
+            false,
+            false,
+            true,
+            Some("/packs"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            fs.get_file_contents(&template_file).unwrap(),
+            "apiVersion: pmp.io/v1\nkind: Template\nmetadata:\n  name: api\n  description: API\nspec:\n  apiVersion: pmp.io/v1\n  kind: Api\n  executor: opentofu\n  # Inputs\n  inputs:\n    - name: size\n      description: Size\n      type: select\n      options:\n        - label: small\n          value: small\n        - label: large\n          value: large\n      default: small\n"
+        );
+
+        let messages = format!("{:?}", output.get_messages());
+        assert!(messages.contains("-    legacy:"));
+        assert!(messages.contains("+    - name: size"));
+        assert!(messages.contains("Applied 3 fix(es) to 1 file(s)"));
+        assert!(messages.contains("Linting passed - no issues found"));
+    }
+}
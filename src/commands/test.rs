@@ -1,7 +1,7 @@
 use crate::collection::{CollectionDiscovery, CollectionManager, DependencyNode};
 use crate::commands::project_group::ProjectGroupHandler;
 use crate::commands::ExecutionHelper;
-use crate::executor::{Executor, ExecutorConfig, OpenTofuExecutor};
+use crate::executor::ExecutorConfig;
 use crate::hooks::{HookOutcome, HooksRunner};
 use crate::template::metadata::{FailureBehavior, ParallelConfig};
 use crate::template::{DynamicProjectEnvironmentResource, ProjectResource};
@@ -93,7 +93,7 @@ impl TestCommand {
 
             // Run pre-test hooks
             if !hooks.pre_test.is_empty()
-                && HooksRunner::run_hooks(&hooks.pre_test, env_dir_str, "pre-test")?
+                && HooksRunner::run_hooks(ctx, &hooks.pre_test, env_dir_str, "pre-test")?
                     == HookOutcome::Cancel
             {
                 ctx.output.blank();
@@ -122,7 +122,7 @@ impl TestCommand {
 
             // Run post-test hooks
             if !hooks.post_test.is_empty()
-                && HooksRunner::run_hooks(&hooks.post_test, env_dir_str, "post-test")?
+                && HooksRunner::run_hooks(ctx, &hooks.post_test, env_dir_str, "post-test")?
                     == HookOutcome::Cancel
             {
                 ctx.output.blank();
@@ -191,7 +191,7 @@ impl TestCommand {
         );
 
         // Get executor
-        let executor = Self::get_executor(&executor_config.name)?;
+        let executor = ctx.executor_registry.get(&executor_config.name)?;
 
         // Check if executor is installed
         ctx.output.subsection("Prerequisites");
@@ -216,7 +216,7 @@ impl TestCommand {
 
         // Run pre-test hooks
         if !hooks.pre_test.is_empty()
-            && HooksRunner::run_hooks(&hooks.pre_test, env_dir_str, "pre-test")?
+            && HooksRunner::run_hooks(ctx, &hooks.pre_test, env_dir_str, "pre-test")?
                 == HookOutcome::Cancel
         {
             ctx.output.blank();
@@ -272,6 +272,7 @@ impl TestCommand {
             refresh_command: None,
             test_command: None,
             command_options,
+            plan_file: None,
         };
 
         // Run test
@@ -286,7 +287,7 @@ impl TestCommand {
 
         // Run post-test hooks
         if !hooks.post_test.is_empty()
-            && HooksRunner::run_hooks(&hooks.post_test, env_dir_str, "post-test")?
+            && HooksRunner::run_hooks(ctx, &hooks.post_test, env_dir_str, "post-test")?
                 == HookOutcome::Cancel
         {
             ctx.output.blank();
@@ -420,14 +421,6 @@ impl TestCommand {
         Ok((env_path, selected_project.name.clone(), env_name))
     }
 
-    /// Get the appropriate executor based on name
-    fn get_executor(name: &str) -> Result<Box<dyn Executor>> {
-        match name {
-            "opentofu" => Ok(Box::new(OpenTofuExecutor::new())),
-            _ => anyhow::bail!("Unknown executor: {}", name),
-        }
-    }
-
     /// Build parallel config from CLI flag or infrastructure config
     fn build_parallel_config(
         cli_parallel: Option<usize>,
@@ -469,7 +462,7 @@ impl TestCommand {
         let executor_config = resource.get_executor_config();
 
         // Get executor
-        let executor = ExecutionHelper::get_executor(&executor_config.name)?;
+        let executor = ctx.executor_registry.get(&executor_config.name)?;
 
         // Build executor config
         let mut command_options = std::collections::HashMap::new();
@@ -487,6 +480,7 @@ impl TestCommand {
             refresh_command: None,
             test_command: None,
             command_options,
+            plan_file: None,
         };
 
         // Execute test on this node
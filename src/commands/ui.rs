@@ -17,6 +17,9 @@ use std::sync::Arc;
 use tokio::sync::Mutex as TokioMutex;
 use tower_http::cors::{Any, CorsLayer};
 
+use crate::history::{OperationHistory, OperationRecord};
+use crate::template::metadata::HistoryConfig;
+
 /// Handles the 'ui' command - starts HTTP server with web interface
 pub struct UiCommand;
 
@@ -26,17 +29,42 @@ pub struct OperationStatus {
     pub id: String,
     pub operation: String,
     pub project_path: String,
+    pub project: Option<String>,
+    pub environment: Option<String>,
+    pub user: String,
     pub status: String,
     pub started_at: String,
     pub finished_at: Option<String>,
     pub success: Option<bool>,
+    pub error: Option<String>,
+}
+
+impl From<&OperationRecord> for OperationStatus {
+    fn from(record: &OperationRecord) -> Self {
+        Self {
+            id: record.id.clone(),
+            operation: record.operation.clone(),
+            project_path: record.path.clone(),
+            project: record.project.clone(),
+            environment: record.environment.clone(),
+            user: record.user.clone(),
+            status: record.status().to_string(),
+            started_at: record.started_at.to_rfc3339(),
+            finished_at: record.finished_at.map(|t| t.to_rfc3339()),
+            success: record.success,
+            error: record.error.clone(),
+        }
+    }
 }
 
 /// Shared application state
 #[derive(Clone)]
 struct AppState {
     ctx: Arc<crate::context::Context>,
-    operations: Arc<TokioMutex<HashMap<String, OperationStatus>>>,
+
+    /// Operations started in this session; they are also recorded in the infrastructure's
+    /// operation history when there is one
+    operations: Arc<TokioMutex<HashMap<String, OperationRecord>>>,
 }
 
 /// Request/Response structures
@@ -52,6 +80,14 @@ struct CreateProjectRequest {
     output: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct InputOptionsRequest {
+    environment: Option<String>,
+    /// Values of the inputs entered so far, for `${var:...}` interpolation
+    #[serde(default)]
+    inputs: HashMap<String, serde_json::Value>,
+}
+
 #[derive(Debug, Deserialize)]
 struct GenerateRequest {
     template_pack: String,
@@ -112,6 +148,37 @@ struct WsOperationRequest {
     path: Option<String>,
     executor_args: Option<Vec<String>>,
     yes: Option<bool>,
+    /// How prompts (e.g. of confirm hooks) are answered
+    #[serde(default)]
+    prompts: PromptPolicy,
+}
+
+/// How prompts of a WebSocket operation are answered
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum PromptPolicy {
+    /// Send `prompt` messages and wait for `prompt_response` messages
+    #[default]
+    Defer,
+    /// Answer with the prompt's default, failing when it has none
+    AutoAnswer,
+    /// Fail on any prompt
+    Fail,
+}
+
+/// Answer of the client to a `prompt` message
+#[derive(Debug, Deserialize)]
+struct WsPromptResponse {
+    #[serde(rename = "type")]
+    msg_type: String,
+    /// `null` cancels the prompt
+    #[serde(default)]
+    value: Option<serde_json::Value>,
+}
+
+/// Whether a message read from the client means it has gone away
+fn is_client_disconnect(msg: &Option<Result<Message, axum::Error>>) -> bool {
+    matches!(msg, None | Some(Err(_)) | Some(Ok(Message::Close(_))))
 }
 
 /// WebSocket message types
@@ -169,6 +236,8 @@ struct InputInfo {
     required: bool,
     /// For select/multiselect types
     options: Option<Vec<SelectOption>>,
+    /// Options are also loaded from `options_from` (see `get_input_options`)
+    dynamic_options: bool,
     /// For number type
     min: Option<f64>,
     max: Option<f64>,
@@ -182,11 +251,94 @@ struct SelectOption {
     value: String,
 }
 
+/// A condition as evaluated by the web UI
+///
+/// `condition` is an operator on `field` (`exists`, `equals`, `not_equals`, `in`, `not_in`,
+/// `gt`, `gte`, `lt`, `lte`, `matches`) or a group (`any`, `all`, `not`) of `conditions`.
 #[derive(Debug, Serialize)]
 struct InputConditionInfo {
+    #[serde(skip_serializing_if = "String::is_empty")]
     field: String,
     condition: String,
     value: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    conditions: Vec<InputConditionInfo>,
+}
+
+impl InputConditionInfo {
+    fn group(condition: &str, conditions: Vec<InputConditionInfo>) -> Self {
+        Self {
+            field: String::new(),
+            condition: condition.to_string(),
+            value: None,
+            conditions,
+        }
+    }
+}
+
+/// Convert an InputCondition to the form evaluated by the web UI
+fn convert_condition_to_info(
+    condition: &crate::template::metadata::InputCondition,
+) -> InputConditionInfo {
+    use crate::template::metadata::InputCondition;
+
+    match condition {
+        InputCondition::Any { any } => {
+            InputConditionInfo::group("any", any.iter().map(convert_condition_to_info).collect())
+        }
+        InputCondition::All { all } => {
+            InputConditionInfo::group("all", all.iter().map(convert_condition_to_info).collect())
+        }
+        InputCondition::Not { not } => {
+            InputConditionInfo::group("not", vec![convert_condition_to_info(not)])
+        }
+        InputCondition::Input(comparison) => {
+            let number = |n: Option<f64>| n.map(serde_json::Value::from);
+            let operators = [
+                ("equals", comparison.equals.clone()),
+                ("not_equals", comparison.not_equals.clone()),
+                (
+                    "in",
+                    comparison.in_values.clone().map(serde_json::Value::from),
+                ),
+                (
+                    "not_in",
+                    comparison.not_in.clone().map(serde_json::Value::from),
+                ),
+                ("gt", number(comparison.gt)),
+                ("gte", number(comparison.gte)),
+                ("lt", number(comparison.lt)),
+                ("lte", number(comparison.lte)),
+                (
+                    "matches",
+                    comparison.matches.clone().map(serde_json::Value::from),
+                ),
+            ];
+
+            let mut infos: Vec<InputConditionInfo> = operators
+                .into_iter()
+                .filter_map(|(condition, value)| {
+                    Some(InputConditionInfo {
+                        field: comparison.input_name.clone(),
+                        condition: condition.to_string(),
+                        value: Some(value?),
+                        conditions: Vec::new(),
+                    })
+                })
+                .collect();
+
+            match infos.len() {
+                0 => InputConditionInfo {
+                    field: comparison.input_name.clone(),
+                    condition: "exists".to_string(),
+                    value: None,
+                    conditions: Vec::new(),
+                },
+                1 => infos.remove(0),
+                _ => InputConditionInfo::group("all", infos),
+            }
+        }
+    }
 }
 
 /// Convert an InputDefinition to InputInfo for API response
@@ -200,7 +352,7 @@ fn convert_input_to_info(input: &crate::template::metadata::InputDefinition) ->
         Some(InputType::Number { min, max, .. }) => {
             ("number".to_string(), None, *min, *max)
         }
-        Some(InputType::Select { options }) => {
+        Some(InputType::Select { options, .. }) => {
             let opts: Vec<SelectOption> = options
                 .iter()
                 .map(|o| SelectOption {
@@ -241,6 +393,7 @@ fn convert_input_to_info(input: &crate::template::metadata::InputDefinition) ->
         Some(InputType::DateTime { .. }) => ("datetime".to_string(), None, None, None),
         Some(InputType::ProjectSelect { .. }) => ("project_select".to_string(), None, None, None),
         Some(InputType::MultiProjectSelect { .. }) => ("multi_project_select".to_string(), None, None, None),
+        Some(InputType::Computed { .. }) => ("computed".to_string(), None, None, None),
         None => {
             // Handle deprecated enum_values or default to string
             if input.enum_values.is_some() {
@@ -261,7 +414,7 @@ fn convert_input_to_info(input: &crate::template::metadata::InputDefinition) ->
         }
     };
 
-    // Convert conditions (simplified - InputCondition uses input_name and equals)
+    // Convert conditions (all must be met)
     let conditions = if input.conditions.is_empty() {
         None
     } else {
@@ -269,15 +422,7 @@ fn convert_input_to_info(input: &crate::template::metadata::InputDefinition) ->
             input
                 .conditions
                 .iter()
-                .map(|c| InputConditionInfo {
-                    field: c.input_name.clone(),
-                    condition: if c.equals.is_some() {
-                        "equals".to_string()
-                    } else {
-                        "exists".to_string()
-                    },
-                    value: c.equals.clone(),
-                })
+                .map(convert_condition_to_info)
                 .collect(),
         )
     };
@@ -289,6 +434,10 @@ fn convert_input_to_info(input: &crate::template::metadata::InputDefinition) ->
         default: input.default.clone(),
         required: input.default.is_none(),
         options,
+        dynamic_options: input
+            .input_type
+            .as_ref()
+            .is_some_and(|t| t.options_from().is_some()),
         min,
         max,
         conditions,
@@ -360,6 +509,21 @@ impl UiCommand {
 
         ctx.output
             .success(&format!("Found infrastructure: {}", infra_path.display()));
+
+        // Operations still recorded as running were cut short when a previous UI stopped
+        if let Some((history, _)) = operation_history(&*ctx.fs) {
+            match history.interrupt_running() {
+                Ok(interrupted) if !interrupted.is_empty() => ctx.output.warning(&format!(
+                    "Marked {} unfinished operation(s) of a previous session as interrupted",
+                    interrupted.len()
+                )),
+                Ok(_) => {}
+                Err(e) => ctx
+                    .output
+                    .warning(&format!("Failed to update the operation history: {:#}", e)),
+            }
+        }
+
         ctx.output.dimmed("Starting HTTP server...");
 
         let port = port.unwrap_or(8080);
@@ -386,6 +550,10 @@ impl UiCommand {
                 "/api/template-packs/:pack/templates/:template",
                 get(get_template_details),
             )
+            .route(
+                "/api/template-packs/:pack/templates/:template/inputs/:input/options",
+                post(get_input_options),
+            )
             .route("/api/template-packs/install-git", post(install_git_pack))
             .route(
                 "/api/template-packs/install-local",
@@ -408,6 +576,8 @@ impl UiCommand {
             // Dashboard API routes
             .route("/api/dashboard", get(get_dashboard))
             .route("/api/operations", get(list_operations))
+            .route("/api/operations/:id", get(get_operation))
+            .route("/api/operations/:id/log", get(get_operation_log))
             // CORS layer for development
             .layer(
                 CorsLayer::new()
@@ -740,6 +910,83 @@ async fn get_template_details(
     }
 }
 
+/// Options of a select input for the create form
+async fn get_input_options(
+    State(state): State<AppState>,
+    Path((pack_name, template_name, input_name)): Path<(String, String, String)>,
+    Json(req): Json<InputOptionsRequest>,
+) -> Json<ApiResponse<Vec<SelectOption>>> {
+    let result = resolve_input_options(&state.ctx, &pack_name, &template_name, &input_name, req);
+
+    match result {
+        Ok(options) => Json(ApiResponse {
+            success: true,
+            data: Some(options),
+            error: None,
+        }),
+        Err(e) => Json(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(format!("{:#}", e)),
+        }),
+    }
+}
+
+/// Resolve the options of a select input, including those from `options_from`
+fn resolve_input_options(
+    ctx: &crate::context::Context,
+    pack_name: &str,
+    template_name: &str,
+    input_name: &str,
+    req: InputOptionsRequest,
+) -> Result<Vec<SelectOption>> {
+    let pack = crate::template::TemplateDiscovery::discover_template_packs(&*ctx.fs, &*ctx.output)?
+        .into_iter()
+        .find(|p| p.resource.metadata.name == pack_name)
+        .with_context(|| format!("Template pack '{}' not found", pack_name))?;
+
+    let template = crate::template::TemplateDiscovery::discover_templates_in_pack(
+        &*ctx.fs,
+        &*ctx.output,
+        &pack.path,
+    )?
+    .into_iter()
+    .find(|t| t.resource.metadata.name == template_name)
+    .with_context(|| format!("Template '{}' not found", template_name))?;
+
+    let input_type = template
+        .resource
+        .spec
+        .inputs
+        .iter()
+        .find(|i| i.name == input_name)
+        .and_then(|i| i.input_type.clone())
+        .with_context(|| format!("Input '{}' not found", input_name))?;
+
+    let mut vars = req.inputs;
+    if let Some(environment) = &req.environment {
+        vars.insert(
+            "_environment_name".to_string(),
+            serde_json::Value::String(environment.clone()),
+        );
+    }
+
+    let options = crate::template::options::select_options(
+        ctx,
+        &input_type,
+        &vars,
+        req.environment.as_deref(),
+    )?;
+
+    Ok(options
+        .into_iter()
+        .map(|o| SelectOption {
+            label: o.label,
+            value: o.value,
+        })
+        .collect())
+}
+
 async fn get_infrastructure(
     State(state): State<AppState>,
 ) -> Json<ApiResponse<InfrastructureInfo>> {
@@ -846,15 +1093,17 @@ async fn list_projects(
         });
     }
 
-    match crate::collection::CollectionDiscovery::discover_projects(
+    match crate::collection::CollectionDiscovery::load_index(
         &*state.ctx.fs,
         &*state.ctx.output,
         &base_dir,
     ) {
-        Ok(projects) => {
+        Ok(index) => {
             let mut project_infos: Vec<ProjectInfo> = Vec::new();
 
-            for p in projects {
+            for p in index.projects() {
+                let kind = p.kind().unwrap_or_default();
+
                 // Filter by name if provided
                 if let Some(ref name_filter) = params.name
                     && !p.name.to_lowercase().contains(&name_filter.to_lowercase())
@@ -864,25 +1113,19 @@ async fn list_projects(
 
                 // Filter by kind if provided
                 if let Some(ref kind_filter) = params.kind
-                    && p.kind != *kind_filter
+                    && kind != kind_filter
                 {
                     continue;
                 }
 
-                // Discover environments for this project
                 let project_path = base_dir.join(&p.path);
-                let environments = crate::collection::CollectionDiscovery::discover_environments(
-                    &*state.ctx.fs,
-                    &project_path,
-                )
-                .unwrap_or_default();
 
                 project_infos.push(ProjectInfo {
                     name: p.name.clone(),
                     description: None,
-                    kind: p.kind.clone(),
+                    kind: kind.to_string(),
                     path: project_path.to_string_lossy().to_string(),
-                    environments,
+                    environments: p.environments.keys().cloned().collect(),
                 });
             }
 
@@ -958,23 +1201,9 @@ async fn create_project(
     let mut temp_ctx = (*state.ctx).clone();
     temp_ctx.output = buffered_output.clone();
 
-    // Convert inputs to JSON string format for CreateCommand
-    // Format: key1=value1,key2=value2
+    // Pass inputs as JSON so numbers and booleans keep their type for validation rules
     let inputs_str = if !req.inputs.is_empty() {
-        let inputs_vec: Vec<String> = req
-            .inputs
-            .iter()
-            .map(|(k, v)| {
-                let value_str = match v {
-                    serde_json::Value::String(s) => s.clone(),
-                    serde_json::Value::Bool(b) => b.to_string(),
-                    serde_json::Value::Number(n) => n.to_string(),
-                    _ => v.to_string(),
-                };
-                format!("{}={}", k, value_str)
-            })
-            .collect();
-        Some(inputs_vec.join(","))
+        serde_json::to_string(&req.inputs).ok()
     } else {
         None
     };
@@ -1096,12 +1325,12 @@ async fn get_dependency_graph(
     };
 
     // Discover all projects
-    let projects = match CollectionDiscovery::discover_projects(
+    let index = match CollectionDiscovery::load_index(
         &*state.ctx.fs,
         &*state.ctx.output,
         &infrastructure_root,
     ) {
-        Ok(p) => p,
+        Ok(index) => index,
         Err(e) => {
             return Json(ApiResponse {
                 success: false,
@@ -1116,46 +1345,30 @@ async fn get_dependency_graph(
     let mut dependencies: HashMap<String, Vec<String>> = HashMap::new();
     let mut node_info: HashMap<String, GraphNode> = HashMap::new();
 
-    for project in &projects {
-        let project_path = infrastructure_root.join(&project.path);
-        let environments_dir = project_path.join("environments");
-
-        if let Ok(env_entries) = state.ctx.fs.read_dir(&environments_dir) {
-            for env_path in env_entries {
-                let env_file = env_path.join(".pmp.environment.yaml");
-
-                if state.ctx.fs.exists(&env_file)
-                    && let Ok(env_resource) =
-                        crate::template::metadata::DynamicProjectEnvironmentResource::from_file(
-                            &*state.ctx.fs,
-                            &env_file,
-                        )
-                {
-                    let env_name = env_resource.metadata.environment_name.clone();
-                    let node_key = format!("{}:{}", project.name, env_name);
-
-                    all_nodes.insert(node_key.clone());
-                    node_info.insert(
-                        node_key.clone(),
-                        GraphNode {
-                            id: node_key.clone(),
-                            project_name: project.name.clone(),
-                            environment: env_name.clone(),
-                            kind: Some(env_resource.kind.clone()),
-                        },
-                    );
+    for (project, environment) in index.environments() {
+        let env_resource = &environment.resource;
+        let env_name = env_resource.metadata.environment_name.clone();
+        let node_key = format!("{}:{}", project.name, env_name);
+
+        all_nodes.insert(node_key.clone());
+        node_info.insert(
+            node_key.clone(),
+            GraphNode {
+                id: node_key.clone(),
+                project_name: project.name.clone(),
+                environment: env_name.clone(),
+                kind: Some(env_resource.kind.clone()),
+            },
+        );
 
-                    // Extract dependencies
-                    for dep in &env_resource.spec.dependencies {
-                        for dep_env in &dep.project.environments {
-                            let dep_key = format!("{}:{}", dep.project.name, dep_env);
-                            dependencies
-                                .entry(node_key.clone())
-                                .or_default()
-                                .push(dep_key);
-                        }
-                    }
-                }
+        // Extract dependencies
+        for dep in &env_resource.spec.dependencies {
+            for dep_env in &dep.project.environments {
+                let dep_key = format!("{}:{}", dep.project.name, dep_env);
+                dependencies
+                    .entry(node_key.clone())
+                    .or_default()
+                    .push(dep_key);
             }
         }
     }
@@ -1235,6 +1448,7 @@ async fn preview(
         None,   // diff_output
         false,  // show_unchanged
         false,  // show_sensitive
+        None,   // out - saved plans not supported in UI yet
         &req.executor_args,
     );
 
@@ -1757,7 +1971,7 @@ async fn handle_ws_connection(socket: WebSocket, state: AppState) {
 
             match request {
                 Ok(req) => {
-                    execute_streaming_operation(&mut sender, &state, req).await;
+                    execute_streaming_operation(&mut sender, &mut receiver, &state, req).await;
                 }
                 Err(e) => {
                     let error_msg = WsMessage {
@@ -1775,13 +1989,18 @@ async fn handle_ws_connection(socket: WebSocket, state: AppState) {
 
 async fn execute_streaming_operation(
     sender: &mut futures::stream::SplitSink<WebSocket, Message>,
+    receiver: &mut futures::stream::SplitStream<WebSocket>,
     state: &AppState,
     req: WsOperationRequest,
 ) {
-    use crate::traits::{StreamingOutput, format_output_message};
+    use crate::traits::{
+        DeferredUserInput, NonInteractiveUserInput, StreamingOutput, format_output_message,
+    };
 
-    let operation_id = uuid::Uuid::new_v4().to_string();
     let path = req.path.clone().unwrap_or_else(|| ".".to_string());
+    let mut record = OperationRecord::new(&req.operation, &path);
+    (record.project, record.environment) = operation_target(&*state.ctx.fs, &path);
+    let operation_id = record.id.clone();
 
     // Send start message
     let start_msg = WsMessage {
@@ -1796,32 +2015,27 @@ async fn execute_streaming_operation(
         .send(Message::Text(serde_json::to_string(&start_msg).unwrap()))
         .await;
 
-    // Track operation
-    let now = chrono::Utc::now().to_rfc3339();
-    {
-        let mut ops = state.operations.lock().await;
-        ops.insert(
-            operation_id.clone(),
-            OperationStatus {
-                id: operation_id.clone(),
-                operation: req.operation.clone(),
-                project_path: path.clone(),
-                status: "running".to_string(),
-                started_at: now.clone(),
-                finished_at: None,
-                success: None,
-            },
-        );
-    }
+    // Track operation; its output is appended to the history log as it streams
+    record_operation(state, &record, None).await;
+    let mut log = OperationLog::new(&*state.ctx.fs, &operation_id);
 
     // Create streaming output
-    let (streaming_output, mut receiver) = StreamingOutput::new();
+    let (streaming_output, mut output_receiver) = StreamingOutput::new();
     let streaming_output = Arc::new(streaming_output);
 
+    // Prompts are sent to the client instead of the terminal pmp ui runs in
+    let (prompt_sender, mut prompts) = tokio::sync::mpsc::unbounded_channel();
+    let (answers, answer_receiver) = std::sync::mpsc::channel();
+
     // Clone for the spawned task
     let ctx = (*state.ctx).clone();
     let mut temp_ctx = ctx;
     temp_ctx.output = streaming_output.clone();
+    temp_ctx.input = match req.prompts {
+        PromptPolicy::Defer => Arc::new(DeferredUserInput::new(prompt_sender, answer_receiver)),
+        PromptPolicy::AutoAnswer => Arc::new(NonInteractiveUserInput::new(true)),
+        PromptPolicy::Fail => Arc::new(NonInteractiveUserInput::new(false)),
+    };
 
     let operation = req.operation.clone();
     let executor_args = req.executor_args.unwrap_or_default();
@@ -1843,6 +2057,7 @@ async fn execute_streaming_operation(
                 None,   // diff_output
                 false,  // show_unchanged
                 false,  // show_sensitive
+                None,   // out - saved plans not supported in UI yet
                 &executor_args,
             ),
             "apply" => crate::commands::ApplyCommand::execute(
@@ -1871,15 +2086,17 @@ async fn execute_streaming_operation(
 
     // Pin the handle for use in the select loop
     let mut handle = std::pin::pin!(handle);
-    let mut operation_complete = false;
+    let mut finished = None;
 
-    // Stream output messages as they arrive
-    while !operation_complete {
+    // Stream output messages as they arrive, until the operation finishes or the client leaves
+    while finished.is_none() {
         tokio::select! {
-            msg = receiver.recv() => {
+            msg = output_receiver.recv() => {
                 match msg {
                     Ok(output_msg) => {
                         let text = format_output_message(&output_msg);
+                        log.append(&text);
+
                         let ws_msg = WsMessage {
                             msg_type: "output".to_string(),
                             data: serde_json::json!({ "text": text }),
@@ -1889,40 +2106,91 @@ async fn execute_streaming_operation(
                             break;
                         }
                     }
-                    Err(tokio::sync::broadcast::error::RecvError::Closed) => {
-                        operation_complete = true;
-                    }
-                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => log.complete = false,
                 }
             }
-            result = &mut handle => {
-                let success = matches!(result, Ok(Ok(_)));
+            Some(prompt) = prompts.recv() => {
+                let ws_msg = WsMessage {
+                    msg_type: "prompt".to_string(),
+                    data: serde_json::to_value(&prompt).unwrap_or_default(),
+                };
 
-                // Update operation status
-                let finished_at = chrono::Utc::now().to_rfc3339();
+                if sender.send(Message::Text(serde_json::to_string(&ws_msg).unwrap())).await.is_err() {
+                    break;
+                }
+            }
+            msg = receiver.next() => {
+                if is_client_disconnect(&msg) {
+                    break;
+                }
+
+                if let Some(Ok(Message::Text(text))) = msg
+                    && let Ok(response) = serde_json::from_str::<WsPromptResponse>(&text)
+                    && response.msg_type == "prompt_response"
                 {
-                    let mut ops = state.operations.lock().await;
-                    if let Some(op) = ops.get_mut(&operation_id) {
-                        op.status = if success { "completed".to_string() } else { "failed".to_string() };
-                        op.finished_at = Some(finished_at.clone());
-                        op.success = Some(success);
-                    }
+                    let _ = answers.send(response.value);
                 }
+            }
+            result = &mut handle => finished = Some(result),
+        }
+    }
 
-                // Send completion message
-                let end_msg = WsMessage {
-                    msg_type: "complete".to_string(),
-                    data: serde_json::json!({
-                        "operation_id": operation_id,
-                        "success": success,
-                        "finished_at": finished_at
-                    }),
-                };
-                let _ = sender.send(Message::Text(serde_json::to_string(&end_msg).unwrap())).await;
-                operation_complete = true;
+    // Without a client, prompts can't be answered: closing both channels makes pending and later
+    // prompts fail, and the operation still runs to the end and is recorded
+    let result = match finished {
+        Some(result) => result,
+        None => {
+            drop(answers);
+            drop(prompts);
+
+            loop {
+                tokio::select! {
+                    msg = output_receiver.recv() => match msg {
+                        Ok(output_msg) => log.append(&format_output_message(&output_msg)),
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break handle.await,
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => log.complete = false,
+                    },
+                    result = &mut handle => break result,
+                }
             }
         }
+    };
+
+    // Output sent right before the operation finished
+    loop {
+        match output_receiver.try_recv() {
+            Ok(output_msg) => log.append(&format_output_message(&output_msg)),
+            Err(tokio::sync::broadcast::error::TryRecvError::Lagged(_)) => log.complete = false,
+            Err(_) => break,
+        }
     }
+
+    let error = match result {
+        Ok(Ok(_)) => None,
+        Ok(Err(e)) => Some(format!("{:#}", e)),
+        Err(e) => Some(format!("Operation task failed: {}", e)),
+    };
+    let success = error.is_none();
+
+    // Update operation status, saving the full output only when the log missed some of it
+    record.finish(error);
+    let finished_at = record.finished_at.unwrap_or_default().to_rfc3339();
+    let full_log = (!log.complete).then(|| streaming_output.to_text());
+    record_operation(state, &record, full_log.as_deref()).await;
+
+    // Send completion message
+    let end_msg = WsMessage {
+        msg_type: "complete".to_string(),
+        data: serde_json::json!({
+            "operation_id": operation_id,
+            "success": success,
+            "finished_at": finished_at
+        }),
+    };
+    let _ = sender
+        .send(Message::Text(serde_json::to_string(&end_msg).unwrap()))
+        .await;
 }
 
 // ============================================================================
@@ -1984,40 +2252,32 @@ async fn get_dashboard(State(state): State<AppState>) -> Json<ApiResponse<Dashbo
         .unwrap_or_default();
 
     // Discover projects
-    let projects = CollectionDiscovery::discover_projects(
-        &*state.ctx.fs,
-        &*state.ctx.output,
-        &current_dir,
-    )
-    .unwrap_or_default();
+    let index = CollectionDiscovery::load_index(&*state.ctx.fs, &*state.ctx.output, &current_dir)
+        .unwrap_or_default();
 
-    let project_count = projects.len();
+    let project_count = index.projects().count();
 
     // Count projects by kind
     let mut projects_by_kind: HashMap<String, usize> = HashMap::new();
-    for project in &projects {
-        *projects_by_kind.entry(project.kind.clone()).or_insert(0) += 1;
+    for kind in index.projects().filter_map(|project| project.kind()) {
+        *projects_by_kind.entry(kind.to_string()).or_insert(0) += 1;
     }
 
     // Count projects by environment
     let mut projects_by_environment: HashMap<String, usize> = HashMap::new();
-    for project in &projects {
-        let project_path = current_dir.join(&project.path);
-        let envs = CollectionDiscovery::discover_environments(&*state.ctx.fs, &project_path)
-            .unwrap_or_default();
-
-        for env in envs {
-            *projects_by_environment.entry(env).or_insert(0) += 1;
+    for project in index.projects() {
+        for env in project.environments.keys() {
+            *projects_by_environment.entry(env.clone()).or_insert(0) += 1;
         }
     }
 
     // Get recent operations (last 10)
-    let recent_operations: Vec<OperationStatus> = {
-        let ops = state.operations.lock().await;
-        let mut ops_vec: Vec<_> = ops.values().cloned().collect();
-        ops_vec.sort_by(|a, b| b.started_at.cmp(&a.started_at));
-        ops_vec.into_iter().take(10).collect()
-    };
+    let recent_operations: Vec<OperationStatus> = recorded_operations(&state)
+        .await
+        .iter()
+        .take(10)
+        .map(OperationStatus::from)
+        .collect();
 
     Json(ApiResponse {
         success: true,
@@ -2036,17 +2296,189 @@ async fn get_dashboard(State(state): State<AppState>) -> Json<ApiResponse<Dashbo
 async fn list_operations(
     State(state): State<AppState>,
 ) -> Json<ApiResponse<Vec<OperationStatus>>> {
-    let ops = state.operations.lock().await;
-    let mut ops_vec: Vec<_> = ops.values().cloned().collect();
-    ops_vec.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    let ops = recorded_operations(&state).await;
 
     Json(ApiResponse {
         success: true,
-        data: Some(ops_vec),
+        data: Some(ops.iter().map(OperationStatus::from).collect()),
         error: None,
     })
 }
 
+async fn get_operation(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Json<ApiResponse<OperationStatus>> {
+    let record = match state.operations.lock().await.get(&id) {
+        Some(record) => Ok(record.clone()),
+        None => match operation_history(&*state.ctx.fs) {
+            Some((history, _)) => history.get(&id),
+            None => Err(anyhow::anyhow!("Operation not found: {}", id)),
+        },
+    };
+
+    match record {
+        Ok(record) => Json(ApiResponse {
+            success: true,
+            data: Some(OperationStatus::from(&record)),
+            error: None,
+        }),
+        Err(e) => Json(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+async fn get_operation_log(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Json<ApiResponse<String>> {
+    let log = match operation_history(&*state.ctx.fs) {
+        Some((history, _)) => history.log(&id),
+        None => Err(anyhow::anyhow!(
+            "No operation history in this infrastructure"
+        )),
+    };
+
+    match log {
+        Ok(Some(log)) => Json(ApiResponse {
+            success: true,
+            data: Some(log),
+            error: None,
+        }),
+        Ok(None) => Json(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(format!("No log saved for operation {} yet", id)),
+        }),
+        Err(e) => Json(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Operation history of the current infrastructure, unless there is none or it is disabled
+fn operation_history(
+    fs: &dyn crate::traits::FileSystem,
+) -> Option<(OperationHistory<'_>, HistoryConfig)> {
+    let (infrastructure, root) = crate::collection::CollectionDiscovery::find_collection(fs)
+        .ok()
+        .flatten()?;
+    let config = infrastructure.spec.history.unwrap_or_default();
+
+    config.enabled.then(|| {
+        (
+            OperationHistory::for_infrastructure(fs, &root, &config),
+            config,
+        )
+    })
+}
+
+/// History log of an operation, appended to line by line as its output streams
+struct OperationLog<'a> {
+    history: Option<OperationHistory<'a>>,
+    id: String,
+    /// Cleared when output could not be appended, so the full output is saved at the end
+    complete: bool,
+}
+
+impl<'a> OperationLog<'a> {
+    fn new(fs: &'a dyn crate::traits::FileSystem, id: &str) -> Self {
+        Self {
+            history: operation_history(fs).map(|(history, _)| history),
+            id: id.to_string(),
+            complete: true,
+        }
+    }
+
+    fn append(&mut self, line: &str) {
+        if let Some(history) = &self.history
+            && history
+                .append_log(&self.id, &format!("{}\n", line))
+                .is_err()
+        {
+            self.complete = false;
+        }
+    }
+}
+
+/// Project and environment an operation path points to, when it is a project or environment
+fn operation_target(
+    fs: &dyn crate::traits::FileSystem,
+    path: &str,
+) -> (Option<String>, Option<String>) {
+    use crate::template::{DynamicProjectEnvironmentResource, ProjectResource};
+
+    let dir = std::path::Path::new(path);
+
+    if let Ok(env) =
+        DynamicProjectEnvironmentResource::from_file(fs, &dir.join(".pmp.environment.yaml"))
+    {
+        return (Some(env.metadata.name), Some(env.metadata.environment_name));
+    }
+
+    match ProjectResource::from_file(fs, &dir.join(".pmp.project.yaml")) {
+        Ok(project) => (Some(project.metadata.name), None),
+        Err(_) => (None, None),
+    }
+}
+
+/// Track an operation and record it in the operation history, with its output once finished
+async fn record_operation(state: &AppState, record: &OperationRecord, log: Option<&str>) {
+    state
+        .operations
+        .lock()
+        .await
+        .insert(record.id.clone(), record.clone());
+
+    let Some((history, config)) = operation_history(&*state.ctx.fs) else {
+        return;
+    };
+
+    let saved = history
+        .save(record)
+        .and_then(|_| match log {
+            Some(log) => history.save_log(&record.id, log),
+            None => Ok(()),
+        })
+        .and_then(|_| history.prune(&config).map(|_| ()));
+
+    if let Err(e) = saved {
+        state.ctx.output.warning(&format!(
+            "Failed to record operation {}: {:#}",
+            record.id, e
+        ));
+    }
+}
+
+/// Operations of this session and the operation history, newest first
+async fn recorded_operations(state: &AppState) -> Vec<OperationRecord> {
+    let (records, warnings) = operation_history(&*state.ctx.fs)
+        .and_then(|(history, _)| history.list_with_warnings().ok())
+        .unwrap_or_default();
+    for warning in &warnings {
+        state.ctx.output.warning(warning);
+    }
+
+    let mut records: HashMap<String, OperationRecord> = records
+        .into_iter()
+        .map(|record| (record.id.clone(), record))
+        .collect();
+
+    // Operations of this session are up to date even when they could not be recorded
+    for (id, record) in state.operations.lock().await.iter() {
+        records.insert(id.clone(), record.clone());
+    }
+
+    let mut records: Vec<_> = records.into_values().collect();
+    records.sort_by(|a, b| b.started_at.cmp(&a.started_at).then(b.id.cmp(&a.id)));
+    records
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2085,10 +2517,14 @@ mod tests {
             id: "op-123".to_string(),
             operation: "apply".to_string(),
             project_path: "/path/to/project".to_string(),
+            project: None,
+            environment: None,
+            user: "alice@laptop".to_string(),
             status: "running".to_string(),
             started_at: "2024-01-01T00:00:00Z".to_string(),
             finished_at: None,
             success: None,
+            error: None,
         };
 
         let json = serde_json::to_string(&status).unwrap();
@@ -2103,10 +2539,14 @@ mod tests {
             id: "op-456".to_string(),
             operation: "destroy".to_string(),
             project_path: "/path/to/project".to_string(),
+            project: None,
+            environment: None,
+            user: "alice@laptop".to_string(),
             status: "completed".to_string(),
             started_at: "2024-01-01T00:00:00Z".to_string(),
             finished_at: Some("2024-01-01T00:05:00Z".to_string()),
             success: Some(true),
+            error: None,
         };
 
         let json = serde_json::to_string(&status).unwrap();
@@ -2114,6 +2554,22 @@ mod tests {
         assert!(json.contains("\"success\":true"));
     }
 
+    #[test]
+    fn test_operation_status_from_record() {
+        let mut record = OperationRecord::new("apply", "projects/vpc/environments/dev");
+        record.project = Some("vpc".to_string());
+        record.environment = Some("dev".to_string());
+        record.finish(Some("exit status 1".to_string()));
+
+        let status = OperationStatus::from(&record);
+        assert_eq!(status.project_path, "projects/vpc/environments/dev");
+        assert_eq!(status.environment.as_deref(), Some("dev"));
+        assert_eq!(status.status, "failed");
+        assert_eq!(status.success, Some(false));
+        assert_eq!(status.error.as_deref(), Some("exit status 1"));
+        assert!(status.finished_at.is_some());
+    }
+
     #[test]
     fn test_directory_entry_serialization() {
         let entry = DirectoryEntry {
@@ -2168,6 +2624,7 @@ mod tests {
             default: Some(serde_json::json!("my-project")),
             required: false,
             options: None,
+            dynamic_options: false,
             min: None,
             max: None,
             conditions: None,
@@ -2188,6 +2645,7 @@ mod tests {
             default: Some(serde_json::json!(3)),
             required: false,
             options: None,
+            dynamic_options: false,
             min: Some(1.0),
             max: Some(10.0),
             conditions: None,
@@ -2217,6 +2675,7 @@ mod tests {
                     value: "prod".to_string(),
                 },
             ]),
+            dynamic_options: false,
             min: None,
             max: None,
             conditions: None,
@@ -2229,6 +2688,43 @@ mod tests {
         assert!(json.contains("\"value\":\"dev\""));
     }
 
+    #[test]
+    fn test_convert_condition_to_info() {
+        let condition: crate::template::metadata::InputCondition = serde_yaml::from_str(
+            r#"
+any:
+  - input_name: tier
+    in: [staging, production]
+  - not:
+      input_name: replicas
+      gt: 1
+      lte: 5
+  - input_name: enabled
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(convert_condition_to_info(&condition)).unwrap(),
+            serde_json::json!({
+                "condition": "any",
+                "value": null,
+                "conditions": [
+                    {"field": "tier", "condition": "in", "value": ["staging", "production"]},
+                    {"condition": "not", "value": null, "conditions": [{
+                        "condition": "all",
+                        "value": null,
+                        "conditions": [
+                            {"field": "replicas", "condition": "gt", "value": 1.0},
+                            {"field": "replicas", "condition": "lte", "value": 5.0}
+                        ]
+                    }]},
+                    {"field": "enabled", "condition": "exists", "value": null}
+                ]
+            })
+        );
+    }
+
     #[test]
     fn test_input_info_with_conditions() {
         let input = InputInfo {
@@ -2238,12 +2734,14 @@ mod tests {
             default: None,
             required: true,
             options: None,
+            dynamic_options: false,
             min: None,
             max: None,
             conditions: Some(vec![InputConditionInfo {
                 field: "enable_ssl".to_string(),
                 condition: "equals".to_string(),
                 value: Some(serde_json::json!(true)),
+                conditions: Vec::new(),
             }]),
         };
 
@@ -2253,6 +2751,42 @@ mod tests {
         assert!(json.contains("\"condition\":\"equals\""));
     }
 
+    #[test]
+    fn test_is_client_disconnect() {
+        assert!(is_client_disconnect(&None));
+        assert!(is_client_disconnect(&Some(Ok(Message::Close(None)))));
+        assert!(is_client_disconnect(&Some(Err(axum::Error::new(
+            std::io::Error::from(std::io::ErrorKind::ConnectionReset)
+        )))));
+        assert!(!is_client_disconnect(&Some(Ok(Message::Ping(vec![])))));
+        assert!(!is_client_disconnect(&Some(Ok(Message::Text(
+            r#"{"type":"prompt_response","value":true}"#.to_string()
+        )))));
+    }
+
+    #[test]
+    fn test_operation_log_appends_each_line() {
+        use crate::traits::{FileSystem, MockFileSystem};
+
+        let fs = MockFileSystem::new();
+        crate::test_helpers::create_opentofu_infrastructure(&fs, &["dev"]);
+        let log_path = fs.current_dir().unwrap().join(".pmp/history/op-1.log");
+
+        let mut log = OperationLog::new(&fs, "op-1");
+        log.append("Running apply...");
+        assert_eq!(
+            fs.get_file_contents(&log_path).as_deref(),
+            Some("Running apply...\n")
+        );
+
+        log.append("Apply complete");
+        assert!(log.complete);
+        assert_eq!(
+            fs.get_file_contents(&log_path).as_deref(),
+            Some("Running apply...\nApply complete\n")
+        );
+    }
+
     #[test]
     fn test_ws_message_serialization() {
         let msg = WsMessage {
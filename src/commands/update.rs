@@ -1,13 +1,15 @@
 use crate::collection::{CollectionDiscovery, CollectionManager};
 use crate::commands::apply::ApplyCommand;
 use crate::output;
+use crate::template::merge::{FileOutcome, MergeSummary};
 use crate::template::metadata::{
     AddedPlugin, AddedPluginReference, AllowedPluginConfig, InfrastructureResource,
     PluginProjectReference, ProjectPlugins,
 };
 use crate::template::{
-    DynamicProjectEnvironmentResource, PluginInfo, ProjectReference, ProjectResource,
-    TemplateDiscovery, TemplateInfo, TemplateRenderer,
+    DynamicProjectEnvironmentResource, MergeStrategy, PluginInfo, ProjectReference,
+    ProjectResource, RenderBaseline, RenderMerge, TemplateDiscovery, TemplateInfo,
+    TemplateRenderer,
 };
 use anyhow::{Context, Result};
 use serde_json::Value;
@@ -52,13 +54,24 @@ struct CollectedPluginInfo {
     plugin_spec: crate::template::metadata::PluginSpec,
 }
 
+/// Version of a discovered template (legacy templates are 0.0.1)
+pub(crate) fn template_version(template: &TemplateInfo) -> semver::Version {
+    template
+        .version
+        .clone()
+        .unwrap_or_else(|| semver::Version::new(0, 0, 1))
+}
+
 impl UpdateCommand {
     /// Execute the update command
+    ///
+    /// Regenerated files are merged with local edits according to `strategy`.
     pub fn execute(
         ctx: &crate::context::Context,
         project_path: Option<&str>,
         template_packs_paths: Option<&str>,
         inputs_str: Option<&str>,
+        strategy: MergeStrategy,
     ) -> Result<()> {
         // Parse pre-defined inputs if provided
         let predefined_inputs: Option<HashMap<String, Value>> = if let Some(inputs) = inputs_str {
@@ -66,6 +79,30 @@ impl UpdateCommand {
         } else {
             None
         };
+
+        Self::regenerate(
+            ctx,
+            project_path,
+            template_packs_paths,
+            predefined_inputs,
+            strategy,
+            None,
+        )
+    }
+
+    /// Regenerate an environment from its template
+    ///
+    /// With `target_version`, the environment is moved to that version of its template
+    /// (used by `pmp project upgrade`) and the plugin actions menu is skipped. Otherwise the
+    /// version recorded in the environment is used.
+    pub(crate) fn regenerate(
+        ctx: &crate::context::Context,
+        project_path: Option<&str>,
+        template_packs_paths: Option<&str>,
+        predefined_inputs: Option<HashMap<String, Value>>,
+        strategy: MergeStrategy,
+        target_version: Option<&semver::Version>,
+    ) -> Result<()> {
         // Determine working directory
         let work_dir = if let Some(path) = project_path {
             PathBuf::from(path)
@@ -100,82 +137,86 @@ impl UpdateCommand {
         let (collection, collection_root) = CollectionDiscovery::find_collection(&*ctx.fs)?
             .context("Infrastructure is required to run commands")?;
 
-        // Discover plugins with compatible projects
-        let plugins_with_projects = Self::discover_plugins_with_compatible_projects(
-            ctx,
-            &collection,
-            &collection_root,
-            template_packs_paths,
-            &current_env_resource, // Pass target project info for filtering
-        )?;
+        // Plugin actions are only offered on a plain update
+        if target_version.is_none() {
+            // Discover plugins with compatible projects
+            let plugins_with_projects = Self::discover_plugins_with_compatible_projects(
+                ctx,
+                &collection,
+                &collection_root,
+                template_packs_paths,
+                &current_env_resource, // Pass target project info for filtering
+            )?;
 
-        // Check if there are any plugins currently added
-        let has_plugins = current_env_resource
-            .spec
-            .plugins
-            .as_ref()
-            .map(|p| !p.added.is_empty())
-            .unwrap_or(false);
+            // Check if there are any plugins currently added
+            let has_plugins = current_env_resource
+                .spec
+                .plugins
+                .as_ref()
+                .map(|p| !p.added.is_empty())
+                .unwrap_or(false);
 
-        // If there are plugins with compatible projects or plugins to remove, ask user what they want to do
-        if !plugins_with_projects.is_empty() || has_plugins {
-            output::blank();
-            let mut options: Vec<String> = vec!["Update the project".to_string()];
-            if !plugins_with_projects.is_empty() {
-                options.push("Add Plugin".to_string());
-            }
-            if has_plugins {
-                options.push("Remove Plugin".to_string());
-                options.push("Update Plugin Inputs".to_string());
-            }
+            // If there are plugins with compatible projects or plugins to remove, ask user what they want to do
+            if !plugins_with_projects.is_empty() || has_plugins {
+                output::blank();
+                let mut options: Vec<String> = vec!["Update the project".to_string()];
+                if !plugins_with_projects.is_empty() {
+                    options.push("Add Plugin".to_string());
+                }
+                if has_plugins {
+                    options.push("Remove Plugin".to_string());
+                    options.push("Update Plugin Inputs".to_string());
+                }
 
-            let action = ctx
-                .input
-                .select("What would you like to do?", options, None)
-                .context("Failed to select action")?;
+                let action = ctx
+                    .input
+                    .select("What would you like to do?", options, None)
+                    .context("Failed to select action")?;
 
-            if action == "Add Plugin" {
-                // Add plugin flow with project selection
-                // Pass the current project's context (PROJECT A) as the target
-                return Self::add_plugin_with_project_selection(
-                    ctx,
-                    &collection_root,
-                    &collection,
-                    plugins_with_projects,
-                    template_packs_paths,
-                    &env_path,
-                    &project_name,
-                    &env_name,
-                    current_env_resource,
-                );
-            }
+                if action == "Add Plugin" {
+                    // Add plugin flow with project selection
+                    // Pass the current project's context (PROJECT A) as the target
+                    return Self::add_plugin_with_project_selection(
+                        ctx,
+                        &collection_root,
+                        &collection,
+                        plugins_with_projects,
+                        template_packs_paths,
+                        &env_path,
+                        &project_name,
+                        &env_name,
+                        current_env_resource,
+                    );
+                }
 
-            if action == "Remove Plugin" {
-                // Remove plugin flow
-                return Self::remove_plugin_interactive(
-                    ctx,
-                    &collection_root,
-                    &collection,
-                    &env_path,
-                    &project_name,
-                    &env_name,
-                    current_env_resource,
-                    template_packs_paths,
-                );
-            }
+                if action == "Remove Plugin" {
+                    // Remove plugin flow
+                    return Self::remove_plugin_interactive(
+                        ctx,
+                        &collection_root,
+                        &collection,
+                        &env_path,
+                        &project_name,
+                        &env_name,
+                        current_env_resource,
+                        template_packs_paths,
+                    );
+                }
 
-            if action == "Update Plugin Inputs" {
-                // Update plugin inputs flow
-                return Self::update_plugin_inputs_interactive(
-                    ctx,
-                    &collection_root,
-                    &collection,
-                    &env_path,
-                    &project_name,
-                    &env_name,
-                    current_env_resource,
-                    template_packs_paths,
-                );
+                if action == "Update Plugin Inputs" {
+                    // Update plugin inputs flow
+                    return Self::update_plugin_inputs_interactive(
+                        ctx,
+                        &collection_root,
+                        &collection,
+                        &env_path,
+                        &project_name,
+                        &env_name,
+                        current_env_resource,
+                        template_packs_paths,
+                        strategy,
+                    );
+                }
             }
         }
 
@@ -232,8 +273,12 @@ impl UpdateCommand {
         };
 
         // Find the original template using the metadata stored in the environment resource
-        let matching_template =
-            Self::find_original_template(ctx, &current_env_resource, template_packs_paths)?;
+        let matching_template = Self::find_original_template(
+            ctx,
+            &current_env_resource,
+            template_packs_paths,
+            target_version,
+        )?;
 
         // Note: matching_pack is no longer tracked separately since we find the exact original template
         // The template pack information is stored in current_env_resource.spec.template.template_pack_name
@@ -276,19 +321,16 @@ impl UpdateCommand {
         )
         .context("Failed to collect inputs")?;
 
+        // Check rules relating several inputs
+        crate::template::computed::check_validations(
+            &matching_template.resource.spec.validations,
+            &new_inputs,
+            &project_name,
+            Some(&env_name),
+        )?;
+
         // Add internal fields for template rendering
-        new_inputs.insert(
-            "_environment".to_string(),
-            serde_json::Value::String(env_name.clone()),
-        );
-        new_inputs.insert(
-            "_resource_api_version".to_string(),
-            serde_json::Value::String(matching_template.resource.spec.api_version.clone()),
-        );
-        new_inputs.insert(
-            "_resource_kind".to_string(),
-            serde_json::Value::String(matching_template.resource.spec.kind.clone()),
-        );
+        Self::insert_internal_variables(&mut new_inputs, &matching_template, &env_name);
 
         // Process installed plugins from template spec
         let mut newly_collected_plugins = Vec::new();
@@ -417,6 +459,9 @@ impl UpdateCommand {
             return Ok(());
         }
 
+        // Regenerated files are merged with the files on disk instead of overwriting them
+        let mut merge = RenderMerge::new(&*ctx.fs, &env_path, strategy)?;
+
         // Render newly collected plugins first (if any)
         let mut newly_added_plugins = Vec::new();
         if !newly_collected_plugins.is_empty() {
@@ -578,29 +623,12 @@ impl UpdateCommand {
                     module_path = module_path.join(&first_ref.name);
                 }
 
-                // Delete existing plugin module directory to ensure clean regeneration
-                if ctx.fs.exists(&module_path) {
-                    ctx.fs.remove_dir_all(&module_path).with_context(|| {
-                        format!("Failed to delete existing plugin module: {:?}", module_path)
-                    })?;
-                }
-
-                // Re-render plugin from template
+                // Re-render plugin from template and merge with the module on disk
                 let plugin_renderer = TemplateRenderer::new();
-                let plugin_context = Some((
-                    existing_plugin.template_pack_name.as_str(),
-                    existing_plugin.name.as_str(),
-                ));
-
-                let _generated_files = plugin_renderer
-                    .render_template(
-                        ctx,
-                        &plugin_info.path,
-                        &module_path,
-                        &existing_plugin.inputs,
-                        plugin_context,
-                    )
+                let rendered_files = plugin_renderer
+                    .render_files(ctx, &plugin_info.path, &existing_plugin.inputs)
                     .context("Failed to re-render plugin files")?;
+                merge.apply(&module_path, rendered_files)?;
 
                 ctx.output.dimmed(&format!(
                     "  Regenerated: {}/{}",
@@ -628,9 +656,13 @@ impl UpdateCommand {
             anyhow::bail!("Template directory not found: {}", template_src.display());
         }
 
-        let _generated_files = renderer
-            .render_template(ctx, template_src, env_path.as_path(), &new_inputs, None)
+        let rendered_files = renderer
+            .render_files(ctx, template_src, &new_inputs)
             .context("Failed to render template")?;
+        merge.apply(&env_path, rendered_files)?;
+
+        let merge_summary = merge.finish()?;
+        Self::print_merge_summary(ctx, &merge_summary);
 
         // Generate common file if executor config is present
         if let Some(executor_config) = &collection.spec.executor
@@ -638,11 +670,7 @@ impl UpdateCommand {
         {
             // Create executor instance based on template's executor
             let template_executor_name = matching_template.resource.spec.executor.name();
-            let executor: Box<dyn crate::executor::Executor> = match template_executor_name {
-                "opentofu" => Box::new(crate::executor::OpenTofuExecutor::new()),
-                "none" => Box::new(crate::executor::NoneExecutor::new()),
-                _ => anyhow::bail!("Unknown executor: {}", template_executor_name),
-            };
+            let executor = ctx.executor_registry.get(template_executor_name)?;
 
             // Use merged plugins list (existing + newly added)
             let plugins = if !all_plugins_for_rendering.added.is_empty() {
@@ -721,6 +749,7 @@ impl UpdateCommand {
             &new_inputs,
             template_pack_name,
             &matching_template.resource.metadata.name,
+            &template_version(&matching_template).to_string(),
             plugins_for_yaml,
             &current_env_resource,
             &merged_template_reference_projects,
@@ -735,6 +764,19 @@ impl UpdateCommand {
         output::environment_badge(&env_name);
         output::key_value("Path", &env_path.display().to_string());
 
+        // Conflicts must be resolved before the files can be applied
+        if merge_summary.needs_attention() {
+            let mut next_steps_list: Vec<String> = merge_summary
+                .attention_paths()
+                .iter()
+                .map(|path| format!("Resolve {}", env_path.join(path).display()))
+                .collect();
+            next_steps_list
+                .push("Run 'pmp preview' to see what changes will be applied".to_string());
+            output::next_steps(&next_steps_list);
+            return Ok(());
+        }
+
         // Ask if user wants to execute apply
         output::blank();
         let should_apply = ctx
@@ -760,6 +802,50 @@ impl UpdateCommand {
         Ok(())
     }
 
+    /// Print the files touched by the update
+    fn print_merge_summary(ctx: &crate::context::Context, summary: &MergeSummary) {
+        for (path, outcome) in &summary.files {
+            match outcome {
+                FileOutcome::Unchanged => {}
+                FileOutcome::Conflict => ctx
+                    .output
+                    .warning(&format!("  Conflict: {} (both sides changed)", path)),
+                FileOutcome::Rejected => ctx.output.warning(&format!(
+                    "  Rejected: {} (edited locally, template version in {}.rej)",
+                    path, path
+                )),
+                FileOutcome::Orphaned => ctx.output.warning(&format!(
+                    "  Orphaned: {} (no longer generated, kept local edits)",
+                    path
+                )),
+                _ => ctx.output.info(&format!("  {}: {}", outcome.label(), path)),
+            }
+        }
+
+        let counts: Vec<String> = [
+            FileOutcome::Created,
+            FileOutcome::Updated,
+            FileOutcome::Merged,
+            FileOutcome::KeptLocal,
+            FileOutcome::KeptDeleted,
+            FileOutcome::Removed,
+            FileOutcome::Orphaned,
+            FileOutcome::Conflict,
+            FileOutcome::Rejected,
+            FileOutcome::Unchanged,
+        ]
+        .into_iter()
+        .filter_map(|outcome| match summary.count(outcome) {
+            0 => None,
+            count => Some(format!("{} {}", count, outcome.label().to_lowercase())),
+        })
+        .collect();
+
+        if !counts.is_empty() {
+            ctx.output.key_value("Files", &counts.join(", "));
+        }
+    }
+
     /// Discover all plugins that have compatible projects in the collection
     /// Returns a list of plugins with their compatible projects
     fn discover_plugins_with_compatible_projects(
@@ -1434,6 +1520,7 @@ impl UpdateCommand {
                 plugin_context,
             )
             .context("Failed to render plugin files")?;
+        RenderBaseline::record(&*ctx.fs, target_env_path, &module_path, &generated_files)?;
 
         // 8. Update target project's .pmp.environment.yaml to track the added plugin
         output::dimmed("  Updating .pmp.environment.yaml...");
@@ -1539,12 +1626,7 @@ impl UpdateCommand {
             && !executor_config.config.is_empty()
         {
             let project_executor_name = &env_resource.spec.executor.name;
-            let executor: Box<dyn crate::executor::Executor> = match project_executor_name.as_str()
-            {
-                "opentofu" => Box::new(crate::executor::OpenTofuExecutor::new()),
-                "none" => Box::new(crate::executor::NoneExecutor::new()),
-                _ => anyhow::bail!("Unknown executor: {}", project_executor_name),
-            };
+            let executor = ctx.executor_registry.get(project_executor_name)?;
 
             let plugins = env_resource
                 .spec
@@ -1698,6 +1780,7 @@ impl UpdateCommand {
             ctx.fs
                 .remove_dir_all(&plugin_path)
                 .with_context(|| format!("Failed to remove plugin directory: {:?}", plugin_path))?;
+            RenderBaseline::forget(&*ctx.fs, env_path, &plugin_path)?;
             output::dimmed(&format!("  Deleted: {}", plugin_path.display()));
         } else {
             output::warning(&format!(
@@ -1725,12 +1808,7 @@ impl UpdateCommand {
             && !executor_config.config.is_empty()
         {
             let project_executor_name = &env_resource.spec.executor.name;
-            let executor: Box<dyn crate::executor::Executor> = match project_executor_name.as_str()
-            {
-                "opentofu" => Box::new(crate::executor::OpenTofuExecutor::new()),
-                "none" => Box::new(crate::executor::NoneExecutor::new()),
-                _ => anyhow::bail!("Unknown executor: {}", project_executor_name),
-            };
+            let executor = ctx.executor_registry.get(project_executor_name)?;
 
             let plugins = env_resource
                 .spec
@@ -1784,6 +1862,7 @@ impl UpdateCommand {
         env_name: &str,
         mut env_resource: DynamicProjectEnvironmentResource,
         template_packs_paths: Option<&str>,
+        strategy: MergeStrategy,
     ) -> Result<()> {
         output::section("Update Plugin Inputs");
 
@@ -1934,19 +2013,16 @@ impl UpdateCommand {
             plugin_path = plugin_path.join(&first_ref.name);
         }
 
-        // Re-render plugin files with new inputs
+        // Re-render plugin files with new inputs and merge them with local edits
         let renderer = crate::template::TemplateRenderer::new();
-        let plugin_context = Some((plugin_pack.as_str(), plugin_name.as_str()));
-
-        let generated_files = renderer
-            .render_template(
-                ctx,
-                &plugin_info.path,
-                &plugin_path,
-                &new_inputs,
-                plugin_context,
-            )
+        let rendered_files = renderer
+            .render_files(ctx, &plugin_info.path, &new_inputs)
             .context("Failed to re-render plugin files")?;
+        let generated_files: Vec<String> = rendered_files.iter().map(|f| f.path.clone()).collect();
+
+        let mut merge = RenderMerge::new(&*ctx.fs, env_path, strategy)?;
+        merge.apply(&plugin_path, rendered_files)?;
+        Self::print_merge_summary(ctx, &merge.finish()?);
 
         output::dimmed(&format!(
             "  Regenerated {} file(s) in: {}",
@@ -1975,12 +2051,7 @@ impl UpdateCommand {
             && !executor_config.config.is_empty()
         {
             let project_executor_name = &env_resource.spec.executor.name;
-            let executor: Box<dyn crate::executor::Executor> = match project_executor_name.as_str()
-            {
-                "opentofu" => Box::new(crate::executor::OpenTofuExecutor::new()),
-                "none" => Box::new(crate::executor::NoneExecutor::new()),
-                _ => anyhow::bail!("Unknown executor: {}", project_executor_name),
-            };
+            let executor = ctx.executor_registry.get(project_executor_name)?;
 
             let plugins = env_resource
                 .spec
@@ -2023,9 +2094,73 @@ impl UpdateCommand {
         Ok(())
     }
 
+    /// Add the internal fields a template is rendered with (`_environment`, `_resource_*`)
+    fn insert_internal_variables(
+        variables: &mut HashMap<String, Value>,
+        template: &TemplateInfo,
+        env_name: &str,
+    ) {
+        variables.insert(
+            "_environment".to_string(),
+            Value::String(env_name.to_string()),
+        );
+        variables.insert(
+            "_resource_api_version".to_string(),
+            Value::String(template.resource.spec.api_version.clone()),
+        );
+        variables.insert(
+            "_resource_kind".to_string(),
+            Value::String(template.resource.spec.kind.clone()),
+        );
+    }
+
+    /// Variables an environment is regenerated with from known input values, without prompting
+    ///
+    /// Adds what `regenerate` adds to the collected inputs: project name variables, computed
+    /// inputs, the internal fields and the plugins added to the environment.
+    pub(crate) fn render_variables(
+        template: &TemplateInfo,
+        inputs_spec: &[crate::template::metadata::InputDefinition],
+        env_resource: &DynamicProjectEnvironmentResource,
+        inputs: &HashMap<String, Value>,
+    ) -> Result<HashMap<String, Value>> {
+        let project_name = &env_resource.metadata.name;
+        let env_name = &env_resource.metadata.environment_name;
+
+        let mut variables = inputs.clone();
+        variables.insert(
+            "_project_name_underscores".to_string(),
+            Value::String(project_name.replace('-', "_")),
+        );
+        variables.insert(
+            "_project_name_hyphens".to_string(),
+            Value::String(project_name.replace('_', "-")),
+        );
+
+        for input_def in inputs_spec {
+            crate::template::computed::apply_computed_input(
+                input_def,
+                &mut variables,
+                project_name,
+                Some(env_name),
+            )?;
+        }
+
+        Self::insert_internal_variables(&mut variables, template, env_name);
+
+        if let Some(plugins) = &env_resource.spec.plugins {
+            variables.insert(
+                "_plugins".to_string(),
+                serde_json::to_value(plugins).context("Failed to serialize plugins")?,
+            );
+        }
+
+        Ok(variables)
+    }
+
     /// Detect context and select project/environment
     /// Returns: (environment_path, project_name, environment_name)
-    fn detect_and_select_environment(
+    pub(crate) fn detect_and_select_environment(
         ctx: &crate::context::Context,
         work_dir: &Path,
     ) -> Result<(PathBuf, String, String)> {
@@ -2176,6 +2311,16 @@ impl UpdateCommand {
                 continue;
             }
 
+            // Computed inputs are derived from the inputs collected so far
+            if crate::template::computed::apply_computed_input(
+                input_def,
+                &mut inputs,
+                project_name,
+                Some(environment_name),
+            )? {
+                continue;
+            }
+
             // Check if there's a predefined value for this input
             if let Some(predefined) = predefined_inputs.and_then(|p| p.get(&input_def.name)) {
                 // Build vars for interpolation
@@ -2268,9 +2413,17 @@ impl UpdateCommand {
             };
 
             let value = if let Some(input_type) = &input_def.input_type {
+                // Select options, including dynamic ones (None falls back to free text)
+                let select_options = crate::template::options::prompt_options(
+                    ctx,
+                    input_type,
+                    &vars,
+                    Some(environment_name),
+                );
+
                 // Handle based on input type
-                match input_type {
-                    crate::template::metadata::InputType::Select { options } => {
+                match (input_type, select_options) {
+                    (crate::template::metadata::InputType::Select { .. }, Some(options)) => {
                         // Build list of display labels
                         let labels: Vec<String> =
                             options.iter().map(|opt| opt.label.clone()).collect();
@@ -2341,12 +2494,55 @@ impl UpdateCommand {
     /// Find the original template used to create a project
     ///
     /// Uses the template pack name and template name stored in the environment resource's
-    /// spec.template field to locate the exact template that was originally used.
+    /// spec.template field to locate the exact template that was originally used. With
+    /// `version`, that version of the template is returned instead of the recorded one.
     fn find_original_template(
         ctx: &crate::context::Context,
         env_resource: &DynamicProjectEnvironmentResource,
         template_packs_paths: Option<&str>,
+        version: Option<&semver::Version>,
     ) -> Result<TemplateInfo> {
+        let template_ref = env_resource.spec.template.as_ref()
+            .context("Environment resource missing template reference. This environment may have been created with an older version of PMP.")?;
+        let versions = Self::find_template_versions(ctx, env_resource, template_packs_paths)?;
+
+        if let Some(version) = version {
+            return versions
+                .into_iter()
+                .find(|t| template_version(t) == *version)
+                .with_context(|| {
+                    format!(
+                        "Version {} of template '{}' not found in pack '{}'",
+                        version, template_ref.name, template_ref.template_pack_name
+                    )
+                });
+        }
+
+        let recorded = semver::Version::parse(&template_ref.version).ok();
+        match versions
+            .iter()
+            .position(|t| Some(template_version(t)) == recorded)
+        {
+            Some(index) => Ok(versions.into_iter().nth(index).unwrap()),
+            None => {
+                // Versions are sorted latest first
+                let latest = versions.into_iter().next().unwrap();
+                ctx.output.warning(&format!(
+                    "Template version {} not found, using {}",
+                    template_ref.version,
+                    template_version(&latest)
+                ));
+                Ok(latest)
+            }
+        }
+    }
+
+    /// All versions of the template an environment was created from, latest first
+    pub(crate) fn find_template_versions(
+        ctx: &crate::context::Context,
+        env_resource: &DynamicProjectEnvironmentResource,
+        template_packs_paths: Option<&str>,
+    ) -> Result<Vec<TemplateInfo>> {
         // Get original template info from environment resource
         let template_ref = env_resource.spec.template.as_ref()
             .context("Environment resource missing template reference. This environment may have been created with an older version of PMP.")?;
@@ -2384,13 +2580,19 @@ impl UpdateCommand {
             let templates_in_pack =
                 TemplateDiscovery::discover_templates_in_pack(&*ctx.fs, &*ctx.output, &pack.path)?;
 
-            // Find template matching both the name and resource kind
-            if let Some(template) = templates_in_pack.into_iter().find(|t| {
-                t.resource.metadata.name == *original_template_name
-                    && t.resource.spec.api_version == env_resource.api_version
-                    && t.resource.spec.kind == env_resource.kind
-            }) {
-                return Ok(template);
+            // Find templates matching both the name and resource kind
+            let mut versions: Vec<TemplateInfo> = templates_in_pack
+                .into_iter()
+                .filter(|t| {
+                    t.resource.metadata.name == *original_template_name
+                        && t.resource.spec.api_version == env_resource.api_version
+                        && t.resource.spec.kind == env_resource.kind
+                })
+                .collect();
+
+            if !versions.is_empty() {
+                versions.sort_by_key(|t| std::cmp::Reverse(template_version(t)));
+                return Ok(versions);
             }
         }
 
@@ -2701,6 +2903,16 @@ impl UpdateCommand {
                 continue;
             }
 
+            // Computed inputs are derived from the inputs collected so far
+            if crate::template::computed::apply_computed_input(
+                input_def,
+                &mut inputs,
+                project_name,
+                Some(environment_name),
+            )? {
+                continue;
+            }
+
             if let Some(default) = &input_def.default {
                 // Build variables map for interpolation
                 let mut vars = HashMap::new();
@@ -3026,6 +3238,7 @@ impl UpdateCommand {
         inputs: &std::collections::HashMap<String, serde_json::Value>,
         template_pack_name: &str,
         template_name: &str,
+        template_version: &str,
         merged_plugins: Option<&crate::template::metadata::ProjectPlugins>,
         current_env: &DynamicProjectEnvironmentResource,
         merged_template_reference_projects: &[crate::template::metadata::TemplateReferenceProject],
@@ -3071,7 +3284,7 @@ impl UpdateCommand {
                 template: Some(TemplateReference {
                     template_pack_name: template_pack_name.to_string(),
                     name: template_name.to_string(),
-                    version: "0.0.1".to_string(), // Default version for legacy templates
+                    version: template_version.to_string(),
                 }),
                 environment: Some(EnvironmentReference {
                     name: environment_name.to_string(),
@@ -3140,6 +3353,16 @@ impl UpdateCommand {
                 continue;
             }
 
+            // Computed inputs are derived from the inputs collected so far
+            if crate::template::computed::apply_computed_input(
+                input_def,
+                &mut inputs,
+                project_name,
+                Some(environment_name),
+            )? {
+                continue;
+            }
+
             // Get variables for interpolation
             let mut vars = HashMap::new();
             vars.insert(
@@ -3176,9 +3399,17 @@ impl UpdateCommand {
             };
 
             let value = if let Some(input_type) = &input_def.input_type {
+                // Select options, including dynamic ones (None falls back to free text)
+                let select_options = crate::template::options::prompt_options(
+                    ctx,
+                    input_type,
+                    &vars,
+                    Some(environment_name),
+                );
+
                 // Handle based on input type
-                match input_type {
-                    crate::template::metadata::InputType::Select { options } => {
+                match (input_type, select_options) {
+                    (crate::template::metadata::InputType::Select { .. }, Some(options)) => {
                         // Build list of display labels
                         let labels: Vec<String> =
                             options.iter().map(|opt| opt.label.clone()).collect();
@@ -3358,6 +3589,16 @@ impl UpdateCommand {
                 continue;
             }
 
+            // Computed inputs are derived from the inputs collected so far
+            if crate::template::computed::apply_computed_input(
+                input_def,
+                &mut inputs,
+                project_name,
+                Some(environment_name),
+            )? {
+                continue;
+            }
+
             // Get variables for interpolation
             let mut vars = HashMap::new();
             vars.insert(
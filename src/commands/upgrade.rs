@@ -0,0 +1,512 @@
+use crate::commands::update::{UpdateCommand, template_version};
+use crate::context::Context;
+use crate::template::metadata::InputDefinition;
+use crate::template::migration::{InputChange, apply_migrations};
+use crate::template::{
+    DynamicProjectEnvironmentResource, MergeStrategy, RenderBaseline, TemplateInfo,
+    TemplateRenderer,
+};
+use anyhow::{Context as AnyhowContext, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Handles the 'upgrade' command - moves an environment to a newer version of its template
+pub struct UpgradeCommand;
+
+/// An input change, with the template version whose migrations made it
+struct VersionedChange {
+    version: semver::Version,
+    change: InputChange,
+}
+
+impl UpgradeCommand {
+    /// Execute the upgrade command
+    ///
+    /// Applies the input migrations of every version after the current one up to the target,
+    /// then regenerates the environment from the target version (merging local edits).
+    pub fn execute(
+        ctx: &Context,
+        project_path: Option<&str>,
+        to: Option<&str>,
+        template_packs_paths: Option<&str>,
+        dry_run: bool,
+        strategy: MergeStrategy,
+    ) -> Result<()> {
+        let work_dir = if let Some(path) = project_path {
+            PathBuf::from(path)
+        } else {
+            std::env::current_dir().context("Failed to get current directory")?
+        };
+
+        let (env_path, project_name, env_name) =
+            UpdateCommand::detect_and_select_environment(ctx, &work_dir)?;
+
+        let env_file = env_path.join(".pmp.environment.yaml");
+        if !ctx.fs.exists(&env_file) {
+            anyhow::bail!("Environment file not found: {:?}", env_file);
+        }
+
+        let env_resource = DynamicProjectEnvironmentResource::from_file(&*ctx.fs, &env_file)
+            .context("Failed to load environment resource")?;
+        let template_ref = env_resource.spec.template.as_ref()
+            .context("Environment resource missing template reference. This environment may have been created with an older version of PMP.")?;
+        let current = semver::Version::parse(&template_ref.version).with_context(|| {
+            format!(
+                "Invalid template version in environment: {}",
+                template_ref.version
+            )
+        })?;
+
+        ctx.output.section("Upgrade Environment");
+        ctx.output.key_value_highlight("Project", &project_name);
+        ctx.output.environment_badge(&env_name);
+        ctx.output.key_value(
+            "Template",
+            &format!("{}/{}", template_ref.template_pack_name, template_ref.name),
+        );
+        ctx.output
+            .key_value("Current Version", &current.to_string());
+
+        let versions =
+            UpdateCommand::find_template_versions(ctx, &env_resource, template_packs_paths)?;
+        let newer: Vec<&TemplateInfo> = versions
+            .iter()
+            .filter(|t| template_version(t) > current)
+            .collect();
+
+        if newer.is_empty() {
+            ctx.output.blank();
+            ctx.output
+                .success(&format!("Already at the latest version ({})", current));
+            return Ok(());
+        }
+
+        ctx.output.subsection("Available Versions");
+        for template in &newer {
+            ctx.output
+                .dimmed(&format!("  {}", template_version(template)));
+        }
+
+        let target = Self::select_target(ctx, &newer, &current, to, dry_run)?;
+        let target_version = template_version(target);
+        ctx.output
+            .key_value_highlight("Target Version", &target_version.to_string());
+
+        // Migrations of every version after the current one, oldest first
+        let mut inputs = env_resource.spec.inputs.clone();
+        let mut changes = Vec::new();
+
+        for template in newer
+            .iter()
+            .rev()
+            .filter(|t| template_version(t) <= target_version)
+        {
+            let version = template_version(template);
+
+            for change in apply_migrations(&template.resource.spec.migrations, &mut inputs)? {
+                changes.push(VersionedChange {
+                    version: version.clone(),
+                    change,
+                });
+            }
+        }
+
+        // New inputs without a migrated value take the template default
+        let mut missing = Vec::new();
+        for input_def in Self::environment_inputs(target, &env_name) {
+            if inputs.contains_key(&input_def.name) {
+                continue;
+            }
+
+            match &input_def.default {
+                Some(default) => {
+                    inputs.insert(input_def.name.clone(), default.clone());
+                    changes.push(VersionedChange {
+                        version: target_version.clone(),
+                        change: InputChange::Defaulted {
+                            input: input_def.name.clone(),
+                            value: default.clone(),
+                        },
+                    });
+                }
+                None => missing.push(input_def.name.clone()),
+            }
+        }
+
+        ctx.output.subsection("Input Changes");
+        if changes.is_empty() && missing.is_empty() {
+            ctx.output.dimmed("No input changes");
+        }
+        for change in &changes {
+            ctx.output
+                .info(&format!("  {}: {}", change.version, change.change));
+        }
+        for name in &missing {
+            ctx.output
+                .warning(&format!("  {}: no value or default (will prompt)", name));
+        }
+
+        if dry_run {
+            Self::print_file_changes(ctx, &env_path, target, &env_resource, &inputs)?;
+            ctx.output.blank();
+            ctx.output.dimmed("Dry run - no changes made");
+            return Ok(());
+        }
+
+        let env_path_str = env_path
+            .to_str()
+            .context("Failed to convert environment path to string")?;
+
+        UpdateCommand::regenerate(
+            ctx,
+            Some(env_path_str),
+            template_packs_paths,
+            Some(inputs),
+            strategy,
+            Some(&target_version),
+        )
+    }
+
+    /// Pick the version to upgrade to: `--to`, the latest one on a dry run, or a prompt
+    fn select_target<'a>(
+        ctx: &Context,
+        newer: &[&'a TemplateInfo],
+        current: &semver::Version,
+        to: Option<&str>,
+        dry_run: bool,
+    ) -> Result<&'a TemplateInfo> {
+        if let Some(to) = to {
+            let version = semver::Version::parse(to.trim_start_matches('v'))
+                .with_context(|| format!("Invalid version: {}", to))?;
+
+            if version <= *current {
+                anyhow::bail!(
+                    "Version {} is not newer than the current version {}",
+                    version,
+                    current
+                );
+            }
+
+            return newer
+                .iter()
+                .copied()
+                .find(|t| template_version(t) == version)
+                .with_context(|| format!("Template version {} not found", version));
+        }
+
+        if dry_run {
+            return Ok(newer[0]);
+        }
+
+        let options: Vec<String> = newer
+            .iter()
+            .map(|t| template_version(t).to_string())
+            .collect();
+        let selected = ctx
+            .input
+            .select("Upgrade to version:", options.clone(), Some(0))
+            .context("Failed to select version")?;
+        let index = options
+            .iter()
+            .position(|o| *o == selected)
+            .context("Invalid version selection")?;
+
+        Ok(newer[index])
+    }
+
+    /// Inputs of a template for an environment (environment overrides replace base inputs)
+    fn environment_inputs(template: &TemplateInfo, env_name: &str) -> Vec<InputDefinition> {
+        let mut inputs = template.resource.spec.inputs.clone();
+
+        if let Some(env_overrides) = template.resource.spec.environments.get(env_name) {
+            for env_input in &env_overrides.overrides.inputs {
+                inputs.retain(|input_def| input_def.name != env_input.name);
+                inputs.push(env_input.clone());
+            }
+        }
+
+        inputs
+    }
+
+    /// Render the target version in memory and list the files it would add, change or remove
+    fn print_file_changes(
+        ctx: &Context,
+        env_path: &Path,
+        target: &TemplateInfo,
+        env_resource: &DynamicProjectEnvironmentResource,
+        inputs: &HashMap<String, Value>,
+    ) -> Result<()> {
+        let variables = UpdateCommand::render_variables(
+            target,
+            &Self::environment_inputs(target, &env_resource.metadata.environment_name),
+            env_resource,
+            inputs,
+        )?;
+
+        let rendered = TemplateRenderer::new()
+            .render_files(ctx, &target.path, &variables)
+            .context("Failed to render template")?;
+
+        ctx.output.subsection("File Changes");
+        let mut changed = 0;
+
+        for file in &rendered {
+            let path = env_path.join(&file.path);
+
+            let status = if !ctx.fs.exists(&path) {
+                "added"
+            } else if ctx.fs.read_to_string(&path)? != file.content {
+                "changed"
+            } else {
+                continue;
+            };
+
+            ctx.output.info(&format!("  {}: {}", status, file.path));
+            changed += 1;
+        }
+
+        let baseline = RenderBaseline::load(&*ctx.fs, env_path)?;
+        if let Some(previous) = baseline.outputs.get("") {
+            for path in previous.keys() {
+                if !rendered.iter().any(|f| f.path == *path) && ctx.fs.exists(&env_path.join(path))
+                {
+                    ctx.output.info(&format!("  removed: {}", path));
+                    changed += 1;
+                }
+            }
+        }
+
+        if changed == 0 {
+            ctx.output.dimmed("No file changes");
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{FileSystem, MockFileSystem, MockOutput};
+    use std::sync::Arc;
+
+    fn template_yaml(version_inputs: &str, migrations: &str) -> String {
+        format!(
+            r#"apiVersion: pmp.io/v1
+kind: Template
+metadata:
+  name: api
+spec:
+  apiVersion: pmp.io/v1
+  kind: Api
+  executor: opentofu
+  inputs:
+{}
+  migrations:
+{}
+"#,
+            version_inputs, migrations
+        )
+    }
+
+    fn setup() -> (Arc<MockFileSystem>, PathBuf) {
+        let fs = Arc::new(MockFileSystem::new());
+        let template_dir = PathBuf::from("/packs/core/templates/api/versions");
+
+        fs.write(
+            &PathBuf::from("/packs/core/.pmp.template-pack.yaml"),
+            "apiVersion: pmp.io/v1\nkind: TemplatePack\nmetadata:\n  name: core\nspec: {}\n",
+        )
+        .unwrap();
+
+        fs.write(
+            &template_dir.join("1.0.0/.pmp.template.yaml"),
+            &template_yaml("    - name: size\n      default: small", "    []"),
+        )
+        .unwrap();
+        fs.write(
+            &template_dir.join("1.0.0/src/main.tf.hbs"),
+            "size = {{size}}\n",
+        )
+        .unwrap();
+
+        fs.write(
+            &template_dir.join("2.0.0/.pmp.template.yaml"),
+            &template_yaml(
+                "    - name: instance_size\n    - name: region\n      default: eu-west-1",
+                "    - type: rename\n      from: size\n      to: instance_size\n    - type: transform\n      input: instance_size\n      values:\n        - from: small\n          to: t3.small",
+            ),
+        )
+        .unwrap();
+        fs.write(
+            &template_dir.join("2.0.0/src/main.tf.hbs"),
+            "instance_size = {{instance_size}}\nregion = {{region}}\n",
+        )
+        .unwrap();
+
+        fs.set_current_dir(PathBuf::from("/infra"));
+        crate::test_helpers::create_opentofu_infrastructure(&fs, &["dev"]);
+
+        let env_path = PathBuf::from("/infra/projects/api/environments/dev");
+        fs.write(
+            &env_path.join(".pmp.environment.yaml"),
+            r#"apiVersion: pmp.io/v1
+kind: Api
+metadata:
+  name: api
+  environment_name: dev
+spec:
+  resource:
+    apiVersion: pmp.io/v1
+    kind: Api
+  executor:
+    name: opentofu
+  inputs:
+    size: small
+  template:
+    template_pack_name: core
+    name: api
+    version: 1.0.0
+"#,
+        )
+        .unwrap();
+        fs.write(&env_path.join("main.tf"), "size = small\n")
+            .unwrap();
+        RenderBaseline::record(&*fs, &env_path, &env_path, &["main.tf".to_string()]).unwrap();
+
+        (fs, env_path)
+    }
+
+    #[test]
+    fn test_upgrade_dry_run_lists_changes() {
+        let (fs, env_path) = setup();
+        let output = Arc::new(MockOutput::new());
+        let ctx = Context::test_with(
+            fs.clone(),
+            Arc::new(crate::traits::MockUserInput::new()),
+            output.clone(),
+            Arc::new(crate::traits::MockCommandExecutor::new()),
+            Arc::new(crate::executor::DefaultExecutorRegistry::with_defaults()),
+        );
+
+        UpgradeCommand::execute(
+            &ctx,
+            Some(env_path.to_str().unwrap()),
+            Some("2.0.0"),
+            Some("/packs"),
+            true,
+            MergeStrategy::Merge,
+        )
+        .unwrap();
+
+        let messages = format!("{:?}", output.get_messages());
+        assert!(messages.contains("2.0.0: renamed size -> instance_size"));
+        assert!(messages.contains("2.0.0: changed instance_size"));
+        assert!(messages.contains("2.0.0: set region"));
+        assert!(messages.contains("changed: main.tf"));
+
+        // Nothing was written
+        assert_eq!(
+            fs.get_file_contents(&env_path.join("main.tf")).unwrap(),
+            "size = small\n"
+        );
+    }
+
+    #[test]
+    fn test_upgrade_dry_run_renders_like_regenerate() {
+        let (fs, env_path) = setup();
+        fs.write(
+            &PathBuf::from("/packs/core/templates/api/versions/2.0.0/src/name.tf.hbs"),
+            "name = {{_project_name_underscores}}-{{_environment}}\n",
+        )
+        .unwrap();
+        fs.write(&env_path.join("name.tf"), "name = api-dev\n")
+            .unwrap();
+
+        let output = Arc::new(MockOutput::new());
+        let ctx = Context::test_with(
+            fs.clone(),
+            Arc::new(crate::traits::MockUserInput::new()),
+            output.clone(),
+            Arc::new(crate::traits::MockCommandExecutor::new()),
+            Arc::new(crate::executor::DefaultExecutorRegistry::with_defaults()),
+        );
+
+        UpgradeCommand::execute(
+            &ctx,
+            Some(env_path.to_str().unwrap()),
+            Some("2.0.0"),
+            Some("/packs"),
+            true,
+            MergeStrategy::Merge,
+        )
+        .unwrap();
+
+        let messages = format!("{:?}", output.get_messages());
+        assert!(messages.contains("changed: main.tf"));
+        assert!(!messages.contains("name.tf"));
+    }
+
+    #[test]
+    fn test_upgrade_regenerates_and_records_version() {
+        let (fs, env_path) = setup();
+
+        let input = Arc::new(crate::traits::MockUserInput::new());
+        input.add_response(crate::traits::user_input::MockResponse::Confirm(true)); // regenerate
+        input.add_response(crate::traits::user_input::MockResponse::Confirm(false)); // apply
+        let ctx = Context::test_with(
+            fs.clone(),
+            input,
+            Arc::new(MockOutput::new()),
+            Arc::new(crate::traits::MockCommandExecutor::new()),
+            Arc::new(crate::executor::DefaultExecutorRegistry::with_defaults()),
+        );
+
+        UpgradeCommand::execute(
+            &ctx,
+            Some(env_path.to_str().unwrap()),
+            Some("2.0.0"),
+            Some("/packs"),
+            false,
+            MergeStrategy::Merge,
+        )
+        .unwrap();
+
+        assert_eq!(
+            fs.get_file_contents(&env_path.join("main.tf")).unwrap(),
+            "instance_size = t3.small\nregion = eu-west-1\n"
+        );
+
+        let env_resource = DynamicProjectEnvironmentResource::from_file(
+            &*fs,
+            &env_path.join(".pmp.environment.yaml"),
+        )
+        .unwrap();
+        assert_eq!(env_resource.spec.template.unwrap().version, "2.0.0");
+        assert_eq!(env_resource.spec.inputs["instance_size"], "t3.small");
+        assert!(!env_resource.spec.inputs.contains_key("size"));
+    }
+
+    #[test]
+    fn test_upgrade_rejects_older_version() {
+        let (fs, env_path) = setup();
+        let ctx = Context::test_with(
+            fs,
+            Arc::new(crate::traits::MockUserInput::new()),
+            Arc::new(MockOutput::new()),
+            Arc::new(crate::traits::MockCommandExecutor::new()),
+            Arc::new(crate::executor::DefaultExecutorRegistry::with_defaults()),
+        );
+
+        let result = UpgradeCommand::execute(
+            &ctx,
+            Some(env_path.to_str().unwrap()),
+            Some("1.0.0"),
+            Some("/packs"),
+            true,
+            MergeStrategy::Merge,
+        );
+        assert!(result.unwrap_err().to_string().contains("not newer"));
+    }
+}
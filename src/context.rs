@@ -14,7 +14,6 @@ pub struct Context {
     pub output: Arc<dyn Output>,
     #[allow(dead_code)]
     pub command: Arc<dyn CommandExecutor>,
-    #[allow(dead_code)]
     pub executor_registry: Arc<dyn ExecutorRegistry>,
 }
 
@@ -0,0 +1,475 @@
+//! JSON plan parser for OpenTofu/Terraform
+//!
+//! This module parses the machine-readable plan produced by `tofu show -json <planfile>`
+//! (or `terraform show -json`) into the same structured model used for rendering.
+//! Attribute changes are computed from `change.before`/`change.after`, so nested
+//! blocks, lists, maps and heredocs are handled without scraping human output.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::types::{
+    AttributeChange, AttributeChangeType, DiffChangeType, ParsedPlan, ResourceChange,
+};
+
+/// Attribute path: object keys and list indexes from the resource root
+type AttributePath = Vec<String>;
+
+/// Top-level JSON plan representation (only the fields pmp needs)
+#[derive(Debug, Deserialize)]
+struct JsonPlan {
+    #[serde(default)]
+    resource_changes: Vec<JsonResourceChange>,
+}
+
+/// Entry of `resource_changes[]`
+#[derive(Debug, Deserialize)]
+struct JsonResourceChange {
+    address: String,
+    change: JsonChange,
+}
+
+/// The `change` object of a resource change
+#[derive(Debug, Deserialize)]
+struct JsonChange {
+    #[serde(default)]
+    actions: Vec<String>,
+    #[serde(default)]
+    before: Value,
+    #[serde(default)]
+    after: Value,
+    #[serde(default)]
+    after_unknown: Value,
+    #[serde(default)]
+    before_sensitive: Value,
+    #[serde(default)]
+    after_sensitive: Value,
+    #[serde(default)]
+    replace_paths: Vec<Vec<Value>>,
+}
+
+/// Parser for OpenTofu/Terraform JSON plan output
+#[derive(Debug, Default)]
+pub struct JsonPlanParser;
+
+impl JsonPlanParser {
+    /// Create a new JSON plan parser
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Parse a JSON plan document and return a structured ParsedPlan
+    pub fn parse(&self, json: &str) -> Result<ParsedPlan> {
+        let plan: JsonPlan = serde_json::from_str(json).context("Failed to parse JSON plan")?;
+
+        let mut parsed = ParsedPlan::new();
+        parsed.raw_output = json.to_string();
+
+        for resource_change in plan.resource_changes {
+            let change_type = Self::parse_actions(&resource_change.change.actions);
+
+            // No-op resources only count towards the summary
+            if change_type == DiffChangeType::NoOp {
+                parsed.summary.unchanged += 1;
+                continue;
+            }
+
+            let mut resource = ResourceChange::new(&resource_change.address, change_type);
+            for attr in self.diff_attributes(&resource_change.change) {
+                resource.add_attribute(attr);
+            }
+
+            parsed.add_resource(resource);
+        }
+
+        Ok(parsed)
+    }
+
+    /// Map the `actions` list to a change type
+    fn parse_actions(actions: &[String]) -> DiffChangeType {
+        let actions: Vec<&str> = actions.iter().map(|a| a.as_str()).collect();
+
+        match actions.as_slice() {
+            ["create"] => DiffChangeType::Create,
+            ["update"] => DiffChangeType::Update,
+            ["delete"] => DiffChangeType::Destroy,
+            ["read"] => DiffChangeType::Read,
+            ["delete", "create"] | ["create", "delete"] => DiffChangeType::Replace,
+            _ => DiffChangeType::NoOp,
+        }
+    }
+
+    /// Compute attribute changes between `before` and `after`
+    fn diff_attributes(&self, change: &JsonChange) -> Vec<AttributeChange> {
+        let mut before = BTreeMap::new();
+        flatten_value(&change.before, &mut Vec::new(), &mut before);
+
+        let mut after = BTreeMap::new();
+        flatten_value(&change.after, &mut Vec::new(), &mut after);
+
+        let mut unknown = BTreeSet::new();
+        collect_marked_paths(&change.after_unknown, &mut Vec::new(), &mut unknown);
+
+        let replace_paths: Vec<AttributePath> = change
+            .replace_paths
+            .iter()
+            .map(|path| path.iter().map(path_segment).collect())
+            .collect();
+
+        let paths: BTreeSet<&AttributePath> = before
+            .keys()
+            .chain(after.keys())
+            .chain(unknown.iter())
+            .collect();
+
+        let mut attributes = Vec::new();
+
+        for path in paths {
+            let old = before.get(path);
+            let computed = is_marked(&change.after_unknown, path);
+            let new = if computed { None } else { after.get(path) };
+
+            let change_type = match (old, new) {
+                _ if computed && old.is_some() => AttributeChangeType::Modified,
+                _ if computed => AttributeChangeType::Added,
+                (None, Some(_)) => AttributeChangeType::Added,
+                (Some(_), None) => AttributeChangeType::Removed,
+                (Some(old), Some(new)) if old == new => AttributeChangeType::Unchanged,
+                (Some(_), Some(_)) => AttributeChangeType::Modified,
+                (None, None) => continue,
+            };
+
+            let mut attr = AttributeChange::new(&path.join("."), change_type);
+            attr.old_value = old.map(format_value);
+            attr.new_value = if computed {
+                Some("(known after apply)".to_string())
+            } else {
+                new.map(format_value)
+            };
+            attr.computed = computed;
+            attr.sensitive = is_marked(&change.before_sensitive, path)
+                || is_marked(&change.after_sensitive, path);
+            attr.forces_replacement = replace_paths
+                .iter()
+                .any(|replace_path| !replace_path.is_empty() && path.starts_with(replace_path));
+
+            attributes.push(attr);
+        }
+
+        attributes
+    }
+}
+
+/// Flatten a JSON value into leaf values keyed by attribute path
+///
+/// Nulls are treated as unset; empty lists and maps are kept as leaves so that
+/// clearing a collection shows up as a change.
+fn flatten_value(
+    value: &Value,
+    path: &mut AttributePath,
+    leaves: &mut BTreeMap<AttributePath, Value>,
+) {
+    match value {
+        Value::Null => {}
+        Value::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                path.push(key.clone());
+                flatten_value(child, path, leaves);
+                path.pop();
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for (index, child) in items.iter().enumerate() {
+                path.push(index.to_string());
+                flatten_value(child, path, leaves);
+                path.pop();
+            }
+        }
+        _ if path.is_empty() => {}
+        _ => {
+            leaves.insert(path.clone(), value.clone());
+        }
+    }
+}
+
+/// Collect the paths marked `true` in an `after_unknown`-style mask
+fn collect_marked_paths(
+    mask: &Value,
+    path: &mut AttributePath,
+    paths: &mut BTreeSet<AttributePath>,
+) {
+    match mask {
+        Value::Bool(true) if !path.is_empty() => {
+            paths.insert(path.clone());
+        }
+        Value::Object(map) => {
+            for (key, child) in map {
+                path.push(key.clone());
+                collect_marked_paths(child, path, paths);
+                path.pop();
+            }
+        }
+        Value::Array(items) => {
+            for (index, child) in items.iter().enumerate() {
+                path.push(index.to_string());
+                collect_marked_paths(child, path, paths);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Check whether a path (or one of its ancestors) is marked `true` in a mask
+fn is_marked(mask: &Value, path: &[String]) -> bool {
+    let mut current = mask;
+
+    for segment in path {
+        current = match current {
+            Value::Bool(marked) => return *marked,
+            Value::Object(map) => match map.get(segment) {
+                Some(child) => child,
+                None => return false,
+            },
+            Value::Array(items) => match segment.parse::<usize>().ok().and_then(|i| items.get(i)) {
+                Some(child) => child,
+                None => return false,
+            },
+            _ => return false,
+        };
+    }
+
+    matches!(current, Value::Bool(true))
+}
+
+/// Convert a `replace_paths` step (string key or numeric index) to a path segment
+fn path_segment(step: &Value) -> String {
+    match step {
+        Value::String(key) => key.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Format a leaf value for display
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_json_plan() -> &'static str {
+        r##"{
+  "format_version": "1.2",
+  "resource_changes": [
+    {
+      "address": "aws_instance.web_server",
+      "type": "aws_instance",
+      "name": "web_server",
+      "change": {
+        "actions": ["create"],
+        "before": null,
+        "after": {
+          "ami": "ami-12345678",
+          "instance_type": "t3.micro",
+          "user_data": "#!/bin/bash\necho hello\n",
+          "tags": {"Name": "web-server"},
+          "ebs_block_device": [{"volume_size": 20}],
+          "monitoring": null
+        },
+        "after_unknown": {"id": true, "tags": {}, "ebs_block_device": [{"volume_id": true}]},
+        "before_sensitive": false,
+        "after_sensitive": {"tags": {}, "ebs_block_device": [{}]}
+      }
+    },
+    {
+      "address": "module.network.aws_security_group.main",
+      "module_address": "module.network",
+      "type": "aws_security_group",
+      "name": "main",
+      "change": {
+        "actions": ["update"],
+        "before": {"name": "main-sg", "ingress": [{"from_port": 80, "cidr_blocks": ["10.0.0.0/8"]}]},
+        "after": {"name": "main-sg", "ingress": [{"from_port": 443, "cidr_blocks": ["10.0.0.0/8"]}]},
+        "after_unknown": {"ingress": [{"cidr_blocks": [false]}]},
+        "before_sensitive": {},
+        "after_sensitive": {}
+      }
+    },
+    {
+      "address": "aws_db_instance.main",
+      "type": "aws_db_instance",
+      "name": "main",
+      "change": {
+        "actions": ["delete", "create"],
+        "before": {"engine_version": "14", "password": "old-secret"},
+        "after": {"engine_version": "15", "password": "new-secret"},
+        "after_unknown": {},
+        "before_sensitive": {"password": true},
+        "after_sensitive": {"password": true},
+        "replace_paths": [["engine_version"]]
+      }
+    },
+    {
+      "address": "aws_instance.old_server",
+      "type": "aws_instance",
+      "name": "old_server",
+      "change": {
+        "actions": ["delete"],
+        "before": {"ami": "ami-old12345"},
+        "after": null,
+        "after_unknown": {},
+        "before_sensitive": {},
+        "after_sensitive": false
+      }
+    },
+    {
+      "address": "aws_s3_bucket.logs",
+      "type": "aws_s3_bucket",
+      "name": "logs",
+      "change": {
+        "actions": ["no-op"],
+        "before": {"bucket": "logs"},
+        "after": {"bucket": "logs"},
+        "after_unknown": {},
+        "before_sensitive": {},
+        "after_sensitive": {}
+      }
+    }
+  ]
+}"##
+    }
+
+    fn find_resource<'a>(plan: &'a ParsedPlan, address: &str) -> &'a ResourceChange {
+        plan.resources
+            .iter()
+            .find(|r| r.address == address)
+            .unwrap()
+    }
+
+    fn find_attribute<'a>(resource: &'a ResourceChange, name: &str) -> &'a AttributeChange {
+        resource.attributes.iter().find(|a| a.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_parse_summary() {
+        let plan = JsonPlanParser::new().parse(sample_json_plan()).unwrap();
+
+        assert_eq!(plan.resources.len(), 4);
+        assert_eq!(plan.summary.to_add, 1);
+        assert_eq!(plan.summary.to_change, 1);
+        assert_eq!(plan.summary.to_replace, 1);
+        assert_eq!(plan.summary.to_destroy, 1);
+        assert_eq!(plan.summary.unchanged, 1);
+        assert!(plan.has_changes);
+    }
+
+    #[test]
+    fn test_parse_create_with_nested_values() {
+        let plan = JsonPlanParser::new().parse(sample_json_plan()).unwrap();
+        let resource = find_resource(&plan, "aws_instance.web_server");
+
+        assert_eq!(resource.change_type, DiffChangeType::Create);
+
+        let tag = find_attribute(resource, "tags.Name");
+        assert_eq!(tag.change_type, AttributeChangeType::Added);
+        assert_eq!(tag.new_value, Some("web-server".to_string()));
+
+        let volume = find_attribute(resource, "ebs_block_device.0.volume_size");
+        assert_eq!(volume.new_value, Some("20".to_string()));
+
+        let user_data = find_attribute(resource, "user_data");
+        assert_eq!(
+            user_data.new_value,
+            Some("#!/bin/bash\necho hello\n".to_string())
+        );
+
+        // Null attributes are treated as unset
+        assert!(resource.attributes.iter().all(|a| a.name != "monitoring"));
+    }
+
+    #[test]
+    fn test_parse_computed_attributes() {
+        let plan = JsonPlanParser::new().parse(sample_json_plan()).unwrap();
+        let resource = find_resource(&plan, "aws_instance.web_server");
+
+        let id = find_attribute(resource, "id");
+        assert!(id.computed);
+        assert_eq!(id.change_type, AttributeChangeType::Added);
+        assert_eq!(id.new_value, Some("(known after apply)".to_string()));
+
+        let volume_id = find_attribute(resource, "ebs_block_device.0.volume_id");
+        assert!(volume_id.computed);
+    }
+
+    #[test]
+    fn test_parse_update_in_module() {
+        let plan = JsonPlanParser::new().parse(sample_json_plan()).unwrap();
+        let resource = find_resource(&plan, "module.network.aws_security_group.main");
+
+        assert_eq!(resource.change_type, DiffChangeType::Update);
+        assert_eq!(resource.module_path, Some("module.network".to_string()));
+
+        let port = find_attribute(resource, "ingress.0.from_port");
+        assert_eq!(port.change_type, AttributeChangeType::Modified);
+        assert_eq!(port.old_value, Some("80".to_string()));
+        assert_eq!(port.new_value, Some("443".to_string()));
+
+        let name = find_attribute(resource, "name");
+        assert_eq!(name.change_type, AttributeChangeType::Unchanged);
+
+        let cidr = find_attribute(resource, "ingress.0.cidr_blocks.0");
+        assert_eq!(cidr.change_type, AttributeChangeType::Unchanged);
+        assert!(!cidr.computed);
+    }
+
+    #[test]
+    fn test_parse_replace_with_sensitive_values() {
+        let plan = JsonPlanParser::new().parse(sample_json_plan()).unwrap();
+        let resource = find_resource(&plan, "aws_db_instance.main");
+
+        assert_eq!(resource.change_type, DiffChangeType::Replace);
+        assert_eq!(
+            resource.forces_replacement,
+            vec!["engine_version".to_string()]
+        );
+
+        let password = find_attribute(resource, "password");
+        assert!(password.sensitive);
+        assert!(!password.forces_replacement);
+    }
+
+    #[test]
+    fn test_parse_destroy() {
+        let plan = JsonPlanParser::new().parse(sample_json_plan()).unwrap();
+        let resource = find_resource(&plan, "aws_instance.old_server");
+
+        assert_eq!(resource.change_type, DiffChangeType::Destroy);
+
+        let ami = find_attribute(resource, "ami");
+        assert_eq!(ami.change_type, AttributeChangeType::Removed);
+        assert_eq!(ami.old_value, Some("ami-old12345".to_string()));
+        assert_eq!(ami.new_value, None);
+    }
+
+    #[test]
+    fn test_parse_no_changes() {
+        let plan = JsonPlanParser::new()
+            .parse(r#"{"format_version": "1.2", "planned_values": {}}"#)
+            .unwrap();
+
+        assert!(plan.resources.is_empty());
+        assert!(!plan.has_changes);
+    }
+
+    #[test]
+    fn test_parse_invalid_json() {
+        assert!(JsonPlanParser::new().parse("Plan: 1 to add").is_err());
+    }
+}
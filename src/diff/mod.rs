@@ -5,7 +5,8 @@
 //!
 //! # Features
 //!
-//! - **Parsing**: Extract resource and attribute changes from plan output
+//! - **Parsing**: Extract resource and attribute changes from JSON plans (`show -json`),
+//!   with a text parser as fallback for executors without JSON support
 //! - **ASCII Rendering**: Terminal-friendly colored diff output
 //! - **HTML Rendering**: Export diffs to HTML for documentation/sharing
 //! - **Side-by-side view**: Optional two-column comparison
@@ -13,10 +14,10 @@
 //! # Example
 //!
 //! ```ignore
-//! use pmp::diff::{PlanParser, AsciiRenderer, DiffRenderer, DiffRenderOptions};
+//! use pmp::diff::{JsonPlanParser, AsciiRenderer, DiffRenderer, DiffRenderOptions};
 //!
-//! let parser = PlanParser::new();
-//! let plan = parser.parse(&plan_output)?;
+//! let parser = JsonPlanParser::new();
+//! let plan = parser.parse(&plan_json)?;
 //!
 //! let renderer = AsciiRenderer::new();
 //! let options = DiffRenderOptions::default();
@@ -25,10 +26,12 @@
 //! println!("{}", diff_output);
 //! ```
 
+mod json_parser;
 mod parser;
 mod renderer;
 mod types;
 
+pub use json_parser::JsonPlanParser;
 pub use parser::PlanParser;
 pub use renderer::{AsciiRenderer, DiffRenderer, HtmlRenderer};
 pub use types::{
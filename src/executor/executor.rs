@@ -18,6 +18,23 @@ pub struct ExecutorConfig {
     pub test_command: Option<String>,
     /// Command-specific options from template configuration
     pub command_options: std::collections::HashMap<String, Vec<String>>,
+    /// Optional saved plan file: plan writes the plan to it and apply applies exactly it
+    pub plan_file: Option<String>,
+}
+
+/// Default file name of the binary plan saved in the environment directory
+pub const DEFAULT_PLAN_FILE: &str = "plan.tfplan";
+
+/// Default file name of the JSON plan (also used as OPA policy input)
+pub const DEFAULT_PLAN_JSON_FILE: &str = "plan.json";
+
+/// Plan saved to disk by `Executor::save_plan`
+#[derive(Debug)]
+pub struct SavedPlan {
+    /// Raw output of the plan command (exit code 0 = no changes, 2 = changes)
+    pub output: Output,
+    /// JSON representation of the saved plan, if the executor supports it
+    pub json: Option<String>,
 }
 
 /// Project metadata for backend table name generation
@@ -90,6 +107,55 @@ pub trait Executor: Send + Sync {
     /// Returns the raw Output containing stdout/stderr and exit status
     fn plan_with_output(&self, working_dir: &str, extra_args: &[String]) -> Result<Output>;
 
+    /// Execute the plan command saving the binary plan to `plan_file` (relative to working_dir)
+    /// and render it as JSON (e.g., 'tofu show -json')
+    /// Default implementation falls back to plan_with_output without a JSON representation
+    fn save_plan(
+        &self,
+        working_dir: &str,
+        _plan_file: &str,
+        extra_args: &[String],
+    ) -> Result<SavedPlan> {
+        Ok(SavedPlan {
+            output: self.plan_with_output(working_dir, extra_args)?,
+            json: None,
+        })
+    }
+
+    /// Render a saved plan as JSON (e.g., 'tofu show -json <plan_file>')
+    /// Returns None when the executor has no JSON plan representation
+    fn show_plan_json(&self, _working_dir: &str, _plan_file: &str) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Download the current state from the configured backend (e.g., 'tofu state pull')
+    /// Returns an empty string when no state exists yet
+    fn state_pull(&self, _working_dir: &str) -> Result<String> {
+        anyhow::bail!("{} does not support state pull", self.get_name())
+    }
+
+    /// Upload a state file to the configured backend (e.g., 'tofu state push')
+    /// `force` skips the lineage and serial checks, as needed to restore an older state
+    fn state_push(&self, _working_dir: &str, _state_file: &str, _force: bool) -> Result<()> {
+        anyhow::bail!("{} does not support state push", self.get_name())
+    }
+
+    /// Rename a resource in the state (e.g., 'tofu state mv')
+    fn state_mv(&self, _working_dir: &str, _source: &str, _destination: &str) -> Result<()> {
+        anyhow::bail!("{} does not support state mv", self.get_name())
+    }
+
+    /// Stop managing resources without destroying them (e.g., 'tofu state rm')
+    fn state_rm(&self, _working_dir: &str, _addresses: &[String]) -> Result<()> {
+        anyhow::bail!("{} does not support state rm", self.get_name())
+    }
+
+    /// Re-initialize against a changed backend configuration, copying the state from the
+    /// previously initialized backend (e.g., 'tofu init -migrate-state'), without prompting
+    fn migrate_state(&self, _working_dir: &str) -> Result<()> {
+        anyhow::bail!("{} does not support state migration", self.get_name())
+    }
+
     /// Get the name of this executor (e.g., "opentofu", "terraform")
     fn get_name(&self) -> &str;
 
@@ -0,0 +1,423 @@
+use super::executor::{Executor, ExecutorConfig};
+use super::registry::ExecutorRegistry;
+use crate::template::metadata::ExternalExecutorConfig;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// Version of the JSON/stdio protocol spoken with external executors
+pub const EXTERNAL_PROTOCOL_VERSION: u32 = 1;
+
+/// Names reserved by the built-in executors
+const BUILTIN_EXECUTORS: &[&str] = &["opentofu", "terraform", "none"];
+
+/// Request sent as JSON on stdin to an external executor
+#[derive(Debug, Serialize)]
+pub struct ExternalRequest<'a> {
+    pub protocol_version: u32,
+    pub executor: &'a str,
+    pub operation: &'a str,
+    pub working_dir: &'a str,
+    /// Command options from the template configuration for this operation
+    pub options: Vec<String>,
+    /// Extra args passed after `--` on the pmp command line
+    pub extra_args: Vec<String>,
+    /// Saved plan file to write (`plan`) or apply (`apply`), when requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plan_file: Option<&'a str>,
+}
+
+/// Executor backed by an external binary declared in `.pmp.infrastructure.yaml`
+///
+/// Protocol (version 1):
+/// - pmp runs `<command> [args...] <operation>` in the environment directory
+/// - the request is written as a single JSON document on stdin, then stdin is closed
+/// - `PMP_EXECUTOR_PROTOCOL` and `PMP_OPERATION` are set in the environment
+/// - operations: `check`, `init`, `plan`, `apply`, `destroy`, `refresh`, `test`, `plan-output`
+/// - exit code 0 means success; for `plan-output` 0 = no changes, 2 = changes, other = error
+/// - stdout/stderr stream to the terminal, except for `check`, `init` and `plan-output`
+///   whose output is captured by pmp
+pub struct ExternalExecutor {
+    config: ExternalExecutorConfig,
+}
+
+impl ExternalExecutor {
+    pub fn new(config: ExternalExecutorConfig) -> Self {
+        Self { config }
+    }
+
+    /// Build the command for an operation with the request piped on stdin
+    fn build_command(&self, operation: &str, working_dir: &str) -> Command {
+        let mut command = Command::new(&self.config.command);
+        command
+            .args(&self.config.args)
+            .arg(operation)
+            .current_dir(working_dir)
+            .envs(&self.config.env)
+            .env("PMP_EXECUTOR_PROTOCOL", EXTERNAL_PROTOCOL_VERSION.to_string())
+            .env("PMP_OPERATION", operation)
+            .stdin(Stdio::piped());
+        command
+    }
+
+    /// Serialize the request for an operation
+    fn build_request(
+        &self,
+        operation: &str,
+        working_dir: &str,
+        options: &[String],
+        extra_args: &[String],
+        plan_file: Option<&str>,
+    ) -> Result<String> {
+        let request = ExternalRequest {
+            protocol_version: EXTERNAL_PROTOCOL_VERSION,
+            executor: &self.config.name,
+            operation,
+            working_dir,
+            options: options.to_vec(),
+            extra_args: extra_args.to_vec(),
+            plan_file,
+        };
+
+        serde_json::to_string(&request).context("Failed to serialize external executor request")
+    }
+
+    /// Spawn the binary, write the request and wait for completion
+    fn run(
+        &self,
+        operation: &str,
+        working_dir: &str,
+        options: &[String],
+        extra_args: &[String],
+        plan_file: Option<&str>,
+        capture: bool,
+    ) -> Result<Output> {
+        let request = self.build_request(operation, working_dir, options, extra_args, plan_file)?;
+
+        let mut command = self.build_command(operation, working_dir);
+        if capture {
+            command.stdout(Stdio::piped()).stderr(Stdio::piped());
+        } else {
+            command.stdout(Stdio::inherit()).stderr(Stdio::inherit());
+        }
+
+        let mut child = command.spawn().with_context(|| {
+            format!(
+                "Failed to spawn external executor '{}' ({})",
+                self.config.name, self.config.command
+            )
+        })?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            // The binary may exit without reading stdin; a broken pipe is not an error
+            let _ = stdin.write_all(request.as_bytes());
+        }
+
+        child
+            .wait_with_output()
+            .with_context(|| format!("Failed to wait for external executor '{}'", self.config.name))
+    }
+
+    /// Run an operation with inherited stdio and fail on non-zero exit
+    fn run_operation(
+        &self,
+        operation: &str,
+        config: &ExecutorConfig,
+        working_dir: &str,
+        extra_args: &[String],
+    ) -> Result<()> {
+        let options = config
+            .command_options
+            .get(operation)
+            .cloned()
+            .unwrap_or_default();
+
+        // Only plan and apply operate on saved plan files
+        let plan_file = match operation {
+            "plan" | "apply" => config.plan_file.as_deref(),
+            _ => None,
+        };
+
+        let output = self.run(operation, working_dir, &options, extra_args, plan_file, false)?;
+
+        if !output.status.success() {
+            anyhow::bail!(
+                "External executor '{}' {} failed with exit code: {:?}",
+                self.config.name,
+                operation,
+                output.status.code()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Executor for ExternalExecutor {
+    fn check_installed(&self) -> Result<bool> {
+        match self.run("check", ".", &[], &[], None, true) {
+            Ok(output) => Ok(output.status.success()),
+            Err(_) => Ok(false), // Binary not found or failed to execute
+        }
+    }
+
+    fn init(&self, working_dir: &str) -> Result<Output> {
+        self.run("init", working_dir, &[], &[], None, true)
+    }
+
+    fn plan(
+        &self,
+        config: &ExecutorConfig,
+        working_dir: &str,
+        extra_args: &[String],
+    ) -> Result<()> {
+        self.run_operation("plan", config, working_dir, extra_args)
+    }
+
+    fn apply(
+        &self,
+        config: &ExecutorConfig,
+        working_dir: &str,
+        extra_args: &[String],
+    ) -> Result<()> {
+        self.run_operation("apply", config, working_dir, extra_args)
+    }
+
+    fn destroy(
+        &self,
+        config: &ExecutorConfig,
+        working_dir: &str,
+        extra_args: &[String],
+    ) -> Result<()> {
+        self.run_operation("destroy", config, working_dir, extra_args)
+    }
+
+    fn refresh(
+        &self,
+        config: &ExecutorConfig,
+        working_dir: &str,
+        extra_args: &[String],
+    ) -> Result<()> {
+        self.run_operation("refresh", config, working_dir, extra_args)
+    }
+
+    fn test(
+        &self,
+        config: &ExecutorConfig,
+        working_dir: &str,
+        extra_args: &[String],
+    ) -> Result<()> {
+        self.run_operation("test", config, working_dir, extra_args)
+    }
+
+    fn plan_with_output(&self, working_dir: &str, extra_args: &[String]) -> Result<Output> {
+        self.run("plan-output", working_dir, &[], extra_args, None, true)
+    }
+
+    fn get_name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn default_plan_command(&self) -> &str {
+        &self.config.command
+    }
+
+    fn default_apply_command(&self) -> &str {
+        &self.config.command
+    }
+
+    fn default_destroy_command(&self) -> &str {
+        &self.config.command
+    }
+
+    fn default_refresh_command(&self) -> &str {
+        &self.config.command
+    }
+
+    fn default_test_command(&self) -> &str {
+        &self.config.command
+    }
+}
+
+/// Check an external executor declaration before it is registered
+fn validate_external_executor(config: &ExternalExecutorConfig) -> Result<()> {
+    if config.name.trim().is_empty() {
+        anyhow::bail!("External executor name cannot be empty");
+    }
+
+    if BUILTIN_EXECUTORS.contains(&config.name.as_str()) {
+        anyhow::bail!(
+            "External executor '{}' conflicts with a built-in executor",
+            config.name
+        );
+    }
+
+    if config.command.trim().is_empty() {
+        anyhow::bail!("External executor '{}' must declare a command", config.name);
+    }
+
+    Ok(())
+}
+
+/// Register external executors declared by an infrastructure
+///
+/// Each entry is validated on its own: valid entries are registered even when others are
+/// invalid, and the errors of the skipped entries are returned.
+pub fn register_external_executors(
+    registry: &dyn ExecutorRegistry,
+    configs: &[ExternalExecutorConfig],
+) -> Vec<anyhow::Error> {
+    let mut errors = Vec::new();
+
+    for config in configs {
+        if let Err(e) = validate_external_executor(config) {
+            errors.push(e);
+            continue;
+        }
+
+        registry.register(
+            config.name.clone(),
+            Box::new(ExternalExecutor::new(config.clone())),
+        );
+    }
+
+    errors
+}
+
+/// Register the external executors of the infrastructure containing the current directory
+///
+/// Invalid entries are skipped with a warning so they only affect commands that use them.
+pub fn register_infrastructure_executors(ctx: &crate::context::Context) -> Result<()> {
+    if let Some((infrastructure, _)) =
+        crate::collection::CollectionDiscovery::find_collection(&*ctx.fs)?
+    {
+        for e in
+            register_external_executors(&*ctx.executor_registry, &infrastructure.spec.executors)
+        {
+            ctx.output.warning(&format!(
+                "Skipping external executor in .pmp.infrastructure.yaml: {:#}",
+                e
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::DefaultExecutorRegistry;
+    use std::collections::HashMap;
+
+    fn external_config(name: &str, script: &str) -> ExternalExecutorConfig {
+        ExternalExecutorConfig {
+            name: name.to_string(),
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), script.to_string(), "executor".to_string()],
+            env: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_register_external_executors() {
+        let registry = DefaultExecutorRegistry::with_defaults();
+        let errors = register_external_executors(&registry, &[external_config("pulumi", "exit 0")]);
+
+        assert!(errors.is_empty());
+
+        assert!(registry.has("pulumi"));
+        assert!(registry.has("opentofu"));
+        assert_eq!(registry.get("pulumi").unwrap().get_name(), "pulumi");
+    }
+
+    #[test]
+    fn test_register_external_executor_rejects_builtin_name() {
+        let registry = DefaultExecutorRegistry::with_defaults();
+        let errors =
+            register_external_executors(&registry, &[external_config("opentofu", "exit 0")]);
+
+        assert_eq!(errors.len(), 1);
+        assert!(
+            errors[0]
+                .to_string()
+                .contains("conflicts with a built-in executor")
+        );
+    }
+
+    #[test]
+    fn test_register_external_executor_requires_command() {
+        let registry = DefaultExecutorRegistry::new();
+        let mut config = external_config("helmfile", "exit 0");
+        config.command = String::new();
+
+        assert_eq!(register_external_executors(&registry, &[config]).len(), 1);
+        assert!(!registry.has("helmfile"));
+    }
+
+    #[test]
+    fn test_register_external_executors_skips_invalid_entries() {
+        let registry = DefaultExecutorRegistry::with_defaults();
+        let mut invalid = external_config("helmfile", "exit 0");
+        invalid.command = String::new();
+
+        let errors =
+            register_external_executors(&registry, &[invalid, external_config("pulumi", "exit 0")]);
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].to_string().contains("'helmfile'"));
+        assert!(!registry.has("helmfile"));
+        assert!(registry.has("pulumi"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_external_executor_receives_request_on_stdin() {
+        // $1 is the operation; echo the operation and the JSON request back
+        let executor = ExternalExecutor::new(external_config(
+            "pulumi",
+            "echo \"op=$1 proto=$PMP_EXECUTOR_PROTOCOL\"; cat; exit 2",
+        ));
+
+        let output = executor
+            .plan_with_output("/tmp", &["--diff".to_string()])
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        assert_eq!(output.status.code(), Some(2));
+        assert!(stdout.contains("op=plan-output proto=1"));
+
+        let request_line = stdout.lines().nth(1).unwrap();
+        let request: serde_json::Value = serde_json::from_str(request_line).unwrap();
+        assert_eq!(request["operation"], "plan-output");
+        assert_eq!(request["executor"], "pulumi");
+        assert_eq!(request["extra_args"][0], "--diff");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_external_executor_operation_failure() {
+        let executor = ExternalExecutor::new(external_config("pulumi", "cat > /dev/null; exit 3"));
+
+        let result = executor.apply(&ExecutorConfig::default(), "/tmp", &[]);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("apply failed"));
+        assert!(!executor.check_installed().unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_external_executor_check_installed() {
+        let installed = ExternalExecutor::new(external_config("ok", "exit 0"));
+        assert!(installed.check_installed().unwrap());
+
+        let missing = ExternalExecutor::new(ExternalExecutorConfig {
+            name: "missing".to_string(),
+            command: "pmp-nonexistent-executor-binary".to_string(),
+            args: vec![],
+            env: HashMap::new(),
+        });
+        assert!(!missing.check_installed().unwrap());
+    }
+}
@@ -1,13 +1,17 @@
 #[allow(clippy::module_inception)]
 pub mod executor;
+pub mod external;
 pub mod none;
 pub mod opentofu;
 pub mod registry;
+pub mod terraform;
 
-pub use executor::{Executor, ExecutorConfig, ProjectMetadata};
+pub use executor::{
+    DEFAULT_PLAN_FILE, DEFAULT_PLAN_JSON_FILE, Executor, ExecutorConfig, ProjectMetadata,
+};
+pub use external::register_infrastructure_executors;
 pub use none::NoneExecutor;
 pub use opentofu::OpenTofuExecutor;
+pub use terraform::TerraformExecutor;
 
-// Registry types available for future use (Phase 3: dependency injection)
-#[allow(unused_imports)]
 pub use registry::{DefaultExecutorRegistry, ExecutorRegistry};
@@ -143,6 +143,7 @@ mod tests {
             refresh_command: None,
             test_command: None,
             command_options: std::collections::HashMap::new(),
+            plan_file: None,
         };
         assert!(executor.plan(&config, "/tmp", &[]).is_ok());
     }
@@ -157,6 +158,7 @@ mod tests {
             refresh_command: None,
             test_command: None,
             command_options: std::collections::HashMap::new(),
+            plan_file: None,
         };
         assert!(executor.apply(&config, "/tmp", &[]).is_ok());
     }
@@ -171,6 +173,7 @@ mod tests {
             refresh_command: None,
             test_command: None,
             command_options: std::collections::HashMap::new(),
+            plan_file: None,
         };
         assert!(executor.destroy(&config, "/tmp", &[]).is_ok());
     }
@@ -185,6 +188,7 @@ mod tests {
             refresh_command: None,
             test_command: None,
             command_options: std::collections::HashMap::new(),
+            plan_file: None,
         };
         assert!(executor.refresh(&config, "/tmp", &[]).is_ok());
     }
@@ -199,6 +203,7 @@ mod tests {
             refresh_command: None,
             test_command: None,
             command_options: std::collections::HashMap::new(),
+            plan_file: None,
         };
         assert!(executor.test(&config, "/tmp", &[]).is_ok());
     }
@@ -1,4 +1,4 @@
-use super::executor::{Executor, ExecutorConfig, ProjectMetadata};
+use super::executor::{Executor, ExecutorConfig, ProjectMetadata, SavedPlan};
 use crate::template::metadata::AddedPlugin;
 use anyhow::{Context, Result};
 use serde_json::Value;
@@ -1052,86 +1052,254 @@ lazy_static::lazy_static! {
 }
 static HANDLER_INIT: Once = Once::new();
 
-/// OpenTofu executor implementation
-pub struct OpenTofuExecutor;
+/// Initialize the CTRL+C handler (only runs once per process)
+fn init_signal_handler() {
+    HANDLER_INIT.call_once(|| {
+        let _ = ctrlc::set_handler(move || {
+            INTERRUPTED.store(true, Ordering::SeqCst);
+            if let Ok(mut child_guard) = CHILD_PROCESS.lock()
+                && let Some(child) = child_guard.as_mut()
+            {
+                // Kill the child process
+                let _ = child.kill();
+            }
+            std::process::exit(130); // Standard exit code for SIGINT
+        });
+    });
+}
 
-impl OpenTofuExecutor {
-    pub fn new() -> Self {
-        Self
+/// Execute a command with proper signal handling to kill child processes on CTRL+C
+pub(crate) fn execute_with_signal_handling(
+    command: &str,
+    args: &[&str],
+    working_dir: &str,
+) -> Result<()> {
+    // Initialize handler if not already done
+    init_signal_handler();
+
+    // Reset interrupted flag for this execution
+    INTERRUPTED.store(false, Ordering::SeqCst);
+
+    // Spawn the child process
+    let child = Command::new(command)
+        .args(args)
+        .current_dir(working_dir)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .context("Failed to spawn child process")?;
+
+    // Store the child process handle
+    {
+        let mut child_guard = CHILD_PROCESS.lock().unwrap();
+        *child_guard = Some(child);
     }
 
-    /// Initialize the CTRL+C handler (only runs once per process)
-    fn init_signal_handler() {
-        HANDLER_INIT.call_once(|| {
-            let _ = ctrlc::set_handler(move || {
-                INTERRUPTED.store(true, Ordering::SeqCst);
-                if let Ok(mut child_guard) = CHILD_PROCESS.lock()
-                    && let Some(child) = child_guard.as_mut()
-                {
-                    // Kill the child process
-                    let _ = child.kill();
-                }
-                std::process::exit(130); // Standard exit code for SIGINT
-            });
-        });
+    // Wait for the child to complete
+    let status = {
+        let mut child_guard = CHILD_PROCESS.lock().unwrap();
+        if let Some(ref mut c) = *child_guard {
+            c.wait().context("Failed to wait for child process")?
+        } else {
+            anyhow::bail!("Child process handle lost");
+        }
+    };
+
+    // Clear the child process handle
+    {
+        let mut child_guard = CHILD_PROCESS.lock().unwrap();
+        *child_guard = None;
     }
 
-    /// Execute a command with proper signal handling to kill child processes on CTRL+C
-    fn execute_with_signal_handling(
-        &self,
-        command: &str,
-        args: &[&str],
-        working_dir: &str,
-    ) -> Result<()> {
-        // Initialize handler if not already done
-        Self::init_signal_handler();
+    // Check if we were interrupted
+    if INTERRUPTED.load(Ordering::SeqCst) {
+        anyhow::bail!("Command interrupted by user");
+    }
 
-        // Reset interrupted flag for this execution
-        INTERRUPTED.store(false, Ordering::SeqCst);
+    // Check exit status
+    if !status.success() {
+        anyhow::bail!("Command failed with exit code: {:?}", status.code());
+    }
 
-        // Spawn the child process
-        let child = Command::new(command)
-            .args(args)
-            .current_dir(working_dir)
-            .stdin(Stdio::inherit())
-            .stdout(Stdio::inherit())
-            .stderr(Stdio::inherit())
-            .spawn()
-            .context("Failed to spawn child process")?;
+    Ok(())
+}
 
-        // Store the child process handle
-        {
-            let mut child_guard = CHILD_PROCESS.lock().unwrap();
-            *child_guard = Some(child);
-        }
+/// Run `<binary> plan -out=<plan_file>` and render the saved plan with `<binary> show -json`
+/// Shared by the OpenTofu and Terraform executors, which use the same plan format
+pub(crate) fn save_plan_as_json(
+    binary: &str,
+    working_dir: &str,
+    plan_file: &str,
+    extra_args: &[String],
+) -> Result<SavedPlan> {
+    let out_arg = format!("-out={}", plan_file);
+    let mut args = vec![
+        "plan",
+        "-detailed-exitcode",
+        "-no-color",
+        "-input=false",
+        out_arg.as_str(),
+    ];
+    args.extend(extra_args.iter().map(|s| s.as_str()));
 
-        // Wait for the child to complete
-        let status = {
-            let mut child_guard = CHILD_PROCESS.lock().unwrap();
-            if let Some(ref mut c) = *child_guard {
-                c.wait().context("Failed to wait for child process")?
-            } else {
-                anyhow::bail!("Child process handle lost");
-            }
-        };
+    let output = Command::new(binary)
+        .args(&args)
+        .current_dir(working_dir)
+        .output()
+        .with_context(|| format!("Failed to execute {} plan command", binary))?;
 
-        // Clear the child process handle
-        {
-            let mut child_guard = CHILD_PROCESS.lock().unwrap();
-            *child_guard = None;
-        }
+    // Any exit code other than 0 (no changes) or 2 (changes) means no plan was saved
+    if !matches!(output.status.code(), Some(0) | Some(2)) {
+        return Ok(SavedPlan { output, json: None });
+    }
 
-        // Check if we were interrupted
-        if INTERRUPTED.load(Ordering::SeqCst) {
-            anyhow::bail!("Command interrupted by user");
-        }
+    Ok(SavedPlan {
+        output,
+        json: Some(show_plan_as_json(binary, working_dir, plan_file)?),
+    })
+}
 
-        // Check exit status
-        if !status.success() {
-            anyhow::bail!("Command failed with exit code: {:?}", status.code());
-        }
+/// Render a saved plan with `<binary> show -json <plan_file>`
+pub(crate) fn show_plan_as_json(
+    binary: &str,
+    working_dir: &str,
+    plan_file: &str,
+) -> Result<String> {
+    let show = Command::new(binary)
+        .args(["show", "-json", plan_file])
+        .current_dir(working_dir)
+        .output()
+        .with_context(|| format!("Failed to execute {} show command", binary))?;
 
-        Ok(())
+    if !show.status.success() {
+        anyhow::bail!(
+            "Failed to render saved plan as JSON: {}",
+            String::from_utf8_lossy(&show.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&show.stdout).into_owned())
+}
+
+/// Run `<binary> state pull` and return the state JSON (empty when no state exists)
+/// Shared by the OpenTofu and Terraform executors
+pub(crate) fn pull_state(binary: &str, working_dir: &str) -> Result<String> {
+    let output = Command::new(binary)
+        .args(["state", "pull"])
+        .current_dir(working_dir)
+        .output()
+        .with_context(|| format!("Failed to execute {} state pull command", binary))?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "State pull failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Run `<binary> state push <state_file>` to upload a state to the configured backend
+/// Shared by the OpenTofu and Terraform executors
+pub(crate) fn push_state(
+    binary: &str,
+    working_dir: &str,
+    state_file: &str,
+    force: bool,
+) -> Result<()> {
+    let mut args = vec!["state", "push"];
+    if force {
+        args.push("-force");
+    }
+    args.push(state_file);
+
+    let output = Command::new(binary)
+        .args(&args)
+        .current_dir(working_dir)
+        .output()
+        .with_context(|| format!("Failed to execute {} state push command", binary))?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "State push failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(())
+}
+
+/// Run `<binary> state mv <source> <destination>`
+/// Shared by the OpenTofu and Terraform executors
+pub(crate) fn move_state(
+    binary: &str,
+    working_dir: &str,
+    source: &str,
+    destination: &str,
+) -> Result<()> {
+    let output = Command::new(binary)
+        .args(["state", "mv", source, destination])
+        .current_dir(working_dir)
+        .output()
+        .with_context(|| format!("Failed to execute {} state mv command", binary))?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "State mv failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(())
+}
+
+/// Run `<binary> state rm <addresses...>`
+/// Shared by the OpenTofu and Terraform executors
+pub(crate) fn remove_state(binary: &str, working_dir: &str, addresses: &[String]) -> Result<()> {
+    let output = Command::new(binary)
+        .args(["state", "rm"])
+        .args(addresses)
+        .current_dir(working_dir)
+        .output()
+        .with_context(|| format!("Failed to execute {} state rm command", binary))?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "State rm failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(())
+}
+
+/// Run `<binary> init -migrate-state` non-interactively to move state to a new backend
+/// Shared by the OpenTofu and Terraform executors
+pub(crate) fn migrate_state(binary: &str, working_dir: &str) -> Result<()> {
+    let output = Command::new(binary)
+        .args(["init", "-migrate-state", "-force-copy", "-input=false"])
+        .current_dir(working_dir)
+        .output()
+        .with_context(|| format!("Failed to execute {} init -migrate-state command", binary))?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "State migration failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(())
+}
+
+/// OpenTofu executor implementation
+pub struct OpenTofuExecutor;
+
+impl OpenTofuExecutor {
+    pub fn new() -> Self {
+        Self
     }
 }
 
@@ -1184,11 +1352,17 @@ impl Executor for OpenTofuExecutor {
             }
         }
 
+        // Save the plan to a file when requested
+        let out_arg = config.plan_file.as_ref().map(|f| format!("-out={}", f));
+        if let Some(out_arg) = &out_arg {
+            all_args.push(out_arg.as_str());
+        }
+
         let extra_args_refs: Vec<&str> = extra_args.iter().map(|s| s.as_str()).collect();
         all_args.extend(extra_args_refs);
 
         // Execute with signal handling
-        self.execute_with_signal_handling(parts[0], &all_args, working_dir)?;
+        execute_with_signal_handling(parts[0], &all_args, working_dir)?;
 
         Ok(())
     }
@@ -1215,7 +1389,10 @@ impl Executor for OpenTofuExecutor {
         let mut all_args: Vec<&str> = parts[1..].to_vec();
 
         // Add command-specific options from template configuration
-        if let Some(options) = config.command_options.get("apply") {
+        // Planning options are baked into a saved plan, so they are skipped when applying one
+        if config.plan_file.is_none()
+            && let Some(options) = config.command_options.get("apply")
+        {
             for opt in options {
                 all_args.push(opt.as_str());
             }
@@ -1224,8 +1401,13 @@ impl Executor for OpenTofuExecutor {
         let extra_args_refs: Vec<&str> = extra_args.iter().map(|s| s.as_str()).collect();
         all_args.extend(extra_args_refs);
 
+        // Apply exactly the saved plan when provided (must be the last argument)
+        if let Some(plan_file) = &config.plan_file {
+            all_args.push(plan_file.as_str());
+        }
+
         // Execute with signal handling
-        self.execute_with_signal_handling(parts[0], &all_args, working_dir)?;
+        execute_with_signal_handling(parts[0], &all_args, working_dir)?;
 
         Ok(())
     }
@@ -1262,7 +1444,7 @@ impl Executor for OpenTofuExecutor {
         all_args.extend(extra_args_refs);
 
         // Execute with signal handling
-        self.execute_with_signal_handling(parts[0], &all_args, working_dir)?;
+        execute_with_signal_handling(parts[0], &all_args, working_dir)?;
 
         Ok(())
     }
@@ -1315,7 +1497,7 @@ impl Executor for OpenTofuExecutor {
         all_args.extend(extra_args_refs);
 
         // Execute with signal handling
-        self.execute_with_signal_handling(parts[0], &all_args, working_dir)?;
+        execute_with_signal_handling(parts[0], &all_args, working_dir)?;
 
         Ok(())
     }
@@ -1356,7 +1538,7 @@ impl Executor for OpenTofuExecutor {
         all_args.extend(extra_args_refs);
 
         // Execute with signal handling
-        self.execute_with_signal_handling(parts[0], &all_args, working_dir)?;
+        execute_with_signal_handling(parts[0], &all_args, working_dir)?;
 
         Ok(())
     }
@@ -1482,6 +1664,39 @@ impl Executor for OpenTofuExecutor {
 
         Ok(output)
     }
+
+    fn save_plan(
+        &self,
+        working_dir: &str,
+        plan_file: &str,
+        extra_args: &[String],
+    ) -> Result<SavedPlan> {
+        save_plan_as_json("tofu", working_dir, plan_file, extra_args)
+    }
+
+    fn show_plan_json(&self, working_dir: &str, plan_file: &str) -> Result<Option<String>> {
+        show_plan_as_json("tofu", working_dir, plan_file).map(Some)
+    }
+
+    fn state_pull(&self, working_dir: &str) -> Result<String> {
+        pull_state("tofu", working_dir)
+    }
+
+    fn state_push(&self, working_dir: &str, state_file: &str, force: bool) -> Result<()> {
+        push_state("tofu", working_dir, state_file, force)
+    }
+
+    fn state_mv(&self, working_dir: &str, source: &str, destination: &str) -> Result<()> {
+        move_state("tofu", working_dir, source, destination)
+    }
+
+    fn state_rm(&self, working_dir: &str, addresses: &[String]) -> Result<()> {
+        remove_state("tofu", working_dir, addresses)
+    }
+
+    fn migrate_state(&self, working_dir: &str) -> Result<()> {
+        migrate_state("tofu", working_dir)
+    }
 }
 
 impl Default for OpenTofuExecutor {
@@ -6,8 +6,8 @@ use std::sync::{Arc, RwLock};
 /// Trait for executor registry that manages available executors
 #[allow(dead_code)]
 pub trait ExecutorRegistry: Send + Sync {
-    /// Register an executor with the given name
-    fn register(&mut self, name: String, executor: Box<dyn Executor>);
+    /// Register an executor with the given name (replaces any executor with the same name)
+    fn register(&self, name: String, executor: Box<dyn Executor>);
 
     /// Get an executor by name
     fn get(&self, name: &str) -> Result<Arc<dyn Executor>>;
@@ -34,20 +34,28 @@ impl DefaultExecutorRegistry {
         }
     }
 
-    /// Create a new registry with default executors (OpenTofu)
+    /// Create a new registry with default executors (OpenTofu, Terraform, None)
     #[allow(dead_code)]
     pub fn with_defaults() -> Self {
-        let mut registry = Self::new();
+        let registry = Self::new();
         registry.register(
             "opentofu".to_string(),
             Box::new(crate::executor::OpenTofuExecutor::new()),
         );
+        registry.register(
+            "terraform".to_string(),
+            Box::new(crate::executor::TerraformExecutor::new()),
+        );
+        registry.register(
+            "none".to_string(),
+            Box::new(crate::executor::NoneExecutor::new()),
+        );
         registry
     }
 }
 
 impl ExecutorRegistry for DefaultExecutorRegistry {
-    fn register(&mut self, name: String, executor: Box<dyn Executor>) {
+    fn register(&self, name: String, executor: Box<dyn Executor>) {
         let mut executors = self.executors.write().unwrap();
         executors.insert(name, Arc::from(executor));
     }
@@ -189,7 +197,7 @@ mod tests {
 
     #[test]
     fn test_register_and_get_executor() {
-        let mut registry = DefaultExecutorRegistry::new();
+        let registry = DefaultExecutorRegistry::new();
         registry.register("test".to_string(), Box::new(MockExecutor::new("test")));
 
         let executor = registry.get("test").unwrap();
@@ -208,7 +216,7 @@ mod tests {
 
     #[test]
     fn test_has_executor() {
-        let mut registry = DefaultExecutorRegistry::new();
+        let registry = DefaultExecutorRegistry::new();
         assert!(!registry.has("test"));
 
         registry.register("test".to_string(), Box::new(MockExecutor::new("test")));
@@ -217,7 +225,7 @@ mod tests {
 
     #[test]
     fn test_list_executors() {
-        let mut registry = DefaultExecutorRegistry::new();
+        let registry = DefaultExecutorRegistry::new();
         registry.register("test1".to_string(), Box::new(MockExecutor::new("test1")));
         registry.register("test2".to_string(), Box::new(MockExecutor::new("test2")));
 
@@ -235,6 +243,18 @@ mod tests {
         assert_eq!(executor.get_name(), "opentofu");
     }
 
+    #[test]
+    fn test_with_defaults_includes_terraform_and_none() {
+        let registry = DefaultExecutorRegistry::with_defaults();
+
+        let terraform = registry.get("terraform").unwrap();
+        assert_eq!(terraform.get_name(), "terraform");
+        assert_eq!(terraform.default_plan_command(), "terraform plan");
+
+        let none = registry.get("none").unwrap();
+        assert_eq!(none.get_name(), "none");
+    }
+
     #[test]
     fn test_default_includes_opentofu() {
         let registry = DefaultExecutorRegistry::default();
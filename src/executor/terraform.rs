@@ -0,0 +1,346 @@
+use super::executor::{Executor, ExecutorConfig, ProjectMetadata, SavedPlan};
+use super::opentofu::{
+    OpenTofuExecutor, execute_with_signal_handling, migrate_state, move_state, pull_state,
+    push_state, remove_state, save_plan_as_json, show_plan_as_json,
+};
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// HashiCorp Terraform executor implementation
+///
+/// Terraform and OpenTofu share the same HCL language, backend blocks and CLI surface,
+/// so `_common.tf` generation is delegated to the OpenTofu implementation and only the
+/// binary name differs.
+pub struct TerraformExecutor;
+
+impl TerraformExecutor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Run a configured (or default) command with template options and extra args appended
+    fn run_command(
+        &self,
+        command: &str,
+        command_key: &str,
+        config: &ExecutorConfig,
+        working_dir: &str,
+        extra_args: &[String],
+    ) -> Result<()> {
+        // Parse the command string into command and args
+        let parts: Vec<&str> = command.split_whitespace().collect();
+
+        if parts.is_empty() {
+            anyhow::bail!("Empty command provided");
+        }
+
+        // Combine command args with template command options and extra args
+        let mut all_args: Vec<&str> = parts[1..].to_vec();
+
+        // Add command-specific options from template configuration
+        // Planning options are baked into a saved plan, so they are skipped when applying one
+        if !(command_key == "apply" && config.plan_file.is_some())
+            && let Some(options) = config.command_options.get(command_key)
+        {
+            all_args.extend(options.iter().map(|s| s.as_str()));
+        }
+
+        // Save the plan to a file when requested
+        let out_arg = config.plan_file.as_ref().map(|f| format!("-out={}", f));
+        if command_key == "plan"
+            && let Some(out_arg) = &out_arg
+        {
+            all_args.push(out_arg.as_str());
+        }
+
+        all_args.extend(extra_args.iter().map(|s| s.as_str()));
+
+        // Apply exactly the saved plan when provided (must be the last argument)
+        if command_key == "apply"
+            && let Some(plan_file) = &config.plan_file
+        {
+            all_args.push(plan_file.as_str());
+        }
+
+        // Execute with signal handling
+        execute_with_signal_handling(parts[0], &all_args, working_dir)
+    }
+}
+
+impl Executor for TerraformExecutor {
+    fn check_installed(&self) -> Result<bool> {
+        // Try to run 'terraform -version' to check if Terraform is installed
+        let result = Command::new("terraform").arg("-version").output();
+
+        match result {
+            Ok(output) => Ok(output.status.success()),
+            Err(_) => Ok(false), // Command not found or failed to execute
+        }
+    }
+
+    fn init(&self, working_dir: &str) -> Result<Output> {
+        let output = Command::new("terraform")
+            .arg("init")
+            .arg("-input=false")
+            .current_dir(working_dir)
+            .output()
+            .context("Failed to execute terraform init command")?;
+
+        Ok(output)
+    }
+
+    fn plan(
+        &self,
+        config: &ExecutorConfig,
+        working_dir: &str,
+        extra_args: &[String],
+    ) -> Result<()> {
+        let command = config
+            .plan_command
+            .as_deref()
+            .unwrap_or(self.default_plan_command());
+
+        self.run_command(command, "plan", config, working_dir, extra_args)
+    }
+
+    fn apply(
+        &self,
+        config: &ExecutorConfig,
+        working_dir: &str,
+        extra_args: &[String],
+    ) -> Result<()> {
+        let command = config
+            .apply_command
+            .as_deref()
+            .unwrap_or(self.default_apply_command());
+
+        self.run_command(command, "apply", config, working_dir, extra_args)
+    }
+
+    fn destroy(
+        &self,
+        config: &ExecutorConfig,
+        working_dir: &str,
+        extra_args: &[String],
+    ) -> Result<()> {
+        let command = config
+            .destroy_command
+            .as_deref()
+            .unwrap_or(self.default_destroy_command());
+
+        self.run_command(command, "destroy", config, working_dir, extra_args)
+    }
+
+    fn refresh(
+        &self,
+        config: &ExecutorConfig,
+        working_dir: &str,
+        extra_args: &[String],
+    ) -> Result<()> {
+        let command = config
+            .refresh_command
+            .as_deref()
+            .unwrap_or(self.default_refresh_command());
+
+        self.run_command(command, "refresh", config, working_dir, extra_args)
+    }
+
+    fn test(
+        &self,
+        config: &ExecutorConfig,
+        working_dir: &str,
+        extra_args: &[String],
+    ) -> Result<()> {
+        let command = config
+            .test_command
+            .as_deref()
+            .unwrap_or(self.default_test_command());
+
+        self.run_command(command, "test", config, working_dir, extra_args)
+    }
+
+    fn plan_with_output(&self, working_dir: &str, extra_args: &[String]) -> Result<Output> {
+        let mut args = vec!["plan", "-detailed-exitcode", "-no-color", "-input=false"];
+        args.extend(extra_args.iter().map(|s| s.as_str()));
+
+        let output = Command::new("terraform")
+            .args(&args)
+            .current_dir(working_dir)
+            .output()
+            .context("Failed to execute terraform plan command")?;
+
+        Ok(output)
+    }
+
+    fn save_plan(
+        &self,
+        working_dir: &str,
+        plan_file: &str,
+        extra_args: &[String],
+    ) -> Result<SavedPlan> {
+        save_plan_as_json("terraform", working_dir, plan_file, extra_args)
+    }
+
+    fn show_plan_json(&self, working_dir: &str, plan_file: &str) -> Result<Option<String>> {
+        show_plan_as_json("terraform", working_dir, plan_file).map(Some)
+    }
+
+    fn state_pull(&self, working_dir: &str) -> Result<String> {
+        pull_state("terraform", working_dir)
+    }
+
+    fn state_push(&self, working_dir: &str, state_file: &str, force: bool) -> Result<()> {
+        push_state("terraform", working_dir, state_file, force)
+    }
+
+    fn state_mv(&self, working_dir: &str, source: &str, destination: &str) -> Result<()> {
+        move_state("terraform", working_dir, source, destination)
+    }
+
+    fn state_rm(&self, working_dir: &str, addresses: &[String]) -> Result<()> {
+        remove_state("terraform", working_dir, addresses)
+    }
+
+    fn migrate_state(&self, working_dir: &str) -> Result<()> {
+        migrate_state("terraform", working_dir)
+    }
+
+    fn get_name(&self) -> &str {
+        "terraform"
+    }
+
+    fn default_plan_command(&self) -> &str {
+        "terraform plan"
+    }
+
+    fn default_apply_command(&self) -> &str {
+        "terraform apply"
+    }
+
+    fn default_destroy_command(&self) -> &str {
+        "terraform destroy"
+    }
+
+    fn default_refresh_command(&self) -> &str {
+        "terraform refresh"
+    }
+
+    fn default_test_command(&self) -> &str {
+        "terraform test"
+    }
+
+    fn generate_common_file(
+        &self,
+        ctx: &crate::context::Context,
+        environment_path: &Path,
+        executor_config: &HashMap<String, serde_json::Value>,
+        project_metadata: &ProjectMetadata,
+        plugins: Option<&[crate::template::metadata::AddedPlugin]>,
+        template_reference_projects: &[crate::template::metadata::TemplateReferenceProject],
+        secrets: &HashMap<String, crate::template::metadata::SecretReference>,
+        secrets_config: Option<&crate::template::metadata::SecretsConfig>,
+    ) -> Result<()> {
+        // Backend, remote state data sources, plugin modules and secrets are plain HCL
+        // understood by both tools, so reuse the OpenTofu generator as-is
+        OpenTofuExecutor::new().generate_common_file(
+            ctx,
+            environment_path,
+            executor_config,
+            project_metadata,
+            plugins,
+            template_reference_projects,
+            secrets,
+            secrets_config,
+        )
+    }
+
+    fn file_extension(&self) -> &str {
+        ".tf"
+    }
+}
+
+impl Default for TerraformExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{FileSystem, MockFileSystem};
+    use serde_json::json;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_terraform_executor_name_and_commands() {
+        let executor = TerraformExecutor::new();
+        assert_eq!(executor.get_name(), "terraform");
+        assert_eq!(executor.default_plan_command(), "terraform plan");
+        assert_eq!(executor.default_apply_command(), "terraform apply");
+        assert_eq!(executor.default_destroy_command(), "terraform destroy");
+        assert_eq!(executor.default_refresh_command(), "terraform refresh");
+        assert_eq!(executor.default_test_command(), "terraform test");
+        assert_eq!(executor.file_extension(), ".tf");
+    }
+
+    #[test]
+    fn test_terraform_executor_empty_command() {
+        let executor = TerraformExecutor::new();
+        let config = ExecutorConfig {
+            plan_command: Some("   ".to_string()),
+            ..Default::default()
+        };
+
+        let result = executor.plan(&config, "/tmp", &[]);
+        assert!(result.is_err());
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Empty command provided")
+        );
+    }
+
+    #[test]
+    fn test_terraform_generate_common_file_writes_backend() {
+        let fs = Arc::new(MockFileSystem::new());
+        let ctx = crate::context::Context {
+            fs: fs.clone(),
+            ..crate::context::Context::test()
+        };
+
+        let mut executor_config = HashMap::new();
+        executor_config.insert(
+            "backend".to_string(),
+            json!({"type": "s3", "bucket": "state", "key": "{{project_name}}.tfstate"}),
+        );
+
+        let metadata = ProjectMetadata {
+            api_version: "pmp.io/v1",
+            kind: "Network",
+            environment: "dev",
+            project_name: "vpc",
+        };
+
+        let env_path = Path::new("/infra/projects/vpc/environments/dev");
+        TerraformExecutor::new()
+            .generate_common_file(
+                &ctx,
+                env_path,
+                &executor_config,
+                &metadata,
+                None,
+                &[],
+                &HashMap::new(),
+                None,
+            )
+            .unwrap();
+
+        let content = fs.read_to_string(&env_path.join("_common.tf")).unwrap();
+        assert!(content.contains("backend \"s3\" {"));
+        assert!(content.contains("key = \"vpc.tfstate\""));
+    }
+}
@@ -0,0 +1,8 @@
+//! Operation history for PMP.
+//!
+//! Operations started from `pmp ui` are recorded under the infrastructure together with their
+//! full output, so they survive restarts and can be browsed from the UI and `pmp history`.
+
+mod store;
+
+pub use store::{OperationHistory, OperationRecord};
@@ -0,0 +1,448 @@
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::template::metadata::HistoryConfig;
+use crate::traits::FileSystem;
+
+/// Default history directory, relative to the infrastructure root
+pub const DEFAULT_HISTORY_DIR: &str = ".pmp/history";
+
+/// A recorded operation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OperationRecord {
+    pub id: String,
+
+    /// Who ran the operation (user@host)
+    pub user: String,
+
+    /// Operation name (e.g., "preview", "apply", "destroy")
+    pub operation: String,
+
+    /// Path the operation ran on
+    pub path: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<String>,
+
+    pub started_at: DateTime<Utc>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+
+    /// Unset while the operation is running
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub success: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl OperationRecord {
+    pub fn new(operation: &str, path: &str) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            user: crate::locking::current_owner(),
+            operation: operation.to_string(),
+            path: path.to_string(),
+            project: None,
+            environment: None,
+            started_at: Utc::now(),
+            finished_at: None,
+            success: None,
+            error: None,
+        }
+    }
+
+    /// Mark the operation as finished, failed when an error is given
+    pub fn finish(&mut self, error: Option<String>) {
+        self.finished_at = Some(Utc::now());
+        self.success = Some(error.is_none());
+        self.error = error;
+    }
+
+    /// Mark an operation that was left running as failed, without a finish time
+    pub fn interrupt(&mut self) {
+        self.success = Some(false);
+        self.error = Some("Interrupted before the operation finished".to_string());
+    }
+
+    /// "running", "completed" or "failed"
+    pub fn status(&self) -> &'static str {
+        match self.success {
+            None => "running",
+            Some(true) => "completed",
+            Some(false) => "failed",
+        }
+    }
+
+    /// Project and environment the operation ran on, or its path when unknown
+    pub fn target(&self) -> String {
+        match (&self.project, &self.environment) {
+            (Some(project), Some(environment)) => format!("{}/{}", project, environment),
+            (Some(project), None) => project.clone(),
+            _ => self.path.clone(),
+        }
+    }
+}
+
+/// Operation history, stored as `<id>.json` records next to `<id>.log` output files
+pub struct OperationHistory<'a> {
+    fs: &'a dyn FileSystem,
+    dir: PathBuf,
+}
+
+impl<'a> OperationHistory<'a> {
+    pub fn new(fs: &'a dyn FileSystem, dir: PathBuf) -> Self {
+        Self { fs, dir }
+    }
+
+    /// Operation history of an infrastructure
+    pub fn for_infrastructure(
+        fs: &'a dyn FileSystem,
+        infrastructure_root: &Path,
+        config: &HistoryConfig,
+    ) -> Self {
+        Self::new(
+            fs,
+            infrastructure_root.join(config.path.as_deref().unwrap_or(DEFAULT_HISTORY_DIR)),
+        )
+    }
+
+    fn record_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", id))
+    }
+
+    fn log_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.log", id))
+    }
+
+    /// Save a record, replacing the previous version of it
+    pub fn save(&self, record: &OperationRecord) -> Result<()> {
+        self.fs.write(
+            &self.record_path(&record.id),
+            &serde_json::to_string_pretty(record)?,
+        )
+    }
+
+    /// Save the output of an operation
+    pub fn save_log(&self, id: &str, log: &str) -> Result<()> {
+        self.fs.write(&self.log_path(id), log)
+    }
+
+    /// Append output of an operation while it runs
+    pub fn append_log(&self, id: &str, output: &str) -> Result<()> {
+        self.fs.append(&self.log_path(id), output)
+    }
+
+    /// Mark the operations still recorded as running as interrupted, returning them
+    ///
+    /// Meant for startup, when no operation of a previous session can still be running.
+    pub fn interrupt_running(&self) -> Result<Vec<OperationRecord>> {
+        let mut interrupted = Vec::new();
+
+        for mut record in self.list()? {
+            if record.success.is_none() {
+                record.interrupt();
+                self.save(&record)?;
+                interrupted.push(record);
+            }
+        }
+
+        Ok(interrupted)
+    }
+
+    /// Recorded operations, newest first, skipping records that can't be read
+    pub fn list(&self) -> Result<Vec<OperationRecord>> {
+        Ok(self.list_with_warnings()?.0)
+    }
+
+    /// Recorded operations, newest first, and a warning for each record that can't be read
+    pub fn list_with_warnings(&self) -> Result<(Vec<OperationRecord>, Vec<String>)> {
+        if !self.fs.exists(&self.dir) {
+            return Ok((Vec::new(), Vec::new()));
+        }
+
+        let mut records = Vec::new();
+        let mut warnings = Vec::new();
+
+        for path in self.fs.read_dir(&self.dir)? {
+            if !self.fs.is_file(&path) || path.extension().and_then(|e| e.to_str()) != Some("json")
+            {
+                continue;
+            }
+
+            let record = self.fs.read_to_string(&path).and_then(|content| {
+                serde_json::from_str::<OperationRecord>(&content).map_err(Into::into)
+            });
+            match record {
+                Ok(record) => records.push(record),
+                Err(e) => warnings.push(format!(
+                    "Skipping unreadable operation record {}: {:#}",
+                    path.display(),
+                    e
+                )),
+            }
+        }
+
+        records.sort_by(|a, b| b.started_at.cmp(&a.started_at).then(b.id.cmp(&a.id)));
+
+        Ok((records, warnings))
+    }
+
+    /// Find an operation by its id or a unique prefix of it
+    pub fn get(&self, id: &str) -> Result<OperationRecord> {
+        let mut matches: Vec<_> = self
+            .list()?
+            .into_iter()
+            .filter(|r| r.id.starts_with(id))
+            .collect();
+
+        if let Some(index) = matches.iter().position(|r| r.id == id) {
+            return Ok(matches.swap_remove(index));
+        }
+
+        match matches.len() {
+            0 => bail!("Operation not found: {}", id),
+            1 => Ok(matches.remove(0)),
+            n => bail!(
+                "Operation id '{}' is ambiguous ({} operations match)",
+                id,
+                n
+            ),
+        }
+    }
+
+    /// Output of an operation, if it was saved
+    pub fn log(&self, id: &str) -> Result<Option<String>> {
+        let record = self.get(id)?;
+        let path = self.log_path(&record.id);
+
+        if !self.fs.exists(&path) {
+            return Ok(None);
+        }
+
+        self.fs.read_to_string(&path).map(Some)
+    }
+
+    /// Apply the retention rules, returning the deleted operations
+    ///
+    /// Running operations only expire by age, and the newest finished operation is always kept.
+    pub fn prune(&self, config: &HistoryConfig) -> Result<Vec<OperationRecord>> {
+        self.prune_at(config, Utc::now())
+    }
+
+    fn prune_at(&self, config: &HistoryConfig, now: DateTime<Utc>) -> Result<Vec<OperationRecord>> {
+        let max_age = config
+            .max_age_days
+            .map(|days| chrono::Duration::days(days as i64));
+
+        let mut removed = Vec::new();
+        let mut finished = 0;
+
+        for record in self.list()? {
+            let too_old = max_age.is_some_and(|age| now - record.started_at > age);
+
+            let expired = if record.success.is_some() {
+                let index = finished;
+                finished += 1;
+                let over_count = config.keep_last.is_some_and(|keep| index >= keep.max(1));
+                index > 0 && (over_count || too_old)
+            } else {
+                too_old
+            };
+
+            if expired {
+                self.fs.remove_file(&self.record_path(&record.id))?;
+
+                let log_path = self.log_path(&record.id);
+                if self.fs.exists(&log_path) {
+                    self.fs.remove_file(&log_path)?;
+                }
+
+                removed.push(record);
+            }
+        }
+
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::MockFileSystem;
+
+    fn record(id: &str, started_at: &str) -> OperationRecord {
+        OperationRecord {
+            id: id.to_string(),
+            started_at: started_at.parse().unwrap(),
+            ..OperationRecord::new("apply", "projects/vpc/environments/dev")
+        }
+    }
+
+    #[test]
+    fn test_save_and_get() {
+        let fs = MockFileSystem::new();
+        let history = OperationHistory::for_infrastructure(
+            &fs,
+            Path::new("/infra"),
+            &HistoryConfig::default(),
+        );
+
+        let mut first = record("a1b2", "2026-01-01T10:00:00Z");
+        first.project = Some("vpc".to_string());
+        first.environment = Some("dev".to_string());
+        history.save(&first).unwrap();
+        history
+            .save(&record("a1c3", "2026-01-02T10:00:00Z"))
+            .unwrap();
+
+        assert_eq!(first.status(), "running");
+        first.finish(Some("exit status 1".to_string()));
+        history.save(&first).unwrap();
+        history
+            .save_log("a1b2", "Running apply...\n✗ failed")
+            .unwrap();
+        assert!(fs.has_file(Path::new("/infra/.pmp/history/a1b2.log")));
+
+        let records = history.list().unwrap();
+        assert_eq!(
+            records.iter().map(|r| r.id.as_str()).collect::<Vec<_>>(),
+            vec!["a1c3", "a1b2"]
+        );
+
+        let found = history.get("a1b").unwrap();
+        assert_eq!(found, first);
+        assert_eq!(found.status(), "failed");
+        assert_eq!(found.target(), "vpc/dev");
+        assert_eq!(
+            history.log("a1b").unwrap().as_deref(),
+            Some("Running apply...\n✗ failed")
+        );
+        assert_eq!(history.log("a1c3").unwrap(), None);
+
+        assert!(
+            history
+                .get("a1")
+                .unwrap_err()
+                .to_string()
+                .contains("ambiguous")
+        );
+        assert!(history.get("ffff").is_err());
+    }
+
+    #[test]
+    fn test_prune() {
+        let fs = MockFileSystem::new();
+        let history = OperationHistory::new(&fs, PathBuf::from("/history"));
+
+        for (id, started_at) in [
+            ("op-1", "2026-01-01T00:00:00Z"),
+            ("op-2", "2026-01-05T00:00:00Z"),
+            ("op-3", "2026-01-09T00:00:00Z"),
+            ("op-4", "2026-01-10T00:00:00Z"),
+        ] {
+            let mut record = record(id, started_at);
+            record.finish(None);
+            history.save(&record).unwrap();
+            history.save_log(id, "output").unwrap();
+        }
+        // Still running, so not counted, but removed once too old
+        history
+            .save(&record("running", "2026-01-08T00:00:00Z"))
+            .unwrap();
+        history
+            .save(&record("stale", "2026-01-02T00:00:00Z"))
+            .unwrap();
+
+        let now: DateTime<Utc> = "2026-01-11T00:00:00Z".parse().unwrap();
+        let config = HistoryConfig {
+            keep_last: Some(3),
+            max_age_days: Some(5),
+            ..HistoryConfig::default()
+        };
+
+        let removed = history.prune_at(&config, now).unwrap();
+        assert_eq!(
+            removed.iter().map(|r| r.id.as_str()).collect::<Vec<_>>(),
+            vec!["op-2", "stale", "op-1"]
+        );
+        assert!(!fs.has_file(Path::new("/history/op-1.log")));
+        assert_eq!(history.list().unwrap().len(), 3);
+
+        // The newest operation is kept however old it is
+        let config = HistoryConfig {
+            keep_last: None,
+            max_age_days: Some(0),
+            ..HistoryConfig::default()
+        };
+        let removed = history.prune_at(&config, now).unwrap();
+        assert_eq!(removed.len(), 2);
+        assert_eq!(
+            history
+                .list()
+                .unwrap()
+                .iter()
+                .map(|r| r.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["op-4"]
+        );
+    }
+
+    #[test]
+    fn test_append_log_and_interrupt_running() {
+        let fs = MockFileSystem::new();
+        let history = OperationHistory::new(&fs, PathBuf::from("/history"));
+
+        let mut done = record("op-1", "2026-01-01T00:00:00Z");
+        done.finish(None);
+        history.save(&done).unwrap();
+        history
+            .save(&record("op-2", "2026-01-02T00:00:00Z"))
+            .unwrap();
+        history.append_log("op-2", "Running apply...\n").unwrap();
+        history.append_log("op-2", "Plan: 1 to add\n").unwrap();
+
+        let interrupted = history.interrupt_running().unwrap();
+        assert_eq!(
+            interrupted
+                .iter()
+                .map(|r| r.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["op-2"]
+        );
+
+        let record = history.get("op-2").unwrap();
+        assert_eq!(record.status(), "failed");
+        assert_eq!(record.finished_at, None);
+        assert!(record.error.unwrap().contains("Interrupted"));
+        assert_eq!(history.get("op-1").unwrap(), done);
+        assert_eq!(
+            history.log("op-2").unwrap().as_deref(),
+            Some("Running apply...\nPlan: 1 to add\n")
+        );
+        assert!(history.interrupt_running().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unreadable_records_are_skipped() {
+        let fs = MockFileSystem::new();
+        let history = OperationHistory::new(&fs, PathBuf::from("/history"));
+
+        history
+            .save(&record("op-1", "2026-01-01T00:00:00Z"))
+            .unwrap();
+        fs.write(Path::new("/history/broken.json"), "{").unwrap();
+
+        let (records, warnings) = history.list_with_warnings().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("broken.json"));
+        assert_eq!(history.get("op-1").unwrap().id, "op-1");
+    }
+}
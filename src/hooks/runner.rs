@@ -1,9 +1,15 @@
 use anyhow::{Context, Result};
-use inquire::{Confirm, Password, Text};
-use std::process::Command;
-
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::diff::PlanSummary;
+use crate::template::DynamicProjectEnvironmentResource;
 use crate::template::metadata::{
-    CommandHookConfig, ConfirmHookConfig, Hook, SetEnvironmentHookConfig,
+    CommandHookConfig, ConfirmHookConfig, Hook, SetEnvironmentHookConfig, WebhookHookConfig,
 };
 
 /// Outcome of running hooks - determines if the command should continue
@@ -15,182 +21,433 @@ pub enum HookOutcome {
     Cancel,
 }
 
+/// What triggered a hook, passed to command hooks and sent to webhooks
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HookEvent {
+    /// Project of the environment the hooks run in
+    pub project_name: Option<String>,
+    /// Environment the hooks run in
+    pub environment: Option<String>,
+    /// Operation, e.g. `apply`
+    pub operation: String,
+    /// `pre`, `post`, `on-failure` or `finally`
+    pub phase: String,
+    /// `success` for post hooks, which only run after successful operations; `failure` for
+    /// on-failure hooks; `success`, `failure` or `cancelled` for finally hooks
+    pub outcome: Option<String>,
+    /// Error of the failed operation, in on-failure and finally hooks
+    pub error: Option<String>,
+    /// Summary of the plan made by the operation, in its post hooks (`preview --diff`)
+    pub plan_summary: Option<PlanSummary>,
+    /// Infrastructure the environment belongs to (not sent to webhooks)
+    #[serde(skip)]
+    pub infrastructure_root: Option<PathBuf>,
+    /// Input values of the environment (not sent to webhooks)
+    #[serde(skip)]
+    pub inputs: HashMap<String, Value>,
+    /// Inputs fetched from a secret manager, never passed to command hooks
+    #[serde(skip)]
+    pub secret_inputs: HashSet<String>,
+}
+
+impl HookEvent {
+    /// Describe the hooks of a `<phase>-<operation>` hook type run in an environment directory
+    pub fn new(ctx: &crate::context::Context, working_dir: &str, hook_type: &str) -> Self {
+        let (phase, operation) = hook_type.rsplit_once('-').unwrap_or(("", hook_type));
+        let dir = Path::new(working_dir);
+
+        let environment = ctx
+            .fs
+            .read_to_string(&dir.join(".pmp.environment.yaml"))
+            .ok()
+            .and_then(|content| {
+                serde_yaml::from_str::<DynamicProjectEnvironmentResource>(&content).ok()
+            });
+
+        let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+        let infrastructure_root = dir
+            .ancestors()
+            .find(|d| ctx.fs.exists(&d.join(".pmp.infrastructure.yaml")))
+            .map(Path::to_path_buf);
+
+        Self {
+            project_name: environment.as_ref().map(|e| e.metadata.name.clone()),
+            environment: environment
+                .as_ref()
+                .map(|e| e.metadata.environment_name.clone()),
+            operation: operation.to_string(),
+            phase: phase.to_string(),
+            outcome: match phase {
+                "post" => Some("success".to_string()),
+                "on-failure" => Some("failure".to_string()),
+                _ => None,
+            },
+            error: None,
+            plan_summary: None,
+            infrastructure_root,
+            secret_inputs: environment
+                .as_ref()
+                .map(|e| e.spec.secrets.keys().cloned().collect())
+                .unwrap_or_default(),
+            inputs: environment.map(|e| e.spec.inputs).unwrap_or_default(),
+        }
+    }
+
+    /// Inputs passed to command hooks, leaving out the secret ones
+    fn command_inputs(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.inputs
+            .iter()
+            .filter(|(name, _)| !self.secret_inputs.contains(*name))
+    }
+
+    /// Environment variables of command hooks, `context_file` holding the event as JSON
+    fn command_env(&self, context_file: &Path) -> Vec<(String, String)> {
+        let mut env = vec![
+            ("PMP_OPERATION".to_string(), self.operation.clone()),
+            ("PMP_PHASE".to_string(), self.phase.clone()),
+            (
+                "PMP_HOOK_CONTEXT".to_string(),
+                context_file.display().to_string(),
+            ),
+        ];
+
+        let optional = [
+            ("PMP_PROJECT", self.project_name.clone()),
+            ("PMP_ENVIRONMENT", self.environment.clone()),
+            ("PMP_OUTCOME", self.outcome.clone()),
+            ("PMP_ERROR", self.error.clone()),
+            (
+                "PMP_INFRASTRUCTURE_ROOT",
+                self.infrastructure_root
+                    .as_ref()
+                    .map(|root| root.display().to_string()),
+            ),
+        ];
+        for (name, value) in optional {
+            if let Some(value) = value {
+                env.push((name.to_string(), value));
+            }
+        }
+
+        for (name, value) in self.command_inputs() {
+            let name: String = name
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() {
+                        c.to_ascii_uppercase()
+                    } else {
+                        '_'
+                    }
+                })
+                .collect();
+            let value = match value {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            env.push((format!("PMP_INPUT_{}", name), value));
+        }
+
+        env
+    }
+
+    /// Write the event, with the inputs, to a temporary JSON file only the user can read
+    ///
+    /// The file is deleted when the returned handle is dropped.
+    fn write_context_file(&self) -> Result<tempfile::NamedTempFile> {
+        let mut context = serde_json::to_value(self)?;
+        context["infrastructure_root"] = serde_json::json!(self.infrastructure_root);
+        context["inputs"] = serde_json::json!(self.command_inputs().collect::<HashMap<_, _>>());
+
+        let mut file = tempfile::Builder::new()
+            .prefix("pmp-hook-")
+            .suffix(".json")
+            .tempfile()
+            .context("Failed to create hook context file")?;
+        file.write_all(serde_json::to_string_pretty(&context)?.as_bytes())
+            .with_context(|| format!("Failed to write hook context: {}", file.path().display()))?;
+
+        Ok(file)
+    }
+
+    /// Data available to webhook body templates
+    fn template_data(&self) -> serde_json::Value {
+        serde_json::json!({
+            "_project_name": self.project_name,
+            "_environment": self.environment,
+            "_operation": self.operation,
+            "_phase": self.phase,
+            "_outcome": self.outcome,
+            "_error": self.error,
+            "_plan_summary": self.plan_summary,
+        })
+    }
+}
+
 /// Executes pre and post hooks for commands
+///
+/// Hooks run through the context: their output goes to `ctx.output` (so it is streamed to the
+/// web UI), confirm and set_environment hooks prompt through `ctx.input` and commands run
+/// through `ctx.command`.
 pub struct HooksRunner;
 
 impl HooksRunner {
     /// Execute a list of hooks in sequence
     /// Returns HookOutcome::Cancel if any hook indicates execution should stop
     /// Returns an error if any hook fails unexpectedly
-    pub fn run_hooks(hooks: &[Hook], working_dir: &str, hook_type: &str) -> Result<HookOutcome> {
+    pub fn run_hooks(
+        ctx: &crate::context::Context,
+        hooks: &[Hook],
+        working_dir: &str,
+        hook_type: &str,
+    ) -> Result<HookOutcome> {
         if hooks.is_empty() {
             return Ok(HookOutcome::Continue);
         }
 
-        println!("Running {} hooks...", hook_type);
+        Self::run_hooks_with_plan(ctx, hooks, working_dir, hook_type, None)
+    }
 
-        for (index, hook) in hooks.iter().enumerate() {
-            match hook {
-                Hook::Command(config) => {
-                    let outcome = Self::run_command_hook(config, working_dir, hook_type, index)?;
+    /// Execute hooks after the operation made a plan, available to them as `_plan_summary`
+    pub fn run_hooks_with_plan(
+        ctx: &crate::context::Context,
+        hooks: &[Hook],
+        working_dir: &str,
+        hook_type: &str,
+        plan_summary: Option<&PlanSummary>,
+    ) -> Result<HookOutcome> {
+        if hooks.is_empty() {
+            return Ok(HookOutcome::Continue);
+        }
 
-                    if outcome == HookOutcome::Cancel {
-                        return Ok(HookOutcome::Cancel);
-                    }
-                }
-                Hook::Confirm(config) => {
-                    let outcome = Self::run_confirm_hook(config, hook_type, index)?;
+        let mut event = HookEvent::new(ctx, working_dir, hook_type);
+        event.plan_summary = plan_summary.cloned();
+        Self::run_event_hooks(ctx, hooks, working_dir, hook_type, event)
+    }
 
-                    if outcome == HookOutcome::Cancel {
-                        return Ok(HookOutcome::Cancel);
-                    }
+    /// Execute the hooks of a finished operation (`on-failure-*` and `finally-*` hook types)
+    ///
+    /// `result` is the operation's result, reported to the hooks as their outcome and error.
+    pub fn run_hooks_after(
+        ctx: &crate::context::Context,
+        hooks: &[Hook],
+        working_dir: &str,
+        hook_type: &str,
+        result: &Result<HookOutcome>,
+    ) -> Result<HookOutcome> {
+        if hooks.is_empty() {
+            return Ok(HookOutcome::Continue);
+        }
+
+        let mut event = HookEvent::new(ctx, working_dir, hook_type);
+        event.outcome = Some(
+            match result {
+                Ok(HookOutcome::Continue) => "success",
+                Ok(HookOutcome::Cancel) => "cancelled",
+                Err(_) => "failure",
+            }
+            .to_string(),
+        );
+        event.error = result.as_ref().err().map(|e| format!("{:#}", e));
+
+        Self::run_event_hooks(ctx, hooks, working_dir, hook_type, event)
+    }
+
+    /// Execute hooks with the event describing what triggered them
+    fn run_event_hooks(
+        ctx: &crate::context::Context,
+        hooks: &[Hook],
+        working_dir: &str,
+        hook_type: &str,
+        event: HookEvent,
+    ) -> Result<HookOutcome> {
+        ctx.output.info(&format!("Running {} hooks...", hook_type));
+
+        for (index, hook) in hooks.iter().enumerate() {
+            let outcome = match hook {
+                Hook::Command(config) => {
+                    Self::run_command_hook(ctx, config, &event, working_dir, hook_type, index)?
                 }
+                Hook::Confirm(config) => Self::run_confirm_hook(ctx, config, hook_type, index)?,
                 Hook::SetEnvironment(config) => {
-                    let outcome = Self::run_set_environment_hook(config, hook_type, index)?;
-
-                    if outcome == HookOutcome::Cancel {
-                        return Ok(HookOutcome::Cancel);
-                    }
+                    Self::run_set_environment_hook(ctx, config, hook_type, index)?
+                }
+                Hook::Webhook(config) => {
+                    Self::run_webhook_hook(ctx, config, &event, hook_type, index)?;
+                    HookOutcome::Continue
                 }
+            };
+
+            if outcome == HookOutcome::Cancel {
+                return Ok(HookOutcome::Cancel);
             }
         }
 
-        println!("{} hooks completed successfully", hook_type);
+        ctx.output
+            .success(&format!("{} hooks completed successfully", hook_type));
         Ok(HookOutcome::Continue)
     }
 
-    /// Execute a command hook
+    /// Execute a command hook, retrying failed attempts
+    ///
+    /// Each line the command prints is passed on to the output as it is printed.
     fn run_command_hook(
+        ctx: &crate::context::Context,
         config: &CommandHookConfig,
+        event: &HookEvent,
         working_dir: &str,
         hook_type: &str,
         index: usize,
     ) -> Result<HookOutcome> {
-        println!("  [{}] Executing: {}", index + 1, config.command);
-
-        let output = Self::execute_command(&config.command, working_dir)
-            .with_context(|| format!("Failed to execute {} hook: {}", hook_type, config.command))?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!(
-                "{} hook failed: {}\nError: {}",
-                hook_type,
-                config.command,
-                stderr
-            );
+        if !config.when.iter().all(|c| c.is_met(&event.inputs)) {
+            ctx.output.dimmed(&format!(
+                "  [{}] Skipping: {} (conditions not met)",
+                index + 1,
+                config.command
+            ));
+            return Ok(HookOutcome::Continue);
         }
 
-        // Print stdout if there's any output
-        let stdout = String::from_utf8_lossy(&output.stdout);
+        ctx.output
+            .info(&format!("  [{}] Executing: {}", index + 1, config.command));
 
-        if !stdout.trim().is_empty() {
-            println!("  Output: {}", stdout.trim());
-        }
+        let context_file = event.write_context_file()?;
+        let env = event.command_env(context_file.path());
+        let timeout = config.timeout.map(Duration::from_secs);
+        let print_line = |line: &str, is_stderr: bool| match is_stderr {
+            true => ctx.output.dimmed(&format!("    {}", line)),
+            false => ctx.output.info(&format!("    {}", line)),
+        };
 
-        Ok(HookOutcome::Continue)
+        let mut attempt = 0;
+        let result = loop {
+            let result = ctx
+                .command
+                .execute_shell_streaming(
+                    &config.command,
+                    Path::new(working_dir),
+                    &env,
+                    timeout,
+                    &print_line,
+                )
+                .with_context(|| {
+                    format!("Failed to execute {} hook: {}", hook_type, config.command)
+                })
+                .and_then(|output| {
+                    if !output.status.success() {
+                        anyhow::bail!(
+                            "{} hook failed: {} ({})",
+                            hook_type,
+                            config.command,
+                            output.status
+                        );
+                    }
+                    Ok(())
+                });
+
+            match result {
+                Err(_) if attempt < config.retries => {
+                    attempt += 1;
+                    ctx.output.warning(&format!(
+                        "Command failed, retrying ({}/{})",
+                        attempt, config.retries
+                    ));
+                }
+                result => break result,
+            }
+        };
+
+        drop(context_file);
+
+        match result {
+            Ok(()) => Ok(HookOutcome::Continue),
+            Err(e) if config.continue_on_error => {
+                ctx.output.warning(&format!("{:#}", e));
+                ctx.output
+                    .dimmed("  Continuing as configured (continue_on_error)");
+                Ok(HookOutcome::Continue)
+            }
+            Err(e) => Err(e),
+        }
     }
 
     /// Execute a confirm hook
     fn run_confirm_hook(
+        ctx: &crate::context::Context,
         config: &ConfirmHookConfig,
         hook_type: &str,
         index: usize,
     ) -> Result<HookOutcome> {
-        println!("  [{}] Confirmation required", index + 1);
-        println!(); // Add blank line for better readability
-
-        // Prompt user with the question from config
-        let confirmed = match config.default {
-            Some(value) => {
-                // Default provided - allow Enter to accept it
-                Confirm::new(&config.question)
-                    .with_default(value)
-                    .prompt()
-                    .with_context(|| {
-                        format!(
-                            "Failed to get user confirmation for {} hook (question: '{}')",
-                            hook_type, config.question
-                        )
-                    })?
-            }
-            None => {
-                // No default - require explicit Y/N input
-                let help_msg = "Please enter Y or N explicitly";
-                Confirm::new(&config.question)
-                    .with_help_message(help_msg)
-                    .prompt()
-                    .with_context(|| {
-                        format!(
-                            "Failed to get user confirmation for {} hook (question: '{}')",
-                            hook_type, config.question
-                        )
-                    })?
-            }
-        };
+        ctx.output
+            .info(&format!("  [{}] Confirmation required", index + 1));
+        ctx.output.blank();
+
+        // Without a default, the answer must be given explicitly
+        let confirmed = ctx
+            .input
+            .confirm(&config.question, config.default)
+            .with_context(|| {
+                format!(
+                    "Failed to get user confirmation for {} hook (question: '{}')",
+                    hook_type, config.question
+                )
+            })?;
 
-        println!(); // Add blank line after confirmation
+        ctx.output.blank();
 
         if confirmed {
             // User confirmed
             if config.exit_on_confirm {
-                println!("  User confirmed - cancelling command as configured");
+                ctx.output
+                    .info("  User confirmed - cancelling command as configured");
                 return Ok(HookOutcome::Cancel);
             }
-            println!("  User confirmed - continuing");
+            ctx.output.info("  User confirmed - continuing");
             Ok(HookOutcome::Continue)
         } else {
             // User declined/cancelled
             if config.exit_on_cancel {
-                println!("  User declined - cancelling command as configured");
+                ctx.output
+                    .info("  User declined - cancelling command as configured");
                 return Ok(HookOutcome::Cancel);
             }
-            println!("  User declined - continuing anyway");
+            ctx.output.info("  User declined - continuing anyway");
             Ok(HookOutcome::Continue)
         }
     }
 
     /// Execute a set_environment hook
     fn run_set_environment_hook(
+        ctx: &crate::context::Context,
         config: &SetEnvironmentHookConfig,
         hook_type: &str,
         index: usize,
     ) -> Result<HookOutcome> {
-        println!(
+        ctx.output.info(&format!(
             "  [{}] Setting environment variable: {}",
             index + 1,
             config.name
-        );
-
-        // Check if the environment variable already exists
-        let current_value = std::env::var(&config.name).ok();
+        ));
 
         let value = if config.sensitive {
-            // Use password input for sensitive values
             // For security reasons, don't show the current value as default for sensitive inputs
-            Password::new(&config.prompt)
-                .without_confirmation()
-                .prompt()
+            ctx.input.password(&config.prompt).with_context(|| {
+                format!(
+                    "Failed to get sensitive input for {} hook (env: {})",
+                    hook_type, config.name
+                )
+            })?
+        } else {
+            // Use the current value, if the variable already exists, as default
+            let current_value = std::env::var(&config.name).ok();
+
+            ctx.input
+                .text(&config.prompt, current_value.as_deref())
                 .with_context(|| {
                     format!(
-                        "Failed to get sensitive input for {} hook (env: {})",
+                        "Failed to get input for {} hook (env: {})",
                         hook_type, config.name
                     )
                 })?
-        } else {
-            // Use regular text input with current value as default if it exists
-            let mut text_prompt = Text::new(&config.prompt);
-
-            if let Some(ref default_val) = current_value {
-                text_prompt = text_prompt.with_default(default_val);
-            }
-
-            text_prompt.prompt().with_context(|| {
-                format!(
-                    "Failed to get input for {} hook (env: {})",
-                    hook_type, config.name
-                )
-            })?
         };
 
         // Set the environment variable for the current process and all child processes
@@ -201,39 +458,147 @@ impl HooksRunner {
         }
 
         if config.sensitive {
-            println!(
+            ctx.output.info(&format!(
                 "  Environment variable {} set (sensitive value hidden)",
                 config.name
-            );
+            ));
         } else {
-            println!("  Environment variable {} set to: {}", config.name, value);
+            ctx.output.info(&format!(
+                "  Environment variable {} set to: {}",
+                config.name, value
+            ));
         }
 
         Ok(HookOutcome::Continue)
     }
 
-    /// Execute a single shell command
-    fn execute_command(command: &str, working_dir: &str) -> Result<std::process::Output> {
-        // Use shell to execute the command for better compatibility
-        #[cfg(target_os = "windows")]
-        let output = Command::new("cmd")
-            .args(["/C", command])
-            .current_dir(working_dir)
-            .output()?;
+    /// Execute a webhook hook, retrying failed attempts
+    fn run_webhook_hook(
+        ctx: &crate::context::Context,
+        config: &WebhookHookConfig,
+        event: &HookEvent,
+        hook_type: &str,
+        index: usize,
+    ) -> Result<()> {
+        // Print the URL as configured: interpolated values may hold secrets
+        ctx.output.info(&format!(
+            "  [{}] Calling webhook: {} {}",
+            index + 1,
+            config.method,
+            config.url
+        ));
+
+        let request = Self::build_webhook_request(config, event)
+            .with_context(|| format!("Failed to prepare {} webhook: {}", hook_type, config.url))?;
+
+        let mut attempt = 0;
+        loop {
+            match request.try_clone().map(|r| r.send()) {
+                Some(Ok(response)) if response.status().is_success() => {
+                    ctx.output
+                        .dimmed(&format!("  Webhook responded with {}", response.status()));
+                    return Ok(());
+                }
+                Some(Ok(response)) if attempt >= config.retries => anyhow::bail!(
+                    "{} webhook failed: {} responded with {}",
+                    hook_type,
+                    config.url,
+                    response.status()
+                ),
+                Some(Err(e)) if attempt >= config.retries => {
+                    // The request error names the interpolated URL, which may hold secrets
+                    anyhow::bail!(
+                        "{} webhook failed: {}\nError: {}",
+                        hook_type,
+                        config.url,
+                        e.without_url()
+                    )
+                }
+                None => anyhow::bail!("{} webhook request cannot be retried", hook_type),
+                _ => {
+                    attempt += 1;
+                    ctx.output.warning(&format!(
+                        "Webhook attempt failed, retrying ({}/{})",
+                        attempt, config.retries
+                    ));
+                    std::thread::sleep(Duration::from_millis(500 * attempt as u64));
+                }
+            }
+        }
+    }
+
+    /// Build a webhook request, interpolating the URL and headers and rendering the body
+    fn build_webhook_request(
+        config: &WebhookHookConfig,
+        event: &HookEvent,
+    ) -> Result<reqwest::blocking::RequestBuilder> {
+        use crate::template::utils::interpolate_env_variables;
+
+        let method = reqwest::Method::from_bytes(config.method.to_uppercase().as_bytes())
+            .with_context(|| format!("Invalid HTTP method: {}", config.method))?;
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(config.timeout))
+            .build()?;
 
-        #[cfg(not(target_os = "windows"))]
-        let output = Command::new("sh")
-            .args(["-c", command])
-            .current_dir(working_dir)
-            .output()?;
+        let mut request = client.request(method, interpolate_env_variables(&config.url)?);
 
-        Ok(output)
+        for (name, value) in &config.headers {
+            request = request.header(name, interpolate_env_variables(value)?);
+        }
+
+        // JSON unless configured otherwise
+        let content_type = config
+            .headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+            .map(|(_, value)| value.to_lowercase());
+        if content_type.is_none() {
+            request = request.header(reqwest::header::CONTENT_TYPE, "application/json");
+        }
+
+        let body = match &config.body {
+            Some(template) => {
+                let mut handlebars = handlebars::Handlebars::new();
+                // Values go inside JSON strings, so quotes and newlines in them must be escaped
+                if content_type.is_none_or(|t| t.contains("json")) {
+                    handlebars.register_escape_fn(json_escape);
+                } else {
+                    handlebars.register_escape_fn(handlebars::no_escape);
+                }
+                crate::template::helpers::register_helpers(&mut handlebars);
+                handlebars
+                    .render_template(template, &event.template_data())
+                    .context("Failed to render webhook body")?
+            }
+            None => serde_json::to_string(event)?,
+        };
+
+        Ok(request.body(body))
     }
 }
 
+/// Escape a value for use inside a JSON string
+fn json_escape(value: &str) -> String {
+    let quoted = Value::String(value.to_string()).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    /// Context running real commands on the real filesystem
+    fn real_context() -> crate::context::Context {
+        crate::context::Context::test_with(
+            Arc::new(crate::traits::RealFileSystem),
+            Arc::new(crate::traits::MockUserInput::new()),
+            Arc::new(crate::traits::MockOutput::new()),
+            Arc::new(crate::traits::RealCommandExecutor::new()),
+            Arc::new(crate::executor::DefaultExecutorRegistry::new()),
+        )
+    }
 
     #[test]
     fn test_hook_outcome_enum() {
@@ -248,7 +613,7 @@ mod tests {
     #[test]
     fn test_run_hooks_empty() {
         let hooks: Vec<Hook> = vec![];
-        let result = HooksRunner::run_hooks(&hooks, ".", "test");
+        let result = HooksRunner::run_hooks(&crate::context::Context::test(), &hooks, ".", "test");
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), HookOutcome::Continue);
     }
@@ -257,6 +622,7 @@ mod tests {
     fn test_command_hook_config_creation() {
         let config = CommandHookConfig {
             command: "echo test".to_string(),
+            ..Default::default()
         };
         assert_eq!(config.command, "echo test");
     }
@@ -351,6 +717,7 @@ config:
     fn test_hook_enum_variants() {
         let command_hook = Hook::Command(CommandHookConfig {
             command: "echo test".to_string(),
+            ..Default::default()
         });
         let confirm_hook = Hook::Confirm(ConfirmHookConfig {
             question: "Continue?".to_string(),
@@ -381,33 +748,6 @@ config:
         }
     }
 
-    #[test]
-    #[cfg(not(target_os = "windows"))]
-    fn test_execute_command_success() {
-        let output = HooksRunner::execute_command("echo test", ".");
-        assert!(output.is_ok());
-        let output = output.unwrap();
-        assert!(output.status.success());
-    }
-
-    #[test]
-    #[cfg(target_os = "windows")]
-    fn test_execute_command_success_windows() {
-        let output = HooksRunner::execute_command("echo test", ".");
-        assert!(output.is_ok());
-        let output = output.unwrap();
-        assert!(output.status.success());
-    }
-
-    #[test]
-    fn test_execute_command_failure() {
-        let output = HooksRunner::execute_command("nonexistent_command_12345", ".");
-        // The command execution itself should succeed, but the command will fail
-        assert!(output.is_ok());
-        let output = output.unwrap();
-        assert!(!output.status.success());
-    }
-
     #[test]
     fn test_set_environment_hook_config_serialization() {
         let config = SetEnvironmentHookConfig {
@@ -474,6 +814,7 @@ config:
         let hooks = [
             Hook::Command(CommandHookConfig {
                 command: "echo Starting deployment".to_string(),
+                ..Default::default()
             }),
             Hook::SetEnvironment(SetEnvironmentHookConfig {
                 name: "DEPLOY_VERSION".to_string(),
@@ -577,4 +918,462 @@ config:
             _ => panic!("Expected SetEnvironment hook"),
         }
     }
+
+    /// Project environment inside an infrastructure, with a secret input
+    fn project_environment() -> (tempfile::TempDir, std::path::PathBuf) {
+        let root = tempfile::TempDir::new().unwrap();
+        std::fs::write(root.path().join(".pmp.infrastructure.yaml"), "").unwrap();
+        let env_dir = root.path().join("projects/vpc/environments/prod");
+        std::fs::create_dir_all(&env_dir).unwrap();
+        std::fs::write(
+            env_dir.join(".pmp.environment.yaml"),
+            "apiVersion: pmp.io/v1\nkind: Network\nmetadata:\n  name: vpc\n  environment_name: prod\nspec:\n  resource:\n    apiVersion: pmp.io/v1\n    kind: Network\n  executor:\n    name: opentofu\n  inputs:\n    region: eu-west-1\n    replicas: 3\n    db_password: hunter2\n  secrets:\n    db_password:\n      manager: vault\n      secret_id: db/password\n      data_source_name: db_password\n",
+        )
+        .unwrap();
+
+        (root, env_dir)
+    }
+
+    /// Run a single command hook in a directory
+    fn run_command(
+        command: &str,
+        working_dir: &std::path::Path,
+        hook_type: &str,
+    ) -> Result<HookOutcome> {
+        let hook = Hook::Command(CommandHookConfig {
+            command: command.to_string(),
+            ..CommandHookConfig::default()
+        });
+        HooksRunner::run_hooks(
+            &real_context(),
+            &[hook],
+            working_dir.to_str().unwrap(),
+            hook_type,
+        )
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn test_command_hook_environment() {
+        let (root, env_dir) = project_environment();
+
+        let command = r#"echo "$PMP_PROJECT $PMP_ENVIRONMENT $PMP_OPERATION $PMP_OUTCOME $PMP_INPUT_REGION $PMP_INPUT_REPLICAS $PMP_INFRASTRUCTURE_ROOT" > hook.txt"#;
+        assert_eq!(
+            run_command(command, &env_dir, "post-apply").unwrap(),
+            HookOutcome::Continue
+        );
+
+        assert_eq!(
+            std::fs::read_to_string(env_dir.join("hook.txt")).unwrap(),
+            format!(
+                "vpc prod apply success eu-west-1 3 {}\n",
+                root.path().canonicalize().unwrap().display()
+            )
+        );
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn test_command_hook_context_file() {
+        let (_root, env_dir) = project_environment();
+
+        let command = r#"cp "$PMP_HOOK_CONTEXT" context.json; ls -l "$PMP_HOOK_CONTEXT" | cut -c1-10 > mode.txt; echo "$PMP_HOOK_CONTEXT" > path.txt"#;
+        run_command(command, &env_dir, "post-apply").unwrap();
+
+        let context: Value =
+            serde_json::from_str(&std::fs::read_to_string(env_dir.join("context.json")).unwrap())
+                .unwrap();
+        assert_eq!(context["inputs"]["replicas"], 3);
+        assert_eq!(context["phase"], "post");
+
+        // Only the owner can read the file, and it is removed once the hook finished
+        assert_eq!(
+            std::fs::read_to_string(env_dir.join("mode.txt")).unwrap(),
+            "-rw-------\n"
+        );
+        let path = std::fs::read_to_string(env_dir.join("path.txt")).unwrap();
+        assert!(!std::path::Path::new(path.trim_end()).exists());
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn test_command_hook_leaves_out_secret_inputs() {
+        let (_root, env_dir) = project_environment();
+
+        let command = r#"echo "${PMP_INPUT_DB_PASSWORD-unset}" > secret.txt; cp "$PMP_HOOK_CONTEXT" context.json"#;
+        run_command(command, &env_dir, "post-apply").unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(env_dir.join("secret.txt")).unwrap(),
+            "unset\n"
+        );
+        let context: Value =
+            serde_json::from_str(&std::fs::read_to_string(env_dir.join("context.json")).unwrap())
+                .unwrap();
+        assert!(context["inputs"].get("db_password").is_none());
+        assert_eq!(context["inputs"]["region"], "eu-west-1");
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn test_command_hook_when_condition() {
+        let (_root, env_dir) = project_environment();
+
+        let hooks: Vec<Hook> = serde_yaml::from_str(
+            r#"
+- type: command
+  config:
+    command: "touch skipped.txt"
+    when:
+      - input_name: region
+        equals: us-east-1
+- type: command
+  config:
+    command: "touch ran.txt"
+    when:
+      - input_name: region
+        equals: eu-west-1
+"#,
+        )
+        .unwrap();
+        HooksRunner::run_hooks(
+            &real_context(),
+            &hooks,
+            env_dir.to_str().unwrap(),
+            "pre-apply",
+        )
+        .unwrap();
+
+        assert!(!env_dir.join("skipped.txt").exists());
+        assert!(env_dir.join("ran.txt").exists());
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn test_command_hook_retries() {
+        let (_root, env_dir) = project_environment();
+
+        let hooks: Vec<Hook> = serde_yaml::from_str(
+            r#"
+- type: command
+  config:
+    command: "echo attempt >> attempts.txt; exit 1"
+    retries: 2
+    continue_on_error: true
+"#,
+        )
+        .unwrap();
+        let result = HooksRunner::run_hooks(
+            &real_context(),
+            &hooks,
+            env_dir.to_str().unwrap(),
+            "post-apply",
+        );
+        assert_eq!(result.unwrap(), HookOutcome::Continue);
+
+        assert_eq!(
+            std::fs::read_to_string(env_dir.join("attempts.txt"))
+                .unwrap()
+                .lines()
+                .count(),
+            3
+        );
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn test_command_hook_timeout() {
+        let (_root, env_dir) = project_environment();
+
+        let timeout: Vec<Hook> = serde_yaml::from_str(
+            "- type: command\n  config:\n    command: sleep 5\n    timeout: 1",
+        )
+        .unwrap();
+        let err = HooksRunner::run_hooks(
+            &real_context(),
+            &timeout,
+            env_dir.to_str().unwrap(),
+            "pre-apply",
+        )
+        .unwrap_err();
+        assert!(format!("{:#}", err).contains("Timed out after 1s"));
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn test_run_hooks_after_operation() {
+        let ctx = real_context();
+        let dir = tempfile::TempDir::new().unwrap();
+        let working_dir = dir.path().to_str().unwrap();
+        let hooks: Vec<Hook> = serde_yaml::from_str(
+            r#"
+- type: command
+  config:
+    command: 'echo "$PMP_PHASE $PMP_OPERATION $PMP_OUTCOME $PMP_ERROR" >> hooks.txt'
+"#,
+        )
+        .unwrap();
+
+        let failed: Result<HookOutcome> =
+            Err(anyhow::anyhow!("Lock timeout").context("Apply failed"));
+        HooksRunner::run_hooks_after(&ctx, &hooks, working_dir, "on-failure-apply", &failed)
+            .unwrap();
+        HooksRunner::run_hooks_after(&ctx, &hooks, working_dir, "finally-apply", &failed).unwrap();
+        HooksRunner::run_hooks_after(
+            &ctx,
+            &hooks,
+            working_dir,
+            "finally-destroy",
+            &Ok(HookOutcome::Cancel),
+        )
+        .unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("hooks.txt")).unwrap(),
+            "on-failure apply failure Apply failed: Lock timeout\n\
+             finally apply failure Apply failed: Lock timeout\n\
+             finally destroy cancelled \n"
+        );
+    }
+
+    #[test]
+    fn test_hooks_run_through_context() {
+        use crate::traits::command::MockCommandResult;
+        use crate::traits::output::OutputMessage;
+        use crate::traits::user_input::MockResponse;
+        use crate::traits::{MockCommandExecutor, MockFileSystem, MockOutput, MockUserInput};
+
+        let output = Arc::new(MockOutput::new());
+        let ctx = crate::context::Context::test_with(
+            Arc::new(MockFileSystem::new()),
+            Arc::new(MockUserInput::with_responses(vec![
+                MockResponse::Text("eu-west-1".to_string()),
+                MockResponse::Confirm(false),
+            ])),
+            output.clone(),
+            Arc::new(MockCommandExecutor::with_outputs(vec![
+                MockCommandResult {
+                    command: "./check.sh".to_string(),
+                    exit_code: 0,
+                    stdout: "checking\nall good\n".to_string(),
+                    stderr: "1 warning\n".to_string(),
+                },
+                MockCommandResult {
+                    command: "./deploy.sh".to_string(),
+                    exit_code: 2,
+                    stdout: String::new(),
+                    stderr: String::new(),
+                },
+            ])),
+            Arc::new(crate::executor::DefaultExecutorRegistry::new()),
+        );
+
+        let hooks: Vec<Hook> = serde_yaml::from_str(
+            r#"
+- type: command
+  config:
+    command: ./check.sh
+- type: set_environment
+  config:
+    name: PMP_TEST_HOOK_REGION
+    prompt: "Region:"
+- type: confirm
+  config:
+    question: "Deploy now?"
+    exit_on_cancel: true
+- type: command
+  config:
+    command: ./deploy.sh
+"#,
+        )
+        .unwrap();
+
+        let result = HooksRunner::run_hooks(&ctx, &hooks, "/project", "pre-apply");
+        assert_eq!(result.unwrap(), HookOutcome::Cancel);
+        assert_eq!(std::env::var("PMP_TEST_HOOK_REGION").unwrap(), "eu-west-1");
+
+        let messages = output.get_messages();
+        for message in [
+            OutputMessage::Info("    checking".to_string()),
+            OutputMessage::Info("    all good".to_string()),
+            OutputMessage::Dimmed("    1 warning".to_string()),
+            OutputMessage::Info("  User declined - cancelling command as configured".to_string()),
+        ] {
+            assert!(messages.contains(&message), "missing {:?}", message);
+        }
+
+        // Without a confirm hook cancelling first, the failing command stops execution
+        let err = HooksRunner::run_hooks(&ctx, &hooks[3..], "/project", "pre-apply").unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("pre-apply hook failed: ./deploy.sh (")
+        );
+    }
+
+    /// Webhook receiver answering each request with the next status and recording it
+    fn start_webhook_server(
+        statuses: Vec<&'static str>,
+    ) -> (String, Arc<Mutex<Vec<crate::test_helpers::HttpRequest>>>) {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&requests);
+        let mut statuses = statuses.into_iter();
+
+        let address = crate::test_helpers::start_http_server(move |request| {
+            seen.lock().unwrap().push(request.clone());
+            let status = statuses.next().unwrap_or("500 Internal Server Error");
+            (status, String::new())
+        });
+
+        (format!("{}/notify", address), requests)
+    }
+
+    #[test]
+    fn test_webhook_hook() {
+        let ctx = real_context();
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join(".pmp.environment.yaml"),
+            "apiVersion: pmp.io/v1\nkind: Network\nmetadata:\n  name: vpc\n  environment_name: prod\nspec:\n  resource:\n    apiVersion: pmp.io/v1\n    kind: Network\n  executor:\n    name: opentofu\n  inputs: {}\n",
+        )
+        .unwrap();
+        let plan_json = r#"{"resource_changes": [{"address": "aws_vpc.main", "change": {"actions": ["create"], "after": {}}}]}"#;
+        std::fs::write(
+            dir.path().join(crate::executor::DEFAULT_PLAN_JSON_FILE),
+            plan_json,
+        )
+        .unwrap();
+        let plan_summary = crate::diff::JsonPlanParser::new()
+            .parse(plan_json)
+            .unwrap()
+            .summary;
+        let working_dir = dir.path().to_str().unwrap();
+
+        let (url, requests) = start_webhook_server(vec!["500 Internal Server Error", "200 OK"]);
+        let hook: Hook = serde_yaml::from_str(&format!(
+            r#"
+type: webhook
+config:
+  url: "{}"
+  headers:
+    Authorization: "Bearer ${{env:PMP_TEST_WEBHOOK_TOKEN_UNSET:secret}}"
+  body: '{{"text": "{{{{_operation}}}} {{{{_project_name}}}}/{{{{_environment}}}}: {{{{_outcome}}}}, +{{{{_plan_summary.to_add}}}}"}}'
+  retries: 1
+"#,
+            url
+        ))
+        .unwrap();
+
+        let result = HooksRunner::run_hooks_with_plan(
+            &ctx,
+            &[hook],
+            working_dir,
+            "post-preview",
+            Some(&plan_summary),
+        );
+        assert_eq!(result.unwrap(), HookOutcome::Continue);
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].method, "POST");
+        assert_eq!(requests[1].target, "/notify");
+        assert_eq!(requests[1].headers["authorization"], "Bearer secret");
+        assert_eq!(
+            requests[1].body,
+            b"{\"text\": \"preview vpc/prod: success, +1\"}"
+        );
+
+        // A plan left in the environment directory by an earlier preview is not reported
+        let event = HookEvent::new(&ctx, working_dir, "post-apply");
+        assert!(event.plan_summary.is_none());
+
+        // Pre hooks have no outcome; without a body the event is sent as JSON
+        let event = HookEvent::new(&ctx, working_dir, "pre-destroy");
+        assert_eq!(event.outcome, None);
+        assert!(event.plan_summary.is_none());
+
+        let (url, requests) = start_webhook_server(vec!["503 Service Unavailable"]);
+        let config = WebhookHookConfig {
+            url,
+            method: "PUT".to_string(),
+            headers: HashMap::new(),
+            body: None,
+            timeout: 5,
+            retries: 0,
+        };
+        let err =
+            HooksRunner::run_webhook_hook(&ctx, &config, &event, "pre-destroy", 0).unwrap_err();
+        assert!(err.to_string().contains("responded with 503"));
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests[0].method, "PUT");
+        assert!(requests[0].body.starts_with(b"{\"project_name\":\"vpc\""));
+    }
+
+    #[test]
+    fn test_webhook_error_hides_interpolated_url() {
+        let ctx = real_context();
+        // Nothing listens on a port freed right after binding it
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let config = WebhookHookConfig {
+            url: format!(
+                "http://127.0.0.1:{}/hooks/${{env:PMP_TEST_WEBHOOK_PATH_UNSET:T0KEN}}",
+                port
+            ),
+            method: "POST".to_string(),
+            headers: HashMap::new(),
+            body: None,
+            timeout: 5,
+            retries: 0,
+        };
+
+        let err =
+            HooksRunner::run_webhook_hook(&ctx, &config, &HookEvent::default(), "post-apply", 0)
+                .unwrap_err();
+        let message = format!("{:#}", err);
+        assert!(message.contains("${env:PMP_TEST_WEBHOOK_PATH_UNSET:T0KEN}"));
+        assert!(!message.contains("/hooks/T0KEN"));
+    }
+
+    #[test]
+    fn test_webhook_body_is_json_escaped() {
+        let event = HookEvent {
+            project_name: Some("vpc".to_string()),
+            operation: "apply".to_string(),
+            phase: "on-failure".to_string(),
+            error: Some("resource \"aws_vpc.main\" failed:\n\ttimeout".to_string()),
+            ..HookEvent::default()
+        };
+        let body = |headers: &[(&str, &str)]| {
+            let config = WebhookHookConfig {
+                url: "http://localhost/notify".to_string(),
+                method: "POST".to_string(),
+                headers: headers
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), value.to_string()))
+                    .collect(),
+                body: Some(r#"{"text": "{{_project_name}}: {{_error}}"}"#.to_string()),
+                timeout: 5,
+                retries: 0,
+            };
+            let request = HooksRunner::build_webhook_request(&config, &event)
+                .unwrap()
+                .build()
+                .unwrap();
+            String::from_utf8(request.body().unwrap().as_bytes().unwrap().to_vec()).unwrap()
+        };
+
+        let json = body(&[]);
+        assert_eq!(
+            serde_json::from_str::<Value>(&json).unwrap()["text"],
+            "vpc: resource \"aws_vpc.main\" failed:\n\ttimeout"
+        );
+        assert_eq!(json, body(&[("Content-Type", "application/vnd.api+json")]));
+        assert_eq!(
+            body(&[("Content-Type", "text/plain")]),
+            "{\"text\": \"vpc: resource \"aws_vpc.main\" failed:\n\ttimeout\"}"
+        );
+    }
 }
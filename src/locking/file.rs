@@ -0,0 +1,196 @@
+use anyhow::{Context, Result};
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+
+use super::{LockInfo, LockStore};
+
+/// Lock store keeping one JSON file per locked key in a shared directory
+///
+/// Locks are created with `create_new`, so two processes cannot acquire the same key.
+pub struct FileLockStore {
+    lock_dir: PathBuf,
+}
+
+impl FileLockStore {
+    pub fn new(lock_dir: PathBuf) -> Self {
+        Self { lock_dir }
+    }
+
+    fn lock_file(&self, key: &str) -> PathBuf {
+        self.lock_dir.join(format!("{}.lock.json", key))
+    }
+
+    /// Atomically create the lock file, returning false if it already exists
+    fn try_create(&self, lock: &LockInfo) -> Result<bool> {
+        let lock_file = self.lock_file(&lock.key);
+
+        if let Some(parent) = lock_file.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create lock directory: {:?}", parent))?;
+        }
+
+        let mut file = match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&lock_file)
+        {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => return Ok(false),
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Failed to create lock file: {:?}", lock_file));
+            }
+        };
+
+        file.write_all(serde_json::to_string_pretty(lock)?.as_bytes())
+            .with_context(|| format!("Failed to write lock file: {:?}", lock_file))?;
+
+        Ok(true)
+    }
+}
+
+impl LockStore for FileLockStore {
+    fn get_type(&self) -> &str {
+        "file"
+    }
+
+    fn acquire(&self, lock: &LockInfo) -> Result<LockInfo> {
+        if self.try_create(lock)? {
+            return Ok(lock.clone());
+        }
+
+        match self.get(&lock.key)? {
+            // Expired locks are taken over
+            Some(current) if current.is_expired() => {
+                self.release(&lock.key, &current.id)?;
+
+                if self.try_create(lock)? {
+                    Ok(lock.clone())
+                } else {
+                    anyhow::bail!("State {} was locked by another process", lock.key)
+                }
+            }
+            Some(current) => anyhow::bail!(
+                "State {} is locked: {} (lock ID: {})",
+                lock.key,
+                current.describe(),
+                current.id
+            ),
+            // Released in the meantime
+            None if self.try_create(lock)? => Ok(lock.clone()),
+            None => anyhow::bail!("State {} was locked by another process", lock.key),
+        }
+    }
+
+    fn release(&self, key: &str, lock_id: &str) -> Result<()> {
+        match self.get(key)? {
+            Some(current) if current.id != lock_id => anyhow::bail!(
+                "Lock ID mismatch for {}: held by {} (lock ID: {})",
+                key,
+                current.owner,
+                current.id
+            ),
+            Some(_) => self.force_release(key),
+            None => Ok(()),
+        }
+    }
+
+    fn force_release(&self, key: &str) -> Result<()> {
+        let lock_file = self.lock_file(key);
+
+        match std::fs::remove_file(&lock_file) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e).with_context(|| format!("Failed to remove lock file: {:?}", lock_file)),
+        }
+    }
+
+    fn get(&self, key: &str) -> Result<Option<LockInfo>> {
+        let lock_file = self.lock_file(key);
+
+        let content = match std::fs::read_to_string(&lock_file) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read lock file: {:?}", lock_file));
+            }
+        };
+
+        let lock = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse lock file: {:?}", lock_file))?;
+
+        Ok(Some(lock))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_acquire_and_release() {
+        let temp = TempDir::new().unwrap();
+        let store = FileLockStore::new(temp.path().to_path_buf());
+        let lock = LockInfo::new("vpc/dev", "alice@host", "apply", None, Some(60));
+
+        store.acquire(&lock).unwrap();
+        assert_eq!(store.get("vpc/dev").unwrap(), Some(lock.clone()));
+        assert!(temp.path().join("vpc/dev.lock.json").exists());
+
+        store.release("vpc/dev", &lock.id).unwrap();
+        assert_eq!(store.get("vpc/dev").unwrap(), None);
+    }
+
+    #[test]
+    fn test_acquire_conflict() {
+        let temp = TempDir::new().unwrap();
+        let store = FileLockStore::new(temp.path().to_path_buf());
+        let first = LockInfo::new("vpc/dev", "alice@host", "apply", Some("release"), Some(60));
+        let second = LockInfo::new("vpc/dev", "bob@host", "destroy", None, Some(60));
+
+        store.acquire(&first).unwrap();
+
+        let err = store.acquire(&second).unwrap_err().to_string();
+        assert!(err.contains("is locked"));
+        assert!(err.contains("alice@host"));
+        assert!(err.contains(&first.id));
+
+        // Other keys are independent
+        let other = LockInfo::new("vpc/prod", "bob@host", "apply", None, None);
+        store.acquire(&other).unwrap();
+    }
+
+    #[test]
+    fn test_expired_lock_is_taken_over() {
+        let temp = TempDir::new().unwrap();
+        let store = FileLockStore::new(temp.path().to_path_buf());
+        let mut stale = LockInfo::new("vpc/dev", "alice@host", "apply", None, Some(60));
+        stale.expires_at = Some(chrono::Utc::now() - chrono::Duration::seconds(1));
+        store.acquire(&stale).unwrap();
+
+        let fresh = LockInfo::new("vpc/dev", "bob@host", "apply", None, Some(60));
+        store.acquire(&fresh).unwrap();
+
+        assert_eq!(store.get("vpc/dev").unwrap().unwrap().owner, "bob@host");
+    }
+
+    #[test]
+    fn test_release_requires_matching_id() {
+        let temp = TempDir::new().unwrap();
+        let store = FileLockStore::new(temp.path().to_path_buf());
+        let lock = LockInfo::new("vpc/dev", "alice@host", "manual", None, None);
+        store.acquire(&lock).unwrap();
+
+        assert!(store.release("vpc/dev", "other-id").is_err());
+        assert!(store.get("vpc/dev").unwrap().is_some());
+
+        store.force_release("vpc/dev").unwrap();
+        assert!(store.get("vpc/dev").unwrap().is_none());
+
+        // Releasing a missing lock is a no-op
+        store.release("vpc/dev", &lock.id).unwrap();
+        store.force_release("vpc/dev").unwrap();
+    }
+}
@@ -0,0 +1,259 @@
+use anyhow::{Context, Result};
+use reqwest::StatusCode;
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::header::CONTENT_TYPE;
+use std::collections::HashMap;
+
+use super::{LockInfo, LockStore};
+
+/// Lock store speaking the pmp HTTP lock protocol (see doc/executors.md)
+///
+/// - `GET {address}/{key}` returns the lock (200) or 404 when unlocked
+/// - `POST {address}/{key}` with a lock body acquires it (200/201) or returns 409/423 with the current lock
+/// - `DELETE {address}/{key}?id=<lock id>` releases it (200/204), 409 on ID mismatch
+/// - `DELETE {address}/{key}?force=true` releases it regardless of owner
+pub struct HttpLockStore {
+    address: String,
+    headers: HashMap<String, String>,
+    client: Client,
+}
+
+impl HttpLockStore {
+    pub fn new(address: &str, headers: HashMap<String, String>) -> Self {
+        Self {
+            address: address.trim_end_matches('/').to_string(),
+            headers,
+            client: Client::new(),
+        }
+    }
+
+    fn url(&self, key: &str) -> String {
+        format!("{}/{}", self.address, key)
+    }
+
+    fn with_headers(&self, mut request: RequestBuilder) -> RequestBuilder {
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        request
+    }
+
+    fn send(&self, request: RequestBuilder, key: &str) -> Result<Response> {
+        self.with_headers(request)
+            .send()
+            .with_context(|| format!("Failed to reach lock server for {}", key))
+    }
+
+    /// Lock held by someone else, as returned in a conflict response body
+    fn conflicting_lock(response: Response) -> Option<LockInfo> {
+        serde_json::from_str(&response.text().ok()?).ok()
+    }
+
+    fn try_acquire(&self, lock: &LockInfo) -> Result<Result<LockInfo, Option<LockInfo>>> {
+        let request = self
+            .client
+            .post(self.url(&lock.key))
+            .header(CONTENT_TYPE, "application/json")
+            .body(serde_json::to_string(lock)?);
+        let response = self.send(request, &lock.key)?;
+
+        match response.status() {
+            StatusCode::OK | StatusCode::CREATED => Ok(Ok(lock.clone())),
+            StatusCode::CONFLICT | StatusCode::LOCKED => Ok(Err(Self::conflicting_lock(response))),
+            status => anyhow::bail!("Lock server returned {} while locking {}", status, lock.key),
+        }
+    }
+}
+
+impl LockStore for HttpLockStore {
+    fn get_type(&self) -> &str {
+        "http"
+    }
+
+    fn acquire(&self, lock: &LockInfo) -> Result<LockInfo> {
+        match self.try_acquire(lock)? {
+            Ok(acquired) => Ok(acquired),
+            // Expired locks are taken over
+            Err(Some(current)) if current.is_expired() => {
+                self.release(&lock.key, &current.id)?;
+
+                match self.try_acquire(lock)? {
+                    Ok(acquired) => Ok(acquired),
+                    Err(_) => anyhow::bail!("State {} was locked by another process", lock.key),
+                }
+            }
+            Err(Some(current)) => anyhow::bail!(
+                "State {} is locked: {} (lock ID: {})",
+                lock.key,
+                current.describe(),
+                current.id
+            ),
+            Err(None) => anyhow::bail!("State {} is locked", lock.key),
+        }
+    }
+
+    fn release(&self, key: &str, lock_id: &str) -> Result<()> {
+        let response = self.send(
+            self.client.delete(self.url(key)).query(&[("id", lock_id)]),
+            key,
+        )?;
+
+        match response.status() {
+            StatusCode::OK | StatusCode::NO_CONTENT | StatusCode::NOT_FOUND => Ok(()),
+            StatusCode::CONFLICT => match Self::conflicting_lock(response) {
+                Some(current) => anyhow::bail!(
+                    "Lock ID mismatch for {}: held by {} (lock ID: {})",
+                    key,
+                    current.owner,
+                    current.id
+                ),
+                None => anyhow::bail!("Lock ID mismatch for {}", key),
+            },
+            status => anyhow::bail!("Lock server returned {} while unlocking {}", status, key),
+        }
+    }
+
+    fn force_release(&self, key: &str) -> Result<()> {
+        let response = self.send(
+            self.client
+                .delete(self.url(key))
+                .query(&[("force", "true")]),
+            key,
+        )?;
+
+        match response.status() {
+            StatusCode::OK | StatusCode::NO_CONTENT | StatusCode::NOT_FOUND => Ok(()),
+            status => anyhow::bail!("Lock server returned {} while unlocking {}", status, key),
+        }
+    }
+
+    fn get(&self, key: &str) -> Result<Option<LockInfo>> {
+        let response = self.send(self.client.get(self.url(key)), key)?;
+
+        match response.status() {
+            StatusCode::OK => {
+                let body = response
+                    .text()
+                    .with_context(|| format!("Failed to read lock for {}", key))?;
+                let lock = serde_json::from_str(&body)
+                    .with_context(|| format!("Invalid lock returned for {}", key))?;
+                Ok(Some(lock))
+            }
+            StatusCode::NOT_FOUND => Ok(None),
+            status => anyhow::bail!("Lock server returned {} while reading {}", status, key),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{HttpRequest, start_http_server};
+    use std::sync::{Arc, Mutex};
+
+    /// Minimal stand-in lock server implementing the protocol in memory
+    fn start_server() -> (String, Arc<Mutex<Vec<String>>>) {
+        let auth_headers = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&auth_headers);
+        let mut locks: HashMap<String, LockInfo> = HashMap::new();
+
+        let address = start_http_server(move |request| handle(request, &mut locks, &seen));
+
+        (format!("{}/locks", address), auth_headers)
+    }
+
+    fn handle(
+        request: &HttpRequest,
+        locks: &mut HashMap<String, LockInfo>,
+        seen: &Mutex<Vec<String>>,
+    ) -> (&'static str, String) {
+        if let Some(authorization) = request.headers.get("authorization") {
+            seen.lock().unwrap().push(authorization.clone());
+        }
+
+        let target = request.target.as_str();
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let key = path.trim_start_matches("/locks/").to_string();
+
+        match request.method.as_str() {
+            "GET" => match locks.get(&key) {
+                Some(lock) => ("200 OK", serde_json::to_string(lock).unwrap()),
+                None => ("404 Not Found", String::new()),
+            },
+            "POST" => match locks.get(&key) {
+                Some(current) => ("409 Conflict", serde_json::to_string(current).unwrap()),
+                None => {
+                    let lock: LockInfo = serde_json::from_slice(&request.body).unwrap();
+                    locks.insert(key, lock);
+                    ("200 OK", String::new())
+                }
+            },
+            "DELETE" => {
+                let force = query.contains("force=true");
+                match locks.get(&key) {
+                    Some(current) if !force && !query.contains(&format!("id={}", current.id)) => {
+                        ("409 Conflict", serde_json::to_string(current).unwrap())
+                    }
+                    Some(_) => {
+                        locks.remove(&key);
+                        ("200 OK", String::new())
+                    }
+                    None => ("404 Not Found", String::new()),
+                }
+            }
+            _ => ("405 Method Not Allowed", String::new()),
+        }
+    }
+
+    #[test]
+    fn test_http_lock_protocol() {
+        let (address, auth_headers) = start_server();
+        let mut headers = HashMap::new();
+        headers.insert("Authorization".to_string(), "Bearer secret".to_string());
+        let store = HttpLockStore::new(&address, headers);
+
+        let lock = LockInfo::new("vpc/dev", "alice@host", "apply", None, Some(60));
+        assert_eq!(store.get("vpc/dev").unwrap(), None);
+
+        store.acquire(&lock).unwrap();
+        assert_eq!(store.get("vpc/dev").unwrap(), Some(lock.clone()));
+
+        // A second lock conflicts
+        let other = LockInfo::new("vpc/dev", "bob@host", "destroy", None, Some(60));
+        let err = store.acquire(&other).unwrap_err().to_string();
+        assert!(err.contains("alice@host"));
+
+        // Release requires the lock ID
+        assert!(store.release("vpc/dev", &other.id).is_err());
+        store.release("vpc/dev", &lock.id).unwrap();
+        assert_eq!(store.get("vpc/dev").unwrap(), None);
+
+        // Force release ignores the owner
+        store.acquire(&other).unwrap();
+        store.force_release("vpc/dev").unwrap();
+        assert_eq!(store.get("vpc/dev").unwrap(), None);
+
+        assert!(
+            auth_headers
+                .lock()
+                .unwrap()
+                .iter()
+                .all(|h| h == "Bearer secret")
+        );
+    }
+
+    #[test]
+    fn test_http_takes_over_expired_lock() {
+        let (address, _) = start_server();
+        let store = HttpLockStore::new(&address, HashMap::new());
+
+        let mut stale = LockInfo::new("vpc/dev", "alice@host", "apply", None, Some(60));
+        stale.expires_at = Some(chrono::Utc::now() - chrono::Duration::seconds(1));
+        store.acquire(&stale).unwrap();
+
+        let fresh = LockInfo::new("vpc/dev", "bob@host", "apply", None, Some(60));
+        store.acquire(&fresh).unwrap();
+
+        assert_eq!(store.get("vpc/dev").unwrap().unwrap().owner, "bob@host");
+    }
+}
@@ -0,0 +1,16 @@
+//! State locking for PMP.
+//!
+//! Apply and destroy acquire a pmp-level lock on the project environment before touching
+//! state, so teammates see each other's operations regardless of the Terraform backend.
+//! Locks live in a lock store configured in `spec.executor.config.lock` of the
+//! infrastructure: a shared directory (`file`, the default) or an HTTP lock server (`http`).
+
+mod file;
+mod http;
+mod store;
+
+pub use file::FileLockStore;
+pub use http::HttpLockStore;
+pub use store::{
+    LockConfig, LockInfo, LockStore, create_lock_store, current_owner, lock_key,
+};
@@ -0,0 +1,296 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::{FileLockStore, HttpLockStore};
+
+/// Default lock TTL in seconds for locks acquired around apply/destroy
+pub const DEFAULT_LOCK_TTL_SECONDS: u64 = 3600;
+
+/// Default directory of the file lock store, relative to the infrastructure root
+pub const DEFAULT_LOCK_DIR: &str = ".pmp/locks";
+
+/// A lock held on the state of a project environment
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LockInfo {
+    /// Unique lock ID, required to release the lock without --force
+    pub id: String,
+
+    /// Locked state key (`<project>/<environment>`)
+    pub key: String,
+
+    /// Who holds the lock (`user@host`)
+    pub owner: String,
+
+    /// Operation holding the lock (e.g., "apply", "destroy", "manual")
+    pub operation: String,
+
+    /// Why the lock was taken
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+
+    /// When the lock was acquired
+    pub created_at: DateTime<Utc>,
+
+    /// When the lock expires; locks without expiry are held until released
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl LockInfo {
+    /// Create a new lock with a fresh ID
+    pub fn new(
+        key: &str,
+        owner: &str,
+        operation: &str,
+        reason: Option<&str>,
+        ttl_seconds: Option<u64>,
+    ) -> Self {
+        let created_at = Utc::now();
+        let expires_at =
+            ttl_seconds.map(|ttl| created_at + chrono::Duration::seconds(ttl as i64));
+
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            key: key.to_string(),
+            owner: owner.to_string(),
+            operation: operation.to_string(),
+            reason: reason.map(str::to_string),
+            created_at,
+            expires_at,
+        }
+    }
+
+    /// Whether the lock expired and can be taken over
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= Utc::now())
+    }
+
+    /// One-line description for output
+    pub fn describe(&self) -> String {
+        let mut description = format!(
+            "{} by {} since {}",
+            self.operation,
+            self.owner,
+            self.created_at.to_rfc3339()
+        );
+
+        if let Some(reason) = &self.reason {
+            description.push_str(&format!(" ({})", reason));
+        }
+
+        description
+    }
+}
+
+/// Lock key of a project environment
+pub fn lock_key(project: &str, environment: &str) -> String {
+    format!("{}/{}", project, environment)
+}
+
+/// Current user as a lock owner (`user@host`)
+pub fn current_owner() -> String {
+    let hostname = whoami::fallible::hostname().unwrap_or_else(|_| "unknown".to_string());
+    format!("{}@{}", whoami::username(), hostname)
+}
+
+/// Store holding pmp state locks, shared by everyone working on an infrastructure
+pub trait LockStore: Send + Sync {
+    /// Store type name (e.g., "file", "http")
+    fn get_type(&self) -> &str;
+
+    /// Acquire a lock, failing if another unexpired lock is held on the same key
+    fn acquire(&self, lock: &LockInfo) -> Result<LockInfo>;
+
+    /// Release a lock by ID, failing if the current lock has a different ID
+    fn release(&self, key: &str, lock_id: &str) -> Result<()>;
+
+    /// Release any lock on a key regardless of owner
+    fn force_release(&self, key: &str) -> Result<()>;
+
+    /// Current lock on a key, if any (expired locks are returned too)
+    fn get(&self, key: &str) -> Result<Option<LockInfo>>;
+}
+
+/// Lock store configuration (`spec.executor.config.lock` in the infrastructure)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LockConfig {
+    /// Store type: "file" (default) or "http"
+    #[serde(rename = "type", default = "default_store_type")]
+    pub store_type: String,
+
+    /// Lock directory for the file store, relative to the infrastructure root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
+    /// Base URL of the HTTP lock server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+
+    /// Extra headers sent to the HTTP lock server
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+
+    /// Environment variable holding a bearer token for the HTTP lock server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_env: Option<String>,
+
+    /// TTL in seconds of locks acquired around apply/destroy
+    #[serde(default = "default_ttl")]
+    pub ttl: u64,
+
+    /// Whether apply/destroy acquire a lock automatically
+    #[serde(default = "default_auto")]
+    pub auto: bool,
+}
+
+fn default_store_type() -> String {
+    "file".to_string()
+}
+
+fn default_ttl() -> u64 {
+    DEFAULT_LOCK_TTL_SECONDS
+}
+
+fn default_auto() -> bool {
+    true
+}
+
+impl Default for LockConfig {
+    fn default() -> Self {
+        Self {
+            store_type: default_store_type(),
+            path: None,
+            address: None,
+            headers: HashMap::new(),
+            token_env: None,
+            ttl: default_ttl(),
+            auto: default_auto(),
+        }
+    }
+}
+
+impl LockConfig {
+    /// Read the lock configuration from the infrastructure executor config
+    pub fn from_executor_config(executor_config: &HashMap<String, Value>) -> Result<Self> {
+        match executor_config.get("lock") {
+            Some(value) => serde_json::from_value(value.clone())
+                .context("Invalid lock configuration in spec.executor.config.lock"),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Directory of the file store
+    pub fn lock_dir(&self, infrastructure_root: &Path) -> PathBuf {
+        infrastructure_root.join(self.path.as_deref().unwrap_or(DEFAULT_LOCK_DIR))
+    }
+}
+
+/// Create the lock store configured for an infrastructure
+pub fn create_lock_store(
+    config: &LockConfig,
+    infrastructure_root: &Path,
+) -> Result<Box<dyn LockStore>> {
+    match config.store_type.as_str() {
+        "file" => Ok(Box::new(FileLockStore::new(
+            config.lock_dir(infrastructure_root),
+        ))),
+        "http" => {
+            let address = config
+                .address
+                .as_deref()
+                .context("HTTP lock store requires 'address' in spec.executor.config.lock")?;
+
+            let mut headers = config.headers.clone();
+            if let Some(token_env) = &config.token_env {
+                let token = std::env::var(token_env).with_context(|| {
+                    format!("Lock store token variable '{}' is not set", token_env)
+                })?;
+                headers.insert("Authorization".to_string(), format!("Bearer {}", token));
+            }
+
+            Ok(Box::new(HttpLockStore::new(address, headers)))
+        }
+        other => anyhow::bail!("Unsupported lock store type: {}", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_config_defaults_to_file_store() {
+        let config = LockConfig::from_executor_config(&HashMap::new()).unwrap();
+
+        assert_eq!(config.store_type, "file");
+        assert_eq!(config.ttl, DEFAULT_LOCK_TTL_SECONDS);
+        assert!(config.auto);
+        assert_eq!(
+            config.lock_dir(Path::new("/infra")),
+            PathBuf::from("/infra/.pmp/locks")
+        );
+    }
+
+    #[test]
+    fn test_lock_config_from_executor_config() {
+        let mut executor_config = HashMap::new();
+        executor_config.insert(
+            "lock".to_string(),
+            serde_json::json!({
+                "type": "http",
+                "address": "http://localhost:8080/locks",
+                "headers": {"X-Team": "platform"},
+                "ttl": 600,
+                "auto": false
+            }),
+        );
+
+        let config = LockConfig::from_executor_config(&executor_config).unwrap();
+
+        assert_eq!(config.store_type, "http");
+        assert_eq!(config.address.as_deref(), Some("http://localhost:8080/locks"));
+        assert_eq!(config.headers.get("X-Team").map(String::as_str), Some("platform"));
+        assert_eq!(config.ttl, 600);
+        assert!(!config.auto);
+    }
+
+    #[test]
+    fn test_create_lock_store_validates_config() {
+        let http = LockConfig {
+            store_type: "http".to_string(),
+            ..Default::default()
+        };
+        assert!(create_lock_store(&http, Path::new("/infra")).is_err());
+
+        let unknown = LockConfig {
+            store_type: "dynamodb".to_string(),
+            ..Default::default()
+        };
+        assert!(create_lock_store(&unknown, Path::new("/infra")).is_err());
+
+        let file = LockConfig::default();
+        assert_eq!(
+            create_lock_store(&file, Path::new("/infra")).unwrap().get_type(),
+            "file"
+        );
+    }
+
+    #[test]
+    fn test_lock_expiry() {
+        let lock = LockInfo::new("vpc/dev", "me@host", "apply", Some("release"), Some(60));
+        assert!(!lock.is_expired());
+        assert!(lock.describe().contains("apply by me@host"));
+        assert!(lock.describe().contains("(release)"));
+
+        let mut expired = lock.clone();
+        expired.expires_at = Some(Utc::now() - chrono::Duration::seconds(1));
+        assert!(expired.is_expired());
+
+        let no_ttl = LockInfo::new("vpc/dev", "me@host", "manual", None, None);
+        assert!(!no_ttl.is_expired());
+    }
+}
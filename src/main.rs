@@ -4,13 +4,16 @@ mod context;
 mod cost;
 mod diff;
 mod executor;
+mod history;
 mod hooks;
 mod infrastructure;
+mod locking;
 mod marketplace;
 mod opa;
 mod output;
 mod schema;
 mod secrets;
+mod state;
 mod template;
 #[cfg(test)]
 mod test_helpers;
@@ -19,11 +22,11 @@ mod traits;
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use commands::{
-    ApplyCommand, CiCommand, CiDetectChangesCommand, CloneCommand, CostCommand, CreateCommand,
+    ApplyCommand, CacheCommand, CiCommand, CiDetectChangesCommand, CloneCommand, CostCommand, CreateCommand,
     DepsCommand, DestroyCommand, DriftCommand, EnvCommand, FindCommand, GenerateCommand,
-    GraphCommand, ImportCommand, InfrastructureCommand, MarketplaceCommand, PolicyCommand,
+    GraphCommand, HistoryCommand, ImportCommand, InfrastructureCommand, MarketplaceCommand, PolicyCommand,
     PreviewCommand, RefreshCommand, SearchCommand, StateCommand, TemplateCommand, TestCommand,
-    UiCommand, UpdateCommand,
+    UiCommand, UpdateCommand, UpgradeCommand,
 };
 
 #[derive(Parser)]
@@ -107,7 +110,7 @@ enum ProjectSubcommands {
 
     /// Update an existing project environment by regenerating files from the original template
     #[command(
-        long_about = "Update an existing project environment by regenerating files from the original template\n\nExamples:\n  pmp project update\n  pmp project update --path ./my-project\n  pmp project update --template-packs-paths /custom/packs1:/custom/packs2\n  pmp project update --inputs '{\"replicas\": 3}'"
+        long_about = "Update an existing project environment by regenerating files from the original template\n\nLocal edits to generated files are merged with the regenerated files. Where both sides\nchanged the same lines, conflict markers are written (--strategy ours|theirs to skip merging).\n\nExamples:\n  pmp project update\n  pmp project update --path ./my-project\n  pmp project update --template-packs-paths /custom/packs1:/custom/packs2\n  pmp project update --inputs '{\"replicas\": 3}'\n  pmp project update --strategy theirs"
     )]
     Update {
         /// Path to the project directory (defaults to current directory)
@@ -121,6 +124,36 @@ enum ProjectSubcommands {
         /// Pre-defined input values as JSON or YAML string (skips prompting for these inputs)
         #[arg(long)]
         inputs: Option<String>,
+
+        /// How to handle locally edited files (ours, theirs, merge)
+        #[arg(long, default_value = "merge")]
+        strategy: String,
+    },
+
+    /// Upgrade a project environment to a newer version of its template
+    #[command(
+        long_about = "Upgrade a project environment to a newer version of its template\n\nApplies the input migrations shipped with each newer version (renames, drops, defaults,\nvalue transforms), regenerates the files from the target version and records it.\n\nExamples:\n  pmp project upgrade\n  pmp project upgrade --to 2.0.0\n  pmp project upgrade --to 2.0.0 --dry-run\n  pmp project upgrade --path ./projects/my-api/environments/dev --strategy ours"
+    )]
+    Upgrade {
+        /// Path to the project directory (defaults to current directory)
+        #[arg(short, long)]
+        path: Option<String>,
+
+        /// Version to upgrade to (prompts if not specified)
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Additional template packs directories to search (colon-separated)
+        #[arg(short, long)]
+        template_packs_paths: Option<String>,
+
+        /// Show the input and file changes without applying them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// How to handle locally edited files (ours, theirs, merge)
+        #[arg(long, default_value = "merge")]
+        strategy: String,
     },
 
     /// Clone an existing project
@@ -142,7 +175,7 @@ enum ProjectSubcommands {
 
     /// Preview changes (run IaC plan)
     #[command(
-        long_about = "Preview changes (run IaC plan)\n\nYou can pass additional executor options after --:\n\nExamples:\n  pmp project preview\n  pmp project preview --path ./my-project\n  pmp project preview --cost\n  pmp project preview --skip-policy\n  pmp project preview --parallel 4\n  pmp project preview --diff\n  pmp project preview --diff --side-by-side\n  pmp project preview --diff --diff-format html --diff-output plan.html\n  pmp project preview -- -no-color\n  pmp project preview -- -var=environment=prod"
+        long_about = "Preview changes (run IaC plan)\n\nYou can pass additional executor options after --:\n\nExamples:\n  pmp project preview\n  pmp project preview --path ./my-project\n  pmp project preview --cost\n  pmp project preview --skip-policy\n  pmp project preview --parallel 4\n  pmp project preview --diff\n  pmp project preview --diff --side-by-side\n  pmp project preview --diff --diff-format html --diff-output plan.html\n  pmp project preview --out ./plans\n  pmp project preview -- -no-color\n  pmp project preview -- -var=environment=prod"
     )]
     Preview {
         /// Path to the project directory (defaults to current directory)
@@ -185,6 +218,10 @@ enum ProjectSubcommands {
         #[arg(long)]
         show_sensitive: bool,
 
+        /// Save a binary plan per project plus a manifest to this directory (apply with --plan)
+        #[arg(long, value_name = "DIR")]
+        out: Option<String>,
+
         /// Additional arguments to pass to the executor (after --)
         #[arg(last = true)]
         executor_args: Vec<String>,
@@ -192,7 +229,7 @@ enum ProjectSubcommands {
 
     /// Apply changes (run IaC apply)
     #[command(
-        long_about = "Apply changes (run IaC apply)\n\nYou can pass additional executor options after --:\n\nExamples:\n  pmp project apply\n  pmp project apply --path ./my-project\n  pmp project apply --cost\n  pmp project apply --skip-policy\n  pmp project apply --parallel 4\n  pmp project apply -- -auto-approve\n  pmp project apply -- -var=environment=prod -auto-approve"
+        long_about = "Apply changes (run IaC apply)\n\nYou can pass additional executor options after --:\n\nExamples:\n  pmp project apply\n  pmp project apply --path ./my-project\n  pmp project apply --cost\n  pmp project apply --skip-policy\n  pmp project apply --parallel 4\n  pmp project apply --plan ./plans\n  pmp project apply -- -auto-approve\n  pmp project apply -- -var=environment=prod -auto-approve"
     )]
     Apply {
         /// Path to the project directory (defaults to current directory)
@@ -211,6 +248,10 @@ enum ProjectSubcommands {
         #[arg(long)]
         parallel: Option<usize>,
 
+        /// Apply exactly the plans saved by 'preview --out' in this directory
+        #[arg(long, value_name = "DIR", conflicts_with_all = ["path", "cost", "parallel"])]
+        plan: Option<String>,
+
         /// Additional arguments to pass to the executor (after --)
         #[arg(last = true)]
         executor_args: Vec<String>,
@@ -321,7 +362,7 @@ enum ProjectSubcommands {
 
     /// State management and drift detection
     #[command(
-        long_about = "Manage infrastructure state and detect drift\n\nSubcommands:\n- list: Show state across all projects\n- drift: Detect configuration drift\n- lock: Lock state for a project\n- unlock: Unlock state for a project\n- sync: Sync remote state\n\nExamples:\n  pmp project state list\n  pmp project state drift\n  pmp project state lock my-project\n  pmp project state unlock my-project --force"
+        long_about = "Manage infrastructure state and detect drift\n\nSubcommands:\n- list: Show state across all projects\n- drift: Detect configuration drift\n- lock: Lock state for a project\n- unlock: Unlock state for a project\n- sync: Sync remote state\n- backup / restore: Back up and restore state\n- backups: List and prune state backups\n- migrate: Migrate state to another backend\n- show / resources / outputs: Inspect state by project name\n- mv / rm: Move or remove resources in state\n- audit: Show the audit log of state changes\n\nExamples:\n  pmp project state list\n  pmp project state drift\n  pmp project state lock my-project\n  pmp project state unlock my-project --force\n  pmp project state resources my-project -e dev"
     )]
     State {
         #[command(subcommand)]
@@ -445,6 +486,24 @@ enum Commands {
         #[command(subcommand)]
         command: MarketplaceSubcommands,
     },
+
+    /// Manage the infrastructure cache
+    #[command(
+        long_about = "Manage the on-disk cache in .pmp/cache\n\nThe project index (.pmp/cache/index.json) stores parsed project and environment files and is refreshed incrementally on every command, re-parsing only files whose modification time and content hash changed.\n\nSubcommands:\n- rebuild: Rebuild the project index from scratch\n- clear: Remove the project index (and state snapshots with --all)\n\nExamples:\n  pmp cache rebuild\n  pmp cache clear\n  pmp cache clear --all"
+    )]
+    Cache {
+        #[command(subcommand)]
+        command: CacheSubcommands,
+    },
+
+    /// Browse operations run from the web UI
+    #[command(
+        long_about = "Browse the operations run from 'pmp ui', recorded with their full output in .pmp/history\n\nRetention is configured with spec.history in the infrastructure (keep_last defaults to 500 operations; max_age_days is optional) and applied after every recorded operation.\n\nSubcommands:\n- list: List recorded operations, newest first\n- show: Show an operation and its log\n- prune: Apply the retention rules\n\nExamples:\n  pmp history list\n  pmp history list --project vpc --environment prod --failed\n  pmp history show 3f2a9c1e\n  pmp history prune"
+    )]
+    History {
+        #[command(subcommand)]
+        command: HistorySubcommands,
+    },
 }
 
 #[derive(Subcommand)]
@@ -557,7 +616,7 @@ enum StateSubcommands {
 
     /// Lock state for a project
     #[command(
-        long_about = "Lock state to prevent concurrent modifications\n\nExample:\n  pmp state lock my-project\n  pmp state lock my-project --environment production"
+        long_about = "Lock state to prevent concurrent modifications\n\nLocks are stored in the lock store configured in spec.executor.config.lock of the infrastructure\n(a shared directory by default, or an HTTP lock server). Apply and destroy acquire the lock automatically.\n\nExample:\n  pmp state lock my-project\n  pmp state lock my-project --environment production\n  pmp state lock my-project --environment production --reason \"DB maintenance\" --ttl 7200"
     )]
     Lock {
         /// Project name
@@ -566,11 +625,19 @@ enum StateSubcommands {
         /// Environment name (optional, prompts if not specified)
         #[arg(short, long)]
         environment: Option<String>,
+
+        /// Why the state is locked (shown to anyone hitting the lock)
+        #[arg(long)]
+        reason: Option<String>,
+
+        /// Lock expiry in seconds (default: held until unlocked)
+        #[arg(long)]
+        ttl: Option<u64>,
     },
 
     /// Unlock state for a project
     #[command(
-        long_about = "Unlock state\n\nExample:\n  pmp state unlock my-project\n  pmp state unlock my-project --force"
+        long_about = "Unlock state\n\nLocks held by another user require their lock ID or --force.\n\nExample:\n  pmp state unlock my-project\n  pmp state unlock my-project --id 6f1c2d7e-...\n  pmp state unlock my-project --force"
     )]
     Unlock {
         /// Project name
@@ -580,18 +647,176 @@ enum StateSubcommands {
         #[arg(short, long)]
         environment: Option<String>,
 
+        /// ID of the lock to release
+        #[arg(long)]
+        id: Option<String>,
+
         /// Force unlock even if locked by another user
         #[arg(short, long)]
         force: bool,
     },
 
+    /// Show the state of a project environment
+    #[command(
+        long_about = "Show the state of a project environment, or the attributes of one resource
+
+State is read through the executor ('state pull'), so it works with any backend.
+
+Example:
+  pmp project state show my-project --environment dev
+  pmp project state show my-project -e dev aws_vpc.main
+  pmp project state show my-project -e dev --format json"
+    )]
+    Show {
+        /// Project name
+        project: String,
+
+        /// Resource address to show (shows a state overview if omitted)
+        address: Option<String>,
+
+        /// Environment name (optional, prompts if not specified)
+        #[arg(short, long)]
+        environment: Option<String>,
+
+        /// Output format (text, json, yaml)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// List the resources in the state of a project environment
+    #[command(
+        long_about = "List the resource instances in the state of a project environment
+
+Example:
+  pmp project state resources my-project --environment dev
+  pmp project state resources my-project -e dev --type aws_subnet
+  pmp project state resources my-project -e dev --format yaml"
+    )]
+    Resources {
+        /// Project name
+        project: String,
+
+        /// Environment name (optional, prompts if not specified)
+        #[arg(short, long)]
+        environment: Option<String>,
+
+        /// Only list resources of this type
+        #[arg(short = 't', long = "type")]
+        resource_type: Option<String>,
+
+        /// Output format (text, json, yaml)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Show the outputs in the state of a project environment
+    #[command(
+        long_about = "Show the root module outputs in the state of a project environment
+
+Sensitive values are masked unless --show-sensitive is given.
+
+Example:
+  pmp project state outputs my-project --environment dev
+  pmp project state outputs my-project -e dev --format json"
+    )]
+    Outputs {
+        /// Project name
+        project: String,
+
+        /// Environment name (optional, prompts if not specified)
+        #[arg(short, long)]
+        environment: Option<String>,
+
+        /// Show values of sensitive outputs
+        #[arg(long)]
+        show_sensitive: bool,
+
+        /// Output format (text, json, yaml)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Move a resource to another address in the state
+    #[command(
+        long_about = "Move a resource, resource instance or module to another address in the state
+
+The state lock is held and a backup is taken first. The operation is recorded in the audit log.
+
+Example:
+  pmp project state mv my-project aws_instance.web aws_instance.app --environment dev
+  pmp project state mv my-project module.vpc module.network -e prod --yes"
+    )]
+    Mv {
+        /// Project name
+        project: String,
+
+        /// Current resource address
+        source: String,
+
+        /// New resource address
+        destination: String,
+
+        /// Environment name (optional, prompts if not specified)
+        #[arg(short, long)]
+        environment: Option<String>,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Remove resources from the state without destroying them
+    #[command(
+        long_about = "Remove resources from the state without destroying them
+
+The state lock is held and a backup is taken first. The operation is recorded in the audit log.
+
+Example:
+  pmp project state rm my-project aws_eip.legacy --environment dev
+  pmp project state rm my-project module.old -e prod --yes"
+    )]
+    Rm {
+        /// Project name
+        project: String,
+
+        /// Resource addresses to remove
+        #[arg(required = true)]
+        addresses: Vec<String>,
+
+        /// Environment name (optional, prompts if not specified)
+        #[arg(short, long)]
+        environment: Option<String>,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Show the audit log of state changes
+    #[command(
+        long_about = "Show the audit log of state changes made with state mv and state rm, newest first
+
+Example:
+  pmp project state audit
+  pmp project state audit --project my-project --limit 50"
+    )]
+    Audit {
+        /// Only show entries for this project
+        #[arg(short, long)]
+        project: Option<String>,
+
+        /// Maximum number of entries to show
+        #[arg(short, long, default_value_t = 20)]
+        limit: usize,
+    },
+
     /// Sync remote state
     #[command(long_about = "Sync state with remote backend\n\nExample:\n  pmp state sync")]
     Sync,
 
     /// Create a manual backup of state
     #[command(
-        long_about = "Create a backup of the current state\n\nExample:\n  pmp state backup\n  pmp state backup --path ./my-project/environments/dev"
+        long_about = "Create a backup of the current state\n\nThe state is pulled through the executor ('state pull'), so any backend works.\nBackups are stored under spec.executor.config.backups.path (default: .pmp/backups in the infrastructure).\nAn unchanged state reuses the latest backup.\n\nExample:\n  pmp state backup\n  pmp state backup --path ./my-project/environments/dev"
     )]
     Backup {
         /// Path to the project environment (defaults to current directory)
@@ -599,6 +824,15 @@ enum StateSubcommands {
         path: Option<String>,
     },
 
+    /// List and prune state backups
+    #[command(
+        long_about = "Manage state backups of a project environment\n\nSubcommands:\n- list: Show backups with serial, lineage and resource count changes\n- prune: Apply the retention rules (keep_last / max_age_days)\n\nExamples:\n  pmp project state backups list\n  pmp project state backups prune --path ./my-project/environments/dev"
+    )]
+    Backups {
+        #[command(subcommand)]
+        command: StateBackupsSubcommands,
+    },
+
     /// Restore state from a backup
     #[command(
         long_about = "Restore state from a previous backup\n\nExample:\n  pmp state restore 20250116_143000\n  pmp state restore 20250116_143000 --force"
@@ -618,12 +852,77 @@ enum StateSubcommands {
 
     /// Migrate state between backends
     #[command(
-        long_about = "Migrate state to a different backend\n\nSupported backends:\n- s3\n- azurerm\n- gcs\n- local\n\nExample:\n  pmp state migrate s3\n  pmp state migrate azurerm --path ./my-project/environments/prod"
+        long_about = "Migrate state to a different backend
+
+Regenerates _common.tf with the target backend, runs 'init -migrate-state' non-interactively
+and verifies that lineage and resource count are unchanged. A backup is taken before each
+environment is migrated, and a failed environment gets its previous _common.tf back.
+
+With --all, every environment of the infrastructure is migrated and, on success,
+spec.executor.config.backend is updated in .pmp.infrastructure.yaml.
+
+Example:
+  pmp project state migrate pg --config conn_str=postgres://db/terraform_state
+  pmp project state migrate s3 --config bucket=tf-state --config region=eu-west-1 --path ./my-project/environments/prod
+  pmp project state migrate --backend-file backend.yaml --all --dry-run
+  pmp project state migrate --backend-file backend.yaml --all --yes"
     )]
     Migrate {
-        /// Target backend type
-        backend_type: String,
+        /// Target backend type (e.g., pg, s3, gcs, local)
+        backend_type: Option<String>,
+
+        /// YAML or JSON file with the full target backend configuration (including 'type')
+        #[arg(long, value_name = "FILE")]
+        backend_file: Option<String>,
+
+        /// Target backend parameter (key=value format, repeatable)
+        #[arg(short, long = "config", value_name = "KEY=VALUE")]
+        config: Vec<String>,
 
+        /// Path to the project environment (defaults to current directory)
+        #[arg(short, long, conflicts_with = "all")]
+        path: Option<String>,
+
+        /// Migrate every environment of the infrastructure
+        #[arg(long)]
+        all: bool,
+
+        /// Report what would be migrated without changing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+#[command(next_display_order = None)] // Sort subcommands alphabetically
+enum StateBackupsSubcommands {
+    /// List state backups
+    #[command(
+        long_about = "List state backups, newest first, with changes against the previous backup
+
+Example:
+  pmp project state backups list
+  pmp project state backups list --path ./my-project/environments/dev"
+    )]
+    List {
+        /// Path to the project environment (defaults to current directory)
+        #[arg(short, long)]
+        path: Option<String>,
+    },
+
+    /// Delete backups outside the retention rules
+    #[command(
+        long_about = "Delete backups outside the retention rules configured in spec.executor.config.backups
+(keep_last, max_age_days). The newest backup is always kept.
+
+Example:
+  pmp project state backups prune"
+    )]
+    Prune {
         /// Path to the project environment (defaults to current directory)
         #[arg(short, long)]
         path: Option<String>,
@@ -824,6 +1123,38 @@ enum CiSubcommands {
 #[derive(Subcommand)]
 #[command(next_display_order = None)] // Sort subcommands alphabetically
 enum TemplateSubcommands {
+    /// Compare two template versions or templates across packs
+    #[command(
+        long_about = "Compare two templates: input additions, removals and type changes, dependency, \
+        plugin and hook changes, and a unified diff of the files under src/\n\n\
+        Templates are referenced as [pack/]template[@version]. TO may be just @version to compare \
+        two versions of the same template; without a version the latest is used.\n\n\
+        Examples:\n  \
+        pmp template diff api@1.0.0 @2.0.0                # Compare two versions\n  \
+        pmp template diff core/api team/api               # Compare across packs\n  \
+        pmp template diff api@1.0.0 @2.0.0 --inputs dev.yaml  # Also compare rendered files\n  \
+        pmp template diff api@1.0.0 @2.0.0 --format json  # Output as JSON"
+    )]
+    Diff {
+        /// Template to compare from ([pack/]template[@version])
+        from: String,
+
+        /// Template to compare to ([pack/]template[@version], or @version)
+        to: String,
+
+        /// Render both templates with inputs from this YAML/JSON file and compare the output
+        #[arg(long)]
+        inputs: Option<String>,
+
+        /// Output format (text or json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+
+        /// Additional template pack paths (colon-separated)
+        #[arg(long, env = "PMP_TEMPLATE_PACKS_PATHS")]
+        template_packs_paths: Option<String>,
+    },
+
     /// Lint template packs for common issues
     #[command(
         long_about = "Validate template packs for common issues\n\n\
@@ -837,7 +1168,8 @@ enum TemplateSubcommands {
         Examples:\n  \
         pmp template lint                    # Lint all template packs\n  \
         pmp template lint --pack my-pack     # Lint specific pack\n  \
-        pmp template lint --format json      # Output as JSON"
+        pmp template lint --format json      # Output as JSON\n  \
+        pmp template lint --fix              # Apply fixes, showing a diff first"
     )]
     Lint {
         /// Lint only the specified template pack
@@ -860,6 +1192,10 @@ enum TemplateSubcommands {
         #[arg(long)]
         skip_handlebars: bool,
 
+        /// Rewrite template files to fix fixable issues
+        #[arg(long)]
+        fix: bool,
+
         /// Additional template pack paths (colon-separated)
         #[arg(long, env = "PMP_TEMPLATE_PACKS_PATHS")]
         template_packs_paths: Option<String>,
@@ -874,6 +1210,36 @@ enum TemplateSubcommands {
         #[arg(short, long)]
         output: Option<String>,
     },
+
+    /// Run golden-file tests of templates
+    #[command(
+        long_about = "Render the test cases shipped with templates and compare them with the expected files\n\n\
+        Each case is a directory tests/<case>/ next to .pmp.template.yaml, holding inputs.yaml and \
+        the expected output tree under expected/. Templates are rendered in memory; nothing is \
+        written unless --update is given.\n\n\
+        Examples:\n  \
+        pmp template test                       # Test all template packs\n  \
+        pmp template test --pack my-pack        # Test a specific pack\n  \
+        pmp template test --template api        # Test one template\n  \
+        pmp template test --update              # Re-bless expected files"
+    )]
+    Test {
+        /// Test only the specified template pack
+        #[arg(short, long)]
+        pack: Option<String>,
+
+        /// Test only templates with this name
+        #[arg(short, long)]
+        template: Option<String>,
+
+        /// Replace expected files with the rendered output
+        #[arg(long)]
+        update: bool,
+
+        /// Additional template pack paths (colon-separated)
+        #[arg(long, env = "PMP_TEMPLATE_PACKS_PATHS")]
+        template_packs_paths: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -973,13 +1339,21 @@ enum SearchSubcommands {
         format: Option<String>,
     },
 
-    /// Search by resource type
+    /// Search resources in state
     #[command(
-        long_about = "Search infrastructure by resource type\n\nExample:\n  pmp search by-resources aws_instance\n  pmp search by-resources aws_s3_bucket --format json"
+        long_about = "Search resources in the state of every environment\n\nSearches the state snapshots refreshed after apply, destroy and refresh. The query matches a\nresource type exactly or any part of a resource address (including module resources).\nAttribute filters use dotted keys for nested attributes.\n\nExample:\n  pmp search by-resources aws_instance\n  pmp search by-resources module.network --format json\n  pmp search by-resources aws_instance --attribute instance_type=t3.micro --attribute tags.Team\n  pmp search by-resources aws_s3_bucket --refresh"
     )]
     ByResources {
-        /// Resource type to search for
-        resource_type: String,
+        /// Resource type or part of a resource address
+        resource_type: Option<String>,
+
+        /// Attribute filter (key=value or key, repeatable)
+        #[arg(short, long = "attribute", value_name = "KEY=VALUE")]
+        attributes: Vec<String>,
+
+        /// Pull the current state of every environment before searching
+        #[arg(long)]
+        refresh: bool,
 
         /// Output format (text, json, yaml)
         #[arg(short, long)]
@@ -1001,18 +1375,92 @@ enum SearchSubcommands {
 
     /// Search by output values
     #[command(
-        long_about = "Search infrastructure by output values\n\nExample:\n  pmp search by-output vpc_id=vpc-123\n  pmp search by-output subnet_id=subnet-456 --format json"
+        long_about = "Search output values in the state of every environment\n\nEvery filter must match an output of the environment. Sensitive values are not searchable.\n\nExample:\n  pmp search by-output vpc_id=vpc-123\n  pmp search by-output subnet_id=subnet-456 --format json\n  pmp search by-output cluster_endpoint --refresh"
     )]
     ByOutput {
-        /// Output values to search for (key=value format)
+        /// Outputs to search for (name=value or name)
+        #[arg(required = true)]
         outputs: Vec<String>,
 
+        /// Pull the current state of every environment before searching
+        #[arg(long)]
+        refresh: bool,
+
         /// Output format (text, json, yaml)
         #[arg(short, long)]
         format: Option<String>,
     },
 }
 
+#[derive(Subcommand)]
+#[command(next_display_order = None)]
+enum CacheSubcommands {
+    /// Rebuild the project index
+    #[command(
+        long_about = "Rebuild the project index from scratch, re-parsing every project and environment file\n\nExamples:\n  pmp cache rebuild"
+    )]
+    Rebuild,
+
+    /// Clear cached data
+    #[command(
+        long_about = "Remove the project index; it is rebuilt on the next command\n\nExamples:\n  pmp cache clear\n  pmp cache clear --all"
+    )]
+    Clear {
+        /// Also remove the state snapshots used by search
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+#[derive(Subcommand)]
+#[command(next_display_order = None)]
+enum HistorySubcommands {
+    /// List recorded operations
+    #[command(
+        long_about = "List recorded operations, newest first\n\nExamples:\n  pmp history list\n  pmp history list --operation apply --limit 50\n  pmp history list --project vpc --environment prod --failed"
+    )]
+    List {
+        /// Only operations on this project
+        #[arg(short, long)]
+        project: Option<String>,
+
+        /// Only operations on this environment
+        #[arg(short, long)]
+        environment: Option<String>,
+
+        /// Only this operation (preview, apply, destroy, refresh)
+        #[arg(long)]
+        operation: Option<String>,
+
+        /// Only failed operations
+        #[arg(long)]
+        failed: bool,
+
+        /// Maximum number of operations to show
+        #[arg(short, long, default_value_t = 20)]
+        limit: usize,
+    },
+
+    /// Show an operation and its log
+    #[command(
+        long_about = "Show an operation and its full output; the ID can be shortened to a unique prefix\n\nExamples:\n  pmp history show 3f2a9c1e\n  pmp history show 3f2a9c1e --no-log"
+    )]
+    Show {
+        /// Operation ID (or a unique prefix of it)
+        id: String,
+
+        /// Only show the operation details
+        #[arg(long)]
+        no_log: bool,
+    },
+
+    /// Apply the retention rules
+    #[command(
+        long_about = "Delete operations beyond spec.history.keep_last or older than spec.history.max_age_days; the newest operation is always kept\n\nExamples:\n  pmp history prune"
+    )]
+    Prune,
+}
+
 #[derive(Subcommand)]
 #[command(next_display_order = None)]
 enum MarketplaceSubcommands {
@@ -1131,6 +1579,13 @@ fn main() -> Result<()> {
     let cli = Cli::parse();
     let ctx = context::Context::new();
 
+    // Make executors declared by the current infrastructure available to all commands; invalid
+    // entries are skipped with a warning so they only matter to commands that use them
+    if let Err(e) = executor::register_infrastructure_executors(&ctx) {
+        ctx.output
+            .warning(&format!("External executors not registered: {:#}", e));
+    }
+
     match cli.command {
         Commands::Infrastructure { command } => match command {
             InfrastructureSubcommands::Init {
@@ -1175,12 +1630,30 @@ fn main() -> Result<()> {
                 path,
                 template_packs_paths,
                 inputs,
+                strategy,
             } => {
                 UpdateCommand::execute(
                     &ctx,
                     path.as_deref(),
                     template_packs_paths.as_deref(),
                     inputs.as_deref(),
+                    template::MergeStrategy::parse(&strategy)?,
+                )?;
+            }
+            ProjectSubcommands::Upgrade {
+                path,
+                to,
+                template_packs_paths,
+                dry_run,
+                strategy,
+            } => {
+                UpgradeCommand::execute(
+                    &ctx,
+                    path.as_deref(),
+                    to.as_deref(),
+                    template_packs_paths.as_deref(),
+                    dry_run,
+                    template::MergeStrategy::parse(&strategy)?,
                 )?;
             }
             ProjectSubcommands::Clone {
@@ -1201,6 +1674,7 @@ fn main() -> Result<()> {
                 diff_output,
                 show_unchanged,
                 show_sensitive,
+                out,
                 executor_args,
             } => {
                 PreviewCommand::execute(
@@ -1215,6 +1689,7 @@ fn main() -> Result<()> {
                     diff_output.as_deref(),
                     show_unchanged,
                     show_sensitive,
+                    out.as_deref(),
                     &executor_args,
                 )?;
             }
@@ -1223,9 +1698,14 @@ fn main() -> Result<()> {
                 cost,
                 skip_policy,
                 parallel,
+                plan,
                 executor_args,
             } => {
-                ApplyCommand::execute(&ctx, path.as_deref(), cost, skip_policy, parallel, &executor_args)?;
+                if let Some(plan_dir) = plan {
+                    ApplyCommand::execute_saved_plans(&ctx, &plan_dir, skip_policy, &executor_args)?;
+                } else {
+                    ApplyCommand::execute(&ctx, path.as_deref(), cost, skip_policy, parallel, &executor_args)?;
+                }
             }
             ProjectSubcommands::Destroy {
                 path,
@@ -1351,22 +1831,120 @@ fn main() -> Result<()> {
                 StateSubcommands::Lock {
                     project,
                     environment,
+                    reason,
+                    ttl,
                 } => {
-                    StateCommand::execute_lock(&ctx, &project, environment.as_deref())?;
+                    StateCommand::execute_lock(
+                        &ctx,
+                        &project,
+                        environment.as_deref(),
+                        reason.as_deref(),
+                        ttl,
+                    )?;
                 }
                 StateSubcommands::Unlock {
                     project,
                     environment,
+                    id,
                     force,
                 } => {
-                    StateCommand::execute_unlock(&ctx, &project, environment.as_deref(), force)?;
+                    StateCommand::execute_unlock(
+                        &ctx,
+                        &project,
+                        environment.as_deref(),
+                        id.as_deref(),
+                        force,
+                    )?;
                 }
                 StateSubcommands::Sync => {
                     StateCommand::execute_sync(&ctx)?;
                 }
+                StateSubcommands::Show {
+                    project,
+                    address,
+                    environment,
+                    format,
+                } => {
+                    StateCommand::execute_show(
+                        &ctx,
+                        &project,
+                        environment.as_deref(),
+                        address.as_deref(),
+                        &format,
+                    )?;
+                }
+                StateSubcommands::Resources {
+                    project,
+                    environment,
+                    resource_type,
+                    format,
+                } => {
+                    StateCommand::execute_resources(
+                        &ctx,
+                        &project,
+                        environment.as_deref(),
+                        resource_type.as_deref(),
+                        &format,
+                    )?;
+                }
+                StateSubcommands::Outputs {
+                    project,
+                    environment,
+                    show_sensitive,
+                    format,
+                } => {
+                    StateCommand::execute_outputs(
+                        &ctx,
+                        &project,
+                        environment.as_deref(),
+                        show_sensitive,
+                        &format,
+                    )?;
+                }
+                StateSubcommands::Mv {
+                    project,
+                    source,
+                    destination,
+                    environment,
+                    yes,
+                } => {
+                    StateCommand::execute_mv(
+                        &ctx,
+                        &project,
+                        environment.as_deref(),
+                        &source,
+                        &destination,
+                        yes,
+                    )?;
+                }
+                StateSubcommands::Rm {
+                    project,
+                    addresses,
+                    environment,
+                    yes,
+                } => {
+                    StateCommand::execute_rm(
+                        &ctx,
+                        &project,
+                        environment.as_deref(),
+                        &addresses,
+                        yes,
+                    )?;
+                }
+                StateSubcommands::Audit { project, limit } => {
+                    StateCommand::execute_audit(&ctx, project.as_deref(), limit)?;
+                }
                 StateSubcommands::Backup { path } => {
                     StateCommand::execute_backup(&ctx, path.as_deref())?;
                 }
+                StateSubcommands::Backups { command } => match command {
+                    StateBackupsSubcommands::List { path } => {
+                        StateCommand::execute_backups_list(&ctx, path.as_deref())?;
+                    }
+                    StateBackupsSubcommands::Prune { path } => {
+                        StateCommand::execute_backups_prune(&ctx, path.as_deref())?;
+                    }
+                },
                 StateSubcommands::Restore {
                     backup_id,
                     path,
@@ -1374,8 +1952,25 @@ fn main() -> Result<()> {
                 } => {
                     StateCommand::execute_restore(&ctx, &backup_id, path.as_deref(), force)?;
                 }
-                StateSubcommands::Migrate { backend_type, path } => {
-                    StateCommand::execute_migrate(&ctx, &backend_type, path.as_deref())?;
+                StateSubcommands::Migrate {
+                    backend_type,
+                    backend_file,
+                    config,
+                    path,
+                    all,
+                    dry_run,
+                    yes,
+                } => {
+                    StateCommand::execute_migrate(
+                        &ctx,
+                        backend_type.as_deref(),
+                        backend_file.as_deref(),
+                        &config,
+                        path.as_deref(),
+                        all,
+                        dry_run,
+                        yes,
+                    )?;
                 }
             },
             ProjectSubcommands::Env { command } => match command {
@@ -1479,12 +2074,29 @@ fn main() -> Result<()> {
             }
         },
         Commands::Template { command } => match command {
+            TemplateSubcommands::Diff {
+                from,
+                to,
+                inputs,
+                format,
+                template_packs_paths,
+            } => {
+                TemplateCommand::execute_diff(
+                    &ctx,
+                    &from,
+                    &to,
+                    inputs.as_deref(),
+                    &format,
+                    template_packs_paths.as_deref(),
+                )?;
+            }
             TemplateSubcommands::Lint {
                 pack,
                 format,
                 include_info,
                 skip_unused_inputs,
                 skip_handlebars,
+                fix,
                 template_packs_paths,
             } => {
                 TemplateCommand::execute_lint(
@@ -1494,28 +2106,92 @@ fn main() -> Result<()> {
                     include_info,
                     skip_unused_inputs,
                     skip_handlebars,
+                    fix,
                     template_packs_paths.as_deref(),
                 )?;
             }
             TemplateSubcommands::Scaffold { output } => {
                 TemplateCommand::execute_scaffold(&ctx, output.as_deref())?;
             }
+            TemplateSubcommands::Test {
+                pack,
+                template,
+                update,
+                template_packs_paths,
+            } => {
+                TemplateCommand::execute_test(
+                    &ctx,
+                    pack.as_deref(),
+                    template.as_deref(),
+                    update,
+                    template_packs_paths.as_deref(),
+                )?;
+            }
         },
         Commands::Search { command } => match command {
-            SearchSubcommands::ByTags { tags, format: _ } => {
-                SearchCommand::execute_by_tags(&ctx, tags)?;
+            SearchSubcommands::ByTags { tags, format } => {
+                SearchCommand::execute_by_tags(&ctx, tags, format.as_deref().unwrap_or("text"))?;
             }
             SearchSubcommands::ByResources {
                 resource_type,
-                format: _,
+                attributes,
+                refresh,
+                format,
+            } => {
+                SearchCommand::execute_by_resources(
+                    &ctx,
+                    resource_type.as_deref(),
+                    &attributes,
+                    refresh,
+                    format.as_deref().unwrap_or("text"),
+                )?;
+            }
+            SearchSubcommands::ByName { pattern, format } => {
+                SearchCommand::execute_by_name(&ctx, &pattern, format.as_deref().unwrap_or("text"))?;
+            }
+            SearchSubcommands::ByOutput {
+                outputs,
+                refresh,
+                format,
+            } => {
+                SearchCommand::execute_by_output(
+                    &ctx,
+                    &outputs,
+                    refresh,
+                    format.as_deref().unwrap_or("text"),
+                )?;
+            }
+        },
+        Commands::Cache { command } => match command {
+            CacheSubcommands::Rebuild => {
+                CacheCommand::execute_rebuild(&ctx)?;
+            }
+            CacheSubcommands::Clear { all } => {
+                CacheCommand::execute_clear(&ctx, all)?;
+            }
+        },
+        Commands::History { command } => match command {
+            HistorySubcommands::List {
+                project,
+                environment,
+                operation,
+                failed,
+                limit,
             } => {
-                SearchCommand::execute_by_resources(&ctx, Some(&resource_type), None)?;
+                HistoryCommand::execute_list(
+                    &ctx,
+                    project.as_deref(),
+                    environment.as_deref(),
+                    operation.as_deref(),
+                    failed,
+                    limit,
+                )?;
             }
-            SearchSubcommands::ByName { pattern, format: _ } => {
-                SearchCommand::execute_by_name(&ctx, &pattern)?;
+            HistorySubcommands::Show { id, no_log } => {
+                HistoryCommand::execute_show(&ctx, &id, !no_log)?;
             }
-            SearchSubcommands::ByOutput { outputs, format: _ } => {
-                SearchCommand::execute_by_output(&ctx, &outputs[0])?;
+            HistorySubcommands::Prune => {
+                HistoryCommand::execute_prune(&ctx)?;
             }
         },
         Commands::Marketplace { command } => match command {
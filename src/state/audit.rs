@@ -0,0 +1,131 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::traits::FileSystem;
+
+/// Default audit log location, relative to the infrastructure root
+pub const DEFAULT_AUDIT_LOG: &str = ".pmp/audit/state.jsonl";
+
+/// A state-modifying operation recorded in the audit log
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+
+    /// Who ran the operation (user@host)
+    pub user: String,
+
+    /// Operation name (e.g., "state-mv", "state-rm")
+    pub operation: String,
+
+    pub project: String,
+
+    pub environment: String,
+
+    /// Resource addresses the operation touched
+    pub addresses: Vec<String>,
+
+    /// Backup taken before the operation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_id: Option<String>,
+
+    pub success: bool,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl AuditEntry {
+    pub fn new(operation: &str, project: &str, environment: &str, addresses: Vec<String>) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            user: crate::locking::current_owner(),
+            operation: operation.to_string(),
+            project: project.to_string(),
+            environment: environment.to_string(),
+            addresses,
+            backup_id: None,
+            success: true,
+            error: None,
+        }
+    }
+}
+
+/// Append-only audit log stored as JSON lines
+pub struct AuditLog<'a> {
+    fs: &'a dyn FileSystem,
+    path: PathBuf,
+}
+
+impl<'a> AuditLog<'a> {
+    pub fn new(fs: &'a dyn FileSystem, path: PathBuf) -> Self {
+        Self { fs, path }
+    }
+
+    /// Audit log of an infrastructure
+    pub fn for_infrastructure(fs: &'a dyn FileSystem, infrastructure_root: &Path) -> Self {
+        Self::new(fs, infrastructure_root.join(DEFAULT_AUDIT_LOG))
+    }
+
+    /// Append an entry to the log
+    pub fn record(&self, entry: &AuditEntry) -> Result<()> {
+        let mut content = if self.fs.exists(&self.path) {
+            self.fs.read_to_string(&self.path)?
+        } else {
+            String::new()
+        };
+
+        content.push_str(&serde_json::to_string(entry)?);
+        content.push('\n');
+
+        self.fs
+            .write(&self.path, &content)
+            .with_context(|| format!("Failed to write audit log: {}", self.path.display()))
+    }
+
+    /// All entries, oldest first
+    pub fn entries(&self) -> Result<Vec<AuditEntry>> {
+        if !self.fs.exists(&self.path) {
+            return Ok(Vec::new());
+        }
+
+        self.fs
+            .read_to_string(&self.path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).context("Invalid audit log entry"))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::MockFileSystem;
+
+    #[test]
+    fn test_record_appends_entries() {
+        let fs = MockFileSystem::new();
+        let log = AuditLog::for_infrastructure(&fs, Path::new("/infra"));
+
+        assert!(log.entries().unwrap().is_empty());
+
+        let mut moved = AuditEntry::new(
+            "state-mv",
+            "vpc",
+            "dev",
+            vec!["aws_vpc.old".to_string(), "aws_vpc.main".to_string()],
+        );
+        moved.backup_id = Some("20261016_101500_123".to_string());
+        log.record(&moved).unwrap();
+
+        let mut removed = AuditEntry::new("state-rm", "vpc", "dev", vec!["aws_eip.x".to_string()]);
+        removed.success = false;
+        removed.error = Some("boom".to_string());
+        log.record(&removed).unwrap();
+
+        assert!(fs.has_file(Path::new("/infra/.pmp/audit/state.jsonl")));
+        assert_eq!(log.entries().unwrap(), vec![moved, removed]);
+    }
+}
@@ -0,0 +1,395 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::StateSummary;
+use crate::traits::FileSystem;
+
+/// Default backup directory, relative to the infrastructure root
+pub const DEFAULT_BACKUP_DIR: &str = ".pmp/backups";
+
+/// Directory holding the content-addressed state files of an environment
+const OBJECTS_DIR: &str = "objects";
+
+/// State backup configuration (`spec.executor.config.backups` in the infrastructure)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BackupConfig {
+    /// Backup directory, relative to the infrastructure root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
+    /// Keep only the newest N backups per environment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_last: Option<usize>,
+
+    /// Delete backups older than this many days
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<u64>,
+
+    /// Whether apply/destroy snapshot the state first
+    #[serde(default = "default_auto")]
+    pub auto: bool,
+}
+
+fn default_auto() -> bool {
+    true
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            path: None,
+            keep_last: None,
+            max_age_days: None,
+            auto: default_auto(),
+        }
+    }
+}
+
+impl BackupConfig {
+    /// Read the backup configuration from the infrastructure executor config
+    pub fn from_executor_config(executor_config: &HashMap<String, Value>) -> Result<Self> {
+        match executor_config.get("backups") {
+            Some(value) => serde_json::from_value(value.clone())
+                .context("Invalid backup configuration in spec.executor.config.backups"),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Backup directory
+    pub fn backup_dir(&self, infrastructure_root: &Path) -> PathBuf {
+        infrastructure_root.join(self.path.as_deref().unwrap_or(DEFAULT_BACKUP_DIR))
+    }
+}
+
+/// A recorded state backup
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BackupEntry {
+    pub id: String,
+    pub project: String,
+    pub environment: String,
+    pub created_at: DateTime<Utc>,
+
+    /// Why the backup was taken (e.g., "manual", "pre-apply", "pre-destroy")
+    pub reason: String,
+
+    /// SHA-256 of the state content, naming the stored state file
+    pub hash: String,
+
+    #[serde(flatten)]
+    pub summary: StateSummary,
+}
+
+/// Result of saving a backup
+#[derive(Debug)]
+pub struct SavedBackup {
+    pub entry: BackupEntry,
+
+    /// False when the state matched the latest backup and that backup was reused
+    pub created: bool,
+}
+
+/// Backups of pulled state, stored per environment as `<project>/<environment>/<id>.json`
+/// entries referencing content-addressed `objects/<sha256>.tfstate` files
+pub struct BackupStore<'a> {
+    fs: &'a dyn FileSystem,
+    dir: PathBuf,
+}
+
+impl<'a> BackupStore<'a> {
+    pub fn new(fs: &'a dyn FileSystem, dir: PathBuf) -> Self {
+        Self { fs, dir }
+    }
+
+    fn environment_dir(&self, project: &str, environment: &str) -> PathBuf {
+        self.dir.join(project).join(environment)
+    }
+
+    fn object_path(&self, project: &str, environment: &str, hash: &str) -> PathBuf {
+        self.environment_dir(project, environment)
+            .join(OBJECTS_DIR)
+            .join(format!("{}.tfstate", hash))
+    }
+
+    /// Save a state backup, reusing the latest backup when the state did not change
+    pub fn save(
+        &self,
+        project: &str,
+        environment: &str,
+        content: &str,
+        reason: &str,
+    ) -> Result<SavedBackup> {
+        let hash = format!("{:x}", Sha256::digest(content.as_bytes()));
+
+        if let Some(latest) = self.list(project, environment)?.pop()
+            && latest.hash == hash
+        {
+            return Ok(SavedBackup {
+                entry: latest,
+                created: false,
+            });
+        }
+
+        let object_path = self.object_path(project, environment, &hash);
+        if !self.fs.exists(&object_path) {
+            self.fs.write(&object_path, content)?;
+        }
+
+        let created_at = Utc::now();
+        let entry = BackupEntry {
+            id: created_at.format("%Y%m%d_%H%M%S_%3f").to_string(),
+            project: project.to_string(),
+            environment: environment.to_string(),
+            created_at,
+            reason: reason.to_string(),
+            hash,
+            summary: StateSummary::parse(content)?,
+        };
+
+        let entry_path = self
+            .environment_dir(project, environment)
+            .join(format!("{}.json", entry.id));
+        self.fs
+            .write(&entry_path, &serde_json::to_string_pretty(&entry)?)?;
+
+        Ok(SavedBackup {
+            entry,
+            created: true,
+        })
+    }
+
+    /// Backups of an environment, oldest first
+    pub fn list(&self, project: &str, environment: &str) -> Result<Vec<BackupEntry>> {
+        let env_dir = self.environment_dir(project, environment);
+
+        if !self.fs.exists(&env_dir) {
+            return Ok(Vec::new());
+        }
+
+        let mut entries = Vec::new();
+
+        for path in self.fs.read_dir(&env_dir)? {
+            if !self.fs.is_file(&path) || path.extension().and_then(|e| e.to_str()) != Some("json")
+            {
+                continue;
+            }
+
+            let content = self.fs.read_to_string(&path)?;
+            let entry: BackupEntry = serde_json::from_str(&content)
+                .with_context(|| format!("Failed to parse backup entry: {:?}", path))?;
+            entries.push(entry);
+        }
+
+        entries.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+
+        Ok(entries)
+    }
+
+    /// Load a backup and its state content
+    pub fn load(&self, project: &str, environment: &str, id: &str) -> Result<(BackupEntry, String)> {
+        let entry = self
+            .list(project, environment)?
+            .into_iter()
+            .find(|e| e.id == id)
+            .with_context(|| format!("Backup not found: {}", id))?;
+
+        let content = self
+            .fs
+            .read_to_string(&self.object_path(project, environment, &entry.hash))
+            .with_context(|| format!("State file of backup {} is missing", id))?;
+
+        Ok((entry, content))
+    }
+
+    /// Apply the retention rules, returning the deleted backups
+    ///
+    /// The newest backup is always kept.
+    pub fn prune(
+        &self,
+        project: &str,
+        environment: &str,
+        config: &BackupConfig,
+    ) -> Result<Vec<BackupEntry>> {
+        self.prune_at(project, environment, config, Utc::now())
+    }
+
+    fn prune_at(
+        &self,
+        project: &str,
+        environment: &str,
+        config: &BackupConfig,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<BackupEntry>> {
+        let entries = self.list(project, environment)?;
+        let max_age = config
+            .max_age_days
+            .map(|days| chrono::Duration::days(days as i64));
+
+        let mut kept = Vec::new();
+        let mut removed = Vec::new();
+
+        // Walk newest first so keep_last counts from the latest backup
+        for (index, entry) in entries.into_iter().rev().enumerate() {
+            let over_count = config.keep_last.is_some_and(|keep| index >= keep.max(1));
+            let too_old = max_age.is_some_and(|age| now - entry.created_at > age);
+
+            if index > 0 && (over_count || too_old) {
+                removed.push(entry);
+            } else {
+                kept.push(entry);
+            }
+        }
+
+        let env_dir = self.environment_dir(project, environment);
+        for entry in &removed {
+            self.fs.remove_file(&env_dir.join(format!("{}.json", entry.id)))?;
+        }
+
+        // Delete state files no longer referenced by any backup
+        let referenced: HashSet<&str> = kept.iter().map(|e| e.hash.as_str()).collect();
+        for entry in &removed {
+            let object_path = self.object_path(project, environment, &entry.hash);
+            if !referenced.contains(entry.hash.as_str()) && self.fs.exists(&object_path) {
+                self.fs.remove_file(&object_path)?;
+            }
+        }
+
+        removed.reverse();
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::MockFileSystem;
+
+    fn state(serial: u64, resources: usize) -> String {
+        let resources: Vec<_> = (0..resources)
+            .map(|i| serde_json::json!({"mode": "managed", "type": "null_resource", "name": format!("r{}", i)}))
+            .collect();
+
+        serde_json::json!({
+            "version": 4,
+            "serial": serial,
+            "lineage": "lineage-1",
+            "resources": resources
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let fs = MockFileSystem::new();
+        let store = BackupStore::new(&fs, PathBuf::from("/infra/.pmp/backups"));
+
+        let saved = store.save("vpc", "dev", &state(3, 2), "manual").unwrap();
+        assert!(saved.created);
+        assert_eq!(saved.entry.summary.serial, Some(3));
+        assert_eq!(saved.entry.summary.resource_count, 2);
+        assert!(fs.has_file(&PathBuf::from(format!(
+            "/infra/.pmp/backups/vpc/dev/objects/{}.tfstate",
+            saved.entry.hash
+        ))));
+
+        let (entry, content) = store.load("vpc", "dev", &saved.entry.id).unwrap();
+        assert_eq!(entry, saved.entry);
+        assert_eq!(content, state(3, 2));
+
+        assert!(store.load("vpc", "dev", "missing").is_err());
+        assert!(store.list("vpc", "prod").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_save_dedups_unchanged_state() {
+        let fs = MockFileSystem::new();
+        let store = BackupStore::new(&fs, PathBuf::from("/backups"));
+
+        let first = store.save("vpc", "dev", &state(3, 2), "manual").unwrap();
+        let second = store.save("vpc", "dev", &state(3, 2), "pre-apply").unwrap();
+
+        assert!(!second.created);
+        assert_eq!(second.entry.id, first.entry.id);
+        assert_eq!(store.list("vpc", "dev").unwrap().len(), 1);
+
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let third = store.save("vpc", "dev", &state(4, 3), "pre-apply").unwrap();
+        assert!(third.created);
+
+        let entries = store.list("vpc", "dev").unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].reason, "pre-apply");
+        assert_eq!(
+            entries[1].summary.diff_from(&entries[0].summary),
+            "serial +1, resources +1"
+        );
+    }
+
+    #[test]
+    fn test_prune_keep_last_and_max_age() {
+        let fs = MockFileSystem::new();
+        let store = BackupStore::new(&fs, PathBuf::from("/backups"));
+
+        for serial in 1..=4 {
+            store.save("vpc", "dev", &state(serial, 1), "manual").unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+
+        let keep_two = BackupConfig {
+            keep_last: Some(2),
+            ..Default::default()
+        };
+        let removed = store.prune("vpc", "dev", &keep_two).unwrap();
+        assert_eq!(removed.len(), 2);
+        assert_eq!(removed[0].summary.serial, Some(1));
+
+        let remaining = store.list("vpc", "dev").unwrap();
+        assert_eq!(remaining.len(), 2);
+        assert_eq!(remaining[0].summary.serial, Some(3));
+        assert!(!fs.has_file(&PathBuf::from(format!(
+            "/backups/vpc/dev/objects/{}.tfstate",
+            removed[0].hash
+        ))));
+
+        // Everything is too old, but the newest backup is always kept
+        let max_age = BackupConfig {
+            max_age_days: Some(7),
+            ..Default::default()
+        };
+        let later = Utc::now() + chrono::Duration::days(30);
+        let removed = store.prune_at("vpc", "dev", &max_age, later).unwrap();
+        assert_eq!(removed.len(), 1);
+
+        let remaining = store.list("vpc", "dev").unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].summary.serial, Some(4));
+    }
+
+    #[test]
+    fn test_backup_config_from_executor_config() {
+        let mut executor_config = HashMap::new();
+        executor_config.insert(
+            "backups".to_string(),
+            serde_json::json!({"path": "state-backups", "keep_last": 10, "max_age_days": 30}),
+        );
+
+        let config = BackupConfig::from_executor_config(&executor_config).unwrap();
+        assert_eq!(config.keep_last, Some(10));
+        assert_eq!(config.max_age_days, Some(30));
+        assert!(config.auto);
+        assert_eq!(
+            config.backup_dir(Path::new("/infra")),
+            PathBuf::from("/infra/state-backups")
+        );
+
+        let default = BackupConfig::from_executor_config(&HashMap::new()).unwrap();
+        assert_eq!(
+            default.backup_dir(Path::new("/infra")),
+            PathBuf::from("/infra/.pmp/backups")
+        );
+    }
+}
@@ -0,0 +1,430 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Parsed Terraform/OpenTofu state
+///
+/// Built from either the raw state format (`state pull`, `terraform.tfstate`) or the JSON
+/// representation of `show -json`. Resources are flattened to one entry per instance.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct StateDocument {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub terraform_version: Option<String>,
+
+    /// State serial (not available from `show -json`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serial: Option<u64>,
+
+    /// State lineage (not available from `show -json`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lineage: Option<String>,
+
+    pub outputs: BTreeMap<String, StateOutput>,
+
+    pub resources: Vec<StateResource>,
+}
+
+/// Root module output value
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StateOutput {
+    pub value: Value,
+
+    #[serde(default)]
+    pub sensitive: bool,
+
+    /// Type constraint of the value (e.g., "string" or ["list", "string"])
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub value_type: Option<Value>,
+}
+
+/// Resource or data source mode
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ResourceMode {
+    Managed,
+    Data,
+}
+
+/// A single resource instance
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StateResource {
+    /// Full address (e.g., `module.network.aws_subnet.private["a"]`)
+    pub address: String,
+
+    pub mode: ResourceMode,
+
+    #[serde(rename = "type")]
+    pub resource_type: String,
+
+    pub name: String,
+
+    /// Module path (e.g., `module.network`), None for the root module
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module: Option<String>,
+
+    /// `count` or `for_each` key of the instance
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<Value>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+
+    pub attributes: Value,
+}
+
+impl StateDocument {
+    /// Parse a state document in the raw state format or the `show -json` format
+    pub fn parse(content: &str) -> Result<Self> {
+        let value: Value = serde_json::from_str(content).context("Failed to parse state JSON")?;
+
+        if value.get("format_version").is_some() {
+            let show: ShowJson =
+                serde_json::from_value(value).context("Invalid show -json state document")?;
+            Ok(Self::from_show_json(show))
+        } else {
+            let raw: RawState = serde_json::from_value(value).context("Invalid state document")?;
+            Ok(Self::from_raw(raw))
+        }
+    }
+
+    /// Managed resources (data sources excluded)
+    pub fn managed_resources(&self) -> impl Iterator<Item = &StateResource> {
+        self.resources
+            .iter()
+            .filter(|r| r.mode == ResourceMode::Managed)
+    }
+
+    /// Find a resource instance by address
+    pub fn resource(&self, address: &str) -> Option<&StateResource> {
+        self.resources.iter().find(|r| r.address == address)
+    }
+
+    /// Resource instances matched by an address: the instance itself, every instance of a
+    /// resource given without an index key, or every resource of a module
+    pub fn matching(&self, address: &str) -> Vec<&StateResource> {
+        let instance_prefix = format!("{}[", address);
+        let module_prefix = format!("{}.", address);
+
+        self.resources
+            .iter()
+            .filter(|r| {
+                r.address == address
+                    || r.address.starts_with(&instance_prefix)
+                    || (address.starts_with("module.") && r.address.starts_with(&module_prefix))
+            })
+            .collect()
+    }
+
+    fn from_raw(raw: RawState) -> Self {
+        let mut resources = Vec::new();
+
+        for resource in raw.resources {
+            let address = resource_address(
+                resource.module.as_deref(),
+                resource.mode,
+                &resource.resource_type,
+                &resource.name,
+            );
+
+            for instance in resource.instances {
+                resources.push(StateResource {
+                    address: format!("{}{}", address, index_suffix(instance.index_key.as_ref())),
+                    mode: resource.mode,
+                    resource_type: resource.resource_type.clone(),
+                    name: resource.name.clone(),
+                    module: resource.module.clone(),
+                    index: instance.index_key,
+                    provider: resource.provider.clone(),
+                    attributes: instance.attributes,
+                });
+            }
+        }
+
+        Self {
+            terraform_version: raw.terraform_version,
+            serial: raw.serial,
+            lineage: raw.lineage,
+            outputs: raw.outputs,
+            resources,
+        }
+    }
+
+    fn from_show_json(show: ShowJson) -> Self {
+        let mut document = Self {
+            terraform_version: show.terraform_version,
+            ..Self::default()
+        };
+
+        if let Some(values) = show.values {
+            document.outputs = values.outputs;
+            collect_module_resources(values.root_module, &mut document.resources);
+        }
+
+        document
+    }
+}
+
+/// Address of a resource without its instance key
+fn resource_address(
+    module: Option<&str>,
+    mode: ResourceMode,
+    resource_type: &str,
+    name: &str,
+) -> String {
+    let mut address = String::new();
+
+    if let Some(module) = module {
+        address.push_str(module);
+        address.push('.');
+    }
+    if mode == ResourceMode::Data {
+        address.push_str("data.");
+    }
+    address.push_str(&format!("{}.{}", resource_type, name));
+
+    address
+}
+
+/// Address suffix for a `count` (`[0]`) or `for_each` (`["a"]`) instance key
+fn index_suffix(index: Option<&Value>) -> String {
+    match index {
+        Some(Value::Number(n)) => format!("[{}]", n),
+        Some(Value::String(s)) => format!("[{:?}]", s),
+        _ => String::new(),
+    }
+}
+
+fn collect_module_resources(module: ShowModule, resources: &mut Vec<StateResource>) {
+    for resource in module.resources {
+        resources.push(StateResource {
+            address: resource.address,
+            mode: resource.mode,
+            resource_type: resource.resource_type,
+            name: resource.name,
+            module: module.address.clone(),
+            index: resource.index,
+            provider: resource.provider_name,
+            attributes: resource.values,
+        });
+    }
+
+    for child in module.child_modules {
+        collect_module_resources(child, resources);
+    }
+}
+
+/// Raw state format (version 4)
+#[derive(Deserialize)]
+struct RawState {
+    terraform_version: Option<String>,
+    serial: Option<u64>,
+    lineage: Option<String>,
+    #[serde(default)]
+    outputs: BTreeMap<String, StateOutput>,
+    #[serde(default)]
+    resources: Vec<RawResource>,
+}
+
+#[derive(Deserialize)]
+struct RawResource {
+    module: Option<String>,
+    mode: ResourceMode,
+    #[serde(rename = "type")]
+    resource_type: String,
+    name: String,
+    provider: Option<String>,
+    #[serde(default)]
+    instances: Vec<RawInstance>,
+}
+
+#[derive(Deserialize)]
+struct RawInstance {
+    index_key: Option<Value>,
+    #[serde(default)]
+    attributes: Value,
+}
+
+/// `show -json` format
+#[derive(Deserialize)]
+struct ShowJson {
+    terraform_version: Option<String>,
+    values: Option<ShowValues>,
+}
+
+#[derive(Deserialize)]
+struct ShowValues {
+    #[serde(default)]
+    outputs: BTreeMap<String, StateOutput>,
+    root_module: ShowModule,
+}
+
+#[derive(Deserialize)]
+struct ShowModule {
+    address: Option<String>,
+    #[serde(default)]
+    resources: Vec<ShowResource>,
+    #[serde(default)]
+    child_modules: Vec<ShowModule>,
+}
+
+#[derive(Deserialize)]
+struct ShowResource {
+    address: String,
+    mode: ResourceMode,
+    #[serde(rename = "type")]
+    resource_type: String,
+    name: String,
+    index: Option<Value>,
+    provider_name: Option<String>,
+    #[serde(default)]
+    values: Value,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RAW_STATE: &str = r#"{
+        "version": 4,
+        "terraform_version": "1.8.0",
+        "serial": 12,
+        "lineage": "abc-123",
+        "outputs": {
+            "vpc_id": {"value": "vpc-1", "type": "string"},
+            "db_password": {"value": "secret", "type": "string", "sensitive": true}
+        },
+        "resources": [
+            {
+                "mode": "managed",
+                "type": "aws_vpc",
+                "name": "main",
+                "provider": "provider[\"registry.opentofu.org/hashicorp/aws\"]",
+                "instances": [{"attributes": {"id": "vpc-1", "cidr_block": "10.0.0.0/16"}}]
+            },
+            {
+                "module": "module.network",
+                "mode": "managed",
+                "type": "aws_subnet",
+                "name": "private",
+                "provider": "provider[\"registry.opentofu.org/hashicorp/aws\"]",
+                "instances": [
+                    {"index_key": "a", "attributes": {"id": "subnet-a"}},
+                    {"index_key": "b", "attributes": {"id": "subnet-b"}}
+                ]
+            },
+            {
+                "mode": "data",
+                "type": "aws_ami",
+                "name": "ubuntu",
+                "provider": "provider[\"registry.opentofu.org/hashicorp/aws\"]",
+                "instances": [{"index_key": 0, "attributes": {"id": "ami-1"}}]
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_parse_raw_state() {
+        let state = StateDocument::parse(RAW_STATE).unwrap();
+
+        assert_eq!(state.serial, Some(12));
+        assert_eq!(state.lineage.as_deref(), Some("abc-123"));
+        assert_eq!(state.outputs["vpc_id"].value, "vpc-1");
+        assert!(state.outputs["db_password"].sensitive);
+
+        let addresses: Vec<_> = state.resources.iter().map(|r| r.address.as_str()).collect();
+        assert_eq!(
+            addresses,
+            vec![
+                "aws_vpc.main",
+                "module.network.aws_subnet.private[\"a\"]",
+                "module.network.aws_subnet.private[\"b\"]",
+                "data.aws_ami.ubuntu[0]",
+            ]
+        );
+        assert_eq!(state.managed_resources().count(), 3);
+
+        let subnet = state
+            .resource("module.network.aws_subnet.private[\"b\"]")
+            .unwrap();
+        assert_eq!(subnet.module.as_deref(), Some("module.network"));
+        assert_eq!(subnet.attributes["id"], "subnet-b");
+    }
+
+    #[test]
+    fn test_matching() {
+        let state = StateDocument::parse(RAW_STATE).unwrap();
+        let addresses = |address| {
+            state
+                .matching(address)
+                .iter()
+                .map(|r| r.address.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(addresses("aws_vpc.main"), vec!["aws_vpc.main"]);
+        assert_eq!(addresses("module.network").len(), 2);
+        assert_eq!(addresses("module.network.aws_subnet.private").len(), 2);
+        assert_eq!(
+            addresses("module.network.aws_subnet.private[\"a\"]"),
+            vec!["module.network.aws_subnet.private[\"a\"]"]
+        );
+        assert!(addresses("aws_vpc.mai").is_empty());
+        assert!(addresses("module.net").is_empty());
+    }
+
+    #[test]
+    fn test_parse_show_json() {
+        let show = r#"{
+            "format_version": "1.0",
+            "terraform_version": "1.8.0",
+            "values": {
+                "outputs": {"vpc_id": {"sensitive": false, "value": "vpc-1", "type": "string"}},
+                "root_module": {
+                    "resources": [{
+                        "address": "aws_vpc.main",
+                        "mode": "managed",
+                        "type": "aws_vpc",
+                        "name": "main",
+                        "provider_name": "registry.opentofu.org/hashicorp/aws",
+                        "values": {"id": "vpc-1"}
+                    }],
+                    "child_modules": [{
+                        "address": "module.network",
+                        "resources": [{
+                            "address": "module.network.aws_subnet.private[0]",
+                            "mode": "managed",
+                            "type": "aws_subnet",
+                            "name": "private",
+                            "index": 0,
+                            "provider_name": "registry.opentofu.org/hashicorp/aws",
+                            "values": {"id": "subnet-a"}
+                        }]
+                    }]
+                }
+            }
+        }"#;
+
+        let state = StateDocument::parse(show).unwrap();
+
+        assert_eq!(state.serial, None);
+        assert_eq!(state.outputs["vpc_id"].value, "vpc-1");
+        assert_eq!(state.resources.len(), 2);
+
+        let subnet = state
+            .resource("module.network.aws_subnet.private[0]")
+            .unwrap();
+        assert_eq!(subnet.module.as_deref(), Some("module.network"));
+        assert_eq!(subnet.index, Some(serde_json::json!(0)));
+    }
+
+    #[test]
+    fn test_parse_empty_show_json() {
+        let state =
+            StateDocument::parse(r#"{"format_version": "1.0", "terraform_version": "1.8.0"}"#)
+                .unwrap();
+
+        assert!(state.resources.is_empty());
+        assert!(state.outputs.is_empty());
+    }
+}
@@ -0,0 +1,193 @@
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::StateSummary;
+use crate::traits::FileSystem;
+
+/// Build the target backend configuration of a state migration
+///
+/// The configuration is read from a YAML/JSON file (`--backend-file`) or assembled from a
+/// backend type and `key=value` parameters. Parameters given on the command line override
+/// the ones in the file. The result has the same shape as `spec.executor.config.backend`.
+pub fn parse_backend_target(
+    fs: &dyn FileSystem,
+    backend_type: Option<&str>,
+    backend_file: Option<&Path>,
+    params: &[String],
+) -> Result<Value> {
+    let mut backend = match backend_file {
+        Some(file) => {
+            let content = fs
+                .read_to_string(file)
+                .with_context(|| format!("Failed to read backend file: {}", file.display()))?;
+            let value: Value = serde_yaml::from_str(&content)
+                .with_context(|| format!("Invalid backend file: {}", file.display()))?;
+
+            match value {
+                Value::Object(map) => map,
+                _ => anyhow::bail!("Backend file must contain an object: {}", file.display()),
+            }
+        }
+        None => serde_json::Map::new(),
+    };
+
+    if let Some(backend_type) = backend_type {
+        backend.insert("type".to_string(), Value::String(backend_type.to_string()));
+    }
+
+    for param in params {
+        let (key, value) = param.split_once('=').with_context(|| {
+            format!("Invalid backend parameter '{}' (expected key=value)", param)
+        })?;
+        backend.insert(key.trim().to_string(), parse_param_value(value.trim()));
+    }
+
+    match backend.get("type") {
+        Some(Value::String(_)) => Ok(Value::Object(backend)),
+        Some(_) => anyhow::bail!("Backend type must be a string"),
+        None => anyhow::bail!(
+            "Target backend type is required (positional argument or 'type' in --backend-file)"
+        ),
+    }
+}
+
+/// Command line parameter value: booleans and integers are typed, everything else is a string
+fn parse_param_value(value: &str) -> Value {
+    match value {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => value
+            .parse::<i64>()
+            .map(Value::from)
+            .unwrap_or_else(|_| Value::String(value.to_string())),
+    }
+}
+
+/// Executor configuration with the backend replaced by the migration target
+pub fn with_backend(
+    executor_config: &HashMap<String, Value>,
+    backend: &Value,
+) -> HashMap<String, Value> {
+    let mut config = executor_config.clone();
+    config.insert("backend".to_string(), backend.clone());
+    config
+}
+
+/// Backend type of an executor configuration (`local` when no backend is configured)
+pub fn backend_type(executor_config: &HashMap<String, Value>) -> &str {
+    executor_config
+        .get("backend")
+        .and_then(|b| b.get("type"))
+        .and_then(|t| t.as_str())
+        .unwrap_or("local")
+}
+
+/// Check that a migrated state matches the state before the migration
+///
+/// Lineage and managed resource count must be unchanged. `None` means no state exists.
+pub fn verify_migration(before: Option<&StateSummary>, after: Option<&StateSummary>) -> Result<()> {
+    let (before, after) = match (before, after) {
+        (None, None) => return Ok(()),
+        (Some(_), None) => anyhow::bail!("State is missing from the target backend"),
+        (None, Some(after)) if after.resource_count == 0 => return Ok(()),
+        (None, Some(after)) => anyhow::bail!(
+            "Target backend already holds a state with {} resource(s)",
+            after.resource_count
+        ),
+        (Some(before), Some(after)) => (before, after),
+    };
+
+    if before.lineage != after.lineage {
+        anyhow::bail!(
+            "State lineage changed from {} to {}",
+            before.lineage.as_deref().unwrap_or("-"),
+            after.lineage.as_deref().unwrap_or("-")
+        );
+    }
+
+    if before.resource_count != after.resource_count {
+        anyhow::bail!(
+            "Resource count changed from {} to {}",
+            before.resource_count,
+            after.resource_count
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::MockFileSystem;
+    use std::path::PathBuf;
+
+    fn summary(lineage: &str, resource_count: usize) -> StateSummary {
+        StateSummary {
+            serial: Some(4),
+            lineage: Some(lineage.to_string()),
+            resource_count,
+        }
+    }
+
+    #[test]
+    fn test_parse_backend_target_from_params() {
+        let fs = MockFileSystem::new();
+        let params = vec![
+            "conn_str=postgres://db/state".to_string(),
+            "skip_schema_creation=true".to_string(),
+        ];
+
+        let backend = parse_backend_target(&fs, Some("pg"), None, &params).unwrap();
+
+        assert_eq!(
+            backend,
+            serde_json::json!({
+                "type": "pg",
+                "conn_str": "postgres://db/state",
+                "skip_schema_creation": true
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_backend_target_from_file() {
+        let fs = MockFileSystem::new();
+        let file = PathBuf::from("/backend.yaml");
+        fs.write(&file, "type: s3\nbucket: state\nregion: eu-west-1\n")
+            .unwrap();
+
+        let params = vec!["region=us-east-1".to_string()];
+        let backend = parse_backend_target(&fs, None, Some(&file), &params).unwrap();
+
+        assert_eq!(backend["type"], "s3");
+        assert_eq!(backend["bucket"], "state");
+        assert_eq!(backend["region"], "us-east-1");
+    }
+
+    #[test]
+    fn test_parse_backend_target_requires_type() {
+        let fs = MockFileSystem::new();
+        let params = vec!["bucket=state".to_string()];
+
+        assert!(parse_backend_target(&fs, None, None, &params).is_err());
+        assert!(parse_backend_target(&fs, Some("s3"), None, &["bucket".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_verify_migration() {
+        assert!(verify_migration(None, None).is_ok());
+        assert!(verify_migration(Some(&summary("abc", 3)), Some(&summary("abc", 3))).is_ok());
+
+        let err = verify_migration(Some(&summary("abc", 3)), Some(&summary("def", 3))).unwrap_err();
+        assert!(err.to_string().contains("lineage"));
+
+        let err = verify_migration(Some(&summary("abc", 3)), Some(&summary("abc", 2))).unwrap_err();
+        assert!(err.to_string().contains("Resource count"));
+
+        assert!(verify_migration(Some(&summary("abc", 3)), None).is_err());
+        assert!(verify_migration(None, Some(&summary("abc", 1))).is_err());
+    }
+}
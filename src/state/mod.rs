@@ -0,0 +1,18 @@
+//! Terraform/OpenTofu state handling for PMP.
+//!
+//! State is always read through the executor (`state pull`), so everything here works the
+//! same for local and remote backends.
+
+mod audit;
+mod backup;
+mod document;
+mod migration;
+mod snapshot;
+mod summary;
+
+pub use audit::{AuditEntry, AuditLog};
+pub use backup::{BackupConfig, BackupEntry, BackupStore, SavedBackup};
+pub use document::{ResourceMode, StateDocument, StateResource};
+pub use migration::{backend_type, parse_backend_target, verify_migration, with_backend};
+pub use snapshot::{SnapshotStore, StateSnapshot};
+pub use summary::StateSummary;
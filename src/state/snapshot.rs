@@ -0,0 +1,152 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::StateDocument;
+use crate::traits::FileSystem;
+
+/// Default snapshot location, relative to the infrastructure root
+pub const DEFAULT_SNAPSHOT_DIR: &str = ".pmp/cache/state";
+
+/// Parsed state of one environment, cached for infrastructure-wide search
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StateSnapshot {
+    pub project: String,
+
+    pub environment: String,
+
+    pub captured_at: DateTime<Utc>,
+
+    /// State with sensitive output values removed
+    pub state: StateDocument,
+}
+
+/// Per-environment state snapshots stored as `<dir>/<project>/<environment>.json`
+///
+/// Snapshots are refreshed whenever pmp changes or refreshes a state, so search can query
+/// real resources and outputs without reaching every backend.
+pub struct SnapshotStore<'a> {
+    fs: &'a dyn FileSystem,
+    dir: PathBuf,
+}
+
+impl<'a> SnapshotStore<'a> {
+    pub fn new(fs: &'a dyn FileSystem, dir: PathBuf) -> Self {
+        Self { fs, dir }
+    }
+
+    /// Snapshot store of an infrastructure
+    pub fn for_infrastructure(fs: &'a dyn FileSystem, infrastructure_root: &Path) -> Self {
+        Self::new(fs, infrastructure_root.join(DEFAULT_SNAPSHOT_DIR))
+    }
+
+    fn snapshot_path(&self, project: &str, environment: &str) -> PathBuf {
+        self.dir.join(project).join(format!("{}.json", environment))
+    }
+
+    /// Store the state of an environment, replacing its previous snapshot
+    pub fn save(
+        &self,
+        project: &str,
+        environment: &str,
+        mut state: StateDocument,
+    ) -> Result<StateSnapshot> {
+        for output in state.outputs.values_mut().filter(|o| o.sensitive) {
+            output.value = serde_json::Value::Null;
+        }
+
+        let snapshot = StateSnapshot {
+            project: project.to_string(),
+            environment: environment.to_string(),
+            captured_at: Utc::now(),
+            state,
+        };
+
+        let path = self.snapshot_path(project, environment);
+        self.fs
+            .write(&path, &serde_json::to_string(&snapshot)?)
+            .with_context(|| format!("Failed to write state snapshot: {}", path.display()))?;
+
+        Ok(snapshot)
+    }
+
+    /// Forget the snapshot of an environment (e.g., after its state became empty)
+    pub fn remove(&self, project: &str, environment: &str) -> Result<()> {
+        let path = self.snapshot_path(project, environment);
+
+        if self.fs.exists(&path) {
+            self.fs.remove_file(&path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Delete all snapshots; returns whether any existed
+    pub fn clear(&self) -> Result<bool> {
+        if !self.fs.exists(&self.dir) {
+            return Ok(false);
+        }
+
+        self.fs.remove_dir_all(&self.dir)?;
+        Ok(true)
+    }
+
+    /// Snapshot of an environment, if one was taken
+    pub fn load(&self, project: &str, environment: &str) -> Result<Option<StateSnapshot>> {
+        let path = self.snapshot_path(project, environment);
+
+        if !self.fs.exists(&path) {
+            return Ok(None);
+        }
+
+        let content = self.fs.read_to_string(&path)?;
+        let snapshot = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid state snapshot: {}", path.display()))?;
+
+        Ok(Some(snapshot))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::MockFileSystem;
+
+    #[test]
+    fn test_save_load_remove() {
+        let fs = MockFileSystem::new();
+        let store = SnapshotStore::for_infrastructure(&fs, Path::new("/infra"));
+
+        assert_eq!(store.load("vpc", "dev").unwrap(), None);
+
+        let state = StateDocument::parse(
+            r#"{
+                "version": 4,
+                "serial": 3,
+                "lineage": "abc",
+                "outputs": {
+                    "vpc_id": {"value": "vpc-1", "type": "string"},
+                    "password": {"value": "hunter2", "type": "string", "sensitive": true}
+                },
+                "resources": []
+            }"#,
+        )
+        .unwrap();
+        let saved = store.save("vpc", "dev", state).unwrap();
+
+        assert!(fs.has_file(Path::new("/infra/.pmp/cache/state/vpc/dev.json")));
+        assert_eq!(
+            saved.state.outputs["password"].value,
+            serde_json::Value::Null
+        );
+        assert_eq!(saved.state.outputs["vpc_id"].value, "vpc-1");
+        assert_eq!(store.load("vpc", "dev").unwrap(), Some(saved));
+
+        store.remove("vpc", "dev").unwrap();
+        assert_eq!(store.load("vpc", "dev").unwrap(), None);
+
+        assert!(store.clear().unwrap());
+        assert!(!fs.exists(Path::new("/infra/.pmp/cache/state")));
+    }
+}
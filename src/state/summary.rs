@@ -0,0 +1,124 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Identifying fields of a Terraform/OpenTofu state document
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct StateSummary {
+    /// State serial, incremented on every write
+    pub serial: Option<u64>,
+
+    /// State lineage, assigned when the state is first created
+    pub lineage: Option<String>,
+
+    /// Number of managed resources (data sources are not counted)
+    pub resource_count: usize,
+}
+
+impl StateSummary {
+    /// Summarize a state document
+    pub fn parse(content: &str) -> Result<Self> {
+        let state: serde_json::Value =
+            serde_json::from_str(content).context("Failed to parse state JSON")?;
+
+        let resource_count = state
+            .get("resources")
+            .and_then(|r| r.as_array())
+            .map(|resources| {
+                resources
+                    .iter()
+                    .filter(|r| r.get("mode").and_then(|m| m.as_str()).unwrap_or("managed") == "managed")
+                    .count()
+            })
+            .unwrap_or(0);
+
+        Ok(Self {
+            serial: state.get("serial").and_then(|s| s.as_u64()),
+            lineage: state
+                .get("lineage")
+                .and_then(|l| l.as_str())
+                .map(str::to_string),
+            resource_count,
+        })
+    }
+
+    /// Describe how this state differs from an older one (e.g., "serial +2, resources +1")
+    pub fn diff_from(&self, older: &StateSummary) -> String {
+        let mut parts = Vec::new();
+
+        if self.lineage != older.lineage {
+            parts.push("lineage changed".to_string());
+        }
+
+        if let (Some(serial), Some(older_serial)) = (self.serial, older.serial)
+            && serial != older_serial
+        {
+            parts.push(format!("serial {:+}", serial as i64 - older_serial as i64));
+        }
+
+        if self.resource_count != older.resource_count {
+            parts.push(format!(
+                "resources {:+}",
+                self.resource_count as i64 - older.resource_count as i64
+            ));
+        }
+
+        if parts.is_empty() {
+            "no changes".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_counts_managed_resources() {
+        let state = r#"{
+            "version": 4,
+            "serial": 7,
+            "lineage": "abc-123",
+            "resources": [
+                {"mode": "managed", "type": "aws_vpc", "name": "main"},
+                {"mode": "data", "type": "aws_ami", "name": "ubuntu"},
+                {"mode": "managed", "type": "aws_subnet", "name": "a"}
+            ]
+        }"#;
+
+        let summary = StateSummary::parse(state).unwrap();
+
+        assert_eq!(summary.serial, Some(7));
+        assert_eq!(summary.lineage.as_deref(), Some("abc-123"));
+        assert_eq!(summary.resource_count, 2);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_json() {
+        assert!(StateSummary::parse("not json").is_err());
+    }
+
+    #[test]
+    fn test_diff_from() {
+        let older = StateSummary {
+            serial: Some(3),
+            lineage: Some("abc".to_string()),
+            resource_count: 5,
+        };
+        let newer = StateSummary {
+            serial: Some(5),
+            lineage: Some("abc".to_string()),
+            resource_count: 4,
+        };
+
+        assert_eq!(newer.diff_from(&older), "serial +2, resources -1");
+        assert_eq!(older.diff_from(&older), "no changes");
+
+        let migrated = StateSummary {
+            lineage: Some("def".to_string()),
+            ..older.clone()
+        };
+        assert_eq!(migrated.diff_from(&older), "lineage changed");
+    }
+}
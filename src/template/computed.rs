@@ -0,0 +1,218 @@
+//! Computed inputs and template-level validation rules
+//!
+//! Computed inputs (`type: computed`) are never prompted: their `value` is a Handlebars
+//! expression rendered over the inputs collected before them. `spec.validations` rules relate
+//! several inputs and are checked once all template inputs are collected, whether they were
+//! prompted, passed with `--inputs` or sent by the web UI.
+
+use anyhow::{Context, Result, bail};
+use handlebars::Handlebars;
+use serde_json::Value;
+use std::collections::HashMap;
+
+use super::helpers::register_helpers;
+use super::metadata::{InputDefinition, InputValidationRule};
+
+/// Set the value of a computed input, returning false for inputs that are prompted
+///
+/// Computed inputs whose conditions are not met get no value.
+pub fn apply_computed_input(
+    input_def: &InputDefinition,
+    inputs: &mut HashMap<String, Value>,
+    project_name: &str,
+    environment_name: Option<&str>,
+) -> Result<bool> {
+    let Some(expression) = input_def.computed_expression() else {
+        return Ok(false);
+    };
+
+    if input_def.should_show(inputs) {
+        let rendered = render(expression, inputs, project_name, environment_name)
+            .with_context(|| format!("Failed to compute input '{}'", input_def.name))?;
+        inputs.insert(input_def.name.clone(), parse_rendered(rendered));
+    }
+
+    Ok(true)
+}
+
+/// Value of a rendered expression: a boolean or number when it renders to exactly such a JSON
+/// literal (`true`, `4`, `0.5`), otherwise the string itself (so `010`, `a: b` or `[x]` stay
+/// strings)
+fn parse_rendered(rendered: String) -> Value {
+    match serde_json::from_str(&rendered) {
+        Ok(value @ (Value::Bool(_) | Value::Number(_))) if rendered.trim() == rendered => value,
+        _ => Value::String(rendered),
+    }
+}
+
+/// Check validation rules against the collected inputs, reporting every failed rule
+pub fn check_validations(
+    rules: &[InputValidationRule],
+    inputs: &HashMap<String, Value>,
+    project_name: &str,
+    environment_name: Option<&str>,
+) -> Result<()> {
+    let mut failures = Vec::new();
+
+    for rule in rules {
+        if !rule.conditions.iter().all(|c| c.is_met(inputs)) {
+            continue;
+        }
+
+        let rendered = render(&rule.rule, inputs, project_name, environment_name)
+            .with_context(|| format!("Failed to evaluate validation rule '{}'", rule.rule))?;
+
+        match rendered.trim() {
+            "true" => {}
+            "false" => failures.push(rule.message.as_str()),
+            other => bail!(
+                "Validation rule '{}' must render to true or false, got '{}'",
+                rule.rule,
+                other
+            ),
+        }
+    }
+
+    if !failures.is_empty() {
+        bail!("Input validation failed:\n  - {}", failures.join("\n  - "));
+    }
+
+    Ok(())
+}
+
+/// Render an expression with the inputs and the project/environment variables
+fn render(
+    expression: &str,
+    inputs: &HashMap<String, Value>,
+    project_name: &str,
+    environment_name: Option<&str>,
+) -> Result<String> {
+    let mut handlebars = Handlebars::new();
+    handlebars.register_escape_fn(handlebars::no_escape);
+    register_helpers(&mut handlebars);
+
+    let mut data = inputs.clone();
+    for name in ["_name", "_project_name"] {
+        data.insert(name.to_string(), Value::String(project_name.to_string()));
+    }
+    data.insert(
+        "_project_name_underscores".to_string(),
+        Value::String(project_name.replace('-', "_")),
+    );
+    data.insert(
+        "_project_name_hyphens".to_string(),
+        Value::String(project_name.replace('_', "-")),
+    );
+    if let Some(environment_name) = environment_name {
+        data.insert(
+            "_environment".to_string(),
+            Value::String(environment_name.to_string()),
+        );
+    }
+
+    Ok(handlebars.render_template(expression, &data)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn inputs(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_apply_computed_input() {
+        let specs: Vec<InputDefinition> = serde_yaml::from_str(
+            r#"
+- name: bucket
+  type: computed
+  value: "{{_project_name}}-{{_environment}}-{{lower region}}"
+- name: max_replicas
+  type: computed
+  value: "{{mul min_replicas 2}}"
+- name: subnet
+  type: computed
+  value: "{{cidrsubnet vpc_cidr 8 1}}"
+  if:
+    - input_name: vpc_cidr
+- name: region
+"#,
+        )
+        .unwrap();
+
+        let mut values = inputs(json!({"region": "EU-WEST-1", "min_replicas": 2}));
+        for spec in &specs {
+            apply_computed_input(spec, &mut values, "api", Some("dev")).unwrap();
+        }
+
+        assert_eq!(values["bucket"], json!("api-dev-eu-west-1"));
+        assert_eq!(values["max_replicas"], json!(4));
+        assert!(!values.contains_key("subnet"));
+        assert!(!apply_computed_input(&specs[3], &mut values, "api", None).unwrap());
+    }
+
+    #[test]
+    fn test_parse_rendered() {
+        for (rendered, expected) in [
+            ("true", json!(true)),
+            ("4", json!(4)),
+            ("-0.5", json!(-0.5)),
+            ("010", json!("010")),
+            (" 4", json!(" 4")),
+            ("a: b", json!("a: b")),
+            ("[x]", json!("[x]")),
+            ("null", json!("null")),
+            ("yes", json!("yes")),
+            ("", json!("")),
+        ] {
+            assert_eq!(
+                parse_rendered(rendered.to_string()),
+                expected,
+                "{:?}",
+                rendered
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_validations() {
+        let rules: Vec<InputValidationRule> = serde_yaml::from_str(
+            r#"
+- rule: "{{lte min_replicas max_replicas}}"
+  message: "min_replicas must not exceed max_replicas"
+- rule: "{{cidrcontains vpc_cidr subnet}}"
+  message: "subnet must be within vpc_cidr"
+  if:
+    - input_name: subnet
+"#,
+        )
+        .unwrap();
+
+        let valid =
+            inputs(json!({"min_replicas": 2, "max_replicas": 4, "vpc_cidr": "10.0.0.0/16"}));
+        assert!(check_validations(&rules, &valid, "api", None).is_ok());
+
+        let invalid = inputs(json!({
+            "min_replicas": 5,
+            "max_replicas": 4,
+            "vpc_cidr": "10.0.0.0/16",
+            "subnet": "10.1.0.0/24"
+        }));
+        let err = check_validations(&rules, &invalid, "api", None).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Input validation failed:\n  - min_replicas must not exceed max_replicas\n  - subnet must be within vpc_cidr"
+        );
+
+        let not_boolean: Vec<InputValidationRule> =
+            serde_yaml::from_str("- rule: \"{{min_replicas}}\"\n  message: m").unwrap();
+        assert!(
+            check_validations(&not_boolean, &valid, "api", None)
+                .unwrap_err()
+                .to_string()
+                .contains("must render to true or false, got '2'")
+        );
+    }
+}
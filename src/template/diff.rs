@@ -0,0 +1,742 @@
+//! Comparison of two templates
+//!
+//! Compares two versions of one template, or two templates across packs: inputs,
+//! dependencies, plugins, hooks and the files under `src/`. Optionally renders both templates
+//! with the same inputs and compares the generated files.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use super::inheritance::ResolvedTemplate;
+use super::merge::matching_lines;
+use super::metadata::{HooksConfig, InputDefinition, TemplateSpec};
+use super::renderer::TemplateRenderer;
+use crate::traits::FileSystem;
+
+/// Lines of unchanged context around each hunk of a file diff
+const DIFF_CONTEXT: usize = 3;
+
+/// One side of a comparison
+pub struct DiffSide<'a> {
+    /// Display label (e.g., "core/api@1.0.0")
+    pub label: String,
+
+    pub template: &'a ResolvedTemplate,
+
+    /// Template pack directory, for partials
+    pub pack_path: &'a Path,
+}
+
+impl DiffSide<'_> {
+    fn render(
+        &self,
+        ctx: &crate::context::Context,
+        inputs: &HashMap<String, Value>,
+    ) -> Result<BTreeMap<String, String>> {
+        TemplateRenderer::new_with_partials(&*ctx.fs, Some(self.pack_path))
+            .context("Failed to initialize template renderer with partials")?
+            .render_resolved(ctx, self.template, inputs)
+    }
+}
+
+/// Differences between two templates
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplateDiff {
+    pub from: String,
+
+    pub to: String,
+
+    pub inputs: Vec<InputDiff>,
+
+    pub dependencies: Vec<ItemDiff>,
+
+    pub plugins: Vec<ItemDiff>,
+
+    pub hooks: Vec<ItemDiff>,
+
+    /// Changes to the source files under src/
+    pub files: Vec<FileDiff>,
+
+    /// Changes to the generated files, when both templates were rendered with the same inputs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rendered: Option<Vec<FileDiff>>,
+}
+
+/// A change to one input
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InputDiff {
+    pub name: String,
+
+    #[serde(flatten)]
+    pub change: InputDiffKind,
+
+    /// Whether environments created from the old template may need attention
+    pub breaking: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum InputDiffKind {
+    Added {
+        default: Option<Value>,
+    },
+    Removed,
+    TypeChanged {
+        from: String,
+        to: String,
+    },
+    DefaultChanged {
+        from: Option<Value>,
+        to: Option<Value>,
+    },
+    OptionsAdded {
+        options: Vec<String>,
+    },
+    OptionsRemoved {
+        options: Vec<String>,
+    },
+}
+
+/// A change to a dependency, plugin or hook
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ItemDiff {
+    pub name: String,
+
+    pub change: ItemChange,
+
+    pub breaking: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemChange {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// A change to one file
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileDiff {
+    pub path: String,
+
+    pub change: ItemChange,
+
+    /// Unified diff of the file contents
+    pub diff: String,
+}
+
+impl TemplateDiff {
+    /// Compare two resolved templates
+    ///
+    /// With `inputs`, both templates are also rendered (template defaults fill missing inputs)
+    /// and the generated files are compared.
+    pub fn compare(
+        ctx: &crate::context::Context,
+        from: &DiffSide,
+        to: &DiffSide,
+        inputs: Option<&HashMap<String, Value>>,
+    ) -> Result<Self> {
+        let from_spec = &from.template.resource.spec;
+        let to_spec = &to.template.resource.spec;
+
+        let from_files = source_files(&*ctx.fs, &from.template.base_paths)?;
+        let to_files = source_files(&*ctx.fs, &to.template.base_paths)?;
+
+        let rendered = match inputs {
+            Some(inputs) => {
+                let from_rendered = from.render(ctx, inputs)?;
+                let to_rendered = to.render(ctx, inputs)?;
+                Some(diff_files(&from_rendered, &to_rendered))
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            from: from.label.clone(),
+            to: to.label.clone(),
+            inputs: diff_inputs(&from_spec.inputs, &to_spec.inputs),
+            dependencies: diff_dependencies(from_spec, to_spec),
+            plugins: diff_plugins(from_spec, to_spec),
+            hooks: diff_hooks(from_spec.hooks.as_ref(), to_spec.hooks.as_ref()),
+            files: diff_files(&from_files, &to_files),
+            rendered,
+        })
+    }
+
+    /// Number of changes that may break environments created from the old template
+    pub fn breaking_changes(&self) -> usize {
+        self.inputs.iter().filter(|i| i.breaking).count()
+            + self
+                .dependencies
+                .iter()
+                .chain(&self.plugins)
+                .chain(&self.hooks)
+                .filter(|i| i.breaking)
+                .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+            && self.dependencies.is_empty()
+            && self.plugins.is_empty()
+            && self.hooks.is_empty()
+            && self.files.is_empty()
+            && self.rendered.as_ref().is_none_or(|r| r.is_empty())
+    }
+}
+
+impl std::fmt::Display for InputDiffKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Added { default: Some(d) } => write!(f, "added (default: {})", d),
+            Self::Added { default: None } => write!(f, "added (required)"),
+            Self::Removed => write!(f, "removed"),
+            Self::TypeChanged { from, to } => write!(f, "type {} → {}", from, to),
+            Self::DefaultChanged { from, to } => write!(
+                f,
+                "default {} → {}",
+                display_optional(from),
+                display_optional(to)
+            ),
+            Self::OptionsAdded { options } => write!(f, "options added: {}", options.join(", ")),
+            Self::OptionsRemoved { options } => {
+                write!(f, "options removed: {}", options.join(", "))
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ItemChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Added => write!(f, "added"),
+            Self::Removed => write!(f, "removed"),
+            Self::Changed => write!(f, "changed"),
+        }
+    }
+}
+
+fn display_optional(value: &Option<Value>) -> String {
+    value
+        .as_ref()
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "none".to_string())
+}
+
+/// Name of an input's type ("string" when unspecified)
+fn input_type_name(input: &InputDefinition) -> String {
+    input
+        .input_type
+        .as_ref()
+        .and_then(|t| serde_json::to_value(t).ok())
+        .and_then(|v| v.get("type").and_then(|t| t.as_str()).map(String::from))
+        .unwrap_or_else(|| "string".to_string())
+}
+
+/// Values of a select-like input's options
+fn input_options(input: &InputDefinition) -> Vec<String> {
+    use super::metadata::InputType;
+
+    match &input.input_type {
+        Some(InputType::Select { options, .. }) | Some(InputType::MultiSelect { options, .. }) => {
+            options.iter().map(|o| o.value.clone()).collect()
+        }
+        _ => input.enum_values.clone().unwrap_or_default(),
+    }
+}
+
+fn diff_inputs(from: &[InputDefinition], to: &[InputDefinition]) -> Vec<InputDiff> {
+    let mut diffs = Vec::new();
+    let mut push = |name: &str, change: InputDiffKind, breaking: bool| {
+        diffs.push(InputDiff {
+            name: name.to_string(),
+            change,
+            breaking,
+        })
+    };
+
+    for old in from {
+        let Some(new) = to.iter().find(|i| i.name == old.name) else {
+            push(&old.name, InputDiffKind::Removed, true);
+            continue;
+        };
+
+        let (old_type, new_type) = (input_type_name(old), input_type_name(new));
+        if old_type != new_type {
+            push(
+                &old.name,
+                InputDiffKind::TypeChanged {
+                    from: old_type,
+                    to: new_type,
+                },
+                true,
+            );
+        } else {
+            let (old_options, new_options) = (input_options(old), input_options(new));
+            let removed: Vec<String> = old_options
+                .iter()
+                .filter(|o| !new_options.contains(o))
+                .cloned()
+                .collect();
+            let added: Vec<String> = new_options
+                .iter()
+                .filter(|o| !old_options.contains(o))
+                .cloned()
+                .collect();
+
+            if !removed.is_empty() {
+                push(
+                    &old.name,
+                    InputDiffKind::OptionsRemoved { options: removed },
+                    true,
+                );
+            }
+            if !added.is_empty() {
+                push(
+                    &old.name,
+                    InputDiffKind::OptionsAdded { options: added },
+                    false,
+                );
+            }
+        }
+
+        if old.default != new.default {
+            push(
+                &old.name,
+                InputDiffKind::DefaultChanged {
+                    from: old.default.clone(),
+                    to: new.default.clone(),
+                },
+                false,
+            );
+        }
+    }
+
+    for new in to.iter().filter(|i| !from.iter().any(|o| o.name == i.name)) {
+        // Existing environments have no value for a new input without a default
+        let breaking = new.default.is_none() && new.conditions.is_empty();
+        push(
+            &new.name,
+            InputDiffKind::Added {
+                default: new.default.clone(),
+            },
+            breaking,
+        );
+    }
+
+    diffs
+}
+
+/// Compare keyed items by their serialized form
+fn diff_items(
+    from: BTreeMap<String, Value>,
+    to: BTreeMap<String, Value>,
+    breaking: impl Fn(ItemChange) -> bool,
+) -> Vec<ItemDiff> {
+    let mut diffs = Vec::new();
+
+    for (name, old) in &from {
+        let change = match to.get(name) {
+            None => ItemChange::Removed,
+            Some(new) if new != old => ItemChange::Changed,
+            Some(_) => continue,
+        };
+        diffs.push(ItemDiff {
+            name: name.clone(),
+            change,
+            breaking: breaking(change),
+        });
+    }
+
+    for name in to.keys().filter(|name| !from.contains_key(*name)) {
+        diffs.push(ItemDiff {
+            name: name.clone(),
+            change: ItemChange::Added,
+            breaking: breaking(ItemChange::Added),
+        });
+    }
+
+    diffs
+}
+
+fn diff_dependencies(from: &TemplateSpec, to: &TemplateSpec) -> Vec<ItemDiff> {
+    let keyed = |spec: &TemplateSpec| -> BTreeMap<String, Value> {
+        spec.dependencies
+            .iter()
+            .map(|d| {
+                let name = d
+                    .dependency_name
+                    .clone()
+                    .unwrap_or_else(|| format!("{}/{}", d.project.api_version, d.project.kind));
+                (name, serde_json::to_value(d).unwrap_or(Value::Null))
+            })
+            .collect()
+    };
+
+    // A new dependency must be selected for every existing environment
+    diff_items(keyed(from), keyed(to), |change| change == ItemChange::Added)
+}
+
+fn diff_plugins(from: &TemplateSpec, to: &TemplateSpec) -> Vec<ItemDiff> {
+    let keyed = |spec: &TemplateSpec| -> BTreeMap<String, Value> {
+        let mut plugins = BTreeMap::new();
+
+        if let Some(config) = &spec.plugins {
+            for (kind, list) in [
+                ("allowed", &config.allowed),
+                ("installed", &config.installed),
+            ] {
+                for plugin in list {
+                    plugins.insert(
+                        format!(
+                            "{} {}/{}",
+                            kind, plugin.template_pack_name, plugin.plugin_name
+                        ),
+                        serde_json::to_value(plugin).unwrap_or(Value::Null),
+                    );
+                }
+            }
+        }
+
+        plugins
+    };
+
+    diff_items(keyed(from), keyed(to), |change| {
+        change == ItemChange::Removed
+    })
+}
+
+fn diff_hooks(from: Option<&HooksConfig>, to: Option<&HooksConfig>) -> Vec<ItemDiff> {
+    // Hooks have no identity, so each hook of a phase is an item named after its description;
+    // hooks sharing a description (e.g., two webhooks) are numbered in order of appearance
+    let keyed = |hooks: Option<&HooksConfig>| -> BTreeMap<String, Value> {
+        let Some(Value::Object(phases)) = hooks.and_then(|h| serde_json::to_value(h).ok()) else {
+            return BTreeMap::new();
+        };
+
+        let mut items = BTreeMap::new();
+        for (phase, list) in phases {
+            let mut seen: HashMap<String, usize> = HashMap::new();
+
+            for hook in list.as_array().into_iter().flatten() {
+                let description = describe_hook(hook);
+                let count = seen.entry(description.clone()).or_default();
+                *count += 1;
+
+                let name = match *count {
+                    1 => format!("{}: {}", phase, description),
+                    n => format!("{}: {} (#{})", phase, description, n),
+                };
+                items.insert(name, hook.clone());
+            }
+        }
+        items
+    };
+
+    diff_items(keyed(from), keyed(to), |_| false)
+}
+
+/// Short description of a serialized hook (e.g., "command `make test`")
+fn describe_hook(hook: &Value) -> String {
+    let kind = hook.get("type").and_then(|t| t.as_str()).unwrap_or("hook");
+    let detail = ["command", "question", "name"].iter().find_map(|field| {
+        hook.get("config")
+            .and_then(|c| c.get(*field))
+            .and_then(|v| v.as_str())
+    });
+
+    match detail {
+        Some(detail) => format!("{} `{}`", kind, detail),
+        None => kind.to_string(),
+    }
+}
+
+/// Files under src/ of each template path, later paths overriding earlier ones
+fn source_files(
+    fs: &dyn FileSystem,
+    template_paths: &[std::path::PathBuf],
+) -> Result<BTreeMap<String, String>> {
+    let mut files = BTreeMap::new();
+
+    for template_path in template_paths {
+        files.extend(read_tree(fs, &template_path.join("src"))?);
+    }
+
+    Ok(files)
+}
+
+/// Contents of the files below a directory, keyed by relative path (empty if it is missing)
+pub(crate) fn read_tree(fs: &dyn FileSystem, dir: &Path) -> Result<BTreeMap<String, String>> {
+    let mut files = BTreeMap::new();
+
+    if !fs.exists(dir) {
+        return Ok(files);
+    }
+
+    for path in fs.walk_dir(dir, 100)? {
+        if !fs.is_file(&path) {
+            continue;
+        }
+
+        let relative = path
+            .strip_prefix(dir)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .to_string();
+        // Binary files are compared by presence only
+        let content = fs.read_to_string(&path).unwrap_or_default();
+        files.insert(relative, content);
+    }
+
+    Ok(files)
+}
+
+pub(crate) fn diff_files(
+    from: &BTreeMap<String, String>,
+    to: &BTreeMap<String, String>,
+) -> Vec<FileDiff> {
+    let mut paths: Vec<&String> = from.keys().chain(to.keys()).collect();
+    paths.sort();
+    paths.dedup();
+
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let (old, new) = (from.get(path), to.get(path));
+            let change = match (old, new) {
+                (Some(_), None) => ItemChange::Removed,
+                (None, Some(_)) => ItemChange::Added,
+                (Some(old), Some(new)) if old != new => ItemChange::Changed,
+                _ => return None,
+            };
+
+            Some(FileDiff {
+                path: path.clone(),
+                change,
+                diff: unified_diff(
+                    path,
+                    old.map(String::as_str).unwrap_or(""),
+                    new.map(String::as_str).unwrap_or(""),
+                ),
+            })
+        })
+        .collect()
+}
+
+/// Unified diff of two texts, with `DIFF_CONTEXT` lines of context around each hunk
+pub fn unified_diff(path: &str, old: &str, new: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    // Edit script: (' ' | '-' | '+', line)
+    let mut ops: Vec<(char, &str)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let matches = matching_lines(&old_lines, &new_lines);
+
+    for (a, b) in matches
+        .into_iter()
+        .chain(std::iter::once((old_lines.len(), new_lines.len())))
+    {
+        ops.extend(old_lines[i..a].iter().map(|line| ('-', *line)));
+        ops.extend(new_lines[j..b].iter().map(|line| ('+', *line)));
+
+        if a < old_lines.len() {
+            ops.push((' ', old_lines[a]));
+        }
+        (i, j) = (a + 1, b + 1);
+    }
+
+    let changed: Vec<usize> = (0..ops.len()).filter(|&k| ops[k].0 != ' ').collect();
+    if changed.is_empty() {
+        return String::new();
+    }
+
+    // Line numbers (0-based) of each op in the old and new text
+    let mut positions = Vec::with_capacity(ops.len() + 1);
+    let (mut old_pos, mut new_pos) = (0, 0);
+    for (op, _) in &ops {
+        positions.push((old_pos, new_pos));
+        match op {
+            '-' => old_pos += 1,
+            '+' => new_pos += 1,
+            _ => {
+                old_pos += 1;
+                new_pos += 1;
+            }
+        }
+    }
+    positions.push((old_pos, new_pos));
+
+    let mut out = format!("--- a/{}\n+++ b/{}\n", path, path);
+
+    // Group changes whose context would overlap into one hunk
+    let mut group_start = 0;
+    while group_start < changed.len() {
+        let mut group_end = group_start;
+        while group_end + 1 < changed.len()
+            && changed[group_end + 1] - changed[group_end] <= 2 * DIFF_CONTEXT
+        {
+            group_end += 1;
+        }
+
+        let start = changed[group_start].saturating_sub(DIFF_CONTEXT);
+        let end = (changed[group_end] + DIFF_CONTEXT + 1).min(ops.len());
+        let old_count = positions[end].0 - positions[start].0;
+        let new_count = positions[end].1 - positions[start].1;
+
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            hunk_start(positions[start].0, old_count),
+            old_count,
+            hunk_start(positions[start].1, new_count),
+            new_count
+        ));
+        for (op, line) in &ops[start..end] {
+            out.push_str(&format!("{}{}\n", op, line));
+        }
+
+        group_start = group_end + 1;
+    }
+
+    out
+}
+
+/// 1-based start line of a hunk; an empty range points at the line before it
+fn hunk_start(position: usize, count: usize) -> usize {
+    if count == 0 { position } else { position + 1 }
+}
+
+/// Load inputs for a rendered comparison from a YAML or JSON file
+pub fn load_inputs_file(fs: &dyn FileSystem, path: &Path) -> Result<HashMap<String, Value>> {
+    let content = fs
+        .read_to_string(path)
+        .with_context(|| format!("Failed to read inputs file: {}", path.display()))?;
+
+    serde_yaml::from_str(&content).with_context(|| {
+        format!(
+            "Inputs file must be a YAML or JSON object: {}",
+            path.display()
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs(yaml: &str) -> Vec<InputDefinition> {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_diff_inputs() {
+        let from = inputs(
+            r#"
+- name: size
+  type: select
+  options: [{label: Small, value: small}, {label: Large, value: large}]
+- name: replicas
+  type: number
+  default: 1
+- name: legacy
+"#,
+        );
+        let to = inputs(
+            r#"
+- name: size
+  type: select
+  options: [{label: Small, value: small}, {label: Medium, value: medium}]
+- name: replicas
+  type: string
+  default: 2
+- name: region
+  default: eu-west-1
+- name: owner
+"#,
+        );
+
+        let diffs = diff_inputs(&from, &to);
+        let summary: Vec<String> = diffs
+            .iter()
+            .map(|d| format!("{} {} {}", d.name, d.change, d.breaking))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                "size options removed: large true",
+                "size options added: medium false",
+                "replicas type number → string true",
+                "replicas default 1 → 2 false",
+                "legacy removed true",
+                "region added (default: \"eu-west-1\") false",
+                "owner added (required) true",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_hooks_with_shared_description() {
+        let hooks = |yaml: &str| -> HooksConfig { serde_yaml::from_str(yaml).unwrap() };
+
+        let from = hooks(
+            r#"
+post_apply:
+  - type: webhook
+    config: {url: "https://hooks.example.com/a"}
+  - type: webhook
+    config: {url: "https://hooks.example.com/b"}
+  - type: command
+    config: {command: "make test"}
+  - type: command
+    config: {command: "make test", when: [{input_name: env, equals: prod}]}
+"#,
+        );
+        let to = hooks(
+            r#"
+post_apply:
+  - type: webhook
+    config: {url: "https://hooks.example.com/a"}
+  - type: webhook
+    config: {url: "https://hooks.example.com/c"}
+  - type: command
+    config: {command: "make test"}
+"#,
+        );
+
+        let summary: Vec<String> = diff_hooks(Some(&from), Some(&to))
+            .iter()
+            .map(|d| format!("{} {}", d.name, d.change))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                "post_apply: command `make test` (#2) removed",
+                "post_apply: webhook (#2) changed",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        let new = "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk\n";
+
+        assert_eq!(
+            unified_diff("main.tf", old, new),
+            "--- a/main.tf\n+++ b/main.tf\n\
+             @@ -1,5 +1,5 @@\n a\n-b\n+B\n c\n d\n e\n\
+             @@ -8,3 +8,4 @@\n h\n i\n j\n+k\n"
+        );
+        assert_eq!(unified_diff("main.tf", old, old), "");
+        assert_eq!(
+            unified_diff("new.tf", "", "x\n"),
+            "--- a/new.tf\n+++ b/new.tf\n@@ -0,0 +1,1 @@\n+x\n"
+        );
+    }
+}
@@ -0,0 +1,939 @@
+//! Handlebars helpers available in templates
+//!
+//! Besides the Handlebars built-ins (`if`, `each`, `gt`, `and`, ...), templates can use the
+//! helpers registered by [`register_helpers`]. Helpers computing a value can also be used as
+//! subexpressions, e.g. `{{join (split zones ",") ", "}}` or `{{#if (gt replicas 1)}}`.
+//! Their output is written unescaped, since generated files are code rather than HTML.
+
+use handlebars::{
+    Handlebars, Helper, HelperDef, HelperResult, Output, RenderContext, RenderError,
+    RenderErrorReason, ScopedJson,
+};
+use serde_json::{Number, Value};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Helpers provided by Handlebars itself
+const BUILTIN_HELPERS: &[&str] = &[
+    "if", "unless", "each", "with", "lookup", "raw", "log", "ne", "gt", "gte", "lt", "lte", "and",
+    "or", "not", "len",
+];
+
+/// Helpers writing their output directly
+const OUTPUT_HELPERS: &[&str] = &["eq", "contains", "k8s_name", "bool", "secret"];
+
+/// A helper computing a value from its parameters
+type ValueFn = fn(&Helper) -> Result<Value, String>;
+
+/// Helpers computing a value, usable as subexpressions
+const VALUE_HELPERS: &[(&str, ValueFn)] = &[
+    // String case
+    ("snake_case", snake_case),
+    ("kebab_case", kebab_case),
+    ("camel_case", camel_case),
+    ("pascal_case", pascal_case),
+    ("upper", upper),
+    ("lower", lower),
+    // Values
+    ("default", default),
+    ("join", join),
+    ("split", split),
+    ("indent", indent),
+    // Encoders
+    ("json", json),
+    ("yaml", yaml),
+    ("hcl", hcl),
+    // Network math
+    ("cidrsubnet", cidrsubnet),
+    ("cidrhost", cidrhost),
+    ("cidrnetmask", cidrnetmask),
+    ("cidrcontains", cidrcontains),
+    // Arithmetic
+    ("add", add),
+    ("sub", sub),
+    ("mul", mul),
+    ("div", div),
+    ("mod", modulo),
+    ("min", min),
+    ("max", max),
+];
+
+/// Register the pmp helpers
+pub fn register_helpers(handlebars: &mut Handlebars) {
+    handlebars.register_helper("eq", Box::new(eq_helper));
+    handlebars.register_helper("contains", Box::new(contains_helper));
+    handlebars.register_helper("k8s_name", Box::new(k8s_name_helper));
+    handlebars.register_helper("bool", Box::new(bool_helper));
+    handlebars.register_helper("secret", Box::new(secret_helper));
+
+    for (name, helper) in VALUE_HELPERS {
+        handlebars.register_helper(name, Box::new(ValueHelper(*helper)));
+    }
+}
+
+/// Whether templates can call a helper by this name
+pub fn is_known_helper(name: &str) -> bool {
+    BUILTIN_HELPERS.contains(&name)
+        || OUTPUT_HELPERS.contains(&name)
+        || VALUE_HELPERS.iter().any(|(helper, _)| *helper == name)
+}
+
+/// Adapts a [`ValueFn`] to Handlebars, writing its value unescaped
+struct ValueHelper(ValueFn);
+
+impl HelperDef for ValueHelper {
+    fn call_inner<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        _: &'reg Handlebars<'reg>,
+        _: &'rc handlebars::Context,
+        _: &mut RenderContext<'reg, 'rc>,
+    ) -> Result<ScopedJson<'rc>, RenderError> {
+        (self.0)(h)
+            .map(ScopedJson::Derived)
+            .map_err(|e| RenderErrorReason::Other(format!("{} helper: {}", h.name(), e)).into())
+    }
+
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc handlebars::Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let value = self.call_inner(h, r, ctx, rc)?;
+        out.write(&value.render())?;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Parameters
+// ============================================================================
+
+fn param<'a>(h: &'a Helper, index: usize) -> Result<&'a Value, String> {
+    h.param(index)
+        .map(|p| p.value())
+        .ok_or_else(|| format!("missing parameter {}", index + 1))
+}
+
+fn str_param<'a>(h: &'a Helper, index: usize) -> Result<&'a str, String> {
+    param(h, index)?
+        .as_str()
+        .ok_or_else(|| format!("parameter {} must be a string", index + 1))
+}
+
+/// A number, or a string holding one
+fn number_param(h: &Helper, index: usize) -> Result<Number, String> {
+    match param(h, index)? {
+        Value::Number(n) => Ok(n.clone()),
+        Value::String(s) => s
+            .trim()
+            .parse()
+            .map_err(|_| format!("parameter {} must be a number, got '{}'", index + 1, s)),
+        other => Err(format!(
+            "parameter {} must be a number, got {}",
+            index + 1,
+            other
+        )),
+    }
+}
+
+fn int_param(h: &Helper, index: usize) -> Result<i64, String> {
+    number_param(h, index)?
+        .as_i64()
+        .ok_or_else(|| format!("parameter {} must be an integer", index + 1))
+}
+
+/// Rendered form of a value inside text (strings are not quoted)
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+// ============================================================================
+// String case
+// ============================================================================
+
+/// Split an identifier into words at separators and case changes (`HTTPServer2Name` →
+/// `HTTP`, `Server2`, `Name`)
+fn words(value: &str) -> Vec<String> {
+    let chars: Vec<char> = value.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+
+        if c.is_uppercase() && !current.is_empty() {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+
+            if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_is_lower) {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+
+        current.push(c);
+    }
+
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+fn snake_case(h: &Helper) -> Result<Value, String> {
+    let words: Vec<String> = words(str_param(h, 0)?)
+        .iter()
+        .map(|w| w.to_lowercase())
+        .collect();
+    Ok(Value::String(words.join("_")))
+}
+
+fn kebab_case(h: &Helper) -> Result<Value, String> {
+    let words: Vec<String> = words(str_param(h, 0)?)
+        .iter()
+        .map(|w| w.to_lowercase())
+        .collect();
+    Ok(Value::String(words.join("-")))
+}
+
+fn camel_case(h: &Helper) -> Result<Value, String> {
+    let words = words(str_param(h, 0)?);
+    let camel: String = words
+        .iter()
+        .enumerate()
+        .map(|(i, w)| {
+            if i == 0 {
+                w.to_lowercase()
+            } else {
+                capitalize(w)
+            }
+        })
+        .collect();
+    Ok(Value::String(camel))
+}
+
+fn pascal_case(h: &Helper) -> Result<Value, String> {
+    let pascal: String = words(str_param(h, 0)?)
+        .iter()
+        .map(|w| capitalize(w))
+        .collect();
+    Ok(Value::String(pascal))
+}
+
+fn upper(h: &Helper) -> Result<Value, String> {
+    Ok(Value::String(str_param(h, 0)?.to_uppercase()))
+}
+
+fn lower(h: &Helper) -> Result<Value, String> {
+    Ok(Value::String(str_param(h, 0)?.to_lowercase()))
+}
+
+// ============================================================================
+// Values
+// ============================================================================
+
+/// `{{default value fallback}}`: the fallback when the value is missing, null or ""
+fn default(h: &Helper) -> Result<Value, String> {
+    let value = h.param(0).map(|p| p.value()).unwrap_or(&Value::Null);
+    let fallback = param(h, 1)?;
+
+    Ok(match value {
+        Value::Null => fallback.clone(),
+        Value::String(s) if s.is_empty() => fallback.clone(),
+        _ => value.clone(),
+    })
+}
+
+/// `{{join list ", "}}`
+fn join(h: &Helper) -> Result<Value, String> {
+    let items = param(h, 0)?
+        .as_array()
+        .ok_or("parameter 1 must be a list")?;
+    let separator = str_param(h, 1)?;
+
+    Ok(Value::String(
+        items
+            .iter()
+            .map(display)
+            .collect::<Vec<_>>()
+            .join(separator),
+    ))
+}
+
+/// `{{#each (split value ",")}}`
+fn split(h: &Helper) -> Result<Value, String> {
+    let value = str_param(h, 0)?;
+    let separator = str_param(h, 1)?;
+
+    if value.is_empty() {
+        return Ok(Value::Array(Vec::new()));
+    }
+
+    Ok(Value::Array(
+        value
+            .split(separator)
+            .map(|s| Value::String(s.to_string()))
+            .collect(),
+    ))
+}
+
+/// `{{indent 4 text}}`: indent every non-empty line
+fn indent(h: &Helper) -> Result<Value, String> {
+    let width = usize::try_from(int_param(h, 0)?).map_err(|_| "width must not be negative")?;
+    let text = display(param(h, 1)?);
+    let pad = " ".repeat(width);
+
+    Ok(Value::String(
+        text.lines()
+            .map(|line| {
+                if line.is_empty() {
+                    String::new()
+                } else {
+                    format!("{}{}", pad, line)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    ))
+}
+
+// ============================================================================
+// Encoders
+// ============================================================================
+
+/// Indent of continuation lines, from the `indent=N` hash parameter
+fn hash_indent(h: &Helper) -> Result<usize, String> {
+    match h.hash_get("indent").map(|v| v.value()) {
+        None => Ok(0),
+        Some(value) => value
+            .as_u64()
+            .map(|n| n as usize)
+            .ok_or_else(|| "indent must be a non-negative integer".to_string()),
+    }
+}
+
+/// Continuation lines shifted right, so a multi-line value lines up with its key
+fn shift(text: &str, indent: usize) -> String {
+    let pad = " ".repeat(indent);
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            if i == 0 || line.is_empty() {
+                line.to_string()
+            } else {
+                format!("{}{}", pad, line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `{{json value}}`: compact JSON
+fn json(h: &Helper) -> Result<Value, String> {
+    serde_json::to_string(param(h, 0)?)
+        .map(Value::String)
+        .map_err(|e| e.to_string())
+}
+
+/// `{{yaml value indent=N}}`: YAML, without the trailing newline
+fn yaml(h: &Helper) -> Result<Value, String> {
+    let yaml = serde_yaml::to_string(param(h, 0)?).map_err(|e| e.to_string())?;
+    Ok(Value::String(shift(yaml.trim_end(), hash_indent(h)?)))
+}
+
+/// `{{hcl value indent=N}}`: HCL expression (strings quoted, objects and lists nested)
+fn hcl(h: &Helper) -> Result<Value, String> {
+    Ok(Value::String(shift(
+        &to_hcl(param(h, 0)?, 0),
+        hash_indent(h)?,
+    )))
+}
+
+/// Encode a value as an HCL expression
+pub fn to_hcl(value: &Value, indent: usize) -> String {
+    let pad = " ".repeat(indent + 2);
+
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => hcl_string(s),
+        Value::Array(items) if items.is_empty() => "[]".to_string(),
+        Value::Array(items) if items.iter().all(|i| !i.is_array() && !i.is_object()) => {
+            let items: Vec<String> = items.iter().map(|i| to_hcl(i, indent)).collect();
+            format!("[{}]", items.join(", "))
+        }
+        Value::Array(items) => {
+            let mut hcl = String::from("[\n");
+            for item in items {
+                hcl.push_str(&format!("{}{},\n", pad, to_hcl(item, indent + 2)));
+            }
+            hcl.push_str(&format!("{}]", " ".repeat(indent)));
+            hcl
+        }
+        Value::Object(map) if map.is_empty() => "{}".to_string(),
+        Value::Object(map) => {
+            let keys: Vec<String> = map.keys().map(|k| hcl_key(k)).collect();
+            let width = keys.iter().map(|k| k.chars().count()).max().unwrap_or(0);
+
+            let mut hcl = String::from("{\n");
+            for (key, value) in keys.iter().zip(map.values()) {
+                hcl.push_str(&format!(
+                    "{}{:width$} = {}\n",
+                    pad,
+                    key,
+                    to_hcl(value, indent + 2),
+                    width = width
+                ));
+            }
+            hcl.push_str(&format!("{}}}", " ".repeat(indent)));
+            hcl
+        }
+    }
+}
+
+/// Object keys are bare identifiers when possible
+fn hcl_key(key: &str) -> String {
+    let is_identifier = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+
+    if is_identifier {
+        key.to_string()
+    } else {
+        hcl_string(key)
+    }
+}
+
+/// Quoted HCL string; template sequences are escaped so values are taken literally
+fn hcl_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            '$' | '%' if chars.peek() == Some(&'{') => {
+                quoted.push(c);
+                quoted.push(c);
+            }
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+// ============================================================================
+// Network math (same semantics as the Terraform functions)
+// ============================================================================
+
+/// Parse `address/prefix` into (address bits, prefix length, address width)
+fn parse_cidr(value: &str) -> Result<(u128, u32, u32), String> {
+    let (address, prefix) = value
+        .split_once('/')
+        .ok_or_else(|| format!("invalid CIDR '{}'", value))?;
+    let address: IpAddr = address
+        .parse()
+        .map_err(|_| format!("invalid CIDR '{}'", value))?;
+    let prefix: u32 = prefix
+        .parse()
+        .map_err(|_| format!("invalid CIDR '{}'", value))?;
+
+    let (bits, width) = match address {
+        IpAddr::V4(v4) => (u128::from(u32::from(v4)), 32),
+        IpAddr::V6(v6) => (u128::from(v6), 128),
+    };
+
+    if prefix > width {
+        return Err(format!("invalid CIDR '{}'", value));
+    }
+
+    Ok((bits & network_mask(prefix, width), prefix, width))
+}
+
+/// Parse a bare address into (address bits, address width)
+fn parse_address(value: &str) -> Result<(u128, u32), String> {
+    match value.parse::<IpAddr>() {
+        Ok(IpAddr::V4(v4)) => Ok((u128::from(u32::from(v4)), 32)),
+        Ok(IpAddr::V6(v6)) => Ok((u128::from(v6), 128)),
+        Err(_) => Err(format!("invalid address '{}'", value)),
+    }
+}
+
+fn network_mask(prefix: u32, width: u32) -> u128 {
+    let all = u128::MAX >> (128 - width);
+    all & !host_mask(prefix, width)
+}
+
+fn host_mask(prefix: u32, width: u32) -> u128 {
+    let all = u128::MAX >> (128 - width);
+    all.checked_shr(prefix).unwrap_or(0)
+}
+
+fn format_address(bits: u128, width: u32) -> String {
+    if width == 32 {
+        Ipv4Addr::from(bits as u32).to_string()
+    } else {
+        Ipv6Addr::from(bits).to_string()
+    }
+}
+
+/// `{{cidrsubnet "10.0.0.0/16" 8 2}}` → `10.0.2.0/24`
+fn cidrsubnet(h: &Helper) -> Result<Value, String> {
+    let (network, prefix, width) = parse_cidr(str_param(h, 0)?)?;
+    let newbits = u32::try_from(int_param(h, 1)?).map_err(|_| "newbits must not be negative")?;
+    let netnum = u128::try_from(int_param(h, 2)?).map_err(|_| "netnum must not be negative")?;
+
+    let new_prefix = prefix + newbits;
+    if new_prefix > width {
+        return Err(format!(
+            "insufficient address space to extend prefix of {} by {}",
+            prefix, newbits
+        ));
+    }
+    if 1u128
+        .checked_shl(newbits)
+        .is_some_and(|count| netnum >= count)
+    {
+        return Err(format!(
+            "prefix extension of {} does not accommodate a subnet numbered {}",
+            newbits, netnum
+        ));
+    }
+
+    let subnet = network | netnum.checked_shl(width - new_prefix).unwrap_or(0);
+    Ok(Value::String(format!(
+        "{}/{}",
+        format_address(subnet, width),
+        new_prefix
+    )))
+}
+
+/// `{{cidrhost "10.0.1.0/24" 5}}` → `10.0.1.5`; negative numbers count from the end
+fn cidrhost(h: &Helper) -> Result<Value, String> {
+    let (network, prefix, width) = parse_cidr(str_param(h, 0)?)?;
+    let hostnum = int_param(h, 1)?;
+    let hosts = host_mask(prefix, width);
+
+    let offset = if hostnum >= 0 {
+        hostnum as u128
+    } else {
+        (hosts + 1).wrapping_sub(hostnum.unsigned_abs() as u128)
+    };
+
+    if offset > hosts || (hostnum < 0 && hostnum.unsigned_abs() as u128 > hosts) {
+        return Err(format!(
+            "prefix of {} does not accommodate a host numbered {}",
+            prefix, hostnum
+        ));
+    }
+
+    Ok(Value::String(format_address(network | offset, width)))
+}
+
+/// `{{cidrnetmask "10.0.0.0/16"}}` → `255.255.0.0` (IPv4 only)
+fn cidrnetmask(h: &Helper) -> Result<Value, String> {
+    let (_, prefix, width) = parse_cidr(str_param(h, 0)?)?;
+
+    if width != 32 {
+        return Err("only IPv4 prefixes have a netmask".to_string());
+    }
+
+    Ok(Value::String(format_address(
+        network_mask(prefix, width),
+        width,
+    )))
+}
+
+/// `{{cidrcontains "10.0.0.0/16" "10.0.4.0/24"}}` → `true`; the second parameter can also be
+/// a bare address
+fn cidrcontains(h: &Helper) -> Result<Value, String> {
+    let (network, prefix, width) = parse_cidr(str_param(h, 0)?)?;
+    let contained = str_param(h, 1)?;
+
+    let (bits, contained_prefix, contained_width) = if contained.contains('/') {
+        parse_cidr(contained)?
+    } else {
+        let (bits, width) = parse_address(contained)?;
+        (bits, width, width)
+    };
+
+    if contained_width != width {
+        return Err("cannot compare IPv4 and IPv6 addresses".to_string());
+    }
+
+    Ok(Value::Bool(
+        contained_prefix >= prefix && bits & network_mask(prefix, width) == network,
+    ))
+}
+
+// ============================================================================
+// Arithmetic
+// ============================================================================
+
+/// Apply an operation to two numbers, as integers when both are integers
+fn arithmetic(
+    h: &Helper,
+    int_op: fn(i64, i64) -> Option<i64>,
+    float_op: fn(f64, f64) -> f64,
+) -> Result<Value, String> {
+    let (a, b) = (number_param(h, 0)?, number_param(h, 1)?);
+
+    if let (Some(a), Some(b)) = (a.as_i64(), b.as_i64()) {
+        return int_op(a, b)
+            .map(Value::from)
+            .ok_or_else(|| "integer overflow".to_string());
+    }
+
+    let result = float_op(a.as_f64().unwrap_or(0.0), b.as_f64().unwrap_or(0.0));
+    Number::from_f64(result)
+        .map(Value::Number)
+        .ok_or_else(|| format!("result is not a number: {}", result))
+}
+
+fn non_zero_divisor(h: &Helper) -> Result<(), String> {
+    if number_param(h, 1)?.as_f64() == Some(0.0) {
+        return Err("division by zero".to_string());
+    }
+    Ok(())
+}
+
+fn add(h: &Helper) -> Result<Value, String> {
+    arithmetic(h, i64::checked_add, |a, b| a + b)
+}
+
+fn sub(h: &Helper) -> Result<Value, String> {
+    arithmetic(h, i64::checked_sub, |a, b| a - b)
+}
+
+fn mul(h: &Helper) -> Result<Value, String> {
+    arithmetic(h, i64::checked_mul, |a, b| a * b)
+}
+
+/// Integer division for integers
+fn div(h: &Helper) -> Result<Value, String> {
+    non_zero_divisor(h)?;
+    arithmetic(h, i64::checked_div, |a, b| a / b)
+}
+
+fn modulo(h: &Helper) -> Result<Value, String> {
+    non_zero_divisor(h)?;
+    arithmetic(h, i64::checked_rem, |a, b| a % b)
+}
+
+fn min(h: &Helper) -> Result<Value, String> {
+    arithmetic(h, |a, b| Some(a.min(b)), f64::min)
+}
+
+fn max(h: &Helper) -> Result<Value, String> {
+    arithmetic(h, |a, b| Some(a.max(b)), f64::max)
+}
+
+// ============================================================================
+// Output helpers
+// ============================================================================
+
+/// Helper function for equality comparison
+fn eq_helper(
+    h: &Helper,
+    _: &Handlebars,
+    _: &handlebars::Context,
+    _: &mut RenderContext,
+    out: &mut dyn Output,
+) -> HelperResult {
+    let param1 = h.param(0).and_then(|v| v.value().as_str());
+    let param2 = h.param(1).and_then(|v| v.value().as_str());
+
+    if let (Some(p1), Some(p2)) = (param1, param2)
+        && p1 == p2
+    {
+        out.write("true")?;
+    }
+
+    Ok(())
+}
+
+/// Helper function to check if an array contains a value
+fn contains_helper(
+    h: &Helper,
+    _: &Handlebars,
+    _: &handlebars::Context,
+    _: &mut RenderContext,
+    out: &mut dyn Output,
+) -> HelperResult {
+    let array = h.param(0).and_then(|v| v.value().as_array());
+    let search_value = h.param(1).and_then(|v| v.value().as_str());
+
+    if let (Some(arr), Some(search)) = (array, search_value) {
+        for item in arr {
+            if let Some(item_str) = item.as_str()
+                && item_str == search
+            {
+                out.write("true")?;
+                return Ok(());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Helper function to convert strings to Kubernetes-compatible DNS subdomain names (RFC 1123)
+/// Rules: lowercase alphanumeric, '-', '.'; must start/end with alphanumeric; max 253 chars
+fn k8s_name_helper(
+    h: &Helper,
+    _: &Handlebars,
+    _: &handlebars::Context,
+    _: &mut RenderContext,
+    out: &mut dyn Output,
+) -> HelperResult {
+    let value = h.param(0).and_then(|v| v.value().as_str()).ok_or_else(|| {
+        handlebars::RenderError::from(handlebars::RenderErrorReason::Other(
+            "k8s_name requires a string parameter".to_string(),
+        ))
+    })?;
+
+    // Sanitize: keep only lowercase alphanumeric, '-', and '.'
+    // Replace underscores with hyphens, convert to lowercase, remove invalid chars
+    let mut sanitized = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            'a'..='z' | '0'..='9' | '-' | '.' => sanitized.push(ch),
+            'A'..='Z' => sanitized.push(ch.to_ascii_lowercase()),
+            '_' => sanitized.push('-'),
+            _ => {} // Skip invalid characters
+        }
+    }
+
+    // Trim non-alphanumeric from start and end
+    let trimmed = sanitized.trim_matches(|c: char| !c.is_alphanumeric());
+
+    // Truncate to 253 characters (Kubernetes DNS subdomain limit)
+    let result = if trimmed.len() > 253 {
+        trimmed[..253].trim_end_matches(|c: char| !c.is_alphanumeric())
+    } else {
+        trimmed
+    };
+
+    out.write(result)?;
+    Ok(())
+}
+
+/// Helper function to explicitly render boolean values as "true" or "false" strings
+/// This prevents Handlebars from treating booleans as conditional expressions
+fn bool_helper(
+    h: &Helper,
+    _: &Handlebars,
+    _: &handlebars::Context,
+    _: &mut RenderContext,
+    out: &mut dyn Output,
+) -> HelperResult {
+    let value = h
+        .param(0)
+        .ok_or_else(|| {
+            handlebars::RenderError::from(handlebars::RenderErrorReason::Other(
+                "bool helper requires a parameter".to_string(),
+            ))
+        })?
+        .value();
+
+    match value {
+        Value::Bool(true) => out.write("true")?,
+        Value::Bool(false) => out.write("false")?,
+        _ => {
+            // For non-boolean values, convert to string representation
+            out.write(&value.to_string())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Secret helper: {{secret input_name}} outputs local.secret_<input_name>
+/// This references the local value generated in _common.tf for secret inputs
+fn secret_helper(
+    h: &Helper,
+    _: &Handlebars,
+    _: &handlebars::Context,
+    _: &mut RenderContext,
+    out: &mut dyn Output,
+) -> HelperResult {
+    let input_name = h
+        .param(0)
+        .ok_or_else(|| {
+            handlebars::RenderError::from(handlebars::RenderErrorReason::Other(
+                "secret helper requires an input name parameter".to_string(),
+            ))
+        })?
+        .value();
+
+    let name_str = match input_name {
+        Value::String(s) => s.clone(),
+        _ => input_name.to_string().trim_matches('"').to_string(),
+    };
+
+    // Sanitize the name for use as a Terraform local variable name
+    let sanitized = name_str
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+
+    out.write(&format!("local.secret_{}", sanitized))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn render(template: &str, data: Value) -> Result<String, String> {
+        let mut handlebars = Handlebars::new();
+        register_helpers(&mut handlebars);
+        handlebars
+            .render_template(template, &data)
+            .map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_string_helpers() {
+        let data = json!({"name": "HTTPServer2Name", "dashed": "my-app_name", "env": ""});
+
+        assert_eq!(
+            render(
+                "{{snake_case name}} {{kebab_case dashed}} {{camel_case dashed}} {{pascal_case dashed}} {{upper dashed}}",
+                data.clone()
+            )
+            .unwrap(),
+            "http_server2_name my-app-name myAppName MyAppName MY-APP_NAME"
+        );
+        assert_eq!(
+            render(
+                r#"{{default env "dev"}}/{{default region "eu-west-1"}}/{{join (split "a,b" ",") "+"}}"#,
+                data
+            )
+            .unwrap(),
+            "dev/eu-west-1/a+b"
+        );
+        assert!(
+            render("{{upper 1}}", json!({}))
+                .unwrap_err()
+                .contains("upper helper")
+        );
+    }
+
+    #[test]
+    fn test_encoders() {
+        let data = json!({
+            "tags": {"team": "core", "cost-center": "42", "owner email": "a\"b"},
+            "ports": [80, 443],
+            "rules": [{"cidr": "10.0.0.0/8", "template": "${x}"}],
+            "empty": {}
+        });
+
+        assert_eq!(
+            render("tags = {{hcl tags indent=2}}", data.clone()).unwrap(),
+            "tags = {\n    cost-center   = \"42\"\n    \"owner email\" = \"a\\\"b\"\n    team          = \"core\"\n  }"
+        );
+        assert_eq!(
+            render("{{hcl ports}} {{hcl empty}} {{hcl rules}}", data.clone()).unwrap(),
+            "[80, 443] {} [\n  {\n    cidr     = \"10.0.0.0/8\"\n    template = \"$${x}\"\n  },\n]"
+        );
+        assert_eq!(
+            render("{{json ports}}\n{{indent 2 (yaml tags)}}", data).unwrap(),
+            "[80,443]\n  cost-center: '42'\n  owner email: a\"b\n  team: core"
+        );
+    }
+
+    #[test]
+    fn test_network_helpers() {
+        assert_eq!(
+            render(
+                r#"{{cidrsubnet "10.0.0.0/16" 8 2}} {{cidrsubnet "10.1.2.3/16" 4 15}} {{cidrhost "10.0.1.0/24" 5}} {{cidrhost "10.0.1.0/24" -2}} {{cidrnetmask "10.0.0.0/12"}}"#,
+                json!({})
+            )
+            .unwrap(),
+            "10.0.2.0/24 10.1.240.0/20 10.0.1.5 10.0.1.254 255.240.0.0"
+        );
+        assert_eq!(
+            render(r#"{{cidrsubnet "fd00::/48" 16 3}}"#, json!({})).unwrap(),
+            "fd00:0:0:3::/64"
+        );
+        assert!(
+            render(r#"{{cidrsubnet "10.0.0.0/16" 8 256}}"#, json!({}))
+                .unwrap_err()
+                .contains("does not accommodate a subnet numbered 256")
+        );
+        assert!(render(r#"{{cidrhost "10.0.1.0/24" 256}}"#, json!({})).is_err());
+        assert_eq!(
+            render(
+                r#"{{cidrcontains "10.0.0.0/16" "10.0.4.0/24"}} {{cidrcontains "10.0.0.0/16" "10.1.0.1"}} {{cidrcontains "10.0.0.0/16" "10.0.0.0/8"}}"#,
+                json!({})
+            )
+            .unwrap(),
+            "true false false"
+        );
+    }
+
+    #[test]
+    fn test_arithmetic_helpers() {
+        let data = json!({"replicas": 3, "ratio": 0.5, "port": "8080"});
+
+        assert_eq!(
+            render(
+                "{{add replicas 1}} {{sub port 80}} {{mul replicas ratio}} {{div 7 2}} {{mod 7 2}} {{max replicas 5}} {{#if (gt replicas 2)}}ha{{/if}}",
+                data
+            )
+            .unwrap(),
+            "4 8000 1.5 3 1 5 ha"
+        );
+        assert!(
+            render("{{div 1 0}}", json!({}))
+                .unwrap_err()
+                .contains("division by zero")
+        );
+    }
+
+    #[test]
+    fn test_is_known_helper() {
+        assert!(is_known_helper("hcl"));
+        assert!(is_known_helper("k8s_name"));
+        assert!(is_known_helper("each"));
+        assert!(!is_known_helper("snakeCase"));
+    }
+}
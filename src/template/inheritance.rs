@@ -207,6 +207,15 @@ impl TemplateResolver {
 
             // Concatenate dependencies
             dependencies: Self::merge_dependencies(&base.dependencies, &child.dependencies),
+
+            // File rules apply to each template's own src/ directory
+            files: child.files.clone(),
+
+            // Migrations describe the child's own version history
+            migrations: child.migrations.clone(),
+
+            // Base rules still hold for the inherited inputs
+            validations: [base.validations.as_slice(), child.validations.as_slice()].concat(),
         }
     }
 
@@ -291,6 +300,10 @@ impl TemplateResolver {
                     post_apply: Self::concat_hooks(&child_hooks.post_apply, &base_hooks.post_apply),
                     pre_destroy: Self::concat_hooks(&child_hooks.pre_destroy, &base_hooks.pre_destroy),
                     post_destroy: Self::concat_hooks(&child_hooks.post_destroy, &base_hooks.post_destroy),
+                    on_failure_apply: Self::concat_hooks(&child_hooks.on_failure_apply, &base_hooks.on_failure_apply),
+                    on_failure_destroy: Self::concat_hooks(&child_hooks.on_failure_destroy, &base_hooks.on_failure_destroy),
+                    finally_apply: Self::concat_hooks(&child_hooks.finally_apply, &base_hooks.finally_apply),
+                    finally_destroy: Self::concat_hooks(&child_hooks.finally_destroy, &base_hooks.finally_destroy),
                     pre_refresh: Self::concat_hooks(&child_hooks.pre_refresh, &base_hooks.pre_refresh),
                     post_refresh: Self::concat_hooks(&child_hooks.post_refresh, &base_hooks.post_refresh),
                     pre_test: Self::concat_hooks(&child_hooks.pre_test, &base_hooks.pre_test),
@@ -304,6 +317,10 @@ impl TemplateResolver {
                     && merged.post_apply.is_empty()
                     && merged.pre_destroy.is_empty()
                     && merged.post_destroy.is_empty()
+                    && merged.on_failure_apply.is_empty()
+                    && merged.on_failure_destroy.is_empty()
+                    && merged.finally_apply.is_empty()
+                    && merged.finally_destroy.is_empty()
                     && merged.pre_refresh.is_empty()
                     && merged.post_refresh.is_empty()
                     && merged.pre_test.is_empty()
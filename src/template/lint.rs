@@ -4,18 +4,24 @@
 //! - Missing required fields
 //! - Unused inputs
 //! - Invalid input type configurations
-//! - Handlebars syntax errors
+//! - Handlebars syntax errors and unknown helpers
 //! - Circular inheritance detection
+//! - File rules referencing missing files or inputs
 //! - Best practices warnings
+//!
+//! Issues that can be resolved mechanically carry a [`LintFix`] (applied by `--fix`).
 
 use anyhow::Result;
-use handlebars::Handlebars;
+use handlebars::Template;
+use handlebars::template::{HelperTemplate, Parameter, TemplateElement};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 use std::path::{Path, PathBuf};
 
 use super::discovery::{TemplateDiscovery, TemplateInfo, TemplatePackInfo};
-use super::metadata::{InputType, TemplateResource};
+use super::helpers;
+use super::lint_fix::LintFix;
+use super::metadata::{Hook, InputCondition, InputType, TemplateResource};
 
 // ============================================================================
 // Types
@@ -57,6 +63,8 @@ pub enum LintCategory {
     HandlebarsError,
     /// Circular inheritance
     CircularInheritance,
+    /// Invalid file rule (`spec.files`)
+    FileRule,
     /// Best practices
     BestPractice,
 }
@@ -69,6 +77,7 @@ impl std::fmt::Display for LintCategory {
             LintCategory::InvalidInputConfig => write!(f, "invalid-input-config"),
             LintCategory::HandlebarsError => write!(f, "handlebars-error"),
             LintCategory::CircularInheritance => write!(f, "circular-inheritance"),
+            LintCategory::FileRule => write!(f, "file-rule"),
             LintCategory::BestPractice => write!(f, "best-practice"),
         }
     }
@@ -92,6 +101,9 @@ pub struct LintIssue {
     /// Suggested fix (if applicable)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub suggestion: Option<String>,
+    /// Machine-applicable fix (if applicable)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<LintFix>,
 }
 
 impl LintIssue {
@@ -103,6 +115,7 @@ impl LintIssue {
             file: None,
             line: None,
             suggestion: None,
+            fix: None,
         }
     }
 
@@ -114,6 +127,7 @@ impl LintIssue {
             file: None,
             line: None,
             suggestion: None,
+            fix: None,
         }
     }
 
@@ -125,6 +139,7 @@ impl LintIssue {
             file: None,
             line: None,
             suggestion: None,
+            fix: None,
         }
     }
 
@@ -137,6 +152,11 @@ impl LintIssue {
         self.suggestion = Some(suggestion.into());
         self
     }
+
+    fn with_fix(mut self, fix: LintFix) -> Self {
+        self.fix = Some(fix);
+        self
+    }
 }
 
 /// Result of linting a template pack
@@ -282,6 +302,12 @@ impl TemplateLinter {
         // 6. Best practices
         issues.extend(Self::check_best_practices(&template.resource, &template_file));
 
+        // 7. File rules
+        issues.extend(Self::validate_file_rules(fs, template));
+
+        // 8. Legacy input format
+        issues.extend(Self::detect_map_form_inputs(fs, &template_file));
+
         Ok(issues)
     }
 
@@ -466,6 +492,99 @@ impl TemplateLinter {
             issues.extend(Self::validate_single_input(input, file));
         }
 
+        // Conditions must be valid and refer to declared inputs
+        let input_names: HashSet<&str> = resource
+            .spec
+            .inputs
+            .iter()
+            .map(|i| i.name.as_str())
+            .collect();
+
+        for input in &resource.spec.inputs {
+            for condition in &input.conditions {
+                if let Err(e) = condition.validate() {
+                    issues.push(
+                        LintIssue::error(
+                            LintCategory::InvalidInputConfig,
+                            format!("Input '{}' has an invalid condition: {}", input.name, e),
+                        )
+                        .with_file(file),
+                    );
+                }
+            }
+
+            for name in input.conditions.iter().flat_map(|c| c.input_names()) {
+                if !name.starts_with('_') && !input_names.contains(name) {
+                    issues.push(
+                        LintIssue::warning(
+                            LintCategory::InvalidInputConfig,
+                            format!(
+                                "Input '{}' has a condition on undefined input '{}'",
+                                input.name, name
+                            ),
+                        )
+                        .with_file(file),
+                    );
+                }
+            }
+        }
+
+        // Computed values and validation rules must compile and refer to declared inputs
+        for input in &resource.spec.inputs {
+            if let Some(expression) = input.computed_expression() {
+                issues.extend(Self::validate_expression(
+                    expression,
+                    &format!("Computed input '{}'", input.name),
+                    &input_names,
+                    file,
+                ));
+            }
+        }
+
+        for rule in &resource.spec.validations {
+            let subject = format!("Validation rule '{}'", rule.rule);
+            issues.extend(Self::validate_expression(
+                &rule.rule,
+                &subject,
+                &input_names,
+                file,
+            ));
+
+            for condition in &rule.conditions {
+                if let Err(e) = condition.validate() {
+                    issues.push(
+                        LintIssue::error(
+                            LintCategory::InvalidInputConfig,
+                            format!("{} has an invalid condition: {}", subject, e),
+                        )
+                        .with_file(file),
+                    );
+                }
+            }
+        }
+
+        // Deprecated enum_values (fixable when the input has no explicit type)
+        for input in &resource.spec.inputs {
+            if input.enum_values.is_none() {
+                continue;
+            }
+
+            let issue = LintIssue::warning(
+                LintCategory::InvalidInputConfig,
+                format!("Input '{}' uses deprecated enum_values", input.name),
+            )
+            .with_file(file)
+            .with_suggestion("Use 'type: select' with options instead");
+
+            issues.push(if input.input_type.is_none() {
+                issue.with_fix(LintFix::ConvertEnumValues {
+                    input: input.name.clone(),
+                })
+            } else {
+                issue
+            });
+        }
+
         // Check for duplicate input names
         let mut seen_names = HashSet::new();
 
@@ -519,12 +638,20 @@ impl TemplateLinter {
                         }
                     }
                 }
-                InputType::Select { options } | InputType::MultiSelect { options, .. } => {
-                    if options.is_empty() {
+                InputType::Select {
+                    options,
+                    options_from,
+                }
+                | InputType::MultiSelect {
+                    options,
+                    options_from,
+                    ..
+                } => {
+                    if options.is_empty() && options_from.is_none() {
                         issues.push(
                             LintIssue::error(
                                 LintCategory::InvalidInputConfig,
-                                format!("Input '{}': select/multiselect must have options", input.name),
+                                format!("Input '{}': select/multiselect must have options or options_from", input.name),
                             )
                             .with_file(file),
                         );
@@ -635,6 +762,61 @@ impl TemplateLinter {
         issues
     }
 
+    /// Validate the Handlebars expression of a computed input or validation rule
+    fn validate_expression(
+        expression: &str,
+        subject: &str,
+        input_names: &HashSet<&str>,
+        file: &Path,
+    ) -> Vec<LintIssue> {
+        let template = match Template::compile(expression) {
+            Ok(template) => template,
+            Err(e) => {
+                return vec![
+                    LintIssue::error(
+                        LintCategory::InvalidInputConfig,
+                        format!("{} is not a valid Handlebars expression: {}", subject, e),
+                    )
+                    .with_file(file),
+                ];
+            }
+        };
+
+        let mut called = BTreeSet::new();
+        let mut vars = HashSet::new();
+        Self::collect_references(&template.elements, &mut called, &mut vars);
+
+        let mut issues: Vec<LintIssue> = called
+            .iter()
+            .filter(|name| !helpers::is_known_helper(name))
+            .map(|name| {
+                LintIssue::error(
+                    LintCategory::InvalidInputConfig,
+                    format!("{} uses unknown Handlebars helper '{}'", subject, name),
+                )
+                .with_file(file)
+            })
+            .collect();
+
+        let mut undefined: Vec<&String> = vars
+            .iter()
+            .filter(|name| !name.starts_with('_') && !input_names.contains(name.as_str()))
+            .collect();
+        undefined.sort();
+
+        for name in undefined {
+            issues.push(
+                LintIssue::warning(
+                    LintCategory::InvalidInputConfig,
+                    format!("{} references undefined input '{}'", subject, name),
+                )
+                .with_file(file),
+            );
+        }
+
+        issues
+    }
+
     /// Detect unused inputs
     fn detect_unused_inputs(
         fs: &dyn crate::traits::FileSystem,
@@ -656,18 +838,86 @@ impl TemplateLinter {
             return Ok(issues);
         }
 
-        // Read all template files and collect used variables
+        // Read all template files and collect used variables. When part of the template can't
+        // be scanned (unreadable files, a base template, hook scripts), an input that looks
+        // unused may still be used, so it is reported without a fix.
         let mut used_vars = HashSet::new();
+        let mut complete = template.resource.spec.extends.is_none();
         let entries = fs.walk_dir(&src_dir, 100)?;
 
         for path in entries {
             if fs.is_file(&path) {
-                if let Ok(content) = fs.read_to_string(&path) {
-                    // Find {{variable}} and {{#if variable}} patterns
-                    Self::extract_handlebars_variables(&content, &mut used_vars);
-                    // Find ${var:variable} patterns
-                    Self::extract_interpolation_variables(&content, &mut used_vars);
+                // Templated file names ({{name}}-values.yaml.hbs)
+                if let Ok(relative) = path.strip_prefix(&src_dir) {
+                    Self::extract_handlebars_variables(&relative.to_string_lossy(), &mut used_vars);
                 }
+
+                match fs.read_to_string(&path) {
+                    Ok(content) => {
+                        // Find {{variable}} and {{#if variable}} patterns
+                        Self::extract_handlebars_variables(&content, &mut used_vars);
+                        // Find ${var:variable} patterns
+                        Self::extract_interpolation_variables(&content, &mut used_vars);
+
+                        complete &= Template::compile(&content).is_ok();
+                    }
+                    Err(_) => complete = false,
+                }
+            }
+        }
+
+        // ${var:...} interpolations in the template definition (defaults, options_from, ...)
+        match fs.read_to_string(&template.path.join(".pmp.template.yaml")) {
+            Ok(content) => Self::extract_interpolation_variables(&content, &mut used_vars),
+            Err(_) => complete = false,
+        }
+
+        // Inputs driving file rules
+        for rule in &template.resource.spec.files {
+            used_vars.extend(rule.for_each.iter().cloned());
+            used_vars.extend(
+                rule.conditions
+                    .iter()
+                    .flat_map(|c| c.input_names())
+                    .map(String::from),
+            );
+        }
+
+        // Inputs feeding computed inputs, conditions of other inputs and validation rules
+        for input in &template.resource.spec.inputs {
+            if let Some(expression) = input.computed_expression() {
+                Self::extract_handlebars_variables(expression, &mut used_vars);
+            }
+            used_vars.extend(
+                input
+                    .conditions
+                    .iter()
+                    .flat_map(InputCondition::input_names)
+                    .map(String::from),
+            );
+        }
+        for rule in &template.resource.spec.validations {
+            Self::extract_handlebars_variables(&rule.rule, &mut used_vars);
+            used_vars.extend(
+                rule.conditions
+                    .iter()
+                    .flat_map(InputCondition::input_names)
+                    .map(String::from),
+            );
+        }
+
+        // Inputs deciding whether hooks run; command hooks also get every input through
+        // PMP_INPUT_* and the context file, which their scripts may read
+        for hook in template.resource.spec.hooks.iter().flat_map(|h| h.iter()) {
+            if let Hook::Command(config) = hook {
+                used_vars.extend(
+                    config
+                        .when
+                        .iter()
+                        .flat_map(InputCondition::input_names)
+                        .map(String::from),
+                );
+                complete = false;
             }
         }
 
@@ -679,18 +929,128 @@ impl TemplateLinter {
             }
 
             if !used_vars.contains(input_name) {
+                let issue = LintIssue::warning(
+                    LintCategory::UnusedInput,
+                    format!(
+                        "Input '{}' is defined but not used in templates",
+                        input_name
+                    ),
+                )
+                .with_file(template.path.join(".pmp.template.yaml"))
+                .with_suggestion("Remove the input or use it in template files");
+
+                issues.push(if complete {
+                    issue.with_fix(LintFix::RemoveInput {
+                        input: input_name.clone(),
+                    })
+                } else {
+                    issue
+                });
+            }
+        }
+
+        Ok(issues)
+    }
+
+    /// Validate file rules (`spec.files`): paths must exist under src/ and inputs must be declared
+    fn validate_file_rules(
+        fs: &dyn crate::traits::FileSystem,
+        template: &TemplateInfo,
+    ) -> Vec<LintIssue> {
+        let mut issues = Vec::new();
+        let template_file = template.path.join(".pmp.template.yaml");
+        let src_dir = template.path.join("src");
+
+        let input_names: HashSet<&str> = template
+            .resource
+            .spec
+            .inputs
+            .iter()
+            .map(|i| i.name.as_str())
+            .collect();
+
+        for rule in &template.resource.spec.files {
+            if !fs.exists(&src_dir.join(rule.path.trim_end_matches('/'))) {
                 issues.push(
                     LintIssue::warning(
-                        LintCategory::UnusedInput,
-                        format!("Input '{}' is defined but not used in templates", input_name),
+                        LintCategory::FileRule,
+                        format!("File rule path '{}' does not exist under src/", rule.path),
+                    )
+                    .with_file(&template_file)
+                    .with_suggestion("Use the path as written under src/, before rendering"),
+                );
+            }
+
+            if let Some(input) = &rule.for_each
+                && !input_names.contains(input.as_str())
+            {
+                issues.push(
+                    LintIssue::error(
+                        LintCategory::FileRule,
+                        format!(
+                            "File rule '{}' loops over undefined input '{}'",
+                            rule.path, input
+                        ),
                     )
-                    .with_file(template.path.join(".pmp.template.yaml"))
-                    .with_suggestion("Remove the input or use it in template files"),
+                    .with_file(&template_file),
                 );
             }
+
+            for condition in &rule.conditions {
+                if let Err(e) = condition.validate() {
+                    issues.push(
+                        LintIssue::error(
+                            LintCategory::FileRule,
+                            format!("File rule '{}' has an invalid condition: {}", rule.path, e),
+                        )
+                        .with_file(&template_file),
+                    );
+                }
+            }
+
+            for name in rule.conditions.iter().flat_map(|c| c.input_names()) {
+                let is_item = rule.for_each.is_some() && name == rule.item_name();
+
+                if !name.starts_with('_') && !is_item && !input_names.contains(name) {
+                    issues.push(
+                        LintIssue::warning(
+                            LintCategory::FileRule,
+                            format!(
+                                "File rule '{}' has a condition on undefined input '{}'",
+                                rule.path, name
+                            ),
+                        )
+                        .with_file(&template_file),
+                    );
+                }
+            }
         }
 
-        Ok(issues)
+        issues
+    }
+
+    /// Detect `spec.inputs` written in the legacy map form (name → spec)
+    fn detect_map_form_inputs(fs: &dyn crate::traits::FileSystem, file: &Path) -> Vec<LintIssue> {
+        let Ok(content) = fs.read_to_string(file) else {
+            return Vec::new();
+        };
+        let Ok(value) = serde_yaml::from_str::<serde_yaml::Value>(&content) else {
+            return Vec::new();
+        };
+
+        if !value["spec"]["inputs"].is_mapping() {
+            return Vec::new();
+        }
+
+        vec![
+            LintIssue::warning(
+                LintCategory::BestPractice,
+                "Inputs use the legacy map form (name: spec)",
+            )
+            .with_file(file)
+            .with_suggestion("Define inputs as a list with a 'name' field")
+            .with_fix(LintFix::ConvertInputsToList),
+        ]
     }
 
     /// Extract Handlebars variable references from content
@@ -718,6 +1078,76 @@ impl TemplateLinter {
                 }
             }
         }
+
+        // Helper arguments and subexpressions ({{hcl (default tags extra_tags)}})
+        if let Ok(template) = Template::compile(content) {
+            Self::collect_references(&template.elements, &mut BTreeSet::new(), vars);
+        }
+    }
+
+    /// Collect the helpers called and the variables referenced by template elements
+    fn collect_references(
+        elements: &[TemplateElement],
+        helpers: &mut BTreeSet<String>,
+        vars: &mut HashSet<String>,
+    ) {
+        for element in elements {
+            if let TemplateElement::Expression(helper)
+            | TemplateElement::HtmlExpression(helper)
+            | TemplateElement::HelperBlock(helper) = element
+            {
+                Self::collect_helper_references(helper, helpers, vars);
+            }
+        }
+    }
+
+    fn collect_helper_references(
+        helper: &HelperTemplate,
+        helpers: &mut BTreeSet<String>,
+        vars: &mut HashSet<String>,
+    ) {
+        let is_call = helper.block || !helper.params.is_empty() || !helper.hash.is_empty();
+
+        if is_call {
+            if let Some(name) = helper.name.as_name() {
+                helpers.insert(name.to_string());
+            }
+        } else {
+            Self::collect_parameter_references(&helper.name, helpers, vars);
+        }
+
+        for param in helper.params.iter().chain(helper.hash.values()) {
+            Self::collect_parameter_references(param, helpers, vars);
+        }
+
+        for template in helper.template.iter().chain(&helper.inverse) {
+            Self::collect_references(&template.elements, helpers, vars);
+        }
+    }
+
+    fn collect_parameter_references(
+        param: &Parameter,
+        helpers: &mut BTreeSet<String>,
+        vars: &mut HashSet<String>,
+    ) {
+        match param {
+            Parameter::Path(_) => {
+                // Root of the path: `../tags.team` → `tags`; `@index` and `this` are not inputs
+                let raw = param.as_name().unwrap_or_default();
+                let raw = raw.trim_start_matches("../").trim_start_matches("this.");
+                let root = raw.split(['.', '/', '[']).next().unwrap_or_default();
+
+                if !root.is_empty() && root != "this" && !root.starts_with('@') {
+                    vars.insert(root.to_string());
+                }
+            }
+            Parameter::Subexpression(subexpression) => {
+                if let TemplateElement::Expression(helper) = subexpression.as_element() {
+                    Self::collect_helper_references(helper, helpers, vars);
+                }
+            }
+            _ => {}
+        }
     }
 
     /// Extract ${var:...} interpolation variables from content
@@ -754,20 +1184,33 @@ impl TemplateLinter {
         let mut issues = Vec::new();
         let entries = fs.walk_dir(dir, 100)?;
 
-        let handlebars = Handlebars::new();
-
         for path in entries {
             if fs.is_file(&path) && path.extension().map_or(false, |e| e == "hbs") {
                 if let Ok(content) = fs.read_to_string(&path) {
-                    // Try to compile the template
-                    if let Err(e) = handlebars.render_template(&content, &serde_json::json!({})) {
-                        // Filter out "missing variable" errors - those are expected
-                        let error_str = e.to_string();
-
-                        if !error_str.contains("not found")
-                            && !error_str.contains("Variable")
-                            && !error_str.contains("missing")
-                        {
+                    // Try to compile the template and check the helpers it calls
+                    match Template::compile(&content) {
+                        Ok(template) => {
+                            let mut called = BTreeSet::new();
+                            Self::collect_references(
+                                &template.elements,
+                                &mut called,
+                                &mut HashSet::new(),
+                            );
+
+                            for name in called.iter().filter(|n| !helpers::is_known_helper(n)) {
+                                issues.push(
+                                    LintIssue::error(
+                                        LintCategory::HandlebarsError,
+                                        format!("Unknown Handlebars helper '{}'", name),
+                                    )
+                                    .with_file(&path)
+                                    .with_suggestion(
+                                        "See the list of helpers in the templates documentation",
+                                    ),
+                                );
+                            }
+                        }
+                        Err(e) => {
                             issues.push(
                                 LintIssue::error(
                                     LintCategory::HandlebarsError,
@@ -929,7 +1372,10 @@ impl TemplateLinter {
                         format!("Input '{}' is missing a description", input.name),
                     )
                     .with_file(file)
-                    .with_suggestion("Add a description field to the input"),
+                    .with_suggestion("Add a description field to the input")
+                    .with_fix(LintFix::AddDescription {
+                        input: input.name.clone(),
+                    }),
                 );
             }
         }
@@ -1060,6 +1506,10 @@ impl LintFormatter {
             output.push_str(&format!("  Suggestion: {}\n", suggestion));
         }
 
+        if issue.fix.is_some() {
+            output.push_str("  Fixable: run with --fix\n");
+        }
+
         output.push('\n');
         output
     }
@@ -1138,6 +1588,51 @@ mod tests {
         assert!(vars.contains("config"));
     }
 
+    #[test]
+    fn test_helper_references() {
+        use crate::traits::{FileSystem, MockFileSystem};
+
+        let content = r#"
+            tags = {{hcl (default tags extra_tags) indent=2}}
+            {{#if (gt replicas 1)}}ha{{/if}}
+            name = "{{snakeCase name}}"
+            {{#each zones}}{{@index}}: {{this.id}} {{../region}}{{/each}}
+        "#;
+
+        let mut vars = HashSet::new();
+        TemplateLinter::extract_handlebars_variables(content, &mut vars);
+
+        for var in ["tags", "extra_tags", "replicas", "name", "zones", "region"] {
+            assert!(vars.contains(var), "{} not found", var);
+        }
+        assert!(!vars.contains("this"));
+
+        let fs = MockFileSystem::new();
+        fs.write(Path::new("/tpl/src/main.tf.hbs"), content)
+            .unwrap();
+        fs.write(Path::new("/tpl/src/broken.tf.hbs"), "{{#if a}}")
+            .unwrap();
+
+        let messages: Vec<_> =
+            TemplateLinter::validate_handlebars_in_dir(&fs, Path::new("/tpl/src"))
+                .unwrap()
+                .into_iter()
+                .map(|i| i.message)
+                .collect();
+
+        assert_eq!(messages.len(), 3);
+        assert!(
+            messages
+                .iter()
+                .any(|m| m.starts_with("Handlebars syntax error"))
+        );
+        assert!(
+            messages
+                .iter()
+                .any(|m| m == "Unknown Handlebars helper 'snakeCase'")
+        );
+    }
+
     #[test]
     fn test_extract_interpolation_variables() {
         let content = r#"
@@ -1167,4 +1662,253 @@ mod tests {
         let issues = TemplateLinter::check_handlebars_common_issues(content, Path::new("test.hbs"));
         assert!(issues.iter().any(|i| i.severity == LintSeverity::Error));
     }
+
+    #[test]
+    fn test_validate_file_rules() {
+        use crate::traits::{FileSystem, MockFileSystem};
+
+        let fs = MockFileSystem::new();
+        fs.write(Path::new("/tpl/src/monitoring/alerts.tf"), "alerts")
+            .unwrap();
+
+        let resource: TemplateResource = serde_yaml::from_str(
+            r#"
+apiVersion: pmp.io/v1
+kind: Template
+metadata:
+  name: app
+spec:
+  apiVersion: pmp.io/v1
+  kind: App
+  executor: opentofu
+  inputs:
+    - name: enable_monitoring
+      type: boolean
+  files:
+    - path: monitoring/
+      if:
+        - input_name: enable_monitoring
+          equals: true
+    - path: "workers/{{item.name}}.tf.hbs"
+      for_each: workers
+      if:
+        - input_name: item
+        - input_name: region
+"#,
+        )
+        .unwrap();
+        let template = TemplateInfo {
+            resource,
+            path: PathBuf::from("/tpl"),
+            version: None,
+        };
+
+        let issues = TemplateLinter::validate_file_rules(&fs, &template);
+        let messages: Vec<_> = issues
+            .iter()
+            .map(|i| (i.severity, i.message.as_str()))
+            .collect();
+
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    LintSeverity::Warning,
+                    "File rule path 'workers/{{item.name}}.tf.hbs' does not exist under src/"
+                ),
+                (
+                    LintSeverity::Error,
+                    "File rule 'workers/{{item.name}}.tf.hbs' loops over undefined input 'workers'"
+                ),
+                (
+                    LintSeverity::Warning,
+                    "File rule 'workers/{{item.name}}.tf.hbs' has a condition on undefined input 'region'"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deprecated_inputs_are_fixable() {
+        use crate::traits::{FileSystem, MockFileSystem};
+
+        let content = r#"
+apiVersion: pmp.io/v1
+kind: Template
+metadata:
+  name: app
+spec:
+  apiVersion: pmp.io/v1
+  kind: App
+  executor: opentofu
+  inputs:
+    size:
+      enum_values: [small, large]
+    tier:
+      type: string
+      enum_values: [free, paid]
+"#;
+        let fs = MockFileSystem::new();
+        let file = Path::new("/tpl/.pmp.template.yaml");
+        fs.write(file, content).unwrap();
+        let resource: TemplateResource = serde_yaml::from_str(content).unwrap();
+
+        let fixes: Vec<_> = TemplateLinter::validate_input_configs(&resource, file)
+            .into_iter()
+            .chain(TemplateLinter::detect_map_form_inputs(&fs, file))
+            .map(|i| (i.message, i.fix))
+            .collect();
+
+        assert_eq!(
+            fixes,
+            vec![
+                (
+                    "Input 'size' uses deprecated enum_values".to_string(),
+                    Some(LintFix::ConvertEnumValues {
+                        input: "size".to_string()
+                    })
+                ),
+                ("Input 'tier' uses deprecated enum_values".to_string(), None),
+                (
+                    "Inputs use the legacy map form (name: spec)".to_string(),
+                    Some(LintFix::ConvertInputsToList)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_computed_inputs_and_rules() {
+        let resource: TemplateResource = serde_yaml::from_str(
+            r#"
+apiVersion: pmp.io/v1
+kind: Template
+metadata:
+  name: app
+spec:
+  apiVersion: pmp.io/v1
+  kind: App
+  executor: opentofu
+  inputs:
+    - name: min_replicas
+      description: Minimum replicas
+    - name: max_replicas
+      description: Maximum replicas
+      type: computed
+      value: "{{mul min_replicas 2}}"
+    - name: bucket
+      description: Bucket name
+      type: computed
+      value: "{{_project_name}}-{{regoin}}"
+  validations:
+    - rule: "{{lte min_replicas max_replicas}}"
+      message: ok
+    - rule: "{{within subnet vpc_cidr}}"
+      message: unknown helper
+    - rule: "{{#if min_replicas}}"
+      message: broken
+"#,
+        )
+        .unwrap();
+
+        let messages: Vec<_> =
+            TemplateLinter::validate_input_configs(&resource, Path::new("/tpl/.pmp.template.yaml"))
+                .into_iter()
+                .map(|i| i.message)
+                .collect();
+
+        assert_eq!(
+            messages[..4],
+            [
+                "Computed input 'bucket' references undefined input 'regoin'",
+                "Validation rule '{{within subnet vpc_cidr}}' uses unknown Handlebars helper 'within'",
+                "Validation rule '{{within subnet vpc_cidr}}' references undefined input 'subnet'",
+                "Validation rule '{{within subnet vpc_cidr}}' references undefined input 'vpc_cidr'",
+            ]
+        );
+        assert!(messages[4].starts_with(
+            "Validation rule '{{#if min_replicas}}' is not a valid Handlebars expression"
+        ));
+        assert_eq!(messages.len(), 5);
+    }
+
+    #[test]
+    fn test_detect_unused_inputs_outside_src() {
+        use crate::traits::{FileSystem, MockFileSystem};
+
+        let template_yaml = r#"
+apiVersion: pmp.io/v1
+kind: Template
+metadata:
+  name: app
+spec:
+  apiVersion: pmp.io/v1
+  kind: App
+  executor: opentofu
+  inputs:
+    - name: enable_backups
+      type: boolean
+    - name: retention_days
+      type: number
+      if:
+        - input_name: enable_backups
+          equals: true
+    - name: network
+      type: project_select
+    - name: subnet
+      type: select
+      options_from:
+        source: project_output
+        project: "${var:network}"
+        output: subnets
+    - name: legacy
+"#;
+        let fs = MockFileSystem::new();
+        fs.write(Path::new("/tpl/.pmp.template.yaml"), template_yaml)
+            .unwrap();
+        fs.write(
+            Path::new("/tpl/src/main.tf.hbs"),
+            "retention = {{retention_days}}\nsubnet = \"{{subnet}}\"\n",
+        )
+        .unwrap();
+
+        let template = |yaml: &str| TemplateInfo {
+            resource: serde_yaml::from_str(yaml).unwrap(),
+            path: PathBuf::from("/tpl"),
+            version: None,
+        };
+        let unused = |template: &TemplateInfo| -> Vec<(String, Option<LintFix>)> {
+            TemplateLinter::detect_unused_inputs(&fs, template)
+                .unwrap()
+                .into_iter()
+                .map(|i| (i.message, i.fix))
+                .collect()
+        };
+
+        assert_eq!(
+            unused(&template(template_yaml)),
+            vec![(
+                "Input 'legacy' is defined but not used in templates".to_string(),
+                Some(LintFix::RemoveInput {
+                    input: "legacy".to_string()
+                })
+            )]
+        );
+
+        // Command hook scripts receive every input, so the input can't be removed automatically
+        let with_hooks = format!(
+            "{}  hooks:\n    post_apply:\n      - type: command\n        config:\n          command: ./notify.sh\n          when:\n            - input_name: legacy\n",
+            template_yaml
+        );
+        assert!(unused(&template(&with_hooks)).is_empty());
+
+        let with_hooks = with_hooks.replace("input_name: legacy", "input_name: network");
+        assert_eq!(
+            unused(&template(&with_hooks)),
+            vec![(
+                "Input 'legacy' is defined but not used in templates".to_string(),
+                None
+            )]
+        );
+    }
 }
@@ -0,0 +1,613 @@
+//! Machine-applicable fixes for lint issues
+//!
+//! Fixes edit the `spec.inputs` block of a template file as text, so comments, key order and
+//! formatting of the lines they do not touch are preserved.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use super::lint::LintResult;
+use crate::traits::FileSystem;
+
+/// An edit that resolves a lint issue in the issue's file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum LintFix {
+    /// Remove an input from `spec.inputs`
+    RemoveInput { input: String },
+
+    /// Replace deprecated `enum_values` with `type: select` and options
+    ConvertEnumValues { input: String },
+
+    /// Rewrite legacy map-form `spec.inputs` as a list
+    ConvertInputsToList,
+
+    /// Add a placeholder description to an input
+    AddDescription { input: String },
+}
+
+impl LintFix {
+    /// Apply the fix to the content of a template file
+    pub fn apply(&self, content: &str) -> Result<String> {
+        let mut lines: Vec<String> = content.lines().map(String::from).collect();
+
+        match self {
+            Self::ConvertInputsToList => convert_inputs_to_list(&mut lines)?,
+            Self::RemoveInput { input } => remove_input(&mut lines, input)?,
+            Self::ConvertEnumValues { input } => convert_enum_values(&mut lines, input)?,
+            Self::AddDescription { input } => add_description(&mut lines, input)?,
+        }
+
+        let mut fixed = lines.join("\n");
+        if content.ends_with('\n') {
+            fixed.push('\n');
+        }
+        Ok(fixed)
+    }
+
+    /// Fixes that change the shape of the inputs block run first
+    fn order(&self) -> u8 {
+        match self {
+            Self::ConvertInputsToList => 0,
+            Self::RemoveInput { .. } => 1,
+            Self::ConvertEnumValues { .. } => 2,
+            Self::AddDescription { .. } => 3,
+        }
+    }
+}
+
+/// A file with fixes applied, not yet written
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedFile {
+    pub path: PathBuf,
+
+    pub original: String,
+
+    pub fixed: String,
+
+    /// Number of fixes applied
+    pub fixes: usize,
+}
+
+/// Apply the fixes of all issues, grouped by file; files that do not change are omitted
+pub fn plan_fixes(fs: &dyn FileSystem, results: &[LintResult]) -> Result<Vec<FixedFile>> {
+    let mut by_file: BTreeMap<&PathBuf, Vec<&LintFix>> = BTreeMap::new();
+
+    for issue in results.iter().flat_map(|r| &r.issues) {
+        if let (Some(file), Some(fix)) = (&issue.file, &issue.fix) {
+            by_file.entry(file).or_default().push(fix);
+        }
+    }
+
+    let mut files = Vec::new();
+
+    for (path, mut fixes) in by_file {
+        fixes.sort_by_key(|f| f.order());
+        fixes.dedup();
+
+        let original = fs
+            .read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+
+        let mut fixed = original.clone();
+        for fix in &fixes {
+            fixed = fix
+                .apply(&fixed)
+                .with_context(|| format!("Failed to fix {}", path.display()))?;
+        }
+
+        if fixed != original {
+            files.push(FixedFile {
+                path: path.clone(),
+                original,
+                fixed,
+                fixes: fixes.len(),
+            });
+        }
+    }
+
+    Ok(files)
+}
+
+// ============================================================================
+// Text editing of spec.inputs
+// ============================================================================
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// Blank or comment-only line
+fn is_trivia(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.is_empty() || trimmed.starts_with('#')
+}
+
+fn is_list_item(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed == "-" || trimmed.starts_with("- ")
+}
+
+/// Split `key: value # comment` into (key, value, comment); the comment keeps its `#`
+fn split_key_line(line: &str) -> Option<(&str, &str, &str)> {
+    let trimmed = line.trim_start().trim_start_matches("- ").trim_start();
+    let (key, rest) = trimmed.split_once(':')?;
+
+    let (value, comment) = match rest.find(" #") {
+        Some(pos) => (&rest[..pos], rest[pos..].trim()),
+        None => (rest, ""),
+    };
+
+    Some((key.trim(), value.trim(), comment))
+}
+
+/// YAML scalar for a string, quoted when needed
+fn yaml_scalar(value: &str) -> String {
+    serde_yaml::to_string(value)
+        .map(|s| s.trim_end().to_string())
+        .unwrap_or_else(|_| format!("{:?}", value))
+}
+
+/// Unquote a YAML key or scalar
+fn parse_scalar(text: &str) -> String {
+    serde_yaml::from_str::<String>(text).unwrap_or_else(|_| text.to_string())
+}
+
+/// The `spec.inputs` key line and the range of lines holding its block value
+struct InputsBlock {
+    key_line: usize,
+    key_indent: usize,
+    /// First line after the key
+    start: usize,
+    /// End of the block (exclusive), without trailing blank or comment lines
+    end: usize,
+}
+
+fn find_inputs_block(lines: &[String]) -> Result<InputsBlock> {
+    let spec_line = lines
+        .iter()
+        .position(|l| indent_of(l) == 0 && l.trim_end().starts_with("spec:"))
+        .context("Template file has no spec")?;
+
+    let mut child_indent = None;
+    let mut key_line = None;
+
+    for (i, line) in lines.iter().enumerate().skip(spec_line + 1) {
+        if is_trivia(line) {
+            continue;
+        }
+
+        let indent = indent_of(line);
+        if indent == 0 {
+            break;
+        }
+
+        let child_indent = *child_indent.get_or_insert(indent);
+        if indent == child_indent && line.trim_start().starts_with("inputs:") {
+            key_line = Some(i);
+            break;
+        }
+    }
+
+    let key_line = key_line.context("Template file has no spec.inputs")?;
+    let key_indent = indent_of(&lines[key_line]);
+
+    let mut end = key_line + 1;
+    for (i, line) in lines.iter().enumerate().skip(key_line + 1) {
+        if is_trivia(line) {
+            continue;
+        }
+
+        let indent = indent_of(line);
+        if indent > key_indent || (indent == key_indent && is_list_item(line)) {
+            end = i + 1;
+        } else {
+            break;
+        }
+    }
+
+    Ok(InputsBlock {
+        key_line,
+        key_indent,
+        start: key_line + 1,
+        end,
+    })
+}
+
+/// An input of a list-form inputs block
+struct InputItem {
+    start: usize,
+    end: usize,
+    /// Indent of the item's keys (after the `- `)
+    content_indent: usize,
+    /// Line holding `name:`
+    name_line: usize,
+}
+
+fn list_items(lines: &[String], block: &InputsBlock) -> Result<Vec<(String, InputItem)>> {
+    let starts: Vec<usize> = (block.start..block.end)
+        .filter(|&i| !is_trivia(&lines[i]) && is_list_item(&lines[i]))
+        .collect();
+
+    let Some(&first) = starts.first() else {
+        return Ok(Vec::new());
+    };
+    let item_indent = indent_of(&lines[first]);
+
+    let starts: Vec<usize> = starts
+        .into_iter()
+        .filter(|&i| indent_of(&lines[i]) == item_indent)
+        .collect();
+
+    let mut items = Vec::new();
+
+    for (n, &start) in starts.iter().enumerate() {
+        let mut end = starts.get(n + 1).copied().unwrap_or(block.end);
+        while end > start + 1 && is_trivia(&lines[end - 1]) {
+            end -= 1;
+        }
+
+        let dash_line = &lines[start];
+        let after_dash = &dash_line[item_indent + 1..];
+        let content_indent = item_indent + 1 + indent_of(after_dash);
+
+        let name_line = (start..end)
+            .find(|&i| {
+                let line = &lines[i];
+                (i == start || indent_of(line) == content_indent)
+                    && split_key_line(line).is_some_and(|(key, _, _)| key == "name")
+            })
+            .with_context(|| format!("Input at line {} has no name", start + 1))?;
+
+        let (_, value, _) = split_key_line(&lines[name_line]).unwrap_or_default();
+        items.push((
+            parse_scalar(value),
+            InputItem {
+                start,
+                end,
+                content_indent,
+                name_line,
+            },
+        ));
+    }
+
+    Ok(items)
+}
+
+fn find_item(lines: &[String], block: &InputsBlock, input: &str) -> Result<InputItem> {
+    list_items(lines, block)?
+        .into_iter()
+        .find(|(name, _)| name == input)
+        .map(|(_, item)| item)
+        .with_context(|| format!("Input '{}' not found in spec.inputs", input))
+}
+
+/// Line of a key of an item (the `- ` line counts as being at the content indent)
+fn find_item_key(lines: &[String], item: &InputItem, key: &str) -> Option<usize> {
+    (item.start..item.end).find(|&i| {
+        (i == item.start || indent_of(&lines[i]) == item.content_indent)
+            && split_key_line(&lines[i]).is_some_and(|(k, _, _)| k == key)
+    })
+}
+
+fn remove_input(lines: &mut Vec<String>, input: &str) -> Result<()> {
+    let block = find_inputs_block(lines)?;
+    let item = find_item(lines, &block, input)?;
+    let remaining = list_items(lines, &block)?.len() - 1;
+
+    // Comments directly above the item document it
+    let item_indent = indent_of(&lines[item.start]);
+    let mut start = item.start;
+    while start > block.start
+        && lines[start - 1].trim_start().starts_with('#')
+        && indent_of(&lines[start - 1]) == item_indent
+    {
+        start -= 1;
+    }
+
+    lines.drain(start..item.end);
+
+    // An empty block would parse as null
+    if remaining == 0 {
+        lines[block.key_line] = format!("{}inputs: []", " ".repeat(block.key_indent));
+    }
+
+    Ok(())
+}
+
+fn add_description(lines: &mut Vec<String>, input: &str) -> Result<()> {
+    let block = find_inputs_block(lines)?;
+    let item = find_item(lines, &block, input)?;
+
+    if find_item_key(lines, &item, "description").is_some() {
+        return Ok(());
+    }
+
+    lines.insert(
+        item.name_line + 1,
+        format!(
+            "{}description: {}",
+            " ".repeat(item.content_indent),
+            yaml_scalar(&format!("TODO: describe {}", input))
+        ),
+    );
+
+    Ok(())
+}
+
+fn convert_enum_values(lines: &mut Vec<String>, input: &str) -> Result<()> {
+    let block = find_inputs_block(lines)?;
+    let item = find_item(lines, &block, input)?;
+
+    if find_item_key(lines, &item, "type").is_some() {
+        anyhow::bail!("Input '{}' already has a type", input);
+    }
+
+    let key_line = find_item_key(lines, &item, "enum_values")
+        .with_context(|| format!("Input '{}' has no enum_values", input))?;
+    let (_, inline, _) = split_key_line(&lines[key_line]).unwrap_or_default();
+
+    // The value is either inline (flow list) or the block below the key
+    let mut value_end = key_line + 1;
+    let value_text = if inline.is_empty() {
+        let mut value_lines = Vec::new();
+        for (i, line) in lines.iter().enumerate().take(item.end).skip(key_line + 1) {
+            let indent = indent_of(line);
+            if !is_trivia(line)
+                && (indent > item.content_indent
+                    || (indent == item.content_indent && is_list_item(line)))
+            {
+                value_lines.push(line.as_str());
+                value_end = i + 1;
+            } else if !is_trivia(line) {
+                break;
+            }
+        }
+        value_lines.join("\n")
+    } else {
+        inline.to_string()
+    };
+
+    let values: Vec<String> = serde_yaml::from_str(&value_text)
+        .with_context(|| format!("Input '{}': enum_values must be a list of strings", input))?;
+
+    // Keep whatever precedes the key on its line (the `- ` of the item)
+    let key_column = lines[key_line]
+        .find("enum_values")
+        .unwrap_or(item.content_indent);
+    let prefix = lines[key_line][..key_column].to_string();
+    let indent = " ".repeat(item.content_indent);
+
+    let mut replacement = vec![
+        format!("{}type: select", prefix),
+        format!("{}options:", indent),
+    ];
+    for value in &values {
+        replacement.push(format!("{}  - label: {}", indent, yaml_scalar(value)));
+        replacement.push(format!("{}    value: {}", indent, yaml_scalar(value)));
+    }
+
+    lines.splice(key_line..value_end, replacement);
+    Ok(())
+}
+
+fn convert_inputs_to_list(lines: &mut Vec<String>) -> Result<()> {
+    let block = find_inputs_block(lines)?;
+
+    let (_, inline, _) = split_key_line(&lines[block.key_line]).unwrap_or_default();
+    if !inline.is_empty() {
+        anyhow::bail!("Inline spec.inputs cannot be converted automatically");
+    }
+
+    let entry_starts: Vec<usize> = (block.start..block.end)
+        .filter(|&i| !is_trivia(&lines[i]))
+        .collect();
+    let Some(&first) = entry_starts.first() else {
+        return Ok(());
+    };
+
+    if is_list_item(&lines[first]) {
+        return Ok(());
+    }
+
+    let entry_indent = indent_of(&lines[first]);
+    let entry_starts: Vec<usize> = entry_starts
+        .into_iter()
+        .filter(|&i| indent_of(&lines[i]) == entry_indent)
+        .collect();
+    let content_indent = entry_indent + 2;
+
+    let mut converted = Vec::new();
+    converted.extend(lines[block.start..first].iter().cloned());
+
+    for (n, &start) in entry_starts.iter().enumerate() {
+        let end = entry_starts.get(n + 1).copied().unwrap_or(block.end);
+        let (key, value, comment) = split_key_line(&lines[start])
+            .with_context(|| format!("Unexpected line {} in spec.inputs", start + 1))?;
+
+        let mut name_line = format!(
+            "{}- name: {}",
+            " ".repeat(entry_indent),
+            yaml_scalar(&parse_scalar(key))
+        );
+        if !comment.is_empty() {
+            name_line.push(' ');
+            name_line.push_str(comment);
+        }
+        converted.push(name_line);
+
+        // Inline spec (e.g., `region: {default: eu-west-1}`)
+        if !matches!(value, "" | "{}" | "~" | "null") {
+            let spec: serde_yaml::Mapping = serde_yaml::from_str(value)
+                .with_context(|| format!("Input '{}': unsupported inline spec '{}'", key, value))?;
+            for line in serde_yaml::to_string(&spec)?.lines() {
+                converted.push(format!("{}{}", " ".repeat(content_indent), line));
+            }
+        }
+
+        // Re-indent the body to the list item's content indent
+        let body = &lines[start + 1..end];
+        let body_indent = body
+            .iter()
+            .find(|l| !is_trivia(l))
+            .map(|l| indent_of(l))
+            .unwrap_or(content_indent);
+
+        for line in body {
+            if line.trim().is_empty() {
+                converted.push(String::new());
+            } else if body_indent >= content_indent {
+                let remove = (body_indent - content_indent).min(indent_of(line));
+                converted.push(line[remove..].to_string());
+            } else {
+                converted.push(format!(
+                    "{}{}",
+                    " ".repeat(content_indent - body_indent),
+                    line
+                ));
+            }
+        }
+    }
+
+    lines.splice(block.start..block.end, converted);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::template::metadata::{InputType, TemplateResource};
+
+    const TEMPLATE: &str = r#"apiVersion: pmp.io/v1
+kind: Template
+metadata:
+  name: api
+spec:
+  apiVersion: pmp.io/v1
+  kind: Api
+  executor: opentofu
+  # Inputs of the API
+  inputs:
+    - name: size   # instance size
+      enum_values:
+        - small
+        - "yes"
+      default: small
+    - name: legacy
+      description: Not used anymore
+    # Region to deploy to
+    - name: region
+      default: eu-west-1
+  files: []
+"#;
+
+    fn parse(content: &str) -> TemplateResource {
+        serde_yaml::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn test_list_form_fixes() {
+        let mut content = TEMPLATE.to_string();
+        for fix in [
+            LintFix::RemoveInput {
+                input: "legacy".to_string(),
+            },
+            LintFix::ConvertEnumValues {
+                input: "size".to_string(),
+            },
+            LintFix::AddDescription {
+                input: "region".to_string(),
+            },
+        ] {
+            content = fix.apply(&content).unwrap();
+        }
+
+        assert_eq!(
+            content,
+            r#"apiVersion: pmp.io/v1
+kind: Template
+metadata:
+  name: api
+spec:
+  apiVersion: pmp.io/v1
+  kind: Api
+  executor: opentofu
+  # Inputs of the API
+  inputs:
+    - name: size   # instance size
+      type: select
+      options:
+        - label: small
+          value: small
+        - label: yes
+          value: yes
+      default: small
+    # Region to deploy to
+    - name: region
+      description: 'TODO: describe region'
+      default: eu-west-1
+  files: []
+"#
+        );
+
+        let resource = parse(&content);
+        assert_eq!(resource.spec.inputs.len(), 2);
+        assert!(matches!(
+            &resource.spec.inputs[0].input_type,
+            Some(InputType::Select { options, .. }) if options[1].value == "yes"
+        ));
+        assert!(resource.spec.inputs[0].enum_values.is_none());
+    }
+
+    #[test]
+    fn test_convert_inputs_to_list() {
+        let content = r#"spec:
+  apiVersion: pmp.io/v1
+  kind: Api
+  executor: opentofu
+  inputs:
+    # Instance size
+    size:
+      type: string
+      default: small
+    region: {default: eu-west-1}
+    tags:
+        description: Extra tags
+  order: 1
+"#;
+
+        let fixed = LintFix::ConvertInputsToList.apply(content).unwrap();
+        assert_eq!(
+            fixed,
+            r#"spec:
+  apiVersion: pmp.io/v1
+  kind: Api
+  executor: opentofu
+  inputs:
+    # Instance size
+    - name: size
+      type: string
+      default: small
+    - name: region
+      default: eu-west-1
+    - name: tags
+      description: Extra tags
+  order: 1
+"#
+        );
+
+        // Removing the last input leaves an empty list
+        let fixed = ["size", "region", "tags"]
+            .iter()
+            .fold(fixed, |content, input| {
+                LintFix::RemoveInput {
+                    input: input.to_string(),
+                }
+                .apply(&content)
+                .unwrap()
+            });
+        assert!(fixed.contains("  inputs: []\n  order: 1\n"));
+    }
+}
@@ -0,0 +1,655 @@
+//! Three-way merge of regenerated template files
+//!
+//! pmp records the content it rendered for every generated file of an environment. On
+//! update, the recorded rendering (base), the file on disk (ours) and the new rendering
+//! (theirs) are merged line by line, so hand edits survive template and input changes.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::renderer::RenderedFile;
+use crate::traits::FileSystem;
+
+/// Recorded renderings, relative to the environment directory
+pub const RENDERED_BASELINE_FILE: &str = ".pmp/rendered.json";
+
+/// Marker lines written around conflicting hunks
+const CONFLICT_START: &str = "<<<<<<< local";
+const CONFLICT_SEPARATOR: &str = "=======";
+const CONFLICT_END: &str = ">>>>>>> template";
+
+/// How to resolve files changed both locally and by the template
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeStrategy {
+    /// Keep locally edited files as they are
+    Ours,
+    /// Overwrite files with the new rendering (discarding local edits)
+    Theirs,
+    /// Merge line by line, writing conflict markers where both sides changed
+    #[default]
+    Merge,
+}
+
+impl MergeStrategy {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "ours" => Ok(Self::Ours),
+            "theirs" => Ok(Self::Theirs),
+            "merge" => Ok(Self::Merge),
+            _ => anyhow::bail!(
+                "Unsupported merge strategy: {} (expected ours, theirs or merge)",
+                s
+            ),
+        }
+    }
+}
+
+/// Content pmp rendered for the generated files of an environment
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RenderBaseline {
+    /// Rendered content per output directory (relative to the environment, "" for the
+    /// environment itself) and file path within it
+    #[serde(default)]
+    pub outputs: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl RenderBaseline {
+    /// Baseline file of an environment
+    pub fn path(env_path: &Path) -> PathBuf {
+        env_path.join(RENDERED_BASELINE_FILE)
+    }
+
+    /// Load the baseline of an environment (empty if none was recorded yet)
+    pub fn load(fs: &dyn FileSystem, env_path: &Path) -> Result<Self> {
+        let path = Self::path(env_path);
+
+        if !fs.exists(&path) {
+            return Ok(Self::default());
+        }
+
+        let content = fs.read_to_string(&path)?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Invalid rendered files baseline: {}", path.display()))
+    }
+
+    pub fn save(&self, fs: &dyn FileSystem, env_path: &Path) -> Result<()> {
+        let path = Self::path(env_path);
+
+        if let Some(parent) = path.parent() {
+            fs.create_dir_all(parent)?;
+        }
+
+        fs.write(&path, &serde_json::to_string_pretty(self)?)
+            .with_context(|| {
+                format!(
+                    "Failed to write rendered files baseline: {}",
+                    path.display()
+                )
+            })
+    }
+
+    /// Record files written by `TemplateRenderer::render_template` into `output_dir`
+    pub fn record(
+        fs: &dyn FileSystem,
+        env_path: &Path,
+        output_dir: &Path,
+        generated_files: &[String],
+    ) -> Result<()> {
+        let mut baseline = Self::load(fs, env_path)?;
+        let output = baseline
+            .outputs
+            .entry(output_key(env_path, output_dir))
+            .or_default();
+
+        for file in generated_files {
+            let content = fs.read_to_string(&output_dir.join(file))?;
+            output.insert(file.clone(), content);
+        }
+
+        baseline.save(fs, env_path)
+    }
+
+    /// Forget the files recorded for `output_dir` (e.g., after removing a plugin module)
+    pub fn forget(fs: &dyn FileSystem, env_path: &Path, output_dir: &Path) -> Result<()> {
+        let mut baseline = Self::load(fs, env_path)?;
+
+        if baseline
+            .outputs
+            .remove(&output_key(env_path, output_dir))
+            .is_some()
+        {
+            baseline.save(fs, env_path)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Baseline key of an output directory
+fn output_key(env_path: &Path, output_dir: &Path) -> String {
+    output_dir
+        .strip_prefix(env_path)
+        .unwrap_or(output_dir)
+        .to_string_lossy()
+        .to_string()
+}
+
+/// What happened to a generated file on update
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileOutcome {
+    Created,
+    Updated,
+    Unchanged,
+    /// Local edits and template changes merged cleanly
+    Merged,
+    /// Local edits kept as they are
+    KeptLocal,
+    /// Both sides changed the same lines; conflict markers were written
+    Conflict,
+    /// No recorded rendering to merge with; the new rendering was written to `<file>.rej`
+    Rejected,
+    /// Deleted locally and left deleted
+    KeptDeleted,
+    /// No longer generated by the template
+    Removed,
+    /// No longer generated by the template, but kept because it was edited locally
+    Orphaned,
+}
+
+impl FileOutcome {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Created => "Created",
+            Self::Updated => "Updated",
+            Self::Unchanged => "Unchanged",
+            Self::Merged => "Merged",
+            Self::KeptLocal => "Kept local",
+            Self::Conflict => "Conflict",
+            Self::Rejected => "Rejected",
+            Self::KeptDeleted => "Kept deleted",
+            Self::Removed => "Removed",
+            Self::Orphaned => "Orphaned",
+        }
+    }
+}
+
+/// Files touched by an update, with paths relative to the environment
+#[derive(Debug, Default)]
+pub struct MergeSummary {
+    pub files: Vec<(String, FileOutcome)>,
+}
+
+impl MergeSummary {
+    pub fn count(&self, outcome: FileOutcome) -> usize {
+        self.files.iter().filter(|(_, o)| *o == outcome).count()
+    }
+
+    /// Files that need manual attention (conflict markers or `.rej` files)
+    pub fn needs_attention(&self) -> bool {
+        !self.attention_paths().is_empty()
+    }
+
+    /// Files to resolve by hand: conflicted files and `.rej` files
+    pub fn attention_paths(&self) -> Vec<String> {
+        self.files
+            .iter()
+            .filter_map(|(path, outcome)| match outcome {
+                FileOutcome::Conflict => Some(path.clone()),
+                FileOutcome::Rejected => Some(format!("{}.rej", path)),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Applies new renderings to an environment, merging them with local edits
+pub struct RenderMerge<'a> {
+    fs: &'a dyn FileSystem,
+    env_path: PathBuf,
+    strategy: MergeStrategy,
+    baseline: RenderBaseline,
+    summary: MergeSummary,
+}
+
+impl<'a> RenderMerge<'a> {
+    pub fn new(fs: &'a dyn FileSystem, env_path: &Path, strategy: MergeStrategy) -> Result<Self> {
+        Ok(Self {
+            fs,
+            env_path: env_path.to_path_buf(),
+            strategy,
+            baseline: RenderBaseline::load(fs, env_path)?,
+            summary: MergeSummary::default(),
+        })
+    }
+
+    /// Apply the files rendered for `output_dir`
+    ///
+    /// Files that were generated before but are no longer rendered are removed, unless they
+    /// were edited locally.
+    pub fn apply(&mut self, output_dir: &Path, files: Vec<RenderedFile>) -> Result<()> {
+        let key = output_key(&self.env_path, output_dir);
+        let mut previous = self.baseline.outputs.remove(&key).unwrap_or_default();
+        let mut rendered = BTreeMap::new();
+
+        for file in files {
+            let base = previous.remove(&file.path);
+            let outcome = self.apply_file(output_dir, &file, base.as_deref())?;
+            self.summary
+                .files
+                .push((display_path(&key, &file.path), outcome));
+            rendered.insert(file.path, file.content);
+        }
+
+        for (path, base) in previous {
+            let output_path = output_dir.join(&path);
+
+            if !self.fs.exists(&output_path) {
+                continue;
+            }
+
+            let ours = self.fs.read_to_string(&output_path)?;
+            let outcome = if ours == base || self.strategy == MergeStrategy::Theirs {
+                self.fs.remove_file(&output_path)?;
+                FileOutcome::Removed
+            } else {
+                FileOutcome::Orphaned
+            };
+            self.summary
+                .files
+                .push((display_path(&key, &path), outcome));
+        }
+
+        self.baseline.outputs.insert(key, rendered);
+        Ok(())
+    }
+
+    fn apply_file(
+        &self,
+        output_dir: &Path,
+        file: &RenderedFile,
+        base: Option<&str>,
+    ) -> Result<FileOutcome> {
+        let output_path = output_dir.join(&file.path);
+        let theirs = file.content.as_str();
+
+        if !self.fs.exists(&output_path) {
+            if base.is_some()
+                && self.strategy != MergeStrategy::Theirs
+                && (base == Some(theirs) || self.strategy == MergeStrategy::Ours)
+            {
+                return Ok(FileOutcome::KeptDeleted);
+            }
+
+            self.write(&output_path, theirs)?;
+            return Ok(FileOutcome::Created);
+        }
+
+        let ours = self.fs.read_to_string(&output_path)?;
+
+        if ours == theirs {
+            return Ok(FileOutcome::Unchanged);
+        }
+
+        if base == Some(ours.as_str()) || self.strategy == MergeStrategy::Theirs {
+            self.write(&output_path, theirs)?;
+            return Ok(FileOutcome::Updated);
+        }
+
+        if base == Some(theirs) || self.strategy == MergeStrategy::Ours {
+            return Ok(FileOutcome::KeptLocal);
+        }
+
+        let Some(base) = base else {
+            let reject_path = output_dir.join(format!("{}.rej", file.path));
+            self.write(&reject_path, theirs)?;
+            return Ok(FileOutcome::Rejected);
+        };
+
+        let merged = merge_lines(base, &ours, theirs);
+        self.write(&output_path, &merged.content)?;
+
+        Ok(if merged.conflicts > 0 {
+            FileOutcome::Conflict
+        } else {
+            FileOutcome::Merged
+        })
+    }
+
+    fn write(&self, path: &Path, content: &str) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            self.fs.create_dir_all(parent)?;
+        }
+
+        self.fs
+            .write(path, content)
+            .with_context(|| format!("Failed to write output file: {:?}", path))
+    }
+
+    /// Save the new renderings as the baseline for the next update
+    pub fn finish(self) -> Result<MergeSummary> {
+        self.baseline.save(self.fs, &self.env_path)?;
+        Ok(self.summary)
+    }
+}
+
+fn display_path(output_key: &str, path: &str) -> String {
+    if output_key.is_empty() {
+        path.to_string()
+    } else {
+        format!("{}/{}", output_key, path)
+    }
+}
+
+/// Result of a line-based three-way merge
+#[derive(Debug, Default, PartialEq)]
+pub struct MergedText {
+    pub content: String,
+
+    /// Number of hunks changed differently on both sides
+    pub conflicts: usize,
+}
+
+/// Merge the changes from `base` to `ours` and from `base` to `theirs` (diff3)
+///
+/// Hunks changed on one side only take that side's version. Hunks changed on both sides
+/// are kept as-is when identical, otherwise both versions are written between conflict
+/// markers.
+pub fn merge_lines(base: &str, ours: &str, theirs: &str) -> MergedText {
+    let base: Vec<&str> = base.split_inclusive('\n').collect();
+    let ours: Vec<&str> = ours.split_inclusive('\n').collect();
+    let theirs: Vec<&str> = theirs.split_inclusive('\n').collect();
+
+    let mut ours_at = vec![None; base.len()];
+    for (b, o) in matching_lines(&base, &ours) {
+        ours_at[b] = Some(o);
+    }
+
+    let mut theirs_at = vec![None; base.len()];
+    for (b, t) in matching_lines(&base, &theirs) {
+        theirs_at[b] = Some(t);
+    }
+
+    let mut merged = MergedText::default();
+    let (mut b, mut o, mut t) = (0, 0, 0);
+
+    loop {
+        // Next base line kept unchanged by both sides
+        let stable = (b..base.len()).find_map(|i| Some((i, ours_at[i]?, theirs_at[i]?)));
+        let (bi, oi, ti) = stable.unwrap_or((base.len(), ours.len(), theirs.len()));
+
+        merged.push_hunk(&base[b..bi], &ours[o..oi], &theirs[t..ti]);
+
+        if stable.is_none() {
+            break;
+        }
+
+        merged.content.push_str(base[bi]);
+        (b, o, t) = (bi + 1, oi + 1, ti + 1);
+    }
+
+    merged
+}
+
+impl MergedText {
+    fn push_hunk(&mut self, base: &[&str], ours: &[&str], theirs: &[&str]) {
+        if ours == base {
+            self.push_lines(theirs);
+        } else if theirs == base || ours == theirs {
+            self.push_lines(ours);
+        } else {
+            self.conflicts += 1;
+            self.push_marker(CONFLICT_START);
+            self.push_lines(ours);
+            self.push_marker(CONFLICT_SEPARATOR);
+            self.push_lines(theirs);
+            self.push_marker(CONFLICT_END);
+        }
+    }
+
+    fn push_lines(&mut self, lines: &[&str]) {
+        lines.iter().for_each(|line| self.content.push_str(line));
+    }
+
+    fn push_marker(&mut self, marker: &str) {
+        if !self.content.is_empty() && !self.content.ends_with('\n') {
+            self.content.push('\n');
+        }
+        self.content.push_str(marker);
+        self.content.push('\n');
+    }
+}
+
+/// Index pairs of the lines in the longest common subsequence of `a` and `b`
+pub fn matching_lines(a: &[&str], b: &[&str]) -> Vec<(usize, usize)> {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+
+    let a_mid = &a[prefix..a.len() - suffix];
+    let b_mid = &b[prefix..b.len() - suffix];
+    let width = b_mid.len() + 1;
+
+    // lengths[i * width + j] = LCS length of a_mid[i..] and b_mid[j..]
+    let mut lengths = vec![0u32; (a_mid.len() + 1) * width];
+    for i in (0..a_mid.len()).rev() {
+        for j in (0..b_mid.len()).rev() {
+            lengths[i * width + j] = if a_mid[i] == b_mid[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut matches: Vec<(usize, usize)> = (0..prefix).map(|i| (i, i)).collect();
+    let (mut i, mut j) = (0, 0);
+
+    while i < a_mid.len() && j < b_mid.len() {
+        if a_mid[i] == b_mid[j] {
+            matches.push((prefix + i, prefix + j));
+            i += 1;
+            j += 1;
+        } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    let (a_end, b_end) = (a.len() - suffix, b.len() - suffix);
+    matches.extend((0..suffix).map(|k| (a_end + k, b_end + k)));
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::MockFileSystem;
+
+    #[test]
+    fn test_merge_lines() {
+        let base = "a\nb\nc\nd\n";
+
+        // Changes on different lines merge cleanly
+        let merged = merge_lines(base, "a\nB\nc\nd\n", "a\nb\nc\nD\n");
+        assert_eq!(merged.content, "a\nB\nc\nD\n");
+        assert_eq!(merged.conflicts, 0);
+
+        // Identical changes are not a conflict
+        let merged = merge_lines(base, "a\nx\nc\nd\n", "a\nx\nc\nd\n");
+        assert_eq!(merged.content, "a\nx\nc\nd\n");
+
+        // Insertions and deletions
+        let merged = merge_lines(base, "a\nb\nlocal\nc\nd\n", "a\nb\nc\n");
+        assert_eq!(merged.content, "a\nb\nlocal\nc\n");
+
+        // Different changes to the same line conflict
+        let merged = merge_lines(base, "a\nours\nc\nd\n", "a\ntheirs\nc\nd\n");
+        assert_eq!(merged.conflicts, 1);
+        assert_eq!(
+            merged.content,
+            "a\n<<<<<<< local\nours\n=======\ntheirs\n>>>>>>> template\nc\nd\n"
+        );
+
+        // Conflict on a last line without newline
+        let merged = merge_lines("x", "y", "z");
+        assert_eq!(
+            merged.content,
+            "<<<<<<< local\ny\n=======\nz\n>>>>>>> template\n"
+        );
+    }
+
+    #[test]
+    fn test_matching_lines() {
+        let a = ["a\n", "b\n", "c\n", "d\n"];
+        let b = ["a\n", "c\n", "x\n", "d\n"];
+        assert_eq!(matching_lines(&a, &b), vec![(0, 0), (2, 1), (3, 3)]);
+        assert!(matching_lines(&[], &b).is_empty());
+    }
+
+    fn rendered(path: &str, content: &str) -> RenderedFile {
+        RenderedFile {
+            path: path.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_render_merge_outcomes() {
+        let fs = MockFileSystem::new();
+        let env = Path::new("/infra/projects/api/environments/dev");
+
+        for (file, content) in [
+            ("untouched.tf", "a\n"),
+            ("edited.tf", "a\nb\nc\n"),
+            ("conflict.tf", "x\n"),
+            ("stale.tf", "old\n"),
+            ("stale-edited.tf", "old\n"),
+        ] {
+            fs.write(&env.join(file), content).unwrap();
+        }
+        RenderBaseline::record(
+            &fs,
+            env,
+            env,
+            &[
+                "untouched.tf".to_string(),
+                "edited.tf".to_string(),
+                "conflict.tf".to_string(),
+                "stale.tf".to_string(),
+                "stale-edited.tf".to_string(),
+            ],
+        )
+        .unwrap();
+
+        // Local edits
+        fs.write(&env.join("edited.tf"), "local\na\nb\nc\n")
+            .unwrap();
+        fs.write(&env.join("conflict.tf"), "ours\n").unwrap();
+        fs.write(&env.join("stale-edited.tf"), "old\nlocal\n")
+            .unwrap();
+        fs.write(&env.join("legacy.tf"), "hand written\n").unwrap();
+
+        let mut merge = RenderMerge::new(&fs, env, MergeStrategy::Merge).unwrap();
+        merge
+            .apply(
+                env,
+                vec![
+                    rendered("untouched.tf", "a2\n"),
+                    rendered("edited.tf", "a\nb\nc\nnew\n"),
+                    rendered("conflict.tf", "theirs\n"),
+                    rendered("legacy.tf", "generated\n"),
+                    rendered("new.tf", "new\n"),
+                ],
+            )
+            .unwrap();
+        let summary = merge.finish().unwrap();
+
+        let outcomes: BTreeMap<_, _> = summary.files.iter().cloned().collect();
+        assert_eq!(outcomes["untouched.tf"], FileOutcome::Updated);
+        assert_eq!(outcomes["edited.tf"], FileOutcome::Merged);
+        assert_eq!(outcomes["conflict.tf"], FileOutcome::Conflict);
+        assert_eq!(outcomes["legacy.tf"], FileOutcome::Rejected);
+        assert_eq!(outcomes["new.tf"], FileOutcome::Created);
+        assert_eq!(outcomes["stale.tf"], FileOutcome::Removed);
+        assert_eq!(outcomes["stale-edited.tf"], FileOutcome::Orphaned);
+        assert!(summary.needs_attention());
+        assert_eq!(
+            summary.attention_paths(),
+            vec!["conflict.tf", "legacy.tf.rej"]
+        );
+
+        assert_eq!(
+            fs.get_file_contents(&env.join("edited.tf")).unwrap(),
+            "local\na\nb\nc\nnew\n"
+        );
+        assert_eq!(
+            fs.get_file_contents(&env.join("legacy.tf")).unwrap(),
+            "hand written\n"
+        );
+        assert_eq!(
+            fs.get_file_contents(&env.join("legacy.tf.rej")).unwrap(),
+            "generated\n"
+        );
+        assert!(!fs.exists(&env.join("stale.tf")));
+        assert!(fs.exists(&env.join("stale-edited.tf")));
+
+        // The new renderings become the baseline
+        let baseline = RenderBaseline::load(&fs, env).unwrap();
+        assert_eq!(baseline.outputs[""].len(), 5);
+        assert_eq!(baseline.outputs[""]["conflict.tf"], "theirs\n");
+    }
+
+    #[test]
+    fn test_render_merge_strategies() {
+        let fs = MockFileSystem::new();
+        let env = Path::new("/env");
+        let module = env.join("modules/pack/plugin");
+
+        fs.write(&module.join("main.tf"), "a\n").unwrap();
+        RenderBaseline::record(&fs, env, &module, &["main.tf".to_string()]).unwrap();
+        fs.write(&module.join("main.tf"), "local\n").unwrap();
+
+        let mut merge = RenderMerge::new(&fs, env, MergeStrategy::Ours).unwrap();
+        merge
+            .apply(&module, vec![rendered("main.tf", "template\n")])
+            .unwrap();
+        let summary = merge.finish().unwrap();
+        assert_eq!(
+            summary.files,
+            vec![(
+                "modules/pack/plugin/main.tf".to_string(),
+                FileOutcome::KeptLocal
+            )]
+        );
+        assert_eq!(
+            fs.get_file_contents(&module.join("main.tf")).unwrap(),
+            "local\n"
+        );
+
+        let mut merge = RenderMerge::new(&fs, env, MergeStrategy::Theirs).unwrap();
+        merge
+            .apply(&module, vec![rendered("main.tf", "template 2\n")])
+            .unwrap();
+        merge.finish().unwrap();
+        assert_eq!(
+            fs.get_file_contents(&module.join("main.tf")).unwrap(),
+            "template 2\n"
+        );
+
+        RenderBaseline::forget(&fs, env, &module).unwrap();
+        assert!(RenderBaseline::load(&fs, env).unwrap().outputs.is_empty());
+
+        assert_eq!(
+            MergeStrategy::parse("THEIRS").unwrap(),
+            MergeStrategy::Theirs
+        );
+        assert!(MergeStrategy::parse("mine").is_err());
+    }
+}
@@ -196,6 +196,74 @@ pub struct TemplateSpec {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub hooks: Option<HooksConfig>,
+
+    /// Rules for generating files under src/ (conditional files, one file per list item)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<TemplateFileRule>,
+
+    /// Input migrations applied when upgrading environments to this version
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub migrations: Vec<super::migration::InputMigration>,
+
+    /// Rules relating several inputs, checked once all template inputs are collected
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub validations: Vec<InputValidationRule>,
+}
+
+/// Template-level validation rule relating several inputs
+///
+/// ```yaml
+/// validations:
+///   - rule: "{{lte min_replicas max_replicas}}"
+///     message: "min_replicas must not exceed max_replicas"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputValidationRule {
+    /// Handlebars expression that must render to `true` or `false`
+    pub rule: String,
+
+    /// Error shown when the rule renders to `false`
+    pub message: String,
+
+    /// Only check the rule when all conditions are met
+    #[serde(default, skip_serializing_if = "Vec::is_empty", rename = "if")]
+    pub conditions: Vec<InputCondition>,
+}
+
+/// Generation rule for a file or directory under a template's src/ directory
+///
+/// Paths are matched before rendering, as they appear under src/. A rule on a directory
+/// applies to every file below it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateFileRule {
+    /// File or directory path relative to src/ (e.g., "monitoring" or "values.yaml.hbs")
+    pub path: String,
+
+    /// Only generate when all conditions are met
+    #[serde(default, skip_serializing_if = "Vec::is_empty", rename = "if")]
+    pub conditions: Vec<InputCondition>,
+
+    /// List input to generate the files once per item of
+    /// The item is available as `item` (or the `as` name) and its position as `item_index`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub for_each: Option<String>,
+
+    /// Variable name of the current item in `for_each` rules (default: "item")
+    #[serde(default, skip_serializing_if = "Option::is_none", rename = "as")]
+    pub item_name: Option<String>,
+}
+
+impl TemplateFileRule {
+    /// Check if the rule applies to a file path relative to src/
+    pub fn matches(&self, relative_path: &std::path::Path) -> bool {
+        let rule_path = std::path::Path::new(self.path.trim_end_matches('/'));
+        relative_path.starts_with(rule_path)
+    }
+
+    /// Variable name of the current item in `for_each` rules
+    pub fn item_name(&self) -> &str {
+        self.item_name.as_deref().unwrap_or("item")
+    }
 }
 
 /// Custom deserializer for inputs that supports both HashMap and Vec formats
@@ -485,6 +553,10 @@ pub struct PluginSpec {
     /// If set, user must select projects matching these dependencies when adding the plugin
     #[serde(default)]
     pub dependencies: Vec<PluginDependency>,
+
+    /// Rules for generating files under src/ (conditional files, one file per list item)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<TemplateFileRule>,
 }
 
 // ============================================================================
@@ -583,10 +655,20 @@ pub enum InputType {
         integer: bool,
     },
     /// Select input with enum options (single selection)
-    Select { options: Vec<EnumOption> },
+    Select {
+        #[serde(default)]
+        options: Vec<EnumOption>,
+        /// Options resolved when the input is prompted, added after `options`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        options_from: Option<OptionsSource>,
+    },
     /// Multi-select input allowing multiple selections
     MultiSelect {
+        #[serde(default)]
         options: Vec<EnumOption>,
+        /// Options resolved when the input is prompted, added after `options`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        options_from: Option<OptionsSource>,
         #[serde(skip_serializing_if = "Option::is_none")]
         min: Option<usize>,
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -780,6 +862,14 @@ pub enum InputType {
         #[serde(default = "default_true", skip_serializing_if = "is_true")]
         allow_build: bool,
     },
+    /// Value derived from other inputs, never prompted
+    ///
+    /// `value` is a Handlebars expression over the inputs collected before this one and the
+    /// `_project_name`/`_environment` variables, e.g. `"{{_project_name}}-{{region}}"`.
+    Computed {
+        /// Handlebars expression rendering the value
+        value: String,
+    },
 }
 
 /// Helper function for serde to determine if a bool is false
@@ -807,6 +897,59 @@ fn default_true() -> bool {
     true
 }
 
+impl InputType {
+    /// Dynamic options source of a select or multiselect input
+    pub fn options_from(&self) -> Option<&OptionsSource> {
+        match self {
+            Self::Select { options_from, .. } | Self::MultiSelect { options_from, .. } => {
+                options_from.as_ref()
+            }
+            _ => None,
+        }
+    }
+
+    /// The same select or multiselect input with resolved options and no dynamic source
+    pub fn with_options(&self, resolved: Vec<EnumOption>) -> Self {
+        let mut input_type = self.clone();
+        if let Self::Select {
+            options,
+            options_from,
+        }
+        | Self::MultiSelect {
+            options,
+            options_from,
+            ..
+        } = &mut input_type
+        {
+            *options = resolved;
+            *options_from = None;
+        }
+        input_type
+    }
+}
+
+/// Where a select input gets its options from when it is prompted
+///
+/// Sources yield a JSON/YAML list (of strings, or of `{label, value}` objects) or a map (keys
+/// become labels). Strings support `${var:...}` and `${env:...}` interpolation, so a source can
+/// depend on earlier inputs, e.g. the project picked by a `project_select` input.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum OptionsSource {
+    /// Shell command printing JSON, run from the infrastructure root
+    Command { command: String },
+    /// JSON or YAML file, relative to the infrastructure root
+    File { path: String },
+    /// Output of a project's state, read from the state snapshots
+    ProjectOutput {
+        project: String,
+        output: String,
+        /// Environment of the project (default: the environment being created)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        environment: Option<String>,
+    },
+}
+
 /// Enum option with display text and value
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnumOption {
@@ -860,33 +1003,249 @@ pub struct UrlValidation {
     pub allowed_schemes: Vec<String>,
 }
 
-/// Condition for showing an input
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct InputCondition {
+/// Condition for showing an input (or generating a file)
+///
+/// A list of conditions is met when all of them are met; `any`, `all` and `not` group nested
+/// conditions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged, try_from = "RawInputCondition")]
+pub enum InputCondition {
+    /// Met when at least one nested condition is met
+    Any { any: Vec<InputCondition> },
+
+    /// Met when every nested condition is met
+    All { all: Vec<InputCondition> },
+
+    /// Met when the nested condition is not met
+    Not { not: Box<InputCondition> },
+
+    /// Comparison of the value of an input
+    Input(InputComparison),
+}
+
+/// Comparison of the value of an input; every operator given must hold
+///
+/// Without operators, the condition only checks that the input has a value. An input without a
+/// value never meets a comparison (use `not` to match missing inputs).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InputComparison {
     /// Name of the input to check
     pub input_name: String,
 
-    /// Value that the input must equal (optional)
+    /// Value that the input must equal
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub equals: Option<Value>,
+
+    /// Value that the input must not equal
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_equals: Option<Value>,
+
+    /// Values one of which the input must equal
+    #[serde(default, skip_serializing_if = "Option::is_none", rename = "in")]
+    pub in_values: Option<Vec<Value>>,
+
+    /// Values none of which the input may equal
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_in: Option<Vec<Value>>,
+
+    /// The input must be a number greater than this
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gt: Option<f64>,
+
+    /// The input must be a number greater than or equal to this
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gte: Option<f64>,
+
+    /// The input must be a number less than this
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lt: Option<f64>,
+
+    /// The input must be a number less than or equal to this
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lte: Option<f64>,
+
+    /// Regular expression the input must match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matches: Option<String>,
+}
+
+/// Any form of condition, checked for unknown and conflicting keys before conversion
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawInputCondition {
+    any: Option<Vec<InputCondition>>,
+    all: Option<Vec<InputCondition>>,
+    not: Option<Box<InputCondition>>,
+    input_name: Option<String>,
+    equals: Option<Value>,
+    not_equals: Option<Value>,
+    #[serde(rename = "in")]
+    in_values: Option<Vec<Value>>,
+    not_in: Option<Vec<Value>>,
+    gt: Option<f64>,
+    gte: Option<f64>,
+    lt: Option<f64>,
+    lte: Option<f64>,
+    matches: Option<String>,
+}
+
+impl TryFrom<RawInputCondition> for InputCondition {
+    type Error = String;
+
+    fn try_from(raw: RawInputCondition) -> Result<Self, Self::Error> {
+        let has_comparison = raw.input_name.is_some()
+            || raw.equals.is_some()
+            || raw.not_equals.is_some()
+            || raw.in_values.is_some()
+            || raw.not_in.is_some()
+            || raw.gt.is_some()
+            || raw.gte.is_some()
+            || raw.lt.is_some()
+            || raw.lte.is_some()
+            || raw.matches.is_some();
+
+        let forms = [
+            raw.any.is_some(),
+            raw.all.is_some(),
+            raw.not.is_some(),
+            has_comparison,
+        ];
+        if forms.iter().filter(|f| **f).count() != 1 {
+            return Err(
+                "a condition needs exactly one of `input_name`, `any`, `all` or `not`".to_string(),
+            );
+        }
+
+        if let Some(any) = raw.any {
+            return Ok(Self::Any { any });
+        }
+        if let Some(all) = raw.all {
+            return Ok(Self::All { all });
+        }
+        if let Some(not) = raw.not {
+            return Ok(Self::Not { not });
+        }
+
+        Ok(Self::Input(InputComparison {
+            input_name: raw
+                .input_name
+                .ok_or("a comparison needs `input_name`".to_string())?,
+            equals: raw.equals,
+            not_equals: raw.not_equals,
+            in_values: raw.in_values,
+            not_in: raw.not_in,
+            gt: raw.gt,
+            gte: raw.gte,
+            lt: raw.lt,
+            lte: raw.lte,
+            matches: raw.matches,
+        }))
+    }
 }
 
 impl InputCondition {
     /// Evaluate if this condition is met given the current inputs
     pub fn is_met(&self, inputs: &std::collections::HashMap<String, Value>) -> bool {
-        // Get the value of the input we're checking
-        let input_value = match inputs.get(&self.input_name) {
-            Some(val) => val,
-            None => return false, // Input not found, condition not met
+        match self {
+            Self::Any { any } => any.iter().any(|c| c.is_met(inputs)),
+            Self::All { all } => all.iter().all(|c| c.is_met(inputs)),
+            Self::Not { not } => !not.is_met(inputs),
+            Self::Input(comparison) => comparison.is_met(inputs),
+        }
+    }
+
+    /// Names of the inputs the condition depends on
+    pub fn input_names(&self) -> Vec<&str> {
+        match self {
+            Self::Any { any: conditions } | Self::All { all: conditions } => {
+                conditions.iter().flat_map(|c| c.input_names()).collect()
+            }
+            Self::Not { not } => not.input_names(),
+            Self::Input(comparison) => vec![comparison.input_name.as_str()],
+        }
+    }
+
+    /// Check the condition for mistakes that would make it never (or always) apply
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::Any { any: conditions } | Self::All { all: conditions } => {
+                if conditions.is_empty() {
+                    return Err("`any` and `all` need at least one condition".to_string());
+                }
+                conditions.iter().try_for_each(|c| c.validate())
+            }
+            Self::Not { not } => not.validate(),
+            Self::Input(comparison) => {
+                if let Some(pattern) = &comparison.matches {
+                    regex::Regex::new(pattern).map_err(|e| {
+                        format!(
+                            "invalid `matches` pattern for '{}': {}",
+                            comparison.input_name, e
+                        )
+                    })?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl InputComparison {
+    /// Evaluate the comparison given the current inputs
+    pub fn is_met(&self, inputs: &std::collections::HashMap<String, Value>) -> bool {
+        // Input not found, condition not met
+        let Some(value) = inputs.get(&self.input_name) else {
+            return false;
         };
 
-        // If equals is not specified, just check if the input exists
-        let Some(ref expected_value) = self.equals else {
-            return true; // Input exists, condition met
+        let number = Self::as_number(value);
+        let bound = |limit: Option<f64>, check: fn(f64, f64) -> bool| {
+            limit.is_none_or(|limit| number.is_some_and(|n| check(n, limit)))
         };
 
-        // Compare the values
-        input_value == expected_value
+        self.equals
+            .as_ref()
+            .is_none_or(|v| Self::values_equal(value, v))
+            && self
+                .not_equals
+                .as_ref()
+                .is_none_or(|v| !Self::values_equal(value, v))
+            && self
+                .in_values
+                .as_ref()
+                .is_none_or(|list| list.iter().any(|v| Self::values_equal(value, v)))
+            && self
+                .not_in
+                .as_ref()
+                .is_none_or(|list| !list.iter().any(|v| Self::values_equal(value, v)))
+            && bound(self.gt, |n, limit| n > limit)
+            && bound(self.gte, |n, limit| n >= limit)
+            && bound(self.lt, |n, limit| n < limit)
+            && bound(self.lte, |n, limit| n <= limit)
+            && self.matches.as_ref().is_none_or(|pattern| {
+                let text = match value {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                regex::Regex::new(pattern).is_ok_and(|re| re.is_match(&text))
+            })
+    }
+
+    /// Numbers compare by value (`1` equals `1.0`); other values must be identical
+    fn values_equal(a: &Value, b: &Value) -> bool {
+        match (a, b) {
+            (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+            _ => a == b,
+        }
+    }
+
+    /// A number, or a string holding one
+    fn as_number(value: &Value) -> Option<f64> {
+        match value {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        }
     }
 }
 
@@ -971,6 +1330,14 @@ impl InputDefinition {
         }
     }
 
+    /// Expression of a computed input (`None` for prompted inputs)
+    pub fn computed_expression(&self) -> Option<&str> {
+        match &self.input_type {
+            Some(InputType::Computed { value }) => Some(value),
+            _ => None,
+        }
+    }
+
     /// Check if all conditions for this input are met
     pub fn should_show(&self, inputs: &std::collections::HashMap<String, Value>) -> bool {
         // If no conditions, always show
@@ -1682,6 +2049,11 @@ pub struct InfrastructureSpec {
     #[serde(default)]
     pub executor: Option<ExecutorCollectionConfig>,
 
+    /// Optional: External executors (binaries implementing the pmp executor protocol)
+    /// registered alongside the built-in executors
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub executors: Vec<ExternalExecutorConfig>,
+
     /// Optional: Cost estimation configuration
     #[serde(default)]
     pub cost: Option<CostConfig>,
@@ -1693,6 +2065,10 @@ pub struct InfrastructureSpec {
     /// Optional: Secrets management configuration
     #[serde(default)]
     pub secrets: Option<SecretsConfig>,
+
+    /// Optional: Operation history configuration (operations run from `pmp ui`)
+    #[serde(default)]
+    pub history: Option<HistoryConfig>,
 }
 
 /// Parallel execution configuration
@@ -1743,6 +2119,27 @@ pub struct ExecutorCollectionConfig {
     pub parallel: Option<ParallelConfig>,
 }
 
+/// External executor declaration at the infrastructure level
+///
+/// The command is invoked as `<command> [args...] <operation>` in the environment
+/// directory and receives a JSON request on stdin (see doc/executors.md)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalExecutorConfig {
+    /// Executor name referenced by templates (e.g., "pulumi", "helmfile")
+    pub name: String,
+
+    /// Binary to execute (absolute path or found in PATH)
+    pub command: String,
+
+    /// Arguments inserted before the operation name
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+
+    /// Extra environment variables passed to the binary
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+}
+
 /// Cost estimation configuration at the infrastructure level
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct CostConfig {
@@ -1859,6 +2256,49 @@ fn default_block_on_error() -> bool {
     true
 }
 
+// ============================================================================
+// Operation History Configuration
+// ============================================================================
+
+/// Operation history configuration at the infrastructure level
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HistoryConfig {
+    /// Record operations and their logs (default: true)
+    #[serde(default = "default_history_enabled")]
+    pub enabled: bool,
+
+    /// History directory, relative to the infrastructure root (default: .pmp/history)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
+    /// Keep only the newest N operations (default: 500)
+    #[serde(default = "default_history_keep_last")]
+    pub keep_last: Option<usize>,
+
+    /// Delete operations older than this many days
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<u64>,
+}
+
+fn default_history_enabled() -> bool {
+    true
+}
+
+fn default_history_keep_last() -> Option<usize> {
+    Some(500)
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_history_enabled(),
+            path: None,
+            keep_last: default_history_keep_last(),
+            max_age_days: None,
+        }
+    }
+}
+
 // ============================================================================
 // Secrets Management Configuration
 // ============================================================================
@@ -1944,10 +2384,30 @@ pub struct SecretReference {
 }
 
 /// Configuration for a command hook
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CommandHookConfig {
     /// The shell command to execute
     pub command: String,
+
+    /// Timeout of each attempt in seconds, the command is killed when exceeded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+
+    /// Number of retries after a failed attempt
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub retries: u32,
+
+    /// If true, a failed command only prints a warning instead of stopping the operation
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub continue_on_error: bool,
+
+    /// Conditions on the environment's inputs, the hook is skipped unless all are met
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub when: Vec<InputCondition>,
+}
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
 }
 
 /// Configuration for a confirm hook
@@ -1989,6 +2449,41 @@ pub struct SetEnvironmentHookConfig {
     pub sensitive: bool,
 }
 
+/// Configuration for a webhook hook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookHookConfig {
+    /// URL to call, supports `${env:...}` interpolation
+    pub url: String,
+
+    /// HTTP method
+    #[serde(default = "default_webhook_method")]
+    pub method: String,
+
+    /// Request headers, values support `${env:...}` interpolation and are never printed
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+
+    /// Handlebars template for the request body (defaults to the hook event as JSON)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+
+    /// Timeout of each attempt in seconds
+    #[serde(default = "default_webhook_timeout")]
+    pub timeout: u64,
+
+    /// Number of retries after a failed attempt
+    #[serde(default)]
+    pub retries: u32,
+}
+
+fn default_webhook_method() -> String {
+    "POST".to_string()
+}
+
+fn default_webhook_timeout() -> u64 {
+    10
+}
+
 /// A hook that can be executed before or after a command
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "config")]
@@ -2004,6 +2499,10 @@ pub enum Hook {
     /// Ask user for a value and set it as an environment variable
     #[serde(rename = "set_environment")]
     SetEnvironment(SetEnvironmentHookConfig),
+
+    /// Send an HTTP request, e.g. a Slack or Teams notification
+    #[serde(rename = "webhook")]
+    Webhook(WebhookHookConfig),
 }
 
 /// Hooks configuration
@@ -2033,6 +2532,22 @@ pub struct HooksConfig {
     #[serde(default)]
     pub post_destroy: Vec<Hook>,
 
+    /// Hooks to run when apply (or one of its pre/post hooks) fails
+    #[serde(default)]
+    pub on_failure_apply: Vec<Hook>,
+
+    /// Hooks to run when destroy (or one of its pre/post hooks) fails
+    #[serde(default)]
+    pub on_failure_destroy: Vec<Hook>,
+
+    /// Hooks to run after apply, whether it succeeded, failed or was cancelled
+    #[serde(default)]
+    pub finally_apply: Vec<Hook>,
+
+    /// Hooks to run after destroy, whether it succeeded, failed or was cancelled
+    #[serde(default)]
+    pub finally_destroy: Vec<Hook>,
+
     /// Hooks to run before refresh
     #[serde(default)]
     pub pre_refresh: Vec<Hook>,
@@ -2050,6 +2565,30 @@ pub struct HooksConfig {
     pub post_test: Vec<Hook>,
 }
 
+impl HooksConfig {
+    /// Hooks of every phase
+    pub fn iter(&self) -> impl Iterator<Item = &Hook> {
+        [
+            &self.pre_preview,
+            &self.post_preview,
+            &self.pre_apply,
+            &self.post_apply,
+            &self.pre_destroy,
+            &self.post_destroy,
+            &self.on_failure_apply,
+            &self.on_failure_destroy,
+            &self.finally_apply,
+            &self.finally_destroy,
+            &self.pre_refresh,
+            &self.post_refresh,
+            &self.pre_test,
+            &self.post_test,
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
 /// Input override configuration for infrastructure-level input customization
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InputOverride {
@@ -2659,9 +3198,11 @@ mod tests {
                 environments: HashMap::new(),
                 hooks: None,
                 executor: None,
+                executors: vec![],
                 cost: None,
                 policy: None,
                 secrets: None,
+                history: None,
             },
         };
 
@@ -2701,9 +3242,11 @@ mod tests {
                 environments: HashMap::new(),
                 hooks: None,
                 executor: None,
+                executors: vec![],
                 cost: None,
                 policy: None,
                 secrets: None,
+                history: None,
             },
         };
 
@@ -2749,9 +3292,11 @@ mod tests {
                 environments: HashMap::new(),
                 hooks: None,
                 executor: None,
+                executors: vec![],
                 cost: None,
                 policy: None,
                 secrets: None,
+                history: None,
             },
         };
 
@@ -2978,9 +3523,11 @@ spec:
                 environments: HashMap::new(),
                 hooks: None,
                 executor: None,
+                executors: vec![],
                 cost: None,
                 policy: None,
                 secrets: None,
+                history: None,
             },
         };
 
@@ -3047,9 +3594,11 @@ spec:
                 environments: HashMap::new(),
                 hooks: None,
                 executor: None,
+                executors: vec![],
                 cost: None,
                 policy: None,
                 secrets: None,
+                history: None,
             },
         };
 
@@ -3366,18 +3915,20 @@ spec:
         let mut inputs = HashMap::new();
         inputs.insert("status".to_string(), Value::String("active".to_string()));
 
-        let condition = InputCondition {
+        let condition = InputCondition::Input(InputComparison {
             input_name: "status".to_string(),
             equals: Some(Value::String("active".to_string())),
-        };
+            ..Default::default()
+        });
 
         assert!(condition.is_met(&inputs));
 
         // Test with different value
-        let condition_not_met = InputCondition {
+        let condition_not_met = InputCondition::Input(InputComparison {
             input_name: "status".to_string(),
             equals: Some(Value::String("inactive".to_string())),
-        };
+            ..Default::default()
+        });
 
         assert!(!condition_not_met.is_met(&inputs));
     }
@@ -3388,18 +3939,18 @@ spec:
         inputs.insert("has_feature".to_string(), Value::Bool(true));
 
         // Condition without equals just checks if input exists
-        let condition = InputCondition {
+        let condition = InputCondition::Input(InputComparison {
             input_name: "has_feature".to_string(),
-            equals: None,
-        };
+            ..Default::default()
+        });
 
         assert!(condition.is_met(&inputs));
 
         // Test with non-existent input
-        let condition_not_found = InputCondition {
+        let condition_not_found = InputCondition::Input(InputComparison {
             input_name: "missing_input".to_string(),
-            equals: None,
-        };
+            ..Default::default()
+        });
 
         assert!(!condition_not_found.is_met(&inputs));
     }
@@ -3430,10 +3981,11 @@ spec:
             default: Some(Value::String("default".to_string())),
             description: Some("A conditional input".to_string()),
             validation: None,
-            conditions: vec![InputCondition {
+            conditions: vec![InputCondition::Input(InputComparison {
                 input_name: "status".to_string(),
                 equals: Some(Value::String("active".to_string())),
-            }],
+                ..Default::default()
+            })],
             secret_manager: None,
         };
 
@@ -3452,10 +4004,11 @@ spec:
             default: Some(Value::String("default".to_string())),
             description: Some("A conditional input".to_string()),
             validation: None,
-            conditions: vec![InputCondition {
+            conditions: vec![InputCondition::Input(InputComparison {
                 input_name: "status".to_string(),
                 equals: Some(Value::String("active".to_string())),
-            }],
+                ..Default::default()
+            })],
             secret_manager: None,
         };
 
@@ -3475,14 +4028,16 @@ spec:
             description: Some("A conditional input".to_string()),
             validation: None,
             conditions: vec![
-                InputCondition {
+                InputCondition::Input(InputComparison {
                     input_name: "external_id".to_string(),
                     equals: Some(Value::String("some-external-id".to_string())),
-                },
-                InputCondition {
+                    ..Default::default()
+                }),
+                InputCondition::Input(InputComparison {
                     input_name: "status".to_string(),
                     equals: Some(Value::String("active".to_string())),
-                },
+                    ..Default::default()
+                }),
             ],
             secret_manager: None,
         };
@@ -3507,14 +4062,16 @@ spec:
             description: Some("A conditional input".to_string()),
             validation: None,
             conditions: vec![
-                InputCondition {
+                InputCondition::Input(InputComparison {
                     input_name: "external_id".to_string(),
                     equals: Some(Value::String("some-external-id".to_string())),
-                },
-                InputCondition {
+                    ..Default::default()
+                }),
+                InputCondition::Input(InputComparison {
                     input_name: "status".to_string(),
                     equals: Some(Value::String("active".to_string())),
-                },
+                    ..Default::default()
+                }),
             ],
             secret_manager: None,
         };
@@ -3529,6 +4086,108 @@ spec:
         assert!(!input_def.should_show(&inputs));
     }
 
+    #[test]
+    fn test_input_condition_operators_and_groups() {
+        let input: InputDefinition = serde_yaml::from_str(
+            r#"
+name: replica_count
+if:
+  - input_name: tier
+    in: [staging, production]
+  - not:
+      input_name: ha
+      equals: false
+  - any:
+      - input_name: region
+        matches: "^eu-"
+      - input_name: size
+        gte: 2
+        lt: 10
+      - input_name: owner
+        not_in: [nobody]
+"#,
+        )
+        .unwrap();
+
+        let inputs =
+            |values: Value| -> HashMap<String, Value> { serde_json::from_value(values).unwrap() };
+
+        assert!(input.should_show(&inputs(serde_json::json!({
+            "tier": "staging", "region": "eu-west-1"
+        }))));
+        assert!(input.should_show(&inputs(serde_json::json!({
+            "tier": "production", "ha": true, "region": "us-east-1", "size": 2.0
+        }))));
+        // tier not in the list
+        assert!(!input.should_show(&inputs(serde_json::json!({
+            "tier": "dev", "region": "eu-west-1"
+        }))));
+        // ha disabled
+        assert!(!input.should_show(&inputs(serde_json::json!({
+            "tier": "staging", "ha": false, "region": "eu-west-1"
+        }))));
+        // no branch of `any` met (size out of range, owner excluded)
+        assert!(!input.should_show(&inputs(serde_json::json!({
+            "tier": "staging", "region": "us-east-1", "size": 10, "owner": "nobody"
+        }))));
+
+        assert_eq!(
+            input
+                .conditions
+                .iter()
+                .flat_map(|c| c.input_names())
+                .collect::<Vec<_>>(),
+            vec!["tier", "ha", "region", "size", "owner"]
+        );
+        assert!(input.conditions.iter().all(|c| c.validate().is_ok()));
+
+        // Conditions serialize back to the same form
+        let yaml = serde_yaml::to_string(&input.conditions).unwrap();
+        let parsed: Vec<InputCondition> = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed, input.conditions);
+    }
+
+    #[test]
+    fn test_input_condition_errors() {
+        let parse = |yaml: &str| serde_yaml::from_str::<InputCondition>(yaml).unwrap_err();
+
+        assert!(
+            parse(
+                "input_name: a
+not_equal: b"
+            )
+            .to_string()
+            .contains("unknown field `not_equal`")
+        );
+        assert!(
+            parse(
+                "input_name: a
+any: []"
+            )
+            .to_string()
+            .contains("exactly one of")
+        );
+        assert!(
+            parse("equals: b")
+                .to_string()
+                .contains("needs `input_name`")
+        );
+
+        let invalid: InputCondition = serde_yaml::from_str(
+            "input_name: a
+matches: '('",
+        )
+        .unwrap();
+        assert!(
+            invalid
+                .validate()
+                .unwrap_err()
+                .contains("invalid `matches` pattern")
+        );
+        let empty: InputCondition = serde_yaml::from_str("any: []").unwrap();
+        assert!(empty.validate().is_err());
+    }
+
     #[test]
     fn test_conditional_input_serialization() {
         let yaml = r#"
@@ -3563,10 +4222,13 @@ spec:
         let conditional_input = &template.spec.inputs[1];
         assert_eq!(conditional_input.name, "conditional_field");
         assert_eq!(conditional_input.conditions.len(), 1);
-        assert_eq!(conditional_input.conditions[0].input_name, "status");
         assert_eq!(
-            conditional_input.conditions[0].equals,
-            Some(Value::String("active".to_string()))
+            conditional_input.conditions[0],
+            InputCondition::Input(InputComparison {
+                input_name: "status".to_string(),
+                equals: Some(Value::String("active".to_string())),
+                ..Default::default()
+            })
         );
     }
 }
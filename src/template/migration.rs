@@ -0,0 +1,255 @@
+//! Input migrations for template version upgrades
+//!
+//! A template version can ship `spec.migrations` describing how the inputs of environments
+//! created from earlier versions map to its own inputs. Upgrading applies the migrations of
+//! every version between the current and the target version, oldest first.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+
+/// A declarative change to the inputs of an environment
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InputMigration {
+    /// Rename an input, keeping its value
+    Rename { from: String, to: String },
+
+    /// Remove an input the template no longer uses
+    Drop { input: String },
+
+    /// Set a value for an input that has none (e.g., a new required input)
+    Default { input: String, value: Value },
+
+    /// Change the value of an input
+    ///
+    /// Values listed in `values` are mapped first; otherwise `template` (Handlebars, with the
+    /// current value as `value` and all inputs available) renders the new value.
+    Transform {
+        input: String,
+
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        values: Vec<ValueMapping>,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        template: Option<String>,
+    },
+}
+
+/// Maps one value of an input to another
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ValueMapping {
+    pub from: Value,
+    pub to: Value,
+}
+
+/// A change made to the inputs by a migration
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputChange {
+    Renamed {
+        from: String,
+        to: String,
+    },
+    Dropped {
+        input: String,
+        value: Value,
+    },
+    Defaulted {
+        input: String,
+        value: Value,
+    },
+    Transformed {
+        input: String,
+        from: Value,
+        to: Value,
+    },
+}
+
+impl fmt::Display for InputChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Renamed { from, to } => write!(f, "renamed {} -> {}", from, to),
+            Self::Dropped { input, value } => write!(f, "dropped {} (was {})", input, value),
+            Self::Defaulted { input, value } => write!(f, "set {} = {}", input, value),
+            Self::Transformed { input, from, to } => {
+                write!(f, "changed {}: {} -> {}", input, from, to)
+            }
+        }
+    }
+}
+
+impl InputMigration {
+    /// Apply the migration to a set of inputs, returning the change it made (if any)
+    pub fn apply(&self, inputs: &mut HashMap<String, Value>) -> Result<Option<InputChange>> {
+        match self {
+            Self::Rename { from, to } => {
+                let Some(value) = inputs.remove(from) else {
+                    return Ok(None);
+                };
+                inputs.insert(to.clone(), value);
+
+                Ok(Some(InputChange::Renamed {
+                    from: from.clone(),
+                    to: to.clone(),
+                }))
+            }
+            Self::Drop { input } => Ok(inputs.remove(input).map(|value| InputChange::Dropped {
+                input: input.clone(),
+                value,
+            })),
+            Self::Default { input, value } => {
+                if inputs.get(input).is_some_and(|v| !v.is_null()) {
+                    return Ok(None);
+                }
+                inputs.insert(input.clone(), value.clone());
+
+                Ok(Some(InputChange::Defaulted {
+                    input: input.clone(),
+                    value: value.clone(),
+                }))
+            }
+            Self::Transform {
+                input,
+                values,
+                template,
+            } => {
+                let Some(current) = inputs.get(input).cloned() else {
+                    return Ok(None);
+                };
+
+                let new_value = if let Some(mapping) = values.iter().find(|m| m.from == current) {
+                    mapping.to.clone()
+                } else if let Some(template) = template {
+                    Self::render_transform(template, input, &current, inputs)?
+                } else {
+                    current.clone()
+                };
+
+                if new_value == current {
+                    return Ok(None);
+                }
+                inputs.insert(input.clone(), new_value.clone());
+
+                Ok(Some(InputChange::Transformed {
+                    input: input.clone(),
+                    from: current,
+                    to: new_value,
+                }))
+            }
+        }
+    }
+
+    /// Render a transform template; the output is parsed as YAML so numbers and booleans
+    /// keep their type
+    fn render_transform(
+        template: &str,
+        input: &str,
+        current: &Value,
+        inputs: &HashMap<String, Value>,
+    ) -> Result<Value> {
+        let mut handlebars = handlebars::Handlebars::new();
+        handlebars.register_escape_fn(handlebars::no_escape);
+
+        let mut data = inputs.clone();
+        data.insert("value".to_string(), current.clone());
+
+        let rendered = handlebars
+            .render_template(template, &data)
+            .with_context(|| format!("Failed to render transform of input '{}'", input))?;
+
+        Ok(serde_yaml::from_str(&rendered).unwrap_or(Value::String(rendered)))
+    }
+}
+
+/// Apply migrations in order, returning the changes they made
+pub fn apply_migrations(
+    migrations: &[InputMigration],
+    inputs: &mut HashMap<String, Value>,
+) -> Result<Vec<InputChange>> {
+    let mut changes = Vec::new();
+
+    for migration in migrations {
+        if let Some(change) = migration.apply(inputs)? {
+            changes.push(change);
+        }
+    }
+
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_apply_migrations() {
+        let migrations: Vec<InputMigration> = serde_yaml::from_str(
+            r#"
+- type: rename
+  from: size
+  to: instance_size
+- type: drop
+  input: legacy
+- type: default
+  input: region
+  value: eu-west-1
+- type: default
+  input: replicas
+  value: 1
+- type: transform
+  input: instance_size
+  values:
+    - from: small
+      to: t3.small
+- type: transform
+  input: port
+  template: "{{value}}0"
+- type: transform
+  input: namespace
+  template: "{{value}}-{{region}}"
+"#,
+        )
+        .unwrap();
+
+        let mut inputs: HashMap<String, Value> = serde_json::from_value(json!({
+            "size": "small",
+            "legacy": true,
+            "replicas": 3,
+            "port": 808,
+            "namespace": "apps"
+        }))
+        .unwrap();
+
+        let changes = apply_migrations(&migrations, &mut inputs).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&inputs).unwrap(),
+            json!({
+                "instance_size": "t3.small",
+                "region": "eu-west-1",
+                "replicas": 3,
+                "port": 8080,
+                "namespace": "apps-eu-west-1"
+            })
+        );
+        assert_eq!(
+            changes.iter().map(|c| c.to_string()).collect::<Vec<_>>(),
+            vec![
+                "renamed size -> instance_size",
+                "dropped legacy (was true)",
+                "set region = \"eu-west-1\"",
+                "changed instance_size: \"small\" -> \"t3.small\"",
+                "changed port: 808 -> 8080",
+                "changed namespace: \"apps\" -> \"apps-eu-west-1\"",
+            ]
+        );
+
+        // Migrations of inputs that are not set are no-ops
+        let mut empty = HashMap::new();
+        let changes = apply_migrations(&migrations[..2], &mut empty).unwrap();
+        assert!(changes.is_empty());
+    }
+}
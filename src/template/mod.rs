@@ -1,10 +1,18 @@
+pub mod computed;
+pub mod diff;
 pub mod discovery;
+pub mod helpers;
 pub mod inheritance;
 pub mod installer;
 pub mod lint;
+pub mod lint_fix;
+pub mod merge;
 pub mod metadata;
+pub mod migration;
+pub mod options;
 pub mod partials;
 pub mod renderer;
+pub mod testing;
 pub mod time_limit;
 pub mod utils;
 
@@ -17,4 +25,5 @@ pub use lint::{LintFormatter, LintOptions, LintResult, TemplateLinter};
 pub use metadata::{
     DynamicProjectEnvironmentResource, PolicyConfig, ProjectReference, ProjectResource,
 };
+pub use merge::{MergeStrategy, RenderBaseline, RenderMerge};
 pub use renderer::TemplateRenderer;
@@ -0,0 +1,356 @@
+//! Dynamic select options (`options_from`)
+//!
+//! Select and multiselect inputs can get their options when they are prompted: from a shell
+//! command printing JSON, a JSON/YAML file, or an output of another project's state (read from
+//! the state snapshots pmp keeps after apply and refresh). Resolved options are cached for a
+//! minute per infrastructure, so prompting several environments or re-rendering the web UI form
+//! does not run the same command again, while a long-running `pmp ui` still sees new options.
+
+use anyhow::{Context, Result, bail};
+use lazy_static::lazy_static;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::metadata::{EnumOption, InputType, OptionsSource};
+use super::utils::interpolate_value_all;
+use crate::collection::CollectionDiscovery;
+use crate::state::SnapshotStore;
+
+/// How long resolved options are reused
+const CACHE_TTL: Duration = Duration::from_secs(60);
+
+lazy_static! {
+    /// Recently resolved options, keyed by infrastructure root, interpolated source and
+    /// environment
+    static ref CACHE: Mutex<HashMap<String, (Instant, Vec<EnumOption>)>> =
+        Mutex::new(HashMap::new());
+}
+
+/// Options of a select or multiselect input, static options first
+///
+/// Sources are interpolated with `vars` before they are resolved.
+pub fn select_options(
+    ctx: &crate::context::Context,
+    input_type: &InputType,
+    vars: &HashMap<String, Value>,
+    environment_name: Option<&str>,
+) -> Result<Vec<EnumOption>> {
+    let mut options = match input_type {
+        InputType::Select { options, .. } | InputType::MultiSelect { options, .. } => {
+            options.clone()
+        }
+        _ => return Ok(Vec::new()),
+    };
+
+    if let Some(source) = input_type.options_from() {
+        for option in resolve(ctx, source, vars, environment_name)? {
+            if !options.iter().any(|o| o.value == option.value) {
+                options.push(option);
+            }
+        }
+    }
+
+    Ok(options)
+}
+
+/// Options to prompt a select or multiselect input with
+///
+/// Sources that fail are reported as warnings. Returns `None` for other input types and when
+/// no option is left, in which case the input is prompted as free text.
+pub fn prompt_options(
+    ctx: &crate::context::Context,
+    input_type: &InputType,
+    vars: &HashMap<String, Value>,
+    environment_name: Option<&str>,
+) -> Option<Vec<EnumOption>> {
+    let options = match select_options(ctx, input_type, vars, environment_name) {
+        Ok(options) => options,
+        Err(e) => {
+            ctx.output
+                .warning(&format!("Could not load options: {:#}", e));
+            match input_type {
+                InputType::Select { options, .. } | InputType::MultiSelect { options, .. } => {
+                    options.clone()
+                }
+                _ => Vec::new(),
+            }
+        }
+    };
+
+    (!options.is_empty()).then_some(options)
+}
+
+/// Resolve the options of a source, using the cache
+pub fn resolve(
+    ctx: &crate::context::Context,
+    source: &OptionsSource,
+    vars: &HashMap<String, Value>,
+    environment_name: Option<&str>,
+) -> Result<Vec<EnumOption>> {
+    let interpolated = interpolate_value_all(&serde_json::to_value(source)?, vars)
+        .context("Failed to interpolate options source")?;
+
+    let infrastructure_root = CollectionDiscovery::find_collection(&*ctx.fs)?.map(|(_, root)| root);
+    let base_dir = match &infrastructure_root {
+        Some(root) => root.clone(),
+        None => ctx.fs.current_dir()?,
+    };
+    let key = format!(
+        "{}|{}@{}",
+        base_dir.display(),
+        interpolated,
+        environment_name.unwrap_or_default()
+    );
+
+    {
+        let mut cache = CACHE.lock().unwrap();
+        cache.retain(|_, (resolved_at, _)| resolved_at.elapsed() < CACHE_TTL);
+        if let Some((_, options)) = cache.get(&key) {
+            return Ok(options.clone());
+        }
+    }
+
+    let source: OptionsSource = serde_json::from_value(interpolated)?;
+    let options = parse_options(&fetch(
+        ctx,
+        &source,
+        infrastructure_root.as_deref(),
+        &base_dir,
+        environment_name,
+    )?)?;
+
+    CACHE
+        .lock()
+        .unwrap()
+        .insert(key, (Instant::now(), options.clone()));
+    Ok(options)
+}
+
+/// Read the raw value of a source
+///
+/// Commands run and files are read from `base_dir`, the infrastructure root when there is one.
+fn fetch(
+    ctx: &crate::context::Context,
+    source: &OptionsSource,
+    infrastructure_root: Option<&Path>,
+    base_dir: &Path,
+    environment_name: Option<&str>,
+) -> Result<Value> {
+    match source {
+        OptionsSource::Command { command } => {
+            let output = ctx.command.execute_shell(command, base_dir)?;
+
+            if !output.status.success() {
+                bail!(
+                    "Command '{}' failed: {}",
+                    command,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+            }
+
+            serde_json::from_slice(&output.stdout)
+                .with_context(|| format!("Command '{}' did not print JSON", command))
+        }
+        OptionsSource::File { path } => {
+            let path = base_dir.join(PathBuf::from(path));
+            let content = ctx
+                .fs
+                .read_to_string(&path)
+                .with_context(|| format!("Failed to read options file: {}", path.display()))?;
+
+            serde_yaml::from_str(&content)
+                .with_context(|| format!("Invalid options file: {}", path.display()))
+        }
+        OptionsSource::ProjectOutput {
+            project,
+            output,
+            environment,
+        } => {
+            let root = infrastructure_root.context("Infrastructure is required")?;
+            let environment = environment
+                .as_deref()
+                .or(environment_name)
+                .context("No environment to read project outputs from")?;
+
+            let snapshot = SnapshotStore::for_infrastructure(&*ctx.fs, root)
+                .load(project, environment)?
+                .with_context(|| {
+                    format!(
+                        "No state snapshot for {}/{} (apply or refresh it first)",
+                        project, environment
+                    )
+                })?;
+
+            snapshot
+                .state
+                .outputs
+                .get(output)
+                .map(|o| o.value.clone())
+                .with_context(|| {
+                    format!(
+                        "Project {}/{} has no output '{}'",
+                        project, environment, output
+                    )
+                })
+        }
+    }
+}
+
+/// Convert a list (of scalars or `{label, value}` objects) or a map into options
+fn parse_options(value: &Value) -> Result<Vec<EnumOption>> {
+    let scalar = |value: &Value| match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(_) | Value::Bool(_) => Some(value.to_string()),
+        _ => None,
+    };
+
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .map(|item| {
+                let (label, value) = match item {
+                    Value::Object(fields) => {
+                        let value = fields.get("value").and_then(scalar);
+                        (
+                            fields.get("label").and_then(scalar).or(value.clone()),
+                            value,
+                        )
+                    }
+                    _ => (scalar(item), scalar(item)),
+                };
+
+                match (label, value) {
+                    (Some(label), Some(value)) => Ok(EnumOption { label, value }),
+                    _ => bail!("Unsupported option: {}", item),
+                }
+            })
+            .collect(),
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(key, value)| {
+                Ok(EnumOption {
+                    label: key.clone(),
+                    value: scalar(value)
+                        .with_context(|| format!("Unsupported option: {}", value))?,
+                })
+            })
+            .collect(),
+        _ => bail!("Options must be a list or a map, got {}", value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{
+        FileSystem, MockCommandExecutor, MockFileSystem, MockOutput, MockUserInput,
+        command::MockCommandResult,
+    };
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn options(values: &[(&str, &str)]) -> Vec<(String, String)> {
+        values
+            .iter()
+            .map(|(label, value)| (label.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn pairs(options: Vec<EnumOption>) -> Vec<(String, String)> {
+        options.into_iter().map(|o| (o.label, o.value)).collect()
+    }
+
+    #[test]
+    fn test_parse_options() {
+        assert_eq!(
+            pairs(
+                parse_options(&json!(["a", 2, {"label": "Cee", "value": "c"}, {"value": "d"}]))
+                    .unwrap()
+            ),
+            options(&[("a", "a"), ("2", "2"), ("Cee", "c"), ("d", "d")])
+        );
+        assert_eq!(
+            pairs(
+                parse_options(&json!({"private-a": "subnet-1", "private-b": "subnet-2"})).unwrap()
+            ),
+            options(&[("private-a", "subnet-1"), ("private-b", "subnet-2")])
+        );
+        assert!(parse_options(&json!("a")).is_err());
+        assert!(parse_options(&json!([["a"]])).is_err());
+    }
+
+    #[test]
+    fn test_select_options_from_sources() {
+        let fs = Arc::new(MockFileSystem::new());
+        let root = fs.current_dir().unwrap();
+        crate::test_helpers::create_test_infrastructure(&fs, &["dev", "prod"]);
+        fs.write(&root.join("regions.yaml"), "- eu-west-1\n- us-east-1\n")
+            .unwrap();
+        SnapshotStore::for_infrastructure(&*fs, &root)
+            .save(
+                "network",
+                "prod",
+                crate::state::StateDocument::parse(
+                    r#"{"version": 4, "outputs": {"subnets": {"value": ["subnet-1"], "type": ["list", "string"]}}, "resources": []}"#,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+
+        let command = Arc::new(MockCommandExecutor::with_outputs(vec![MockCommandResult {
+            command: "kubectl get ns -o json | jq '[.items[].metadata.name]'".to_string(),
+            exit_code: 0,
+            stdout: r#"["default", "apps"]"#.to_string(),
+            stderr: String::new(),
+        }]));
+        let ctx = crate::context::Context::test_with(
+            fs,
+            Arc::new(MockUserInput::new()),
+            Arc::new(MockOutput::new()),
+            command,
+            Arc::new(crate::executor::DefaultExecutorRegistry::new()),
+        );
+
+        let input_type = |yaml: &str| -> InputType { serde_yaml::from_str(yaml).unwrap() };
+        let vars = HashMap::from([("network".to_string(), json!("network"))]);
+
+        let namespaces = input_type(
+            "type: select\noptions:\n  - {label: Default, value: default}\noptions_from:\n  source: command\n  command: \"kubectl get ns -o json | jq '[.items[].metadata.name]'\"",
+        );
+        assert_eq!(
+            pairs(select_options(&ctx, &namespaces, &vars, Some("prod")).unwrap()),
+            options(&[("Default", "default"), ("apps", "apps")])
+        );
+        // Cached: the mock command only answers once
+        assert_eq!(
+            select_options(&ctx, &namespaces, &vars, Some("prod"))
+                .unwrap()
+                .len(),
+            2
+        );
+
+        let regions =
+            input_type("type: multiselect\noptions_from:\n  source: file\n  path: regions.yaml");
+        assert_eq!(
+            pairs(select_options(&ctx, &regions, &vars, None).unwrap()),
+            options(&[("eu-west-1", "eu-west-1"), ("us-east-1", "us-east-1")])
+        );
+
+        let subnets = input_type(
+            "type: select\noptions_from:\n  source: project_output\n  project: \"${var:network}\"\n  output: subnets",
+        );
+        assert_eq!(
+            pairs(select_options(&ctx, &subnets, &vars, Some("prod")).unwrap()),
+            options(&[("subnet-1", "subnet-1")])
+        );
+        assert!(
+            select_options(&ctx, &subnets, &vars, Some("dev"))
+                .unwrap_err()
+                .to_string()
+                .contains("No state snapshot for network/dev")
+        );
+    }
+}
@@ -1,9 +1,14 @@
 use anyhow::{Context, Result};
-use handlebars::{Handlebars, Helper, HelperResult, Output, RenderContext};
+use handlebars::Handlebars;
+use serde::Deserialize;
 use serde_json::Value;
-use std::collections::HashMap;
-use std::path::Path;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
+use super::helpers::register_helpers;
+use super::inheritance::ResolvedTemplate;
+use super::metadata::TemplateFileRule;
 use super::partials::{PartialDiscovery, PartialInfo};
 
 /// Renders templates using Handlebars
@@ -17,11 +22,7 @@ impl TemplateRenderer {
         let mut handlebars = Handlebars::new();
 
         // Register custom helpers
-        handlebars.register_helper("eq", Box::new(eq_helper));
-        handlebars.register_helper("contains", Box::new(contains_helper));
-        handlebars.register_helper("k8s_name", Box::new(k8s_name_helper));
-        handlebars.register_helper("bool", Box::new(bool_helper));
-        handlebars.register_helper("secret", Box::new(secret_helper));
+        register_helpers(&mut handlebars);
 
         Self { handlebars }
     }
@@ -86,6 +87,112 @@ impl TemplateRenderer {
 
         let mut generated_files = Vec::new();
 
+        for file in self.render_files(ctx, template_src_dir, variables)? {
+            let output_path = output_dir.join(&file.path);
+
+            // Create parent directories if needed
+            if let Some(parent) = output_path.parent() {
+                ctx.fs
+                    .create_dir_all(parent)
+                    .context("Failed to create parent directories")?;
+            }
+
+            // Write rendered content
+            ctx.fs
+                .write(&output_path, &file.content)
+                .with_context(|| format!("Failed to write output file: {:?}", output_path))?;
+
+            ctx.output
+                .info(&format!("  Created: {}", output_path.display()));
+
+            generated_files.push(file.path);
+        }
+
+        Ok(generated_files)
+    }
+
+    /// Render a resolved template in memory, base templates first
+    ///
+    /// Inputs missing from `inputs` take their template default and computed inputs are
+    /// computed, as when creating a project. The project and environment names are read from
+    /// the `_project_name` and `_environment` inputs (default: "example" and "dev"). Files of
+    /// later templates in the inheritance chain replace files at the same path.
+    pub fn render_resolved(
+        &self,
+        ctx: &crate::context::Context,
+        template: &ResolvedTemplate,
+        inputs: &HashMap<String, Value>,
+    ) -> Result<BTreeMap<String, String>> {
+        let spec = &template.resource.spec;
+        let mut variables = inputs.clone();
+
+        let name = |key: &str, default: &str| {
+            inputs
+                .get(key)
+                .and_then(|v| v.as_str())
+                .unwrap_or(default)
+                .to_string()
+        };
+        let project_name = name("_project_name", "example");
+        let environment_name = name("_environment", "dev");
+
+        for input in &spec.inputs {
+            if let Some(default) = &input.default {
+                variables
+                    .entry(input.name.clone())
+                    .or_insert_with(|| default.clone());
+            }
+        }
+        for input in &spec.inputs {
+            super::computed::apply_computed_input(
+                input,
+                &mut variables,
+                &project_name,
+                Some(&environment_name),
+            )?;
+        }
+
+        for (key, value) in [
+            ("_environment", environment_name.clone()),
+            ("_project_name_underscores", project_name.replace('-', "_")),
+            ("_project_name_hyphens", project_name.replace('_', "-")),
+        ] {
+            variables
+                .entry(key.to_string())
+                .or_insert(Value::String(value));
+        }
+        variables
+            .entry("_resource_api_version".to_string())
+            .or_insert_with(|| Value::String(spec.api_version.clone()));
+        variables
+            .entry("_resource_kind".to_string())
+            .or_insert_with(|| Value::String(spec.kind.clone()));
+
+        let mut files = BTreeMap::new();
+
+        for template_path in &template.base_paths {
+            let rendered = self
+                .render_files(ctx, template_path, &variables)
+                .with_context(|| format!("Failed to render {}", template_path.display()))?;
+
+            files.extend(rendered.into_iter().map(|f| (f.path, f.content)));
+        }
+
+        Ok(files)
+    }
+
+    /// Render all template files from src directory without writing them
+    ///
+    /// Returns the rendered files with their paths relative to the output directory, so
+    /// callers can decide how to apply them (e.g., merging with local changes on update).
+    pub fn render_files(
+        &self,
+        ctx: &crate::context::Context,
+        template_src_dir: &Path,
+        variables: &HashMap<String, Value>,
+    ) -> Result<Vec<RenderedFile>> {
+        let mut rendered_files = Vec::new();
+
         // Walk through all files in the template/plugin src directory
         let src_dir = template_src_dir.join("src");
 
@@ -98,40 +205,191 @@ impl TemplateRenderer {
             return Ok(Vec::new()); // Return empty file list
         }
 
-        let entries = ctx.fs.walk_dir(&src_dir, 100)?;
+        let rules = Self::load_file_rules(&*ctx.fs, template_src_dir)?;
+        let mut entries = ctx.fs.walk_dir(&src_dir, 100)?;
+        entries.sort();
+
+        let mut seen_outputs = HashSet::new();
 
         for path in entries {
-            if ctx.fs.is_file(&path)
-                && let Some(relative_path) =
-                    self.render_file(ctx, &path, &src_dir, output_dir, variables)?
-            {
-                generated_files.push(relative_path);
+            if !ctx.fs.is_file(&path) {
+                continue;
+            }
+
+            let relative_path = path
+                .strip_prefix(&src_dir)
+                .context("Failed to calculate relative path")?;
+            let applicable: Vec<&TemplateFileRule> =
+                rules.iter().filter(|r| r.matches(relative_path)).collect();
+
+            for file_variables in Self::file_variables(&applicable, variables, relative_path)? {
+                if !applicable
+                    .iter()
+                    .all(|rule| rule.conditions.iter().all(|c| c.is_met(&file_variables)))
+                {
+                    ctx.output.info(&format!(
+                        "  Skipped: {} (condition not met)",
+                        relative_path.display()
+                    ));
+                    continue;
+                }
+
+                let Some(output_relative) = self.render_path(relative_path, &file_variables)?
+                else {
+                    ctx.output.info(&format!(
+                        "  Skipped: {} (empty rendered path)",
+                        relative_path.display()
+                    ));
+                    continue;
+                };
+
+                if !seen_outputs.insert(output_relative.clone()) {
+                    anyhow::bail!(
+                        "Template file {:?} renders to {:?} more than once; include the loop item in its path",
+                        relative_path,
+                        output_relative
+                    );
+                }
+
+                if let Some(content) = self.render_file(ctx, &path, &file_variables)? {
+                    rendered_files.push(RenderedFile {
+                        path: output_relative.to_string_lossy().to_string(),
+                        content,
+                    });
+                }
             }
         }
 
-        Ok(generated_files)
+        Ok(rendered_files)
     }
 
-    /// Render a single file
-    /// Returns the relative path of the generated file, or None if the file was skipped
+    /// Load the file rules (`spec.files`) of a template or plugin
+    fn load_file_rules(
+        fs: &dyn crate::traits::FileSystem,
+        template_src_dir: &Path,
+    ) -> Result<Vec<TemplateFileRule>> {
+        let Some(metadata_file) = [".pmp.template.yaml", ".pmp.plugin.yaml"]
+            .iter()
+            .map(|name| template_src_dir.join(name))
+            .find(|path| fs.exists(path))
+        else {
+            return Ok(Vec::new());
+        };
+
+        let content = fs.read_to_string(&metadata_file)?;
+        let manifest: FileRulesManifest = serde_yaml::from_str(&content)
+            .with_context(|| format!("Invalid file rules in {:?}", metadata_file))?;
+
+        Ok(manifest.spec.files)
+    }
+
+    /// Variables to render a file with: one set per item for `for_each` rules, otherwise the
+    /// template variables as-is
+    fn file_variables<'a>(
+        rules: &[&TemplateFileRule],
+        variables: &'a HashMap<String, Value>,
+        relative_path: &Path,
+    ) -> Result<Vec<Cow<'a, HashMap<String, Value>>>> {
+        let mut loops = rules.iter().filter_map(|rule| {
+            rule.for_each
+                .as_deref()
+                .map(|input| (input, rule.item_name()))
+        });
+
+        let Some((input, item_name)) = loops.next() else {
+            return Ok(vec![Cow::Borrowed(variables)]);
+        };
+
+        if loops.next().is_some() {
+            anyhow::bail!(
+                "Template file {:?} matches more than one for_each rule",
+                relative_path
+            );
+        }
+
+        let items = match variables.get(input) {
+            Some(Value::Array(items)) => items.as_slice(),
+            None | Some(Value::Null) => &[],
+            Some(_) => anyhow::bail!(
+                "for_each input '{}' of {:?} must be a list",
+                input,
+                relative_path
+            ),
+        };
+
+        Ok(items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let mut item_variables = variables.clone();
+                item_variables.insert(item_name.to_string(), item.clone());
+                item_variables.insert(format!("{}_index", item_name), Value::from(index));
+                Cow::Owned(item_variables)
+            })
+            .collect())
+    }
+
+    /// Render the Handlebars expressions in a path relative to src/
+    ///
+    /// The path is rendered as a whole, so block helpers may span directory separators
+    /// (e.g., `{{#if monitoring}}monitoring{{/if}}/alerts.tf`). The `.hbs` suffix is removed
+    /// from the file name. Returns None when a path segment renders to an empty string,
+    /// which excludes the file.
+    fn render_path(
+        &self,
+        relative_path: &Path,
+        variables: &HashMap<String, Value>,
+    ) -> Result<Option<PathBuf>> {
+        let path = relative_path.to_string_lossy();
+        let path = if path.contains("{{") {
+            self.handlebars
+                .render_template(&path, variables)
+                .with_context(|| format!("Failed to render template path: {:?}", relative_path))?
+        } else {
+            path.to_string()
+        };
+
+        let mut rendered = PathBuf::new();
+
+        for segment in path.split('/').map(str::trim) {
+            if segment.is_empty() {
+                return Ok(None);
+            }
+
+            if segment == "." || segment == ".." {
+                anyhow::bail!(
+                    "Template path {:?} renders outside the output directory: {:?}",
+                    relative_path,
+                    path
+                );
+            }
+
+            rendered.push(segment);
+        }
+
+        if let Some(file_name) = rendered
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            && let Some(stripped) = file_name.strip_suffix(".hbs")
+        {
+            rendered.set_file_name(stripped);
+        }
+
+        Ok(Some(rendered))
+    }
+
+    /// Render the content of a single template file
+    /// Returns None if the file was skipped
     fn render_file(
         &self,
         ctx: &crate::context::Context,
         file_path: &Path,
-        template_base_dir: &Path,
-        output_base_dir: &Path,
         variables: &HashMap<String, Value>,
     ) -> Result<Option<String>> {
-        // Calculate relative path
-        let relative_path = file_path
-            .strip_prefix(template_base_dir)
-            .context("Failed to calculate relative path")?;
-
-        // Determine output path (remove .hbs extension if present)
-        let output_path = if let Some(file_name) = relative_path.file_name() {
+        // Skip .pmp.* files - these are auto-generated or metadata
+        if let Some(file_name) = file_path.file_name() {
             let file_name_str = file_name.to_string_lossy();
 
-            // Skip .pmp.* files - these are auto-generated or metadata
             if file_name_str == ".pmp.yaml.hbs"
                 || file_name_str == ".pmp.yaml"
                 || file_name_str == ".pmp.project.yaml.hbs"
@@ -147,26 +405,6 @@ impl TemplateRenderer {
                 ));
                 return Ok(None);
             }
-
-            // Process filename
-            let final_name = if file_name_str.ends_with(".hbs") {
-                file_name_str.trim_end_matches(".hbs").to_string()
-            } else {
-                file_name_str.to_string()
-            };
-
-            // Plugin files no longer need SHA1 prefix since they're in separate module directories
-            let parent = relative_path.parent().unwrap_or_else(|| Path::new(""));
-            output_base_dir.join(parent).join(final_name)
-        } else {
-            output_base_dir.join(relative_path)
-        };
-
-        // Create parent directories if needed
-        if let Some(parent) = output_path.parent() {
-            ctx.fs
-                .create_dir_all(parent)
-                .context("Failed to create parent directories")?;
         }
 
         // Read template content
@@ -185,184 +423,46 @@ impl TemplateRenderer {
         let final_content = crate::template::utils::interpolate_all(&rendered, variables)
             .with_context(|| format!("Failed to interpolate variables in: {:?}", file_path))?;
 
-        // Write rendered content
-        ctx.fs
-            .write(&output_path, &final_content)
-            .with_context(|| format!("Failed to write output file: {:?}", output_path))?;
-
-        ctx.output
-            .info(&format!("  Created: {}", output_path.display()));
-
-        // Return relative path from output_base_dir
-        let relative_output = output_path
-            .strip_prefix(output_base_dir)
-            .context("Failed to calculate relative output path")?
-            .to_string_lossy()
-            .to_string();
-
-        Ok(Some(relative_output))
+        Ok(Some(final_content))
     }
 }
 
-impl Default for TemplateRenderer {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-/// Helper function for equality comparison
-fn eq_helper(
-    h: &Helper,
-    _: &Handlebars,
-    _: &handlebars::Context,
-    _: &mut RenderContext,
-    out: &mut dyn Output,
-) -> HelperResult {
-    let param1 = h.param(0).and_then(|v| v.value().as_str());
-    let param2 = h.param(1).and_then(|v| v.value().as_str());
-
-    if let (Some(p1), Some(p2)) = (param1, param2)
-        && p1 == p2
-    {
-        out.write("true")?;
-    }
+/// A rendered template file, not yet written to disk
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderedFile {
+    /// Path relative to the output directory
+    pub path: String,
 
-    Ok(())
+    pub content: String,
 }
 
-/// Helper function to check if an array contains a value
-fn contains_helper(
-    h: &Helper,
-    _: &Handlebars,
-    _: &handlebars::Context,
-    _: &mut RenderContext,
-    out: &mut dyn Output,
-) -> HelperResult {
-    let array = h.param(0).and_then(|v| v.value().as_array());
-    let search_value = h.param(1).and_then(|v| v.value().as_str());
-
-    if let (Some(arr), Some(search)) = (array, search_value) {
-        for item in arr {
-            if let Some(item_str) = item.as_str()
-                && item_str == search
-            {
-                out.write("true")?;
-                return Ok(());
-            }
-        }
-    }
-
-    Ok(())
+/// File rules section of a template or plugin metadata file
+#[derive(Default, Deserialize)]
+struct FileRulesManifest {
+    #[serde(default)]
+    spec: FileRulesSpec,
 }
 
-/// Helper function to convert strings to Kubernetes-compatible DNS subdomain names (RFC 1123)
-/// Rules: lowercase alphanumeric, '-', '.'; must start/end with alphanumeric; max 253 chars
-fn k8s_name_helper(
-    h: &Helper,
-    _: &Handlebars,
-    _: &handlebars::Context,
-    _: &mut RenderContext,
-    out: &mut dyn Output,
-) -> HelperResult {
-    let value = h.param(0).and_then(|v| v.value().as_str()).ok_or_else(|| {
-        handlebars::RenderError::from(handlebars::RenderErrorReason::Other(
-            "k8s_name requires a string parameter".to_string(),
-        ))
-    })?;
-
-    // Sanitize: keep only lowercase alphanumeric, '-', and '.'
-    // Replace underscores with hyphens, convert to lowercase, remove invalid chars
-    let mut sanitized = String::with_capacity(value.len());
-    for ch in value.chars() {
-        match ch {
-            'a'..='z' | '0'..='9' | '-' | '.' => sanitized.push(ch),
-            'A'..='Z' => sanitized.push(ch.to_ascii_lowercase()),
-            '_' => sanitized.push('-'),
-            _ => {} // Skip invalid characters
-        }
-    }
-
-    // Trim non-alphanumeric from start and end
-    let trimmed = sanitized.trim_matches(|c: char| !c.is_alphanumeric());
-
-    // Truncate to 253 characters (Kubernetes DNS subdomain limit)
-    let result = if trimmed.len() > 253 {
-        trimmed[..253].trim_end_matches(|c: char| !c.is_alphanumeric())
-    } else {
-        trimmed
-    };
-
-    out.write(result)?;
-    Ok(())
+#[derive(Default, Deserialize)]
+struct FileRulesSpec {
+    #[serde(default)]
+    files: Vec<TemplateFileRule>,
 }
 
-/// Helper function to explicitly render boolean values as "true" or "false" strings
-/// This prevents Handlebars from treating booleans as conditional expressions
-fn bool_helper(
-    h: &Helper,
-    _: &Handlebars,
-    _: &handlebars::Context,
-    _: &mut RenderContext,
-    out: &mut dyn Output,
-) -> HelperResult {
-    let value = h
-        .param(0)
-        .ok_or_else(|| {
-            handlebars::RenderError::from(handlebars::RenderErrorReason::Other(
-                "bool helper requires a parameter".to_string(),
-            ))
-        })?
-        .value();
-
-    match value {
-        Value::Bool(true) => out.write("true")?,
-        Value::Bool(false) => out.write("false")?,
-        _ => {
-            // For non-boolean values, convert to string representation
-            out.write(&value.to_string())?;
-        }
+impl Default for TemplateRenderer {
+    fn default() -> Self {
+        Self::new()
     }
-
-    Ok(())
-}
-
-/// Secret helper: {{secret input_name}} outputs local.secret_<input_name>
-/// This references the local value generated in _common.tf for secret inputs
-fn secret_helper(
-    h: &Helper,
-    _: &Handlebars,
-    _: &handlebars::Context,
-    _: &mut RenderContext,
-    out: &mut dyn Output,
-) -> HelperResult {
-    let input_name = h
-        .param(0)
-        .ok_or_else(|| {
-            handlebars::RenderError::from(handlebars::RenderErrorReason::Other(
-                "secret helper requires an input name parameter".to_string(),
-            ))
-        })?
-        .value();
-
-    let name_str = match input_name {
-        Value::String(s) => s.clone(),
-        _ => input_name.to_string().trim_matches('"').to_string(),
-    };
-
-    // Sanitize the name for use as a Terraform local variable name
-    let sanitized = name_str
-        .chars()
-        .map(|c| if c.is_alphanumeric() { c } else { '_' })
-        .collect::<String>();
-
-    out.write(&format!("local.secret_{}", sanitized))?;
-    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::traits::{
+        FileSystem, MockCommandExecutor, MockFileSystem, MockOutput, MockUserInput,
+    };
     use serde_json::json;
+    use std::sync::Arc;
 
     #[test]
     fn test_k8s_name_underscore_conversion() {
@@ -459,4 +559,128 @@ mod tests {
             "api.example.com"
         );
     }
+
+    fn render_test_template(
+        files: &[(&str, &str)],
+        variables: Value,
+    ) -> (Arc<MockFileSystem>, Vec<String>) {
+        let fs = Arc::new(MockFileSystem::new());
+        let ctx = crate::context::Context::test_with(
+            fs.clone(),
+            Arc::new(MockUserInput::new()),
+            Arc::new(MockOutput::new()),
+            Arc::new(MockCommandExecutor::new()),
+            Arc::new(crate::executor::DefaultExecutorRegistry::new()),
+        );
+
+        let template_dir = PathBuf::from("/pack/templates/app");
+        for (path, content) in files {
+            fs.write(&template_dir.join(path), content).unwrap();
+        }
+
+        let variables: HashMap<String, Value> = serde_json::from_value(variables).unwrap();
+        let mut generated = TemplateRenderer::new()
+            .render_template(&ctx, &template_dir, Path::new("/out"), &variables, None)
+            .unwrap();
+        generated.sort();
+
+        (fs, generated)
+    }
+
+    #[test]
+    fn test_render_templated_paths() {
+        let (fs, generated) = render_test_template(
+            &[
+                ("src/{{name}}-values.yaml.hbs", "name: {{name}}"),
+                (
+                    "src/{{#if enable_monitoring}}monitoring{{/if}}/alerts.tf",
+                    "alerts",
+                ),
+                ("src/main.tf.hbs", "main"),
+            ],
+            json!({"name": "api", "enable_monitoring": false}),
+        );
+
+        assert_eq!(generated, vec!["api-values.yaml", "main.tf"]);
+        assert_eq!(
+            fs.get_file_contents(Path::new("/out/api-values.yaml"))
+                .unwrap(),
+            "name: api"
+        );
+        assert!(!fs.has_file(Path::new("/out/monitoring/alerts.tf")));
+    }
+
+    #[test]
+    fn test_render_conditional_files_and_for_each() {
+        let manifest = r#"
+apiVersion: pmp.io/v1
+kind: Template
+metadata:
+  name: app
+spec:
+  files:
+    - path: monitoring
+      if:
+        - input_name: enable_monitoring
+          equals: true
+    - path: "workers/{{worker.name}}.tf.hbs"
+      for_each: workers
+      as: worker
+"#;
+        let files = [
+            (".pmp.template.yaml", manifest),
+            ("src/monitoring/alerts.tf", "alerts"),
+            (
+                "src/workers/{{worker.name}}.tf.hbs",
+                "# {{worker_index}}: {{worker.size}}",
+            ),
+        ];
+
+        let (fs, generated) = render_test_template(
+            &files,
+            json!({
+                "enable_monitoring": true,
+                "workers": [{"name": "a", "size": "small"}, {"name": "b", "size": "large"}]
+            }),
+        );
+        assert_eq!(
+            generated,
+            vec!["monitoring/alerts.tf", "workers/a.tf", "workers/b.tf"]
+        );
+        assert_eq!(
+            fs.get_file_contents(Path::new("/out/workers/b.tf"))
+                .unwrap(),
+            "# 1: large"
+        );
+
+        let (_, generated) =
+            render_test_template(&files, json!({"enable_monitoring": false, "workers": []}));
+        assert!(generated.is_empty());
+    }
+
+    #[test]
+    fn test_render_path_rejects_escaping_output_dir() {
+        let renderer = TemplateRenderer::new();
+        let variables = HashMap::from([("name".to_string(), json!(".."))]);
+
+        assert!(
+            renderer
+                .render_path(Path::new("{{name}}/x.tf"), &variables)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_file_rule_matches() {
+        let rule = TemplateFileRule {
+            path: "monitoring/".to_string(),
+            conditions: vec![],
+            for_each: None,
+            item_name: None,
+        };
+
+        assert!(rule.matches(Path::new("monitoring/alerts.tf")));
+        assert!(!rule.matches(Path::new("monitoring-extra/alerts.tf")));
+        assert_eq!(rule.item_name(), "item");
+    }
 }
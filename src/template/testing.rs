@@ -0,0 +1,239 @@
+//! Golden-file tests for templates
+//!
+//! A template ships test cases next to its `.pmp.template.yaml`, as `tests/<case>/inputs.yaml`
+//! with the files it should generate under `tests/<case>/expected/`. Cases are rendered in
+//! memory and compared with the expected files; nothing is written unless the expected files
+//! are being updated.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::diff::{FileDiff, diff_files, load_inputs_file, read_tree};
+use super::inheritance::ResolvedTemplate;
+use super::renderer::TemplateRenderer;
+use crate::traits::FileSystem;
+
+/// Directory of test cases, relative to the template directory
+pub const TESTS_DIR: &str = "tests";
+
+/// Inputs of a test case, relative to the case directory
+pub const INPUTS_FILE: &str = "inputs.yaml";
+
+/// Expected output tree of a test case, relative to the case directory
+pub const EXPECTED_DIR: &str = "expected";
+
+/// A test case shipped with a template
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateTestCase {
+    pub name: String,
+
+    pub path: PathBuf,
+}
+
+/// Result of running one test case
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TestOutcome {
+    Passed,
+
+    /// The rendered files differ from the expected files (expected → rendered)
+    Failed {
+        files: Vec<FileDiff>,
+    },
+
+    /// The expected files were replaced by the rendered files
+    Updated {
+        files: Vec<FileDiff>,
+    },
+
+    /// The case could not be rendered
+    Error {
+        message: String,
+    },
+}
+
+/// Runs the golden-file tests of templates
+pub struct TemplateTestRunner;
+
+impl TemplateTestRunner {
+    /// Test cases of a template, sorted by name
+    pub fn discover_cases(
+        fs: &dyn FileSystem,
+        template_path: &Path,
+    ) -> Result<Vec<TemplateTestCase>> {
+        let tests_dir = template_path.join(TESTS_DIR);
+
+        if !fs.exists(&tests_dir) {
+            return Ok(Vec::new());
+        }
+
+        let mut cases: Vec<TemplateTestCase> = fs
+            .read_dir(&tests_dir)?
+            .into_iter()
+            .filter(|path| fs.is_dir(path))
+            .filter_map(|path| {
+                let name = path.file_name()?.to_string_lossy().to_string();
+                Some(TemplateTestCase { name, path })
+            })
+            .collect();
+        cases.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(cases)
+    }
+
+    /// Render a test case and compare it with its expected files
+    ///
+    /// With `update`, differing expected files are replaced by the rendered files.
+    pub fn run_case(
+        ctx: &crate::context::Context,
+        renderer: &TemplateRenderer,
+        template: &ResolvedTemplate,
+        case: &TemplateTestCase,
+        update: bool,
+    ) -> TestOutcome {
+        match Self::try_run_case(ctx, renderer, template, case, update) {
+            Ok(outcome) => outcome,
+            Err(e) => TestOutcome::Error {
+                message: format!("{:#}", e),
+            },
+        }
+    }
+
+    fn try_run_case(
+        ctx: &crate::context::Context,
+        renderer: &TemplateRenderer,
+        template: &ResolvedTemplate,
+        case: &TemplateTestCase,
+        update: bool,
+    ) -> Result<TestOutcome> {
+        let inputs_path = case.path.join(INPUTS_FILE);
+        let inputs = if ctx.fs.exists(&inputs_path) {
+            load_inputs_file(&*ctx.fs, &inputs_path)?
+        } else {
+            HashMap::new()
+        };
+
+        let rendered = renderer.render_resolved(ctx, template, &inputs)?;
+
+        let expected_dir = case.path.join(EXPECTED_DIR);
+        let expected = read_tree(&*ctx.fs, &expected_dir)?;
+
+        let files = diff_files(&expected, &rendered);
+        if files.is_empty() {
+            return Ok(TestOutcome::Passed);
+        }
+
+        if !update {
+            return Ok(TestOutcome::Failed { files });
+        }
+
+        if ctx.fs.exists(&expected_dir) {
+            ctx.fs.remove_dir_all(&expected_dir)?;
+        }
+        for (path, content) in &rendered {
+            let output_path = expected_dir.join(path);
+            if let Some(parent) = output_path.parent() {
+                ctx.fs.create_dir_all(parent)?;
+            }
+            ctx.fs
+                .write(&output_path, content)
+                .with_context(|| format!("Failed to write {}", output_path.display()))?;
+        }
+
+        Ok(TestOutcome::Updated { files })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::template::TemplateDiscovery;
+    use crate::template::inheritance::TemplateResolver;
+    use crate::traits::{MockFileSystem, MockOutput};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_run_case() {
+        let fs = Arc::new(MockFileSystem::new());
+        let template_dir = PathBuf::from("/packs/core/templates/api");
+        fs.write(
+            &PathBuf::from("/packs/core/.pmp.template-pack.yaml"),
+            "apiVersion: pmp.io/v1\nkind: TemplatePack\nmetadata:\n  name: core\nspec: {}\n",
+        )
+        .unwrap();
+        fs.write(
+            &template_dir.join(".pmp.template.yaml"),
+            "apiVersion: pmp.io/v1\nkind: Template\nmetadata:\n  name: api\nspec:\n  apiVersion: pmp.io/v1\n  kind: Api\n  executor: opentofu\n  inputs:\n    - name: size\n      default: small\n    - name: bucket\n      type: computed\n      value: \"{{_project_name}}-{{_environment}}-{{size}}\"\n",
+        )
+        .unwrap();
+        fs.write(
+            &template_dir.join("src/main.tf.hbs"),
+            "size = {{size}}\nbucket = {{bucket}}\n",
+        )
+        .unwrap();
+
+        let case_dir = template_dir.join("tests/large");
+        fs.write(
+            &case_dir.join("inputs.yaml"),
+            "_project_name: api\nsize: large\n",
+        )
+        .unwrap();
+        fs.write(&case_dir.join("expected/main.tf"), "size = small\n")
+            .unwrap();
+        fs.write(&case_dir.join("expected/stale.tf"), "").unwrap();
+
+        let ctx = crate::context::Context::test_with(
+            fs.clone(),
+            Arc::new(crate::traits::MockUserInput::new()),
+            Arc::new(MockOutput::new()),
+            Arc::new(crate::traits::MockCommandExecutor::new()),
+            Arc::new(crate::executor::DefaultExecutorRegistry::new()),
+        );
+
+        let packs = TemplateDiscovery::discover_template_packs_with_custom_paths(
+            &*ctx.fs,
+            &*ctx.output,
+            &["/packs"],
+        )
+        .unwrap();
+        let templates =
+            TemplateDiscovery::discover_templates_in_pack(&*ctx.fs, &*ctx.output, &packs[0].path)
+                .unwrap();
+        let resolved =
+            TemplateResolver::resolve(&*ctx.fs, &*ctx.output, &templates[0], &packs[0], &packs)
+                .unwrap();
+
+        let cases = TemplateTestRunner::discover_cases(&*ctx.fs, &template_dir).unwrap();
+        assert_eq!(cases.len(), 1);
+        assert_eq!(cases[0].name, "large");
+
+        let renderer = TemplateRenderer::new();
+        let TestOutcome::Failed { files } =
+            TemplateTestRunner::run_case(&ctx, &renderer, &resolved, &cases[0], false)
+        else {
+            panic!("expected the case to fail");
+        };
+        assert_eq!(
+            files.iter().map(|f| f.path.as_str()).collect::<Vec<_>>(),
+            vec!["main.tf", "stale.tf"]
+        );
+        assert!(files[0].diff.contains("-size = small\n+size = large\n"));
+
+        assert!(matches!(
+            TemplateTestRunner::run_case(&ctx, &renderer, &resolved, &cases[0], true),
+            TestOutcome::Updated { .. }
+        ));
+        assert_eq!(
+            fs.get_file_contents(&case_dir.join("expected/main.tf")),
+            Some("size = large\nbucket = api-dev-large\n".to_string())
+        );
+        assert!(!fs.has_file(&case_dir.join("expected/stale.tf")));
+
+        assert_eq!(
+            TemplateTestRunner::run_case(&ctx, &renderer, &resolved, &cases[0], false),
+            TestOutcome::Passed
+        );
+    }
+}
@@ -1122,3 +1122,74 @@ spec:
     // Create projects directory so infrastructure is recognized
     fs.create_dir_all(&current_dir.join("projects")).unwrap();
 }
+
+/// Request received by the server started with [`start_http_server`]
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: String,
+    /// Path and query string
+    pub target: String,
+    /// Headers keyed by lowercase name
+    pub headers: std::collections::HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+/// Start a minimal HTTP server on a local port
+///
+/// Each request is answered with the status line and body returned by `handler`.
+/// Returns the server address (e.g., `http://127.0.0.1:12345`).
+pub fn start_http_server<F>(mut handler: F) -> String
+where
+    F: FnMut(&HttpRequest) -> (&'static str, String) + Send + 'static,
+{
+    use std::io::{BufRead, BufReader, Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = format!("http://{}", listener.local_addr().unwrap());
+
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+
+            let mut headers = std::collections::HashMap::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end();
+                if line.is_empty() {
+                    break;
+                }
+                let (name, value) = line.split_once(':').unwrap();
+                headers.insert(name.to_ascii_lowercase(), value.trim().to_string());
+            }
+
+            let content_length = headers
+                .get("content-length")
+                .map_or(0, |length| length.parse().unwrap());
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+
+            let mut parts = request_line.split_whitespace();
+            let request = HttpRequest {
+                method: parts.next().unwrap().to_string(),
+                target: parts.next().unwrap().to_string(),
+                headers,
+                body,
+            };
+
+            let (status, response) = handler(&request);
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                response.len(),
+                response
+            )
+            .unwrap();
+        }
+    });
+
+    address
+}
@@ -1,6 +1,12 @@
 use anyhow::Result;
+use std::io::{BufRead, BufReader, Read};
 use std::path::Path;
-use std::process::{Command, Output, Stdio};
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// How long to wait for the remaining output of a streamed command after it exited
+const OUTPUT_GRACE_PERIOD: Duration = Duration::from_millis(500);
 
 /// Trait for executing system commands, allowing for mocking in tests
 pub trait CommandExecutor: Send + Sync {
@@ -15,6 +21,19 @@ pub trait CommandExecutor: Send + Sync {
     /// Execute a shell command (uses cmd on Windows, sh on Unix)
     #[allow(dead_code)]
     fn execute_shell(&self, command: &str, working_dir: &Path) -> Result<Output>;
+
+    /// Execute a shell command with extra environment variables, passing each line it prints
+    /// to `on_line` as soon as it is printed (with `true` for stderr lines)
+    ///
+    /// The command is killed, and an error returned, when it runs longer than `timeout`.
+    fn execute_shell_streaming(
+        &self,
+        command: &str,
+        working_dir: &Path,
+        env: &[(String, String)],
+        timeout: Option<Duration>,
+        on_line: &dyn Fn(&str, bool),
+    ) -> Result<Output>;
 }
 
 /// Real command executor using std::process::Command
@@ -70,6 +89,127 @@ impl CommandExecutor for RealCommandExecutor {
 
         Ok(output)
     }
+
+    fn execute_shell_streaming(
+        &self,
+        command: &str,
+        working_dir: &Path,
+        env: &[(String, String)],
+        timeout: Option<Duration>,
+        on_line: &dyn Fn(&str, bool),
+    ) -> Result<Output> {
+        #[cfg(target_os = "windows")]
+        let (shell, flag) = ("cmd", "/C");
+
+        #[cfg(not(target_os = "windows"))]
+        let (shell, flag) = ("sh", "-c");
+
+        let mut shell_command = Command::new(shell);
+        shell_command
+            .args([flag, command])
+            .current_dir(working_dir)
+            .envs(env.iter().map(|(name, value)| (name, value)))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        // Run in its own process group so a timeout also stops the processes it started
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut shell_command, 0);
+
+        let mut child = shell_command.spawn()?;
+
+        // Read both pipes in the background so a full pipe can't block the command
+        let (sender, lines) = mpsc::channel();
+        if let Some(stdout) = child.stdout.take() {
+            read_lines_in_background(stdout, false, sender.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            read_lines_in_background(stderr, true, sender);
+        }
+
+        let mut output = Output {
+            status: Default::default(),
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+        };
+        let mut handle_line = |(is_stderr, line): (bool, String)| {
+            on_line(line.trim_end_matches(['\r', '\n']), is_stderr);
+            match is_stderr {
+                true => output.stderr.extend(line.as_bytes()),
+                false => output.stdout.extend(line.as_bytes()),
+            }
+        };
+        let started = Instant::now();
+
+        let status = loop {
+            match lines.recv_timeout(Duration::from_millis(50)) {
+                Ok(line) => handle_line(line),
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    std::thread::sleep(Duration::from_millis(50))
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+            }
+
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+
+            if let Some(timeout) = timeout
+                && started.elapsed() >= timeout
+            {
+                kill_process_group(&mut child)?;
+                child.wait()?;
+                anyhow::bail!("Timed out after {}s", timeout.as_secs());
+            }
+        };
+
+        // Lines printed right before the command exited. A process it left running in the
+        // background (`cmd &`) keeps the pipes open, so only wait briefly for them to close.
+        let deadline = Instant::now() + OUTPUT_GRACE_PERIOD;
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            match lines.recv_timeout(remaining) {
+                Ok(line) => handle_line(line),
+                Err(_) => break,
+            }
+        }
+
+        output.status = status;
+        Ok(output)
+    }
+}
+
+/// Kill a command started in its own process group along with the processes it started
+fn kill_process_group(child: &mut Child) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        // The group ID is the PID of its leader
+        let pgid = -(child.id() as libc::pid_t);
+        if unsafe { libc::kill(pgid, libc::SIGKILL) } == 0 {
+            return Ok(());
+        }
+    }
+
+    child.kill()
+}
+
+/// Send each line read from a pipe, with whether it is stderr, until the pipe is closed
+fn read_lines_in_background(
+    pipe: impl Read + Send + 'static,
+    is_stderr: bool,
+    sender: mpsc::Sender<(bool, String)>,
+) {
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(pipe);
+        let mut line = Vec::new();
+
+        while matches!(reader.read_until(b'\n', &mut line), Ok(read) if read > 0) {
+            let text = String::from_utf8_lossy(&line).into_owned();
+            if sender.send((is_stderr, text)).is_err() {
+                break;
+            }
+            line.clear();
+        }
+    });
 }
 
 /// Mock command executor for testing
@@ -174,6 +314,26 @@ impl CommandExecutor for MockCommandExecutor {
             stderr: Vec::new(),
         })
     }
+
+    fn execute_shell_streaming(
+        &self,
+        command: &str,
+        working_dir: &Path,
+        _env: &[(String, String)],
+        _timeout: Option<Duration>,
+        on_line: &dyn Fn(&str, bool),
+    ) -> Result<Output> {
+        let output = self.execute_shell(command, working_dir)?;
+
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            on_line(line, false);
+        }
+        for line in String::from_utf8_lossy(&output.stderr).lines() {
+            on_line(line, true);
+        }
+
+        Ok(output)
+    }
 }
 
 #[cfg(test)]
@@ -220,6 +380,111 @@ mod tests {
         assert!(output.status.success());
     }
 
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn test_real_executor_shell_streaming() {
+        let lines = std::sync::Mutex::new(Vec::new());
+        let env = [("GREETING".to_string(), "hello".to_string())];
+        let output = RealCommandExecutor::new()
+            .execute_shell_streaming(
+                "echo $GREETING; echo oops >&2; printf partial",
+                &PathBuf::from("."),
+                &env,
+                None,
+                &|line, is_stderr| lines.lock().unwrap().push((line.to_string(), is_stderr)),
+            )
+            .unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "hello\npartial");
+        let mut lines = lines.into_inner().unwrap();
+        lines.sort();
+        assert_eq!(
+            lines,
+            vec![
+                ("hello".to_string(), false),
+                ("oops".to_string(), true),
+                ("partial".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn test_real_executor_shell_streaming_background_process() {
+        let started = Instant::now();
+        let output = RealCommandExecutor::new()
+            .execute_shell_streaming(
+                "echo started; sleep 5 &",
+                &PathBuf::from("."),
+                &[],
+                None,
+                &|_, _| {},
+            )
+            .unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "started\n");
+        assert!(started.elapsed() < Duration::from_secs(3));
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn test_real_executor_shell_streaming_timeout_kills_started_processes() {
+        let pid = std::sync::Mutex::new(String::new());
+        let result = RealCommandExecutor::new().execute_shell_streaming(
+            "sleep 30 & echo $!; wait",
+            &PathBuf::from("."),
+            &[],
+            Some(Duration::from_secs(1)),
+            &|line, _| *pid.lock().unwrap() = line.to_string(),
+        );
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Timed out after 1s")
+        );
+
+        // The background sleep is gone, or only left as a zombie waiting to be reaped
+        let pid = pid.into_inner().unwrap();
+        let running = || {
+            let ps = Command::new("ps")
+                .args(["-o", "stat=", "-p", &pid])
+                .output()
+                .unwrap();
+            let state = String::from_utf8_lossy(&ps.stdout).trim().to_string();
+            !state.is_empty() && !state.starts_with('Z')
+        };
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while running() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        assert!(!running());
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn test_real_executor_shell_streaming_windows() {
+        let output = RealCommandExecutor::new()
+            .execute_shell_streaming("echo test", &PathBuf::from("."), &[], None, &|_, _| {})
+            .unwrap();
+        assert!(output.status.success());
+    }
+
+    #[test]
+    fn test_real_executor_shell_streaming_failure() {
+        let output = RealCommandExecutor::new().execute_shell_streaming(
+            "nonexistent_command_12345",
+            &PathBuf::from("."),
+            &[],
+            None,
+            &|_, _| {},
+        );
+        // The command execution itself should succeed, but the command will fail
+        assert!(!output.unwrap().status.success());
+    }
+
     #[test]
     fn test_mock_executor_interactive() {
         let executor = MockCommandExecutor::with_outputs(vec![MockCommandResult {
@@ -2,15 +2,22 @@ use anyhow::{Context, Result};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
 
 /// Trait for filesystem operations to enable testing with mocks
 pub trait FileSystem: Send + Sync {
     /// Read file contents as string
     fn read_to_string(&self, path: &Path) -> Result<String>;
 
+    /// Read file contents as bytes
+    fn read(&self, path: &Path) -> Result<Vec<u8>>;
+
     /// Write string contents to file
     fn write(&self, path: &Path, contents: &str) -> Result<()>;
 
+    /// Append string contents to file, creating it if needed
+    fn append(&self, path: &Path, contents: &str) -> Result<()>;
+
     /// Create directory and all parent directories
     fn create_dir_all(&self, path: &Path) -> Result<()>;
 
@@ -37,6 +44,9 @@ pub trait FileSystem: Send + Sync {
     /// Walk directory recursively (for template discovery)
     fn walk_dir(&self, path: &Path, max_depth: usize) -> Result<Vec<PathBuf>>;
 
+    /// Last modification time of a file, if the filesystem tracks it
+    fn modified(&self, path: &Path) -> Option<SystemTime>;
+
     /// Get current working directory
     fn current_dir(&self) -> Result<PathBuf>;
 }
@@ -49,6 +59,10 @@ impl FileSystem for RealFileSystem {
         std::fs::read_to_string(path).with_context(|| format!("Failed to read file: {:?}", path))
     }
 
+    fn read(&self, path: &Path) -> Result<Vec<u8>> {
+        std::fs::read(path).with_context(|| format!("Failed to read file: {:?}", path))
+    }
+
     fn write(&self, path: &Path, contents: &str) -> Result<()> {
         // Ensure parent directory exists
         if let Some(parent) = path.parent() {
@@ -59,6 +73,22 @@ impl FileSystem for RealFileSystem {
         std::fs::write(path, contents).with_context(|| format!("Failed to write file: {:?}", path))
     }
 
+    fn append(&self, path: &Path, contents: &str) -> Result<()> {
+        use std::io::Write;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create parent directory: {:?}", parent))?;
+        }
+
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(contents.as_bytes()))
+            .with_context(|| format!("Failed to append to file: {:?}", path))
+    }
+
     fn create_dir_all(&self, path: &Path) -> Result<()> {
         std::fs::create_dir_all(path)
             .with_context(|| format!("Failed to create directory: {:?}", path))
@@ -110,6 +140,10 @@ impl FileSystem for RealFileSystem {
         Ok(paths)
     }
 
+    fn modified(&self, path: &Path) -> Option<SystemTime> {
+        std::fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
+
     fn current_dir(&self) -> Result<PathBuf> {
         std::env::current_dir().context("Failed to get current directory")
     }
@@ -173,6 +207,10 @@ impl FileSystem for MockFileSystem {
             .with_context(|| format!("File not found in mock filesystem: {:?}", path))
     }
 
+    fn read(&self, path: &Path) -> Result<Vec<u8>> {
+        self.read_to_string(path).map(String::into_bytes)
+    }
+
     fn write(&self, path: &Path, contents: &str) -> Result<()> {
         // Ensure all parent directories exist in mock (recursively)
         if let Some(parent) = path.parent() {
@@ -186,6 +224,20 @@ impl FileSystem for MockFileSystem {
         Ok(())
     }
 
+    fn append(&self, path: &Path, contents: &str) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            self.create_dir_all(parent)?;
+        }
+
+        self.files
+            .write()
+            .unwrap()
+            .entry(path.to_path_buf())
+            .or_default()
+            .push_str(contents);
+        Ok(())
+    }
+
     fn create_dir_all(&self, path: &Path) -> Result<()> {
         self.directories
             .write()
@@ -306,6 +358,11 @@ impl FileSystem for MockFileSystem {
         Ok(entries)
     }
 
+    fn modified(&self, _path: &Path) -> Option<SystemTime> {
+        // Modification times are not tracked; callers fall back to content hashes
+        None
+    }
+
     fn current_dir(&self) -> Result<PathBuf> {
         Ok(self.current_dir.read().unwrap().clone())
     }
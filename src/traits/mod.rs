@@ -6,7 +6,7 @@ pub mod user_input;
 pub use command::{CommandExecutor, RealCommandExecutor};
 pub use filesystem::{FileSystem, RealFileSystem};
 pub use output::{Output, StreamingOutput, TerminalOutput, format_output_message};
-pub use user_input::{InquireUserInput, UserInput};
+pub use user_input::{DeferredUserInput, InquireUserInput, NonInteractiveUserInput, UserInput};
 
 #[cfg(test)]
 pub use command::MockCommandExecutor;
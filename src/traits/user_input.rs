@@ -1,6 +1,8 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 use std::collections::VecDeque;
-use std::sync::Mutex;
+use std::sync::{Mutex, mpsc};
 
 /// Response type for mock user input
 #[derive(Debug, Clone)]
@@ -102,6 +104,198 @@ impl UserInput for InquireUserInput {
     }
 }
 
+/// A prompt forwarded by `DeferredUserInput`
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PromptRequest {
+    Select {
+        message: String,
+        options: Vec<String>,
+        default: Option<usize>,
+    },
+    MultiSelect {
+        message: String,
+        options: Vec<String>,
+        defaults: Vec<usize>,
+    },
+    Text {
+        message: String,
+        default: Option<String>,
+    },
+    Password {
+        message: String,
+    },
+    Confirm {
+        message: String,
+        default: Option<bool>,
+    },
+}
+
+/// User input that forwards prompts to someone else (e.g. the web UI) and waits for the answers
+///
+/// Answers are JSON values: a string for select, text and password prompts, a list of strings
+/// for multiselect prompts and a boolean for confirmations. `None` cancels the prompt.
+pub struct DeferredUserInput {
+    requests: tokio::sync::mpsc::UnboundedSender<PromptRequest>,
+    answers: Mutex<mpsc::Receiver<Option<serde_json::Value>>>,
+}
+
+impl DeferredUserInput {
+    pub fn new(
+        requests: tokio::sync::mpsc::UnboundedSender<PromptRequest>,
+        answers: mpsc::Receiver<Option<serde_json::Value>>,
+    ) -> Self {
+        Self {
+            requests,
+            answers: Mutex::new(answers),
+        }
+    }
+
+    /// Forward a prompt and wait for its answer
+    fn ask<T: DeserializeOwned>(&self, prompt: &str, request: PromptRequest) -> Result<T> {
+        // One prompt at a time, so answers can't be mixed up
+        let answers = self.answers.lock().unwrap();
+
+        self.requests
+            .send(request)
+            .map_err(|_| anyhow::anyhow!("Nobody is answering prompts: '{}'", prompt))?;
+
+        match answers.recv() {
+            Ok(Some(answer)) => serde_json::from_value(answer)
+                .with_context(|| format!("Invalid answer for prompt: '{}'", prompt)),
+            Ok(None) => anyhow::bail!("Prompt cancelled: '{}'", prompt),
+            Err(_) => anyhow::bail!("Prompt was not answered: '{}'", prompt),
+        }
+    }
+}
+
+impl UserInput for DeferredUserInput {
+    fn select(&self, prompt: &str, options: Vec<String>, default: Option<usize>) -> Result<String> {
+        let answer: String = self.ask(
+            prompt,
+            PromptRequest::Select {
+                message: prompt.to_string(),
+                options: options.clone(),
+                default,
+            },
+        )?;
+
+        if !options.contains(&answer) {
+            anyhow::bail!("'{}' is not an option of prompt: '{}'", answer, prompt);
+        }
+        Ok(answer)
+    }
+
+    fn multi_select(
+        &self,
+        prompt: &str,
+        options: Vec<String>,
+        defaults: Option<&[usize]>,
+    ) -> Result<Vec<String>> {
+        let answers: Vec<String> = self.ask(
+            prompt,
+            PromptRequest::MultiSelect {
+                message: prompt.to_string(),
+                options: options.clone(),
+                defaults: defaults.unwrap_or_default().to_vec(),
+            },
+        )?;
+
+        if let Some(answer) = answers.iter().find(|a| !options.contains(a)) {
+            anyhow::bail!("'{}' is not an option of prompt: '{}'", answer, prompt);
+        }
+        Ok(answers)
+    }
+
+    fn text(&self, prompt: &str, default: Option<&str>) -> Result<String> {
+        self.ask(
+            prompt,
+            PromptRequest::Text {
+                message: prompt.to_string(),
+                default: default.map(str::to_string),
+            },
+        )
+    }
+
+    fn password(&self, prompt: &str) -> Result<String> {
+        self.ask(
+            prompt,
+            PromptRequest::Password {
+                message: prompt.to_string(),
+            },
+        )
+    }
+
+    fn confirm(&self, prompt: &str, default: Option<bool>) -> Result<bool> {
+        self.ask(
+            prompt,
+            PromptRequest::Confirm {
+                message: prompt.to_string(),
+                default,
+            },
+        )
+    }
+}
+
+/// User input for sessions where nobody can answer prompts
+///
+/// Prompts are answered with their default when `auto_answer` is set; prompts without a
+/// default (and all prompts otherwise) fail.
+pub struct NonInteractiveUserInput {
+    auto_answer: bool,
+}
+
+impl NonInteractiveUserInput {
+    pub fn new(auto_answer: bool) -> Self {
+        Self { auto_answer }
+    }
+
+    fn answer<T>(&self, prompt: &str, default: Option<T>) -> Result<T> {
+        match default {
+            Some(default) if self.auto_answer => Ok(default),
+            _ => anyhow::bail!(
+                "Prompt needs an answer, but this session is not interactive: '{}'",
+                prompt
+            ),
+        }
+    }
+}
+
+impl UserInput for NonInteractiveUserInput {
+    fn select(&self, prompt: &str, options: Vec<String>, default: Option<usize>) -> Result<String> {
+        self.answer(
+            prompt,
+            default.and_then(|index| options.get(index).cloned()),
+        )
+    }
+
+    fn multi_select(
+        &self,
+        prompt: &str,
+        options: Vec<String>,
+        defaults: Option<&[usize]>,
+    ) -> Result<Vec<String>> {
+        let defaults = defaults
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|index| options.get(*index).cloned())
+            .collect();
+        self.answer(prompt, Some(defaults))
+    }
+
+    fn text(&self, prompt: &str, default: Option<&str>) -> Result<String> {
+        self.answer(prompt, default.map(str::to_string))
+    }
+
+    fn password(&self, prompt: &str) -> Result<String> {
+        self.answer(prompt, None)
+    }
+
+    fn confirm(&self, prompt: &str, default: Option<bool>) -> Result<bool> {
+        self.answer(prompt, default)
+    }
+}
+
 /// Mock user input implementation for testing
 #[allow(dead_code)]
 pub struct MockUserInput {
@@ -223,3 +417,66 @@ impl UserInput for MockUserInput {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_non_interactive_user_input() {
+        let auto = NonInteractiveUserInput::new(true);
+        assert!(auto.confirm("Apply?", Some(false)).is_ok_and(|a| !a));
+        assert_eq!(auto.text("Region", Some("eu-west-1")).unwrap(), "eu-west-1");
+        assert!(auto.confirm("Apply?", None).is_err());
+        assert!(auto.password("Token").is_err());
+
+        let fail = NonInteractiveUserInput::new(false);
+        assert!(
+            fail.confirm("Apply?", Some(true))
+                .unwrap_err()
+                .to_string()
+                .contains("not interactive: 'Apply?'")
+        );
+    }
+
+    #[test]
+    fn test_deferred_user_input() {
+        let (request_sender, mut requests) = tokio::sync::mpsc::unbounded_channel();
+        let (answer_sender, answers) = mpsc::channel();
+        let input = DeferredUserInput::new(request_sender, answers);
+
+        answer_sender.send(Some(json!(true))).unwrap();
+        answer_sender.send(Some(json!("prod"))).unwrap();
+        answer_sender.send(None).unwrap();
+
+        assert!(input.confirm("Apply?", Some(false)).unwrap());
+        assert_eq!(
+            input
+                .select("Environment", vec!["dev".into(), "prod".into()], None)
+                .unwrap(),
+            "prod"
+        );
+        assert!(
+            input
+                .text("Region", None)
+                .unwrap_err()
+                .to_string()
+                .contains("Prompt cancelled")
+        );
+
+        assert_eq!(
+            serde_json::to_value(requests.try_recv().unwrap()).unwrap(),
+            json!({"kind": "confirm", "message": "Apply?", "default": false})
+        );
+
+        drop(answer_sender);
+        assert!(
+            input
+                .password("Token")
+                .unwrap_err()
+                .to_string()
+                .contains("not answered")
+        );
+    }
+}
@@ -128,6 +128,45 @@ function appendConsoleOutput(message, type = 'info') {
     }
 }
 
+// Answer a prompt of the running operation (e.g. a confirm hook) from the console
+function showConsolePrompt(prompt) {
+    appendConsoleOutput(`? ${prompt.message}`, 'warning');
+
+    const $form = $('<div class="flex flex-wrap gap-2 my-2"></div>');
+    const respond = (value) => {
+        $form.remove();
+        if (activeWebSocket) {
+            activeWebSocket.send(JSON.stringify({ type: 'prompt_response', value: value }));
+        }
+    };
+    const button = (label, onClick) =>
+        $('<button class="px-3 py-1 rounded bg-gray-700 hover:bg-gray-600 text-white text-sm"></button>')
+            .text(label)
+            .on('click', onClick);
+
+    if (prompt.kind === 'confirm') {
+        $form.append(button('Yes', () => respond(true)), button('No', () => respond(false)));
+    } else {
+        let $field;
+        if (prompt.kind === 'select' || prompt.kind === 'multi_select') {
+            const multiple = prompt.kind === 'multi_select';
+            $field = $('<select class="px-2 py-1 rounded text-sm text-gray-900"></select>').prop('multiple', multiple);
+            prompt.options.forEach((option, index) => {
+                const selected = multiple ? prompt.defaults.includes(index) : index === prompt.default;
+                $field.append($('<option></option>').val(option).text(option).prop('selected', selected));
+            });
+        } else {
+            $field = $('<input class="px-2 py-1 rounded text-sm text-gray-900">')
+                .attr('type', prompt.kind === 'password' ? 'password' : 'text')
+                .val(prompt.default || '');
+        }
+        $form.append($field, button('OK', () => respond($field.val())));
+    }
+
+    $form.append(button('Cancel', () => respond(null)));
+    $('#consoleOutput').append($form);
+}
+
 function finishConsole(success, finalMessage = null) {
     $('#consoleSpinner').addClass('hidden');
     $('#consoleActions').removeClass('hidden');
@@ -208,6 +247,9 @@ function executeWithWebSocket(operation, path, options = {}) {
                     case 'output':
                         appendConsoleOutput(msg.data.text);
                         break;
+                    case 'prompt':
+                        showConsolePrompt(msg.data);
+                        break;
                     case 'complete':
                         finishConsole(msg.data.success);
                         activeWebSocket.close();
@@ -387,13 +429,15 @@ function renderRecentOperations(operations) {
                           op.status === 'running' ? '&#8635;' : '&#8226;';
 
         const startTime = new Date(op.started_at).toLocaleString();
+        const target = op.project ? [op.project, op.environment].filter(Boolean).join('/') : op.project_path;
 
         $container.append(`
-            <div class="flex items-center justify-between p-2 bg-gray-50 rounded hover:bg-gray-100">
+            <div class="flex items-center justify-between p-2 bg-gray-50 rounded hover:bg-gray-100 cursor-pointer"
+                 onclick="showOperationLog('${op.id}')" title="Run by ${escapeHtml(op.user)}">
                 <div class="flex items-center gap-2">
                     <span class="text-${statusColor}-600 font-bold">${statusIcon}</span>
                     <span class="font-medium text-sm">${op.operation}</span>
-                    <span class="text-gray-500 text-xs font-mono">${op.project_path}</span>
+                    <span class="text-gray-500 text-xs font-mono">${escapeHtml(target)}</span>
                 </div>
                 <div class="flex items-center gap-2">
                     <span class="text-xs px-2 py-1 rounded bg-${statusColor}-100 text-${statusColor}-700">${op.status}</span>
@@ -404,6 +448,41 @@ function renderRecentOperations(operations) {
     });
 }
 
+// Show the details and saved output of an operation
+async function showOperationLog(id) {
+    try {
+        const [operation, log] = await Promise.all([
+            $.get(`/api/operations/${id}`),
+            $.get(`/api/operations/${id}/log`)
+        ]);
+
+        if (!operation.success) {
+            showStatus('Failed to load operation: ' + operation.error, 'error');
+            return;
+        }
+
+        const op = operation.data;
+        const details = [
+            ['User', op.user],
+            ['Path', op.project_path],
+            ['Started', new Date(op.started_at).toLocaleString()],
+            ['Finished', op.finished_at ? new Date(op.finished_at).toLocaleString() : '-'],
+            ['Status', op.status],
+            ['Error', op.error]
+        ].filter(([, value]) => value)
+            .map(([label, value]) => `<div><span class="text-gray-500">${label}:</span> ${escapeHtml(value)}</div>`)
+            .join('');
+        const output = log.success ? escapeHtml(log.data) : escapeHtml(log.error);
+
+        showModal(`${op.operation} ${op.id}`, `
+            <div class="text-sm space-y-1 mb-4">${details}</div>
+            <pre class="bg-gray-900 text-gray-100 text-xs p-3 rounded max-h-96 overflow-auto">${output}</pre>
+        `);
+    } catch (error) {
+        showStatus('Failed to load operation: ' + error.message, 'error');
+    }
+}
+
 // Load Graph
 async function loadGraph() {
     try {
@@ -663,8 +742,166 @@ function renderTemplateInputs(inputs) {
     if (inputs.length > 0) {
         $('#createTemplateInputsSection').removeClass('hidden');
     }
+
+    updateInputVisibility();
+    loadDynamicOptions();
+}
+
+// Read the current value of a template input field
+function readInputValue(input) {
+    const $field = $(`#input-${input.name}`);
+    if (input.type === 'boolean') {
+        return $field.is(':checked');
+    } else if (input.type === 'number') {
+        return parseFloat($field.val()) || 0;
+    } else if (input.type === 'multiselect') {
+        return $field.val() || [];
+    }
+    return $field.val();
+}
+
+// Values of the template inputs that are currently shown
+function collectVisibleInputValues() {
+    const values = {};
+    currentTemplateInputs.forEach(input => {
+        const $field = $(`#input-${input.name}`);
+        if ($field.length && !$field.prop('disabled')) {
+            values[input.name] = readInputValue(input);
+        }
+    });
+    return values;
+}
+
+// Compare values the way the CLI does: numbers numerically, everything else must be identical
+function valuesEqual(a, b) {
+    if (typeof a === 'number' && typeof b === 'number') {
+        return a === b;
+    }
+    return JSON.stringify(a) === JSON.stringify(b);
+}
+
+// Test a `matches` pattern like the CLI: against the JSON text of non-string values, and never
+// met when the pattern can't be compiled (it is a Rust regex, not all of which JS understands)
+function patternMatches(pattern, value) {
+    const text = typeof value === 'string' ? value : JSON.stringify(value);
+    try {
+        return new RegExp(pattern).test(text);
+    } catch (e) {
+        return false;
+    }
+}
+
+// Evaluate an InputConditionInfo against the current input values
+function conditionMet(cond, values) {
+    switch (cond.condition) {
+        case 'any':
+            return cond.conditions.some(c => conditionMet(c, values));
+        case 'all':
+            return cond.conditions.every(c => conditionMet(c, values));
+        case 'not':
+            return !cond.conditions.every(c => conditionMet(c, values));
+    }
+
+    if (!(cond.field in values)) {
+        return false;
+    }
+    const value = values[cond.field];
+
+    switch (cond.condition) {
+        case 'exists':
+            return true;
+        case 'equals':
+            return valuesEqual(value, cond.value);
+        case 'not_equals':
+            return !valuesEqual(value, cond.value);
+        case 'in':
+            return cond.value.some(v => valuesEqual(value, v));
+        case 'not_in':
+            return !cond.value.some(v => valuesEqual(value, v));
+        case 'gt':
+            return Number(value) > cond.value;
+        case 'gte':
+            return Number(value) >= cond.value;
+        case 'lt':
+            return Number(value) < cond.value;
+        case 'lte':
+            return Number(value) <= cond.value;
+        case 'matches':
+            return patternMatches(cond.value, value);
+        default:
+            return false;
+    }
 }
 
+// Show inputs whose conditions are met and disable the rest so they are not submitted.
+// Inputs are processed in order, so conditions only see the inputs defined before them.
+function updateInputVisibility() {
+    const values = {};
+    currentTemplateInputs.forEach(input => {
+        const $wrapper = $(`#createTemplateInputs [data-input-name="${input.name}"]`);
+        const $field = $(`#input-${input.name}`);
+        const visible = !input.conditions || input.conditions.every(c => conditionMet(c, values));
+
+        $wrapper.toggleClass('hidden', !visible);
+        $field.prop('disabled', !visible);
+        if (visible && $field.length) {
+            values[input.name] = readInputValue(input);
+        }
+    });
+}
+
+$('#createTemplateInputs').on('change input', 'input, select', updateInputVisibility);
+
+// Load the options of select inputs with `options_from`. Options can depend on the environment
+// and on other inputs, so they are reloaded when those change; the server caches them per session.
+// Inputs whose options can't be loaded become free text fields.
+async function loadDynamicOptions(changedInput) {
+    const packName = $('#createTemplatePack').val();
+    const templateName = $('#createTemplate').val();
+    const values = collectVisibleInputValues();
+
+    for (const input of currentTemplateInputs) {
+        const isSelect = input.type === 'select' || input.type === 'multiselect';
+        if (!input.dynamic_options || !isSelect || input.name === changedInput) {
+            continue;
+        }
+
+        let options = [];
+        try {
+            const response = await $.ajax({
+                url: `/api/template-packs/${packName}/templates/${templateName}/inputs/${input.name}/options`,
+                method: 'POST',
+                contentType: 'application/json',
+                data: JSON.stringify({
+                    environment: $('#createEnvironment').val() || null,
+                    inputs: values
+                })
+            });
+            options = response.success ? response.data : [];
+        } catch (error) {
+            options = [];
+        }
+
+        const $field = $(`#input-${input.name}`);
+        const emptyValue = input.type === 'multiselect' ? [] : '';
+        const current = $field.val() || input.default || emptyValue;
+        const dynamicInput = { ...input, options: options };
+        if (options.length === 0) {
+            dynamicInput.type = 'string';
+        }
+
+        const $wrapper = $(`#createTemplateInputs [data-input-name="${input.name}"]`);
+        $wrapper.replaceWith(createInputField({ ...dynamicInput, default: current }));
+    }
+
+    updateInputVisibility();
+}
+
+$('#createTemplateInputs').on('change', 'input, select', function() {
+    loadDynamicOptions($(this).attr('name'));
+});
+$('#createEnvironment').on('change', () => loadDynamicOptions());
+
 function createInputField(input) {
     const id = `input-${input.name}`;
     const required = input.required ? 'required' : '';
@@ -674,12 +911,16 @@ function createInputField(input) {
     let fieldHtml = '';
 
     switch (input.type) {
+        case 'computed':
+            // Derived from other inputs when the project is created
+            return '';
+
         case 'select':
             const options = (input.options || []).map(opt =>
                 `<option value="${opt.value}" ${opt.value === defaultValue ? 'selected' : ''}>${opt.label}</option>`
             ).join('');
             fieldHtml = `
-                <div class="mb-3">
+                <div class="mb-3" data-input-name="${input.name}">
                     <label class="block text-sm font-medium text-gray-700 mb-1" for="${id}">${input.name}</label>
                     <select id="${id}" name="${input.name}" class="w-full px-3 py-2 border border-gray-300 rounded text-sm" ${required}>
                         ${options}
@@ -688,9 +929,24 @@ function createInputField(input) {
                 </div>`;
             break;
 
+        case 'multiselect':
+            const selected = Array.isArray(input.default) ? input.default.map(String) : [];
+            const multiOptions = (input.options || []).map(opt =>
+                `<option value="${opt.value}" ${selected.includes(opt.value) ? 'selected' : ''}>${opt.label}</option>`
+            ).join('');
+            fieldHtml = `
+                <div class="mb-3" data-input-name="${input.name}">
+                    <label class="block text-sm font-medium text-gray-700 mb-1" for="${id}">${input.name}</label>
+                    <select id="${id}" name="${input.name}" multiple class="w-full px-3 py-2 border border-gray-300 rounded text-sm" ${required}>
+                        ${multiOptions}
+                    </select>
+                    ${description ? `<p class="text-xs text-gray-500 mt-1">${description}</p>` : ''}
+                </div>`;
+            break;
+
         case 'boolean':
             fieldHtml = `
-                <div class="mb-3">
+                <div class="mb-3" data-input-name="${input.name}">
                     <label class="flex items-center text-sm">
                         <input type="checkbox" id="${id}" name="${input.name}" ${defaultValue === true || defaultValue === 'true' ? 'checked' : ''} class="mr-2">
                         <span class="font-medium text-gray-700">${input.name}</span>
@@ -701,7 +957,7 @@ function createInputField(input) {
 
         case 'number':
             fieldHtml = `
-                <div class="mb-3">
+                <div class="mb-3" data-input-name="${input.name}">
                     <label class="block text-sm font-medium text-gray-700 mb-1" for="${id}">${input.name}</label>
                     <input type="number" id="${id}" name="${input.name}" value="${defaultValue}"
                         ${input.min !== undefined ? `min="${input.min}"` : ''}
@@ -713,7 +969,7 @@ function createInputField(input) {
 
         case 'password':
             fieldHtml = `
-                <div class="mb-3">
+                <div class="mb-3" data-input-name="${input.name}">
                     <label class="block text-sm font-medium text-gray-700 mb-1" for="${id}">${input.name}</label>
                     <input type="password" id="${id}" name="${input.name}" value="${defaultValue}"
                         class="w-full px-3 py-2 border border-gray-300 rounded text-sm" ${required}>
@@ -723,7 +979,7 @@ function createInputField(input) {
 
         default:
             fieldHtml = `
-                <div class="mb-3">
+                <div class="mb-3" data-input-name="${input.name}">
                     <label class="block text-sm font-medium text-gray-700 mb-1" for="${id}">${input.name}</label>
                     <input type="text" id="${id}" name="${input.name}" value="${defaultValue}"
                         class="w-full px-3 py-2 border border-gray-300 rounded text-sm" ${required}>
@@ -749,19 +1005,7 @@ $('#createProjectForm').on('submit', async function(e) {
         return;
     }
 
-    const inputs = {};
-    currentTemplateInputs.forEach(input => {
-        const $field = $(`#input-${input.name}`);
-        if ($field.length) {
-            if (input.type === 'boolean') {
-                inputs[input.name] = $field.is(':checked');
-            } else if (input.type === 'number') {
-                inputs[input.name] = parseFloat($field.val()) || 0;
-            } else {
-                inputs[input.name] = $field.val();
-            }
-        }
-    });
+    const inputs = collectVisibleInputValues();
 
     showLoading();
 